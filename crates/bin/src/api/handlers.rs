@@ -0,0 +1,294 @@
+use alloy_primitives::{Address, B256};
+use brontes_types::{
+    db::{builder::BuilderInfo, searcher::SearcherInfo, traits::LibmdbxReader},
+    mev::{Bundle, Mev, MevType},
+    pair::Pair,
+    ToFloatNearest,
+};
+use hyper::StatusCode;
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use super::{json_response, ApiError, ApiResult, BrontesApi, Query};
+
+#[derive(Debug, Serialize)]
+struct StatusResponse {
+    latest_block: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct SearcherResponse {
+    address:  Address,
+    eoa:      Option<SearcherInfo>,
+    contract: Option<SearcherInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct BuilderResponse {
+    address: Address,
+    #[serde(flatten)]
+    info:    BuilderInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct DexPriceResponse {
+    block:        u64,
+    tx_idx:       usize,
+    pair:         Pair,
+    pre_state:    f64,
+    post_state:   f64,
+    goes_through: Pair,
+    is_transfer:  bool,
+}
+
+/// bundle filters, a bundle needs to match all of the set ones
+struct BundleFilter {
+    tx_hash:  Option<B256>,
+    eoa:      Option<Address>,
+    contract: Option<Address>,
+    mev_type: Option<MevType>,
+}
+
+impl BundleFilter {
+    fn from_query(query: &Query<'_>) -> Result<Self, ApiError> {
+        let mev_type = query
+            .get("mev_type")
+            .map(|value| {
+                MevType::iter()
+                    .find(|mev_type| mev_type.to_string().eq_ignore_ascii_case(value))
+                    .ok_or_else(|| ApiError::BadRequest(format!("unknown mev_type: {value}")))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            tx_hash: query.optional("tx_hash")?,
+            eoa: query.optional("eoa")?,
+            contract: query.optional("contract")?,
+            mev_type,
+        })
+    }
+
+    fn matches(&self, bundle: &Bundle) -> bool {
+        let header = &bundle.header;
+
+        self.tx_hash.map_or(true, |hash| {
+            header.tx_hash == hash || bundle.data.mev_transaction_hashes().contains(&hash)
+        }) && self.eoa.map_or(true, |eoa| header.eoa == eoa)
+            && self
+                .contract
+                .map_or(true, |contract| header.mev_contract == Some(contract))
+            && self
+                .mev_type
+                .map_or(true, |mev_type| header.mev_type == mev_type)
+    }
+}
+
+impl<DB: LibmdbxReader> BrontesApi<DB> {
+    pub(super) fn status(&self) -> ApiResult {
+        let latest_block = self.db.get_most_recent_block()?;
+        json_response(StatusCode::OK, &StatusResponse { latest_block })
+    }
+
+    pub(super) fn mev_blocks(&self, query: &Query<'_>) -> ApiResult {
+        let (start_block, end_block) = self.block_range(query)?;
+        let blocks = self.db.fetch_mev_block_range(start_block, end_block)?;
+
+        json_response(StatusCode::OK, &blocks)
+    }
+
+    pub(super) fn bundles(&self, query: &Query<'_>) -> ApiResult {
+        let (start_block, end_block) = self.block_range(query)?;
+        let filter = BundleFilter::from_query(query)?;
+
+        let bundles = self
+            .db
+            .fetch_mev_block_range(start_block, end_block)?
+            .into_iter()
+            .flat_map(|block| block.mev)
+            .filter(|bundle| filter.matches(bundle))
+            .collect::<Vec<_>>();
+
+        json_response(StatusCode::OK, &bundles)
+    }
+
+    pub(super) fn searcher(&self, address: Address) -> ApiResult {
+        let eoa = self.db.try_fetch_searcher_eoa_info(address)?;
+        let contract = self.db.try_fetch_searcher_contract_info(address)?;
+
+        if eoa.is_none() && contract.is_none() {
+            return Err(ApiError::NotFound(format!("no searcher info for {address:?}")))
+        }
+
+        json_response(StatusCode::OK, &SearcherResponse { address, eoa, contract })
+    }
+
+    pub(super) fn builder(&self, address: Address) -> ApiResult {
+        let info = self
+            .db
+            .try_fetch_builder_info(address)?
+            .ok_or_else(|| ApiError::NotFound(format!("no builder info for {address:?}")))?;
+
+        json_response(StatusCode::OK, &BuilderResponse { address, info })
+    }
+
+    pub(super) fn token(&self, address: Address) -> ApiResult {
+        // a missing token is returned as an error by the db
+        let info = self
+            .db
+            .try_fetch_token_info(address)
+            .map_err(|_| ApiError::NotFound(format!("no token info for {address:?}")))?;
+
+        json_response(StatusCode::OK, &info)
+    }
+
+    pub(super) fn dex_price(&self, query: &Query<'_>) -> ApiResult {
+        let block: u64 = query.required("block")?;
+        let tx_idx: usize = query.required("tx_idx")?;
+        let pair = Pair(query.required("token0")?, query.required("token1")?);
+
+        let price = self
+            .db
+            .get_dex_quotes(block)?
+            .price_at(pair, tx_idx)
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("no dex price for {pair:?} at {block}:{tx_idx}"))
+            })?;
+
+        json_response(
+            StatusCode::OK,
+            &DexPriceResponse {
+                block,
+                tx_idx,
+                pair,
+                pre_state: price.pre_state.to_float(),
+                post_state: price.post_state.to_float(),
+                goes_through: price.goes_through,
+                is_transfer: price.is_transfer,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_database::libmdbx::{
+        tables::{MevBlocks, MevBlocksData},
+        LibmdbxReadWriter,
+    };
+    use brontes_types::{
+        db::mev_block::MevBlockWithClassified,
+        mev::{BundleHeader, MevBlock},
+    };
+    use serde_json::Value;
+
+    use super::*;
+    use crate::cli::static_object;
+
+    /// api over a fresh db holding blocks 10 to 12, with a sandwich in block 11
+    /// and an atomic arb in the others
+    fn test_api(dir: &tempfile::TempDir) -> BrontesApi<LibmdbxReadWriter> {
+        let db = LibmdbxReadWriter::init_db_tests(dir.path()).unwrap();
+
+        let rows = (10..=12)
+            .map(|block| {
+                let mev_type = if block == 11 { MevType::Sandwich } else { MevType::AtomicArb };
+                let bundle = Bundle {
+                    header: BundleHeader {
+                        block_number: block,
+                        tx_hash: B256::with_last_byte(block as u8),
+                        mev_type,
+                        ..Default::default()
+                    },
+                    data:   Default::default(),
+                };
+                MevBlocksData::new(
+                    block,
+                    MevBlockWithClassified {
+                        block: MevBlock { block_number: block, ..Default::default() },
+                        mev:   vec![bundle],
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        db.db
+            .write_table::<MevBlocks, MevBlocksData>(&rows)
+            .unwrap();
+
+        BrontesApi::new(static_object(db))
+    }
+
+    async fn get(api: &BrontesApi<LibmdbxReadWriter>, path: &str, query: &str) -> Value {
+        let res = api.route(path, &Query::parse(query)).unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn block_numbers(blocks: &Value) -> Vec<u64> {
+        blocks
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|block| block["block"]["block_number"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_mev_blocks_single_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let api = test_api(&dir);
+
+        let blocks = get(&api, "/mev_blocks", "start_block=11").await;
+        assert_eq!(block_numbers(&blocks), vec![11]);
+
+        // the first block in the db
+        let blocks = get(&api, "/mev_blocks", "start_block=10&end_block=10").await;
+        assert_eq!(block_numbers(&blocks), vec![10]);
+    }
+
+    #[tokio::test]
+    async fn test_mev_blocks_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let api = test_api(&dir);
+
+        let blocks = get(&api, "/mev_blocks", "start_block=10&end_block=12").await;
+        assert_eq!(block_numbers(&blocks), vec![10, 11, 12]);
+
+        let blocks = get(&api, "/mev_blocks", "start_block=11&end_block=20").await;
+        assert_eq!(block_numbers(&blocks), vec![11, 12]);
+    }
+
+    #[tokio::test]
+    async fn test_bundles_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let api = test_api(&dir);
+
+        let bundles = get(&api, "/bundles", "start_block=10&end_block=12").await;
+        assert_eq!(bundles.as_array().unwrap().len(), 3);
+
+        let bundles = get(&api, "/bundles", "start_block=10&end_block=12&mev_type=sandwich").await;
+        let bundles = bundles.as_array().unwrap();
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0]["header"]["block_number"], 11);
+
+        let bundles = get(&api, "/bundles", "start_block=12&mev_type=sandwich").await;
+        assert!(bundles.as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_block_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let api = test_api(&dir).with_max_block_range(2);
+
+        let err = api
+            .route("/mev_blocks", &Query::parse("start_block=12&end_block=10"))
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+
+        let err = api
+            .route("/mev_blocks", &Query::parse("start_block=10&end_block=12"))
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,200 @@
+//! Read only HTTP/JSON api over the brontes libmdbx database.
+//!
+//! All endpoints are `GET` and return json:
+//!
+//! - `/status`: most recent block in the db
+//! - `/mev_blocks?start_block=&end_block=`: mev blocks with their bundles
+//! - `/bundles?start_block=&end_block=[&tx_hash=][&eoa=][&contract=][&
+//!   mev_type=]`: bundles in the range matching all of the given filters
+//! - `/searchers/{address}`: searcher eoa & contract info
+//! - `/builders/{address}`: builder info
+//! - `/tokens/{address}`: token info
+//! - `/dex_prices?block=&tx_idx=&token0=&token1=`: dex price of the pair at the
+//!   given tx index
+mod handlers;
+
+use std::{convert::Infallible, net::SocketAddr, str::FromStr};
+
+use brontes_types::{db::traits::LibmdbxReader, FastHashMap};
+use eyre::WrapErr;
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use tracing::{debug, info};
+
+/// max amount of blocks a single range query can cover
+pub const DEFAULT_MAX_BLOCK_RANGE: u64 = 1000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("method not allowed")]
+    MethodNotAllowed,
+    #[error(transparent)]
+    Db(#[from] eyre::Report),
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ApiError::Db(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+pub type ApiResult = Result<Response<Body>, ApiError>;
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+pub struct BrontesApi<DB: LibmdbxReader> {
+    db:              &'static DB,
+    max_block_range: u64,
+}
+
+impl<DB: LibmdbxReader> BrontesApi<DB> {
+    pub fn new(db: &'static DB) -> Self {
+        Self { db, max_block_range: DEFAULT_MAX_BLOCK_RANGE }
+    }
+
+    pub fn with_max_block_range(mut self, max_block_range: u64) -> Self {
+        self.max_block_range = max_block_range;
+        self
+    }
+
+    /// serves the api until the server errors
+    pub async fn serve(self, listen_addr: SocketAddr) -> eyre::Result<()> {
+        let api: &'static Self = Box::leak(Box::new(self));
+
+        let make_svc = make_service_fn(move |_| async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| async move {
+                Ok::<_, Infallible>(api.handle(req).await)
+            }))
+        });
+
+        let server = Server::try_bind(&listen_addr)
+            .wrap_err("Could not bind to address")?
+            .serve(make_svc);
+        info!(target: "brontes::api", %listen_addr, "serving brontes api");
+
+        server.await.wrap_err("api server crashed")
+    }
+
+    async fn handle(&'static self, req: Request<Body>) -> Response<Body> {
+        debug!(target: "brontes::api", method=%req.method(), uri=%req.uri(), "request");
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let query = req.uri().query().unwrap_or_default().to_string();
+
+        // libmdbx reads are blocking
+        let res = tokio::task::spawn_blocking(move || {
+            if method != Method::GET {
+                return Err(ApiError::MethodNotAllowed)
+            }
+            self.route(&path, &Query::parse(&query))
+        })
+        .await
+        .unwrap_or_else(|e| Err(ApiError::Db(eyre::eyre!("request task failed: {e}"))));
+
+        res.unwrap_or_else(|e| {
+            json_response(e.status(), &ErrorResponse { error: e.to_string() })
+                .expect("error response is always valid json")
+        })
+    }
+
+    fn route(&self, path: &str, query: &Query<'_>) -> ApiResult {
+        let segments = path
+            .trim_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        match segments.as_slice() {
+            ["status"] => self.status(),
+            ["mev_blocks"] => self.mev_blocks(query),
+            ["bundles"] => self.bundles(query),
+            ["searchers", address] => self.searcher(parse_value("address", address)?),
+            ["builders", address] => self.builder(parse_value("address", address)?),
+            ["tokens", address] => self.token(parse_value("address", address)?),
+            ["dex_prices"] => self.dex_price(query),
+            _ => Err(ApiError::NotFound(format!("no route for {path}"))),
+        }
+    }
+
+    /// parses the `start_block` & `end_block` params. `end_block` defaults to
+    /// `start_block`
+    fn block_range(&self, query: &Query<'_>) -> Result<(u64, u64), ApiError> {
+        let start_block: u64 = query.required("start_block")?;
+        let end_block = query.optional("end_block")?.unwrap_or(start_block);
+
+        if end_block < start_block {
+            return Err(ApiError::BadRequest("end_block is before start_block".into()))
+        }
+        if end_block - start_block >= self.max_block_range {
+            return Err(ApiError::BadRequest(format!(
+                "block range is limited to {} blocks",
+                self.max_block_range
+            )))
+        }
+
+        Ok((start_block, end_block))
+    }
+}
+
+/// url query params. values are only addresses, hashes, numbers and enum
+/// variants so no percent decoding is needed
+pub struct Query<'a>(FastHashMap<&'a str, &'a str>);
+
+impl<'a> Query<'a> {
+    fn parse(query: &'a str) -> Self {
+        Self(
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .collect(),
+        )
+    }
+
+    fn optional<T: FromStr>(&self, key: &str) -> Result<Option<T>, ApiError> {
+        self.0
+            .get(key)
+            .map(|value| parse_value(key, value))
+            .transpose()
+    }
+
+    fn required<T: FromStr>(&self, key: &str) -> Result<T, ApiError> {
+        self.optional(key)?
+            .ok_or_else(|| ApiError::BadRequest(format!("missing query param {key}")))
+    }
+
+    fn get(&self, key: &str) -> Option<&'a str> {
+        self.0.get(key).copied()
+    }
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, ApiError> {
+    value
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("invalid value for {key}: {value}")))
+}
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> ApiResult {
+    let body = serde_json::to_vec(body).map_err(eyre::Report::from)?;
+
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .map_err(|e| ApiError::Db(e.into()))
+}
@@ -0,0 +1,106 @@
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use brontes_database::{libmdbx::LibmdbxReader, parquet::write_leaderboard};
+use brontes_types::{
+    db::leaderboard::{Leaderboard, LeaderboardEntity, LeaderboardRow, LeaderboardSort},
+    mev::MevType,
+};
+use clap::{Parser, ValueEnum};
+use eyre::WrapErr;
+use itertools::Itertools;
+use strum::IntoEnumIterator;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Csv,
+    Parquet,
+}
+
+#[derive(Debug, Parser)]
+pub struct Analyze {
+    /// Start block of the range to aggregate
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End block of the range to aggregate (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Entities to rank, if omitted searchers, funds and builders are ranked
+    #[arg(long, value_delimiter = ',', ignore_case = true)]
+    pub entity:      Vec<LeaderboardEntity>,
+    /// Mev types to rank, if omitted the aggregate over all types and every
+    /// type present in the range are ranked
+    #[arg(long, short, value_delimiter = ',', ignore_case = true)]
+    pub mev_type:    Vec<MevType>,
+    /// Amount of rows to keep per entity and mev type
+    #[arg(long)]
+    pub top:         Option<usize>,
+    /// Metric the rows are ranked by
+    #[arg(long, value_enum, default_value_t = LeaderboardSort::Profit)]
+    pub sort_by:     LeaderboardSort,
+    /// Output format
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Csv)]
+    pub format:      OutputFormat,
+    /// Output file. Csv is written to stdout if omitted, parquet defaults to
+    /// "leaderboard_<start>_<end>.parquet"
+    #[arg(long, short)]
+    pub path:        Option<PathBuf>,
+}
+
+impl Analyze {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        if self.start_block > self.end_block {
+            eyre::bail!("start block {} is after end block {}", self.start_block, self.end_block)
+        }
+
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_path)?;
+        let leaderboard = libmdbx.fetch_leaderboard(self.start_block, self.end_block)?;
+        let rows = self.ranked_rows(&leaderboard);
+
+        match self.format {
+            OutputFormat::Csv => {
+                if let Some(path) = self.path {
+                    let file = File::create(&path)
+                        .wrap_err_with(|| format!("Failed to create file at {}", path.display()))?;
+                    Leaderboard::write_csv(&mut BufWriter::new(file), rows)?;
+                } else {
+                    Leaderboard::write_csv(&mut std::io::stdout().lock(), rows)?;
+                }
+            }
+            OutputFormat::Parquet => {
+                let path = self.path.unwrap_or_else(|| {
+                    format!("leaderboard_{}_{}.parquet", self.start_block, self.end_block).into()
+                });
+                write_leaderboard(&rows, path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn ranked_rows<'a>(&self, leaderboard: &'a Leaderboard) -> Vec<&'a LeaderboardRow> {
+        let entities = if self.entity.is_empty() {
+            LeaderboardEntity::value_variants().to_vec()
+        } else {
+            self.entity.clone()
+        };
+
+        let mev_types = if self.mev_type.is_empty() {
+            std::iter::once(None)
+                .chain(MevType::iter().map(Some))
+                .collect_vec()
+        } else {
+            self.mev_type.iter().copied().map(Some).collect_vec()
+        };
+
+        entities
+            .into_iter()
+            .cartesian_product(mev_types)
+            .flat_map(|(entity, mev_type)| {
+                leaderboard.top(entity, mev_type, self.sort_by, self.top.unwrap_or(usize::MAX))
+            })
+            .collect()
+    }
+}
@@ -77,6 +77,10 @@ impl DatabaseQuery {
                     AddressMeta,
                     SearcherEOAs,
                     SearcherContracts,
+                    UniswapV4PoolKeys,
+                    PricingSnapshots,
+                    Funds,
+                    TxStateDiffs,
                     TxTraces
                 );
             } else {
@@ -96,6 +100,10 @@ impl DatabaseQuery {
                     AddressMeta,
                     SearcherEOAs,
                     SearcherContracts,
+                    UniswapV4PoolKeys,
+                    PricingSnapshots,
+                    Funds,
+                    TxStateDiffs,
                     TxTraces,
                     PoolCreationBlocks = &self.key
                 );
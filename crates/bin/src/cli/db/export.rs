@@ -1,37 +1,94 @@
-use std::sync::Arc;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
-use brontes_database::{parquet::ParquetExporter, Tables};
+use brontes_classifier::Classifier;
+use brontes_database::{
+    libmdbx::{LibmdbxInit, LibmdbxReadWriter},
+    parquet::{ParquetExporter, TraceClassifier, DEFAULT_PARTITION_SIZE},
+    Tables,
+};
+use brontes_pricing::types::DexPriceMsg;
+use brontes_types::{
+    normalized_actions::Action, structured_trace::TxTrace, traits::TracingProvider, BlockTree,
+};
 use clap::Parser;
 use futures::future::join_all;
-use tokio::task::spawn;
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    task::spawn,
+};
 use tracing::error;
 
 use crate::{
-    cli::{load_libmdbx, static_object},
+    cli::{determine_max_tasks, get_env_vars, get_tracing_provider, load_libmdbx, static_object},
     runner::CliContext,
 };
 #[derive(Debug, Parser)]
 pub struct Export {
     /// Optional tables to exports, if omitted will export all supported tables
-    #[arg(long, short, default_values = &["MevBlocks", "AddressMeta", "SearcherContracts", "Builder"], value_delimiter = ',', ignore_case=true)]
-    pub tables:      Vec<Tables>,
+    /// except TxTraces. Exporting TxTraces classifies the stored traces, which
+    /// requires a node to be configured the same way as for `run`
+    #[arg(
+        long,
+        short,
+        default_values = &[
+            "MevBlocks",
+            "AddressMeta",
+            "SearcherContracts",
+            "Builder",
+            "TokenDecimals",
+            "DexPrice",
+            "CexPrice",
+            "CexTrades",
+        ],
+        value_delimiter = ',',
+        ignore_case = true
+    )]
+    pub tables:         Vec<Tables>,
     /// Optional Start Block, if omitted it will export the entire range to
     /// parquet
     #[arg(long, short)]
-    pub start_block: Option<u64>,
+    pub start_block:    Option<u64>,
     /// Optional End Block
     #[arg(long, short)]
-    pub end_block:   Option<u64>,
+    pub end_block:      Option<u64>,
     /// Optional path, will default to "data_exports/"
     #[arg(long, short)]
-    pub path:        Option<String>,
+    pub path:           Option<String>,
+    /// Amount of blocks per file for the tables keyed by block number
+    /// (DexPrice, CexPrice, CexTrades & TxTraces)
+    #[arg(long, default_value_t = DEFAULT_PARTITION_SIZE)]
+    pub partition_size: u64,
 }
 
 impl Export {
     pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
         let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_path)?);
-        let exporter =
-            Arc::new(ParquetExporter::new(self.start_block, self.end_block, self.path, libmdbx));
+
+        // the partitioned tables are exported over a fixed block range, so we
+        // default to the range of the db when it isn't specified
+        let (mut start_block, mut end_block) = (self.start_block, self.end_block);
+        if self.tables.iter().any(Self::is_partitioned) {
+            let (db_start, db_end) = libmdbx.get_db_range()?;
+            start_block.get_or_insert(db_start);
+            end_block.get_or_insert(db_end);
+        }
+
+        let mut exporter = ParquetExporter::new(start_block, end_block, self.path, libmdbx)
+            .with_partition_size(self.partition_size);
+
+        if self.tables.contains(&Tables::TxTraces) {
+            let db_path = get_env_vars()?;
+            let tracer = Arc::new(get_tracing_provider(
+                Path::new(&db_path),
+                determine_max_tasks(None),
+                ctx.task_executor.clone(),
+            ));
+            exporter = exporter.with_classifier(Arc::new(ExportClassifier::new(libmdbx, tracer)));
+        }
+        let exporter = Arc::new(exporter);
 
         let futures = self.tables.into_iter().map(|t| {
             let exporter = exporter.clone();
@@ -56,4 +113,52 @@ impl Export {
 
         Ok(())
     }
+
+    fn is_partitioned(table: &Tables) -> bool {
+        matches!(table, Tables::DexPrice | Tables::CexPrice | Tables::CexTrades | Tables::TxTraces)
+    }
+}
+
+/// Classifies the stored traces without generating dex prices
+struct ExportClassifier<T: TracingProvider> {
+    classifier: Classifier<'static, T, LibmdbxReadWriter>,
+    tracer:     Arc<T>,
+    /// the classifier still sends pool updates, which we have no use for
+    pricing_rx: Mutex<UnboundedReceiver<DexPriceMsg>>,
+}
+
+impl<T: TracingProvider> ExportClassifier<T> {
+    fn new(libmdbx: &'static LibmdbxReadWriter, tracer: Arc<T>) -> Self {
+        let (pricing_tx, pricing_rx) = unbounded_channel();
+        Self {
+            classifier: Classifier::new(libmdbx, pricing_tx, tracer.clone()),
+            tracer,
+            pricing_rx: Mutex::new(pricing_rx),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: TracingProvider> TraceClassifier for ExportClassifier<T> {
+    async fn classify_block(
+        &self,
+        block: u64,
+        traces: Vec<TxTrace>,
+    ) -> eyre::Result<BlockTree<Action>> {
+        let header = self
+            .tracer
+            .header_by_number(block)
+            .await?
+            .ok_or_else(|| eyre::eyre!("no header found for block {block}"))?;
+
+        let tree = self
+            .classifier
+            .build_block_tree(traces, header, false)
+            .await;
+
+        let mut pricing_rx = self.pricing_rx.lock().unwrap();
+        while pricing_rx.try_recv().is_ok() {}
+
+        Ok(tree)
+    }
 }
@@ -2,6 +2,7 @@ use clap::{Parser, Subcommand};
 mod r2_uploader;
 mod snapshot;
 use crate::runner::CliContext;
+mod analyze;
 mod cex_data;
 #[cfg(feature = "local-clickhouse")]
 mod clickhouse_download;
@@ -14,6 +15,9 @@ mod discovery;
 mod ensure_test_traces;
 mod export;
 mod init;
+mod record_fixtures;
+#[cfg(feature = "local-clickhouse")]
+mod repair;
 mod table_stats;
 #[cfg(feature = "local-clickhouse")]
 mod tip_tracer;
@@ -54,6 +58,10 @@ pub enum DatabaseCommands {
     /// Export libmbdx data to parquet
     #[command(name = "export")]
     Export(export::Export),
+    /// Searcher, fund and builder leaderboards over a block range, computed
+    /// from the stored mev blocks
+    #[command(name = "analyze")]
+    Analyze(analyze::Analyze),
     /// Downloads a database snapshot. Without specified blocks, it fetches
     /// the full range. With start/end blocks, it downloads that range and
     /// merges it into the current database.
@@ -67,6 +75,10 @@ pub enum DatabaseCommands {
     /// of db every 100k blocks to r2
     #[command(name = "r2-upload")]
     UploadSnapshot(r2_uploader::R2Uploader),
+    /// Records the data of the given blocks into self contained fixtures the
+    /// test utils can run from, without a node or clickhouse
+    #[command(name = "record-fixtures")]
+    RecordFixtures(record_fixtures::RecordFixtures),
     #[cfg(feature = "local-clickhouse")]
     /// Traces all blocks required to run the tests and inserts them into
     /// clickhouse
@@ -80,6 +92,11 @@ pub enum DatabaseCommands {
     #[cfg(feature = "local-clickhouse")]
     #[command(name = "run-discovery")]
     Discovery(discovery::DiscoveryFill),
+    /// Compares the mev blocks in libmdbx with clickhouse and re-inserts the
+    /// blocks that are missing rows
+    #[cfg(feature = "local-clickhouse")]
+    #[command(name = "repair")]
+    Repair(repair::Repair),
 }
 
 impl Database {
@@ -92,9 +109,11 @@ impl Database {
             DatabaseCommands::DbClear(cmd) => cmd.execute(brontes_db_path).await,
             DatabaseCommands::UploadSnapshot(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::Export(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            DatabaseCommands::Analyze(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::TableStats(cmd) => cmd.execute(brontes_db_path),
             DatabaseCommands::DownloadSnapshot(cmd) => cmd.execute(brontes_db_path, ctx).await,
             DatabaseCommands::CexData(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            DatabaseCommands::RecordFixtures(cmd) => cmd.execute(brontes_db_path, ctx).await,
             #[cfg(feature = "local-clickhouse")]
             DatabaseCommands::DownloadClickhouse(cmd) => cmd.execute(brontes_db_path, ctx).await,
             #[cfg(feature = "local-clickhouse")]
@@ -103,6 +122,8 @@ impl Database {
             DatabaseCommands::TestTracesInit(cmd) => cmd.execute(brontes_db_path, ctx).await,
             #[cfg(feature = "local-clickhouse")]
             DatabaseCommands::TraceAtTip(cmd) => cmd.execute(brontes_db_path, ctx).await,
+            #[cfg(feature = "local-clickhouse")]
+            DatabaseCommands::Repair(cmd) => cmd.execute(brontes_db_path, ctx).await,
         }
     }
 }
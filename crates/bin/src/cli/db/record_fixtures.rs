@@ -0,0 +1,61 @@
+use std::path::{Path, PathBuf};
+
+use alloy_primitives::TxHash;
+use brontes_database::libmdbx::fixtures::FixtureIndex;
+use brontes_types::{traits::TracingProvider, FastHashSet};
+use clap::Parser;
+use eyre::eyre;
+use itertools::Itertools;
+
+use crate::{
+    cli::{get_env_vars, get_tracing_provider, load_libmdbx},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct RecordFixtures {
+    /// Blocks to record
+    #[arg(long, short, value_delimiter = ',', required_unless_present = "txs")]
+    pub blocks: Vec<u64>,
+    /// Transactions to record the blocks of, e.g. the ones the tests run on
+    #[arg(long, short, value_delimiter = ',')]
+    pub txs:    Vec<TxHash>,
+    /// Directory the fixtures are written to, the test utils read them from
+    /// "fixtures/" in the repository root unless BRONTES_TEST_FIXTURES is set
+    #[arg(long, short, default_value = "fixtures")]
+    pub output: PathBuf,
+}
+
+impl RecordFixtures {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        let db_path = get_env_vars()?;
+
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_path)?;
+        let tracer = get_tracing_provider(Path::new(&db_path), 10, ctx.task_executor.clone());
+
+        let mut blocks = self.blocks.into_iter().collect::<FastHashSet<_>>();
+        for tx in self.txs {
+            let (block, _) = tracer.block_and_tx_index(tx).await?;
+            blocks.insert(block);
+        }
+
+        let mut index = FixtureIndex::load(&self.output)?;
+        for block in blocks.into_iter().sorted() {
+            let header = tracer
+                .header_by_number(block)
+                .await?
+                .ok_or_else(|| eyre!("no header found for block {block}"))?;
+
+            // the traces and metadata have to be in the db already, they can be
+            // generated with `brontes db generate-traces` and `brontes db init`
+            let fixture = libmdbx.record_fixture(block, header)?;
+            let path = fixture.write(&self.output)?;
+            index.insert(&fixture);
+
+            tracing::info!(%block, path = %path.display(), "recorded fixture");
+        }
+        index.save(&self.output)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use brontes_database::{clickhouse::Clickhouse, libmdbx::LibmdbxReader};
+use clap::Parser;
+use indicatif::{ProgressBar, ProgressDrawTarget};
+use itertools::Itertools;
+use tracing::{error, info};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+/// Finds the blocks of a range that are missing rows in clickhouse and
+/// re-inserts them from libmdbx
+#[derive(Debug, Parser)]
+pub struct Repair {
+    /// Start block of the range to check
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End block of the range to check (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Run id the repaired rows are written under
+    #[arg(long)]
+    pub run_id:      u64,
+    /// Only list the missing and partial blocks without repairing them
+    #[arg(long, default_value = "false")]
+    pub dry_run:     bool,
+}
+
+impl Repair {
+    pub async fn execute(self, brontes_db_path: String, ctx: CliContext) -> eyre::Result<()> {
+        if self.start_block > self.end_block {
+            eyre::bail!("start block {} is after end block {}", self.start_block, self.end_block)
+        }
+
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_path)?;
+        let clickhouse = Clickhouse::new_default(Some(self.run_id)).await;
+
+        let blocks = libmdbx.fetch_mev_block_range(self.start_block, self.end_block)?;
+        info!(target: "brontes::db::repair", "checking {} mev blocks", blocks.len());
+
+        let gaps = clickhouse.find_block_gaps(&blocks).await?;
+        for (table, gaps) in &gaps.iter().sorted_by_key(|g| g.table).group_by(|g| g.table) {
+            let gaps = gaps.collect::<Vec<_>>();
+            let missing = gaps.iter().filter(|g| g.is_missing()).count();
+            println!(
+                "{table}: {} missing blocks, {} partial blocks",
+                missing,
+                gaps.len() - missing
+            );
+            for gap in gaps {
+                println!("  {gap}");
+            }
+        }
+
+        let to_repair = gaps.iter().map(|g| g.block_number).unique().collect_vec();
+        if to_repair.is_empty() {
+            info!(target: "brontes::db::repair", "no gaps found");
+            return Ok(())
+        }
+        if self.dry_run {
+            return Ok(())
+        }
+
+        let bar = ProgressBar::with_draw_target(
+            Some(to_repair.len() as u64),
+            ProgressDrawTarget::stderr_with_hz(100),
+        );
+
+        let mut failed = 0;
+        for block in blocks
+            .into_iter()
+            .filter(|b| to_repair.contains(&b.block.block_number))
+        {
+            let block_number = block.block.block_number;
+            if let Err(e) = clickhouse.repair_mev_block(block).await {
+                error!(
+                    target: "brontes::db::repair",
+                    %block_number,
+                    "failed to repair block -- {:?}",
+                    e
+                );
+                failed += 1;
+            }
+            bar.inc(1);
+        }
+        bar.finish();
+
+        if failed > 0 {
+            eyre::bail!("failed to repair {failed} of {} blocks", to_repair.len())
+        }
+        info!(target: "brontes::db::repair", "repaired {} blocks", to_repair.len());
+
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use clap::{Parser, Subcommand};
 mod db;
 mod misc;
 mod run;
+mod serve;
 mod utils;
 mod version_data;
 pub use utils::*;
@@ -38,4 +39,7 @@ pub enum Commands {
     /// Brontes database commands
     #[command(name = "db")]
     Database(db::Database),
+    /// Serve a read only HTTP/JSON api over the brontes db
+    #[command(name = "serve")]
+    Serve(serve::ServeArgs),
 }
@@ -1,8 +1,13 @@
-use std::{path::Path, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
+use brontes_classifier::runtime::load_runtime_classifiers;
 use brontes_core::decoding::Parser as DParser;
 use brontes_database::clickhouse::cex_config::CexDownloadConfig;
-use brontes_inspect::Inspectors;
+use brontes_inspect::{registry::InspectorName, sandwich::VictimSimulator, Inspectors};
 use brontes_metrics::ParserMetricsListener;
 use brontes_types::{
     constants::USDT_ADDRESS_STRING,
@@ -11,13 +16,14 @@ use brontes_types::{
     init_thread_pools, UnboundedYapperReceiver,
 };
 use clap::Parser;
-use tokio::sync::mpsc::unbounded_channel;
+use tokio::{runtime::Handle, sync::mpsc::unbounded_channel};
 
 use super::{determine_max_tasks, get_env_vars, load_clickhouse, load_database, static_object};
 use crate::{
     banner::rain,
     cli::{get_tracing_provider, init_inspectors, load_tip_database},
     runner::CliContext,
+    sinks::{OutputSinks, SinkConfig},
     BrontesRunConfig, MevProcessor, RangeType,
 };
 
@@ -27,35 +33,37 @@ const SECONDS_TO_US_FLOAT: f64 = 1_000_000.0;
 pub struct RunArgs {
     /// Optional Start Block, if omitted it will run at tip until killed
     #[arg(long, short)]
-    pub start_block:          Option<u64>,
+    pub start_block:               Option<u64>,
     /// Optional End Block, if omitted it will run historically & at tip until
     /// killed
     #[arg(long, short)]
-    pub end_block:            Option<u64>,
+    pub end_block:                 Option<u64>,
     /// starts running at tip from where brontes was last left at.
     #[arg(long, default_value_t = false)]
-    pub from_db_tip:          bool,
+    pub from_db_tip:               bool,
     /// Optional Multiple Ranges, format: "start1-end1 start2-end2 ..."
     /// Use this if you want to specify the exact, non continuous block ranges
     /// you want to run
     #[arg(long, num_args = 1.., value_delimiter = ' ')]
-    pub ranges:               Option<Vec<String>>,
+    pub ranges:                    Option<Vec<String>>,
     /// Optional Max Tasks, if omitted it will default to 80% of the number of
     /// physical cores on your machine
     #[arg(long, short)]
-    pub max_tasks:            Option<u64>,
+    pub max_tasks:                 Option<u64>,
     /// Optional minimum batch size
     #[arg(long, default_value = "500")]
-    pub min_batch_size:       u64,
+    pub min_batch_size:            u64,
     /// Optional quote asset, if omitted it will default to USDT
     #[arg(long, short, default_value = USDT_ADDRESS_STRING)]
-    pub quote_asset:          String,
-    /// Inspectors to run. If omitted it defaults to running all inspectors
+    pub quote_asset:               String,
+    /// Inspectors to run, including the ones registered by other crates. If
+    /// omitted it defaults to running all inspectors except the opt-in multi
+    /// block ones
     #[arg(long, short, value_delimiter = ',')]
-    pub inspectors:           Option<Vec<Inspectors>>,
+    pub inspectors:                Option<Vec<InspectorName>>,
     /// Time window arguments for cex data downloads
     #[clap(flatten)]
-    pub time_window_args:     TimeWindowArgs,
+    pub time_window_args:          TimeWindowArgs,
     /// CEX exchanges to consider for cex-dex analysis
     #[arg(
         long,
@@ -63,40 +71,61 @@ pub struct RunArgs {
         default_value = "Binance,Coinbase,Okex,BybitSpot,Kucoin",
         value_delimiter = ','
     )]
-    pub cex_exchanges:        Vec<CexExchange>,
+    pub cex_exchanges:             Vec<CexExchange>,
     /// Force DEX price calculation for every block, ignoring existing database
     /// values.
     #[arg(long, short, default_value = "false")]
-    pub force_dex_pricing:    bool,
+    pub force_dex_pricing:         bool,
     /// Disables DEX pricing. Inspectors needing DEX prices will only calculate
     /// token PnL, not USD PnL, if DEX pricing is unavailable in the
     /// database.
     #[arg(long, default_value = "false")]
-    pub force_no_dex_pricing: bool,
-    /// Number of blocks to lag behind the chain tip when processing.
+    pub force_no_dex_pricing:      bool,
+    /// Saves the dex pricing graph state every n blocks. Range batches are
+    /// aligned to end on these blocks. A batch resumes pricing from the
+    /// nearest saved snapshot at most n blocks before its start, replaying
+    /// the blocks in between instead of re-verifying all subgraphs
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub pricing_snapshot_interval: Option<u64>,
+    /// Number of blocks to lag behind the chain tip when processing. Reorged
+    /// blocks are detected and reprocessed, so this can safely be set to 0
     #[arg(long, default_value = "10")]
-    pub behind_tip:           u64,
+    pub behind_tip:                u64,
     /// Legacy, run in CLI only mode (no TUI) - will output progress bars to
     /// stdout
     #[arg(long, default_value = "true")]
-    pub cli_only:             bool,
+    pub cli_only:                  bool,
     /// Export metrics
     #[arg(long, default_value = "false")]
-    pub with_metrics:         bool,
+    pub with_metrics:              bool,
     /// Wether or not to use a fallback server.
     #[arg(long, default_value_t = false)]
-    pub enable_fallback:      bool,
+    pub enable_fallback:           bool,
     /// Address of the fallback server.
     /// Triggers database writes if the main connection fails, preventing data
     /// loss.
     #[arg(long)]
-    pub fallback_server:      Option<String>,
+    pub fallback_server:           Option<String>,
     /// Set a custom run ID used when inserting data into the Clickhouse
     ///
     /// If omitted, the ID will be automatically incremented from the last run
     /// stored in the Clickhouse database.
     #[arg(long, short)]
-    pub run_id:               Option<u64>,
+    pub run_id:                    Option<u64>,
+    /// Publishes the results of every block processed at tip to the given
+    /// sink, can be passed multiple times. One of `file:<path>`,
+    /// `unix:<path>` or `kafka://<broker>[,<broker>...]/<topic>`
+    #[arg(long = "output-sink")]
+    pub output_sinks:              Vec<SinkConfig>,
+    /// Toml or json file declaring additional classifiers from a json abi and
+    /// a mapping of the decoded params onto the normalized actions
+    #[arg(long)]
+    pub classifier_config:         Option<PathBuf>,
+    /// Measure the loss of sandwich victims by re-executing them against the
+    /// state without the frontruns instead of only reporting their swaps.
+    /// Needs traces that were recorded with state diffs
+    #[arg(long, default_value_t = false)]
+    pub simulate_victim_loss:      bool,
 
     /// shows a cool display at startup
     #[arg(long, short, default_value_t = false)]
@@ -119,6 +148,9 @@ impl RunArgs {
         tracing::info!(target: "brontes", "got env vars");
         let quote_asset = self.quote_asset.parse()?;
         tracing::info!(target: "brontes", "parsed quote asset");
+        if let Some(path) = &self.classifier_config {
+            load_runtime_classifiers(path)?;
+        }
         let task_executor = ctx.task_executor;
 
         let max_tasks = determine_max_tasks(self.max_tasks);
@@ -159,7 +191,8 @@ impl RunArgs {
             .as_ref()
             .map(|f| {
                 f.len() == 1
-                    && (f.contains(&Inspectors::CexDex) || f.contains(&Inspectors::CexDexMarkout))
+                    && (f.contains(&Inspectors::CexDex.into())
+                        || f.contains(&Inspectors::CexDexMarkout.into()))
             })
             .unwrap_or(false);
 
@@ -169,6 +202,13 @@ impl RunArgs {
 
         let trade_config = self.time_window_args.trade_config();
 
+        let tracer =
+            get_tracing_provider(Path::new(&reth_db_path), max_tasks, task_executor.clone());
+
+        let victim_simulator = self
+            .simulate_victim_loss
+            .then(|| VictimSimulator::new(Arc::new(tracer.clone()), Handle::current()));
+
         let inspectors = init_inspectors(
             quote_asset,
             libmdbx,
@@ -176,10 +216,11 @@ impl RunArgs {
             self.cex_exchanges,
             trade_config,
             self.with_metrics,
-        );
+            victim_simulator,
+        )?;
+
+        let output_sinks = OutputSinks::spawn(&self.output_sinks, &task_executor).await?;
 
-        let tracer =
-            get_tracing_provider(Path::new(&reth_db_path), max_tasks, task_executor.clone());
         let parser = static_object(DParser::new(metrics_tx, libmdbx, tracer.clone()).await);
 
         let executor = task_executor.clone();
@@ -202,6 +243,8 @@ impl RunArgs {
                     self.with_metrics,
                     snapshot_mode,
                     load_window,
+                    self.pricing_snapshot_interval,
+                    output_sinks,
                 )
                 .build(task_executor, shutdown)
                 .await
@@ -0,0 +1,33 @@
+use std::net::{IpAddr, SocketAddr};
+
+use clap::Parser;
+
+use crate::{
+    api::{BrontesApi, DEFAULT_MAX_BLOCK_RANGE},
+    cli::{load_libmdbx_read_only, static_object},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct ServeArgs {
+    /// Address the api listens on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host:            IpAddr,
+    /// Port the api listens on
+    #[arg(long, default_value = "6924")]
+    pub port:            u16,
+    /// Max amount of blocks a single range query can cover
+    #[arg(long, default_value_t = DEFAULT_MAX_BLOCK_RANGE)]
+    pub max_block_range: u64,
+}
+
+impl ServeArgs {
+    pub async fn execute(self, brontes_db_path: String, _ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = static_object(load_libmdbx_read_only(brontes_db_path)?);
+
+        BrontesApi::new(libmdbx)
+            .with_max_block_range(self.max_block_range)
+            .serve(SocketAddr::new(self.host, self.port))
+            .await
+    }
+}
@@ -2,21 +2,23 @@ use std::{env, path::Path};
 
 use alloy_primitives::Address;
 #[cfg(not(feature = "local-reth"))]
-use brontes_core::local_provider::LocalProvider;
+use brontes_core::rpc_provider::{RpcTraceMode, RpcTracingProvider};
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::clickhouse_config;
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::Clickhouse;
-#[cfg(not(feature = "local-clickhouse"))]
-use brontes_database::clickhouse::ClickhouseHttpClient;
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::ClickhouseMiddleware;
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::ReadOnlyMiddleware;
 #[cfg(feature = "local-clickhouse")]
-use brontes_database::clickhouse::{dbms::BrontesClickhouseData, ClickhouseBuffered};
+use brontes_database::clickhouse::{
+    dbms::BrontesClickhouseData, ClickhouseBuffered, RollbackRequest,
+};
+#[cfg(not(feature = "local-clickhouse"))]
+use brontes_database::clickhouse::{ClickhouseFileClient, ClickhouseHttpClient, ClickhouseSource};
 use brontes_database::{clickhouse::cex_config::CexDownloadConfig, libmdbx::LibmdbxReadWriter};
-use brontes_inspect::{Inspector, Inspectors};
+use brontes_inspect::{registry::InspectorName, sandwich::VictimSimulator, Inspector};
 use brontes_metrics::inspectors::OutlierMetrics;
 #[cfg(feature = "local-clickhouse")]
 use brontes_types::UnboundedYapperReceiver;
@@ -29,10 +31,8 @@ use brontes_types::{
     mev::Bundle,
     BrontesTaskExecutor,
 };
-use itertools::Itertools;
 #[cfg(feature = "local-reth")]
 use reth_tracing_ext::TracingClient;
-use strum::IntoEnumIterator;
 use tracing::info;
 
 #[cfg(not(feature = "local-clickhouse"))]
@@ -61,9 +61,11 @@ pub async fn load_database(
     let inner = LibmdbxReadWriter::init_db(db_endpoint, None, executor, true)?;
 
     let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-    spawn_db_writer_thread(executor, rx, hr);
+    let (rollback_tx, rollback_rx) = tokio::sync::mpsc::unbounded_channel();
+    spawn_db_writer_thread(executor, rx, rollback_rx, hr);
     let mut clickhouse = Clickhouse::new_default(run_id).await;
     clickhouse.buffered_insert_tx = Some(tx);
+    clickhouse.buffered_rollback_tx = Some(rollback_tx);
 
     Ok(ClickhouseMiddleware::new(clickhouse, inner.into()))
 }
@@ -97,6 +99,11 @@ pub fn load_libmdbx(
     LibmdbxReadWriter::init_db(db_endpoint, None, executor, true)
 }
 
+/// opens the db without a writer, for commands that only ever read from it
+pub fn load_libmdbx_read_only(db_endpoint: String) -> eyre::Result<LibmdbxReadWriter> {
+    LibmdbxReadWriter::init_db_read_only(db_endpoint, None, true)
+}
+
 #[allow(clippy::field_reassign_with_default)]
 #[cfg(feature = "local-clickhouse")]
 pub async fn load_clickhouse(
@@ -109,22 +116,51 @@ pub async fn load_clickhouse(
     Ok(clickhouse)
 }
 
+/// when `CLICKHOUSE_FILES_DIR` is set to a non empty path the cex data and
+/// block metadata are read from the local archives in that directory instead of
+/// the clickhouse api
 #[cfg(not(feature = "local-clickhouse"))]
 pub async fn load_clickhouse(
-    _: CexDownloadConfig,
+    cex_download_config: CexDownloadConfig,
     _: Option<u64>,
-) -> eyre::Result<ClickhouseHttpClient> {
+) -> eyre::Result<ClickhouseSource> {
+    if let Some(data_dir) = env::var("CLICKHOUSE_FILES_DIR")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+    {
+        info!(%data_dir, "loading cex data & block metadata from local files");
+        return Ok(ClickhouseSource::Files(ClickhouseFileClient::new(
+            data_dir,
+            cex_download_config,
+        )?))
+    }
+
     let clickhouse_api = env::var("CLICKHOUSE_API")?;
     let clickhouse_api_key = env::var("CLICKHOUSE_API_KEY").ok();
-    Ok(ClickhouseHttpClient::new(clickhouse_api, clickhouse_api_key).await)
+    Ok(ClickhouseSource::Http(ClickhouseHttpClient::new(clickhouse_api, clickhouse_api_key).await))
 }
 
+/// without a local reth db we trace over rpc, any archive node that exposes
+/// either the `trace_` or `debug_` namespace works. `RPC_TRACE_MODE` selects
+/// which one, defaulting to parity style traces
 #[cfg(not(feature = "local-reth"))]
-pub fn get_tracing_provider(_: &Path, _: u64, _: BrontesTaskExecutor) -> LocalProvider {
+pub fn get_tracing_provider(
+    _: &Path,
+    tracing_tasks: u64,
+    _: BrontesTaskExecutor,
+) -> RpcTracingProvider {
     let db_endpoint = env::var("RETH_ENDPOINT").expect("No db Endpoint in .env");
     let db_port = env::var("RETH_PORT").expect("No DB port.env");
     let url = format!("{db_endpoint}:{db_port}");
-    LocalProvider::new(url, 5)
+    let trace_mode = env::var("RPC_TRACE_MODE")
+        .ok()
+        .map(|mode| {
+            mode.parse::<RpcTraceMode>()
+                .expect("invalid RPC_TRACE_MODE in .env")
+        })
+        .unwrap_or_default();
+
+    RpcTracingProvider::new(url, trace_mode, tracing_tasks as usize, 5)
 }
 
 #[cfg(feature = "local-reth")]
@@ -153,27 +189,26 @@ pub fn static_object<T>(obj: T) -> &'static T {
 pub fn init_inspectors<DB: LibmdbxReader>(
     quote_token: Address,
     db: &'static DB,
-    inspectors: Option<Vec<Inspectors>>,
+    inspectors: Option<Vec<InspectorName>>,
     cex_exchanges: Vec<CexExchange>,
     trade_config: CexDexTradeConfig,
     metrics: bool,
-) -> &'static [&'static dyn Inspector<Result = Vec<Bundle>>] {
+    victim_simulator: Option<VictimSimulator>,
+) -> eyre::Result<&'static [&'static dyn Inspector<Result = Vec<Bundle>>]> {
     let mut res = Vec::new();
     let metrics = metrics.then(OutlierMetrics::new);
-    for inspector in inspectors
-        .map(|i| i.into_iter())
-        .unwrap_or_else(|| Inspectors::iter().collect_vec().into_iter())
-    {
+    for inspector in inspectors.unwrap_or_else(InspectorName::all) {
         res.push(inspector.init_mev_inspector(
             quote_token,
             db,
             &cex_exchanges,
             trade_config,
             metrics.clone(),
-        ));
+            victim_simulator.clone(),
+        )?);
     }
 
-    &*Box::leak(res.into_boxed_slice())
+    Ok(&*Box::leak(res.into_boxed_slice()))
 }
 
 pub fn get_env_vars() -> eyre::Result<String> {
@@ -187,6 +222,7 @@ pub fn get_env_vars() -> eyre::Result<String> {
 fn spawn_db_writer_thread(
     executor: &BrontesTaskExecutor,
     buffered_rx: tokio::sync::mpsc::UnboundedReceiver<Vec<BrontesClickhouseData>>,
+    rollback_rx: tokio::sync::mpsc::UnboundedReceiver<RollbackRequest>,
     hr: Option<HeartRateMonitor>,
 ) {
     let shutdown = executor.get_graceful_shutdown();
@@ -197,6 +233,78 @@ fn spawn_db_writer_thread(
         800,
         hr,
     )
+    .with_rollbacks(rollback_rx)
     .run(shutdown);
     tracing::info!("started writer");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use brontes_inspect::{
+        registry::{register_inspector, DynLibmdbxReader},
+        shared_utils::SharedInspectorUtils,
+        DynMevInspector,
+    };
+    use brontes_types::{constants::USDT_ADDRESS, MultiBlockData};
+
+    use super::*;
+
+    struct DummyInspector {
+        quote:  Address,
+        // external inspectors get the generic utils over the type erased db
+        _utils: SharedInspectorUtils<'static, DynLibmdbxReader>,
+    }
+
+    impl Inspector for DummyInspector {
+        type Result = Vec<Bundle>;
+
+        fn get_id(&self) -> &str {
+            "Dummy"
+        }
+
+        fn inspect_block(&self, _: MultiBlockData) -> Self::Result {
+            vec![]
+        }
+
+        fn get_quote_token(&self) -> Address {
+            self.quote
+        }
+    }
+
+    #[test]
+    fn test_init_registered_inspector() {
+        register_inspector("dummy", |ctx| {
+            static_object(DummyInspector {
+                quote:  ctx.quote_token,
+                _utils: SharedInspectorUtils::new(ctx.quote_token, ctx.db, ctx.metrics),
+            }) as DynMevInspector
+        })
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = static_object(LibmdbxReadWriter::init_db_tests(dir.path()).unwrap());
+
+        let inspectors = init_inspectors(
+            USDT_ADDRESS,
+            db,
+            Some(vec![
+                InspectorName::from_str("dummy").unwrap(),
+                InspectorName::from_str("AtomicArb").unwrap(),
+            ]),
+            vec![],
+            CexDexTradeConfig::default(),
+            false,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(inspectors.len(), 2);
+        assert_eq!(inspectors[0].get_id(), "Dummy");
+        assert_eq!(inspectors[0].get_quote_token(), USDT_ADDRESS);
+        assert!(inspectors[0]
+            .inspect_block(MultiBlockData { blocks: 1, per_block_data: vec![] })
+            .is_empty());
+    }
+}
@@ -12,6 +12,7 @@ use futures::{future::join_all, Stream};
 use indicatif::{ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
 pub use processors::*;
 mod shared;
+pub mod sinks;
 use brontes_database::{clickhouse::ClickhouseHandle, Tables};
 use futures::pin_mut;
 use shared::multi_block_window::MultiBlockWindow;
@@ -30,7 +31,11 @@ use brontes_database::libmdbx::LibmdbxInit;
 use brontes_inspect::Inspector;
 use brontes_pricing::{BrontesBatchPricer, GraphManager, LoadState};
 use brontes_types::{
-    db::traits::LibmdbxReader, BrontesTaskExecutor, FastHashMap, UnboundedYapperReceiver,
+    db::{
+        pricing_snapshot::PricingGraphSnapshot,
+        traits::{DBWriter, LibmdbxReader},
+    },
+    BrontesTaskExecutor, FastHashMap, UnboundedYapperReceiver,
 };
 use futures::{stream::FuturesUnordered, Future, StreamExt};
 use indicatif::MultiProgress;
@@ -40,9 +45,13 @@ use reth_tasks::shutdown::GracefulShutdown;
 pub use tip::TipInspector;
 use tokio::{sync::mpsc::unbounded_channel, task::JoinHandle};
 
-use self::shared::{
-    dex_pricing::WaitingForPricerFuture, metadata_loader::MetadataLoader,
-    state_collector::StateCollector,
+use self::{
+    shared::{
+        dex_pricing::{PricingSnapshots, WaitingForPricerFuture},
+        metadata_loader::MetadataLoader,
+        state_collector::StateCollector,
+    },
+    sinks::OutputSinks,
 };
 use crate::cli::static_object;
 
@@ -65,6 +74,8 @@ pub struct BrontesRunConfig<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseH
     pub metrics: bool,
     pub is_snapshot: bool,
     pub cex_window: usize,
+    pub pricing_snapshot_interval: Option<u64>,
+    pub output_sinks: OutputSinks,
     _p: PhantomData<P>,
 }
 
@@ -88,6 +99,8 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
         metrics: bool,
         is_snapshot: bool,
         cex_window: usize,
+        pricing_snapshot_interval: Option<u64>,
+        output_sinks: OutputSinks,
     ) -> Self {
         Self {
             clickhouse,
@@ -105,6 +118,8 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             tip_db,
             is_snapshot,
             cex_window,
+            pricing_snapshot_interval,
+            output_sinks,
             _p: PhantomData,
         }
     }
@@ -171,26 +186,37 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
                     std::future::ready(())
                 })
                 .await;
-        } else {
-            if self.range_type.get_start_block(self.libmdbx).is_some() {
-                self.build_range_executors(executor.clone(), end_block, pricing_metrics.clone())
-                    .for_each(|block_range| {
-                        futures.push(executor.spawn_critical_with_graceful_shutdown_signal(
-                            "Range Executor",
-                            |shutdown| async move {
-                                block_range.run_until_graceful_shutdown(shutdown).await;
-                            },
-                        ));
-                        std::future::ready(())
-                    })
-                    .await;
-            }
+        } else if self.range_type.get_start_block(self.libmdbx).is_some() {
+            self.build_range_executors(executor.clone(), end_block, pricing_metrics.clone())
+                .for_each(|block_range| {
+                    futures.push(executor.spawn_critical_with_graceful_shutdown_signal(
+                        "Range Executor",
+                        |shutdown| async move {
+                            block_range.run_until_graceful_shutdown(shutdown).await;
+                        },
+                    ));
+                    std::future::ready(())
+                })
+                .await;
+        }
+
+        let metrics = FinishedRange::default();
+        metrics.total_set_range.increment(
+            end_block
+                - self
+                    .range_type
+                    .get_start_block(self.libmdbx)
+                    .unwrap_or(end_block),
+        );
+
+        if should_run_tip_inspector {
             tracing::info!("starting tip inspector");
+            let back_from_tip = self.range_type.back_from_tip();
             let tip_inspector = self.build_tip_inspector(
                 usize::MAX,
                 executor.clone(),
                 end_block,
-                self.range_type.back_from_tip(),
+                back_from_tip,
                 pricing_metrics,
             );
 
@@ -199,16 +225,7 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
                 |shutdown| async move { tip_inspector.run_until_graceful_shutdown(shutdown).await },
             ));
         }
-
-        let metrics = FinishedRange::default();
         metrics.running_ranges.increment(futures.len() as f64);
-        metrics.total_set_range.increment(
-            end_block
-                - self
-                    .range_type
-                    .get_start_block(self.libmdbx)
-                    .unwrap_or(end_block),
-        );
 
         Ok(Brontes { futures, metrics })
     }
@@ -315,24 +332,30 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
                             .unwrap();
                     }
 
+                    // pricing starts right after the snapshot it resumes from, the blocks
+                    // before the range are only replayed to bring the graph up to date
+                    let (pricing_start, snapshot) = self.pricing_resume_point(start_block);
+
                     #[allow(clippy::async_yields_async)]
                     RangeExecutorWithPricing::new(
                         batch_id,
-                        start_block,
+                        pricing_start,
                         end_block,
                         self.init_state_collector(
                             batch_id,
                             executor.clone(),
-                            start_block,
+                            pricing_start,
                             end_block,
                             false,
                             pricing_metrics,
+                            snapshot,
                         ),
                         self.libmdbx,
                         self.inspectors,
                         prgrs_bar,
                         metrics,
                     )
+                    .with_first_result_block(start_block)
                 }
             },
         ))
@@ -340,7 +363,7 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
     }
 
     fn build_tip_inspector(
-        &self,
+        self,
         range_id: usize,
         executor: BrontesTaskExecutor,
         start_block: u64,
@@ -349,19 +372,38 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
     ) -> TipInspector<T, DB, CH, P> {
         let state_collector = self.init_state_collector(
             range_id,
-            executor,
+            executor.clone(),
             start_block,
             start_block,
             true,
-            pricing_metrics,
+            pricing_metrics.clone(),
+            None,
         );
+        let (parser, tip_db, inspectors) = (self.parser, self.tip_db, self.inspectors);
+        let sinks = self.output_sinks.clone();
+
+        // on a reorg, the tip inspector rebuilds its state from the fork block
+        let new_collector = Box::new(move |block| {
+            self.init_state_collector(
+                range_id,
+                executor.clone(),
+                block,
+                block,
+                true,
+                pricing_metrics.clone(),
+                None,
+            )
+        });
+
         TipInspector::new(
             start_block,
             back_from_tip,
             state_collector,
-            self.parser,
-            self.tip_db,
-            self.inspectors,
+            new_collector,
+            parser,
+            tip_db,
+            inspectors,
+            sinks,
         )
     }
 
@@ -379,6 +421,8 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
     /// * `end_block` - The last block in the range.
     /// * `tip` - Boolean flag indicating if this is for tip processing.
     /// * `pricing_metrics` - Optional metrics for DEX pricing.
+    /// * `snapshot` - Pricing graph snapshot taken after the block before
+    ///   `start_block` to resume pricing from.
     ///
     /// # Returns
     ///
@@ -392,6 +436,7 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
         end_block: u64,
         tip: bool,
         pricing_metrics: Option<DexPricingMetrics>,
+        snapshot: Option<PricingGraphSnapshot>,
     ) -> StateCollector<T, DB, CH> {
         let shutdown = Arc::new(AtomicBool::new(false));
         let (tx, rx) = unbounded_channel();
@@ -417,7 +462,7 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
 
         let data_req = Arc::new(AtomicBool::new(true));
 
-        let pricer = BrontesBatchPricer::new(
+        let mut pricer = BrontesBatchPricer::new(
             range_id,
             shutdown.clone(),
             self.quote_asset,
@@ -429,9 +474,27 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             data_req.clone(),
             pricing_metrics.clone(),
             executor.clone(),
-        );
+        )
+        .with_v4_pool_keys(self.libmdbx.fetch_all_v4_pool_keys().unwrap_or_default());
+
+        if let Some(snapshot) = snapshot {
+            if let Err(e) = pricer.restore_graph_snapshot(snapshot) {
+                tracing::warn!(range_id, error = %e, "failed to resume from pricing snapshot");
+            }
+        }
+
+        // the tip pricer follows reorgs, so it doesn't write snapshots
+        let snapshot_interval = self.pricing_snapshot_interval.filter(|_| !tip);
 
-        let pricing = WaitingForPricerFuture::new(pricer, executor);
+        let libmdbx = self.libmdbx;
+        let snapshots = snapshot_interval.map(|interval| PricingSnapshots {
+            interval,
+            save: Arc::new(move |block, snapshot| {
+                Box::pin(libmdbx.save_pricing_snapshot(block, snapshot))
+            }),
+        });
+
+        let pricing = WaitingForPricerFuture::new(pricer, executor, snapshots);
         let fetcher = MetadataLoader::new(
             tip.then_some(self.clickhouse),
             pricing,
@@ -525,6 +588,17 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
         Ok(())
     }
 
+    /// Finds the snapshot a range starting at `start_block` resumes its pricing
+    /// from, returning the block pricing starts at along with it. Without a
+    /// usable snapshot pricing starts cold at `start_block`.
+    fn pricing_resume_point(&self, start_block: u64) -> (u64, Option<PricingGraphSnapshot>) {
+        let Some(interval) = self.pricing_snapshot_interval else { return (start_block, None) };
+
+        resume_point(start_block, interval, |block| {
+            self.libmdbx.try_fetch_latest_pricing_snapshot(block)
+        })
+    }
+
     ///Calculate the block chunks using min batch size and max_tasks.
     /// Max tasks defaults to 50% of physical cores of the system if not set
     fn calculate_chunks(&self, start_block: u64, end_block: u64) -> Vec<(u64, u64)> {
@@ -534,6 +608,10 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
 
         let chunk_size = if cpus == 0 { range + 1 } else { (range / cpus) + 1 };
 
+        if let Some(interval) = self.pricing_snapshot_interval {
+            return snapshot_aligned_chunks(start_block, end_block, chunk_size, interval)
+        }
+
         (start_block..=end_block)
             .chunks(chunk_size.try_into().unwrap())
             .into_iter()
@@ -648,6 +726,50 @@ fn calculate_buffer_size(state_to_init: &StateToInitialize, max_tasks: usize) ->
     }
 }
 
+/// Splits the range into chunks of roughly `chunk_size` blocks that all end on
+/// a snapshot block, so that the chunk after it can resume its pricing from the
+/// snapshot.
+fn snapshot_aligned_chunks(
+    start_block: u64,
+    end_block: u64,
+    chunk_size: u64,
+    interval: u64,
+) -> Vec<(u64, u64)> {
+    let mut chunks = vec![];
+    let mut start = start_block;
+    while start <= end_block {
+        let end = (start + chunk_size - 1)
+            .next_multiple_of(interval)
+            .min(end_block);
+        chunks.push((start, end));
+        start = end + 1;
+    }
+
+    chunks
+}
+
+/// Resumes from the nearest snapshot taken before `start_block`, replaying the
+/// blocks after it. Snapshots more than one interval old are ignored, at that
+/// point replaying costs more than building the graph from scratch.
+fn resume_point<S>(
+    start_block: u64,
+    interval: u64,
+    latest_snapshot: impl FnOnce(u64) -> eyre::Result<Option<(u64, S)>>,
+) -> (u64, Option<S>) {
+    let Some(prev_block) = start_block.checked_sub(1) else { return (start_block, None) };
+
+    match latest_snapshot(prev_block) {
+        Ok(Some((block, snapshot))) if prev_block - block <= interval => {
+            (block + 1, Some(snapshot))
+        }
+        Ok(_) => (start_block, None),
+        Err(e) => {
+            tracing::warn!(start_block, error = %e, "failed to load pricing snapshot");
+            (start_block, None)
+        }
+    }
+}
+
 pub struct Brontes {
     pub futures: FuturesUnordered<JoinHandle<()>>,
     pub metrics: FinishedRange,
@@ -703,3 +825,68 @@ impl RangeType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    /// latest snapshot at or before the block, as the libmdbx lookup does
+    fn latest(
+        snapshots: &BTreeMap<u64, u64>,
+    ) -> impl FnOnce(u64) -> eyre::Result<Option<(u64, u64)>> + '_ {
+        move |block| Ok(snapshots.range(..=block).next_back().map(|(k, v)| (*k, *v)))
+    }
+
+    #[test]
+    fn test_chunks_end_on_snapshot_blocks() {
+        let chunks = snapshot_aligned_chunks(1_001, 5_500, 1_200, 1_000);
+        assert_eq!(chunks, vec![(1_001, 3_000), (3_001, 5_000), (5_001, 5_500)]);
+    }
+
+    #[test]
+    fn test_resume_across_chunk_boundary() {
+        let chunks = snapshot_aligned_chunks(1_001, 5_500, 1_200, 1_000);
+        // snapshots written by a previous run over the same range
+        let snapshots = chunks
+            .iter()
+            .flat_map(|(start, end)| (*start..=*end).filter(|b| b % 1_000 == 0))
+            .map(|block| (block, block))
+            .collect::<BTreeMap<_, _>>();
+
+        // every chunk after the first resumes from the snapshot at the end of the
+        // chunk before it, without replaying any blocks
+        for window in chunks.windows(2) {
+            let [(_, prev_end), (start, _)] = window else { unreachable!() };
+            assert_eq!(resume_point(*start, 1_000, latest(&snapshots)), (*start, Some(*prev_end)));
+        }
+    }
+
+    #[test]
+    fn test_resume_replays_from_nearest_snapshot() {
+        let snapshots = BTreeMap::from([(1_000, 1_000), (2_000, 2_000)]);
+
+        // the nearest snapshot below the start is used and the blocks after it are
+        // replayed
+        assert_eq!(resume_point(2_501, 1_000, latest(&snapshots)), (2_001, Some(2_000)));
+        // a snapshot right before the start needs no replay
+        assert_eq!(resume_point(2_001, 1_000, latest(&snapshots)), (2_001, Some(2_000)));
+        // snapshots after the start are never used
+        assert_eq!(resume_point(1_500, 1_000, latest(&snapshots)), (1_001, Some(1_000)));
+    }
+
+    #[test]
+    fn test_resume_cold_without_usable_snapshot() {
+        let snapshots = BTreeMap::from([(1_000, 1_000)]);
+
+        assert_eq!(resume_point(500, 1_000, latest(&snapshots)), (500, None));
+        // too old to be worth replaying
+        assert_eq!(resume_point(2_500, 1_000, latest(&snapshots)), (2_500, None));
+        assert_eq!(resume_point(0, 1_000, latest(&snapshots)), (0, None));
+        assert_eq!(
+            resume_point::<u64>(2_001, 1_000, |_| Err(eyre::eyre!("db error"))),
+            (2_001, None)
+        );
+    }
+}
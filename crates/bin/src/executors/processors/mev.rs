@@ -22,7 +22,7 @@ use brontes_types::{
 };
 use tracing::debug;
 
-use crate::Processor;
+use crate::{executors::sinks::OutputSinks, Processor};
 
 #[derive(Debug, Clone, Copy)]
 pub struct MevProcessor;
@@ -34,6 +34,7 @@ impl Processor for MevProcessor {
         db: &'static DB,
         inspectors: &'static [&dyn Inspector<Result = Self::InspectType>],
         data: MultiBlockData,
+        sinks: OutputSinks,
     ) {
         let last = data.get_most_recent_block().clone();
         let BlockData { metadata, tree } = last;
@@ -57,6 +58,7 @@ impl Processor for MevProcessor {
         let ComposerResults { block_details, mev_details, block_analysis, .. } =
             execute_on!(async_inspect, { run_block_inspection(inspectors, data, db) }).await;
 
+        sinks.publish(&block_details, &mev_details);
         insert_mev_results(db, block_details, mev_details, block_analysis).await;
     }
 }
@@ -6,6 +6,8 @@ use brontes_types::MultiBlockData;
 use futures::Future;
 pub use mev::*;
 
+use crate::executors::sinks::OutputSinks;
+
 pub trait Processor: Send + Sync + 'static + Unpin + Copy + Clone {
     type InspectType: Send + Sync + Unpin;
 
@@ -13,5 +15,6 @@ pub trait Processor: Send + Sync + 'static + Unpin + Copy + Clone {
         db: &'static DB,
         inspectors: &'static [&dyn Inspector<Result = Self::InspectType>],
         data: MultiBlockData,
+        sinks: OutputSinks,
     ) -> impl Future<Output = ()> + Send;
 }
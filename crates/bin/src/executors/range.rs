@@ -17,7 +17,10 @@ use reth_tasks::shutdown::GracefulShutdown;
 use tracing::debug;
 
 use super::shared::state_collector::StateCollector;
-use crate::{executors::ProgressBar, Processor};
+use crate::{
+    executors::{sinks::OutputSinks, ProgressBar},
+    Processor,
+};
 
 type InsertFutures = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
 
@@ -27,16 +30,19 @@ pub struct RangeExecutorWithPricing<
     CH: ClickhouseHandle,
     P: Processor,
 > {
-    id:             usize,
-    collector:      StateCollector<T, DB, CH>,
-    insert_futures: FuturesUnordered<InsertFutures>,
-    current_block:  u64,
-    end_block:      u64,
-    libmdbx:        &'static DB,
-    inspectors:     &'static [&'static dyn Inspector<Result = P::InspectType>],
-    progress_bar:   Option<ProgressBar>,
-    global_metrics: Option<GlobalRangeMetrics>,
-    _p:             PhantomData<P>,
+    id:                 usize,
+    collector:          StateCollector<T, DB, CH>,
+    insert_futures:     FuturesUnordered<InsertFutures>,
+    current_block:      u64,
+    end_block:          u64,
+    /// blocks before this one are only replayed to bring the pricing graph up
+    /// to date after resuming from a snapshot
+    first_result_block: u64,
+    libmdbx:            &'static DB,
+    inspectors:         &'static [&'static dyn Inspector<Result = P::InspectType>],
+    progress_bar:       Option<ProgressBar>,
+    global_metrics:     Option<GlobalRangeMetrics>,
+    _p:                 PhantomData<P>,
 }
 
 impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P: Processor>
@@ -58,6 +64,7 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
             insert_futures: FuturesUnordered::default(),
             current_block: start_block,
             end_block,
+            first_result_block: start_block,
             libmdbx,
             inspectors,
             progress_bar,
@@ -66,6 +73,11 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
         }
     }
 
+    pub fn with_first_result_block(mut self, block: u64) -> Self {
+        self.first_result_block = block;
+        self
+    }
+
     pub async fn run_until_graceful_shutdown(self, shutdown: GracefulShutdown) {
         let data_batching = self;
         pin_mut!(data_batching, shutdown);
@@ -91,6 +103,10 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
 
     fn on_price_finish(&mut self, data: MultiBlockData) {
         debug!(target:"brontes","Completed DEX pricing");
+        if data.get_most_recent_block().block_number() < self.first_result_block {
+            return
+        }
+
         self.global_metrics
             .as_ref()
             .inspect(|m| m.inc_inspector(self.id));
@@ -98,13 +114,21 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
         let metrics = self.global_metrics.clone();
         let inspectors = self.inspectors;
         let libmdbx = self.libmdbx;
+        // only the tip inspector publishes to the output sinks
         self.insert_futures.push(Box::pin(async move {
             if let Some(metrics) = metrics {
                 metrics
-                    .meter_processing(|| Box::pin(P::process_results(libmdbx, inspectors, data)))
+                    .meter_processing(|| {
+                        Box::pin(P::process_results(
+                            libmdbx,
+                            inspectors,
+                            data,
+                            OutputSinks::default(),
+                        ))
+                    })
                     .await
             } else {
-                P::process_results(libmdbx, inspectors, data).await
+                P::process_results(libmdbx, inspectors, data, OutputSinks::default()).await
             }
         }));
     }
@@ -129,7 +153,11 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
             self.collector.fetch_state_for(block, id, metrics);
 
             self.current_block += 1;
-            if let Some(pb) = self.progress_bar.as_ref() {
+            if let Some(pb) = self
+                .progress_bar
+                .as_ref()
+                .filter(|_| block >= self.first_result_block)
+            {
                 pb.inc(1)
             };
         }
@@ -1,6 +1,7 @@
 use std::{
     collections::VecDeque,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
@@ -9,17 +10,30 @@ use brontes_core::decoding::TracingProvider;
 use brontes_pricing::BrontesBatchPricer;
 use brontes_types::{
     constants::START_OF_CHAINBOUND_MEMPOOL_DATA,
-    db::{dex::DexQuotes, metadata::Metadata},
+    db::{dex::DexQuotes, metadata::Metadata, pricing_snapshot::PricingGraphSnapshot},
     normalized_actions::Action,
     tree::BlockTree,
     BrontesTaskExecutor, FastHashMap, FastHashSet,
 };
-use futures::{Stream, StreamExt};
+use futures::{Future, Stream, StreamExt};
 use tokio::sync::mpsc::{channel, error::TrySendError, Receiver, Sender};
 use tracing::{debug, span, Instrument, Level};
 
 pub type PricingReceiver<T> = Receiver<(BrontesBatchPricer<T>, Option<(u64, DexQuotes)>)>;
 pub type PricingSender<T> = Sender<(BrontesBatchPricer<T>, Option<(u64, DexQuotes)>)>;
+pub type SaveSnapshotFn = Arc<
+    dyn Fn(u64, PricingGraphSnapshot) -> Pin<Box<dyn Future<Output = eyre::Result<()>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// saves the pricer graph state every `interval` blocks so that later runs
+/// can resume pricing from it
+#[derive(Clone)]
+pub struct PricingSnapshots {
+    pub interval: u64,
+    pub save:     SaveSnapshotFn,
+}
 
 pub struct WaitingForPricerFuture<T: TracingProvider> {
     receiver: PricingReceiver<T>,
@@ -32,13 +46,18 @@ pub struct WaitingForPricerFuture<T: TracingProvider> {
     task_executor:            BrontesTaskExecutor,
     max_tree_block:           u64,
     pricing_resolved_cache:   VecDeque<(u64, DexQuotes)>,
+    snapshots:                Option<PricingSnapshots>,
 }
 
 impl<T: TracingProvider> WaitingForPricerFuture<T> {
-    pub fn new(pricer: BrontesBatchPricer<T>, task_executor: BrontesTaskExecutor) -> Self {
+    pub fn new(
+        pricer: BrontesBatchPricer<T>,
+        task_executor: BrontesTaskExecutor,
+        snapshots: Option<PricingSnapshots>,
+    ) -> Self {
         let (tx, rx) = channel(100);
         let tx_clone = tx.clone();
-        let fut = Box::pin(Self::pricing_thread(pricer, tx_clone));
+        let fut = Box::pin(Self::pricing_thread(pricer, tx_clone, snapshots.clone()));
 
         task_executor.spawn_critical("dex pricer", fut);
         Self {
@@ -49,10 +68,15 @@ impl<T: TracingProvider> WaitingForPricerFuture<T> {
             tmp_trees: FastHashSet::default(),
             max_tree_block: 0,
             pricing_resolved_cache: VecDeque::new(),
+            snapshots,
         }
     }
 
-    async fn pricing_thread(mut pricer: BrontesBatchPricer<T>, tx: PricingSender<T>) {
+    async fn pricing_thread(
+        mut pricer: BrontesBatchPricer<T>,
+        tx: PricingSender<T>,
+        snapshots: Option<PricingSnapshots>,
+    ) {
         let block = pricer.current_block_processing();
         let mut res = pricer
             .next()
@@ -60,6 +84,12 @@ impl<T: TracingProvider> WaitingForPricerFuture<T> {
             block_number=%block))
             .await;
 
+        if let (Some((block, _)), Some(snapshots)) = (&res, snapshots) {
+            if block % snapshots.interval == 0 {
+                Self::save_snapshot(&pricer, *block, snapshots).await;
+            }
+        }
+
         // we will keep trying to send util it is resolved or the channel is dropped
         while let Err(e) = tx.try_send((pricer, res)) {
             let TrySendError::Full((f_pricer, f_res)) = e else { return };
@@ -70,6 +100,21 @@ impl<T: TracingProvider> WaitingForPricerFuture<T> {
         }
     }
 
+    async fn save_snapshot(
+        pricer: &BrontesBatchPricer<T>,
+        block: u64,
+        snapshots: PricingSnapshots,
+    ) {
+        let res = match pricer.graph_snapshot(block) {
+            Ok(snapshot) => (snapshots.save)(block, snapshot).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = res {
+            tracing::error!(%block, error = %e, "failed to save pricing graph snapshot");
+        }
+    }
+
     pub fn pending_trees(&self) -> usize {
         self.tmp_trees.len() + self.pending_trees.len()
     }
@@ -80,7 +125,7 @@ impl<T: TracingProvider> WaitingForPricerFuture<T> {
 
     fn reschedule(&mut self, pricer: BrontesBatchPricer<T>) {
         let tx = self.tx.clone();
-        let fut = Box::pin(Self::pricing_thread(pricer, tx));
+        let fut = Box::pin(Self::pricing_thread(pricer, tx, self.snapshots.clone()));
 
         self.task_executor.spawn_critical("dex pricer", fut);
     }
@@ -75,6 +75,17 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
         self.collection_future.is_some()
     }
 
+    /// true when there are no blocks waiting on metadata or pricing
+    pub fn is_idle(&self) -> bool {
+        self.metadata_fetcher.is_finished()
+    }
+
+    /// marks the collector as finished so the pricer winds down. Used when
+    /// the collector is being discarded
+    pub fn shutdown(&self) {
+        self.mark_as_finished.store(true, SeqCst);
+    }
+
     pub fn should_process_next_block(&self) -> bool {
         self.metadata_fetcher.should_process_next_block()
     }
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rskafka::{
+    client::{
+        partition::{Compression, PartitionClient, UnknownTopicHandling},
+        ClientBuilder,
+    },
+    record::Record,
+};
+
+use super::OutputSink;
+
+/// All results are produced to a single partition so consumers see the blocks
+/// in order
+const PARTITION: i32 = 0;
+
+/// Produces every message to a kafka topic, keyed by the block number. Speaks
+/// the kafka wire protocol directly, so it works with any compatible broker.
+/// The topic has to exist before brontes is started.
+pub struct KafkaSink {
+    topic:     String,
+    partition: PartitionClient,
+}
+
+impl KafkaSink {
+    pub async fn connect(brokers: Vec<String>, topic: String) -> eyre::Result<Self> {
+        let client = ClientBuilder::new(brokers).build().await?;
+        let partition = client
+            .partition_client(topic.clone(), PARTITION, UnknownTopicHandling::Error)
+            .await?;
+
+        Ok(Self { topic, partition })
+    }
+}
+
+impl OutputSink for KafkaSink {
+    fn name(&self) -> String {
+        format!("kafka:{}", self.topic)
+    }
+
+    async fn publish(&mut self, block_number: u64, message: &[u8]) -> eyre::Result<()> {
+        let record = Record {
+            key:       Some(block_number.to_be_bytes().to_vec()),
+            value:     Some(message.to_vec()),
+            headers:   BTreeMap::new(),
+            timestamp: Utc::now(),
+        };
+        self.partition
+            .produce(vec![record], Compression::NoCompression)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rskafka::client::partition::OffsetAt;
+
+    use super::*;
+
+    const TOPIC: &str = "brontes-sink-test";
+
+    /// needs a broker, run it with `BRONTES_TEST_KAFKA_BROKERS` set to a comma
+    /// separated list of brokers and `--ignored`
+    #[tokio::test]
+    #[ignore = "needs a kafka broker"]
+    async fn test_kafka_round_trip() {
+        let brokers = std::env::var("BRONTES_TEST_KAFKA_BROKERS")
+            .expect("BRONTES_TEST_KAFKA_BROKERS needs to be set");
+        let brokers = brokers.split(',').map(str::to_string).collect::<Vec<_>>();
+
+        // the topic is left around between runs
+        let client = ClientBuilder::new(brokers.clone()).build().await.unwrap();
+        let _ = client
+            .controller_client()
+            .unwrap()
+            .create_topic(TOPIC, 1, 1, 5_000)
+            .await;
+
+        let mut sink = KafkaSink::connect(brokers, TOPIC.to_string())
+            .await
+            .unwrap();
+        let offset = sink.partition.get_offset(OffsetAt::Latest).await.unwrap();
+
+        sink.publish(10, br#"{"block":10}"#).await.unwrap();
+        sink.publish(11, br#"{"block":11}"#).await.unwrap();
+
+        let (records, _) = sink
+            .partition
+            .fetch_records(offset, 1..1_000_000, 1_000)
+            .await
+            .unwrap();
+        let records = records
+            .into_iter()
+            .map(|r| (r.record.key.unwrap(), r.record.value.unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records,
+            vec![
+                (10u64.to_be_bytes().to_vec(), br#"{"block":10}"#.to_vec()),
+                (11u64.to_be_bytes().to_vec(), br#"{"block":11}"#.to_vec()),
+            ]
+        );
+    }
+}
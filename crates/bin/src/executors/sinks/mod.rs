@@ -0,0 +1,171 @@
+//! Output sinks the tip inspector pushes its results to.
+//!
+//! Every processed block is published as a single json object containing the
+//! [`MevBlock`] and its bundles. Each sink writes from its own task, so a slow
+//! or unavailable consumer never holds up block processing.
+mod kafka;
+mod ndjson;
+
+use std::{path::PathBuf, str::FromStr, sync::Arc};
+
+use brontes_types::{
+    mev::{Bundle, MevBlock},
+    BrontesTaskExecutor,
+};
+use futures::Future;
+pub use kafka::KafkaSink;
+pub use ndjson::NdJsonSink;
+use serde::Serialize;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+/// A destination the results of each block are published to
+pub trait OutputSink: Send + 'static {
+    /// Used to tell the sinks apart in the logs
+    fn name(&self) -> String;
+
+    /// Publishes the serialized results of the given block
+    fn publish(
+        &mut self,
+        block_number: u64,
+        message: &[u8],
+    ) -> impl Future<Output = eyre::Result<()>> + Send;
+}
+
+/// Sink as passed on the command line:
+///
+/// - `file:<path>`: appends newline delimited json to the file
+/// - `unix:<path>`: writes newline delimited json to the unix socket
+/// - `kafka://<broker>[,<broker>...]/<topic>`: produces to partition 0 of the
+///   topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkConfig {
+    File(PathBuf),
+    UnixSocket(PathBuf),
+    Kafka { brokers: Vec<String>, topic: String },
+}
+
+impl FromStr for SinkConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("file:") {
+            return Ok(Self::File(path.into()))
+        }
+        if let Some(path) = s.strip_prefix("unix:") {
+            return Ok(Self::UnixSocket(path.into()))
+        }
+        if let Some(rest) = s.strip_prefix("kafka://") {
+            let (brokers, topic) = rest
+                .split_once('/')
+                .ok_or_else(|| format!("kafka sink {s} is missing a topic"))?;
+            if brokers.is_empty() || topic.is_empty() {
+                return Err(format!("kafka sink {s} needs at least one broker and a topic"))
+            }
+
+            return Ok(Self::Kafka {
+                brokers: brokers.split(',').map(str::to_string).collect(),
+                topic:   topic.to_string(),
+            })
+        }
+
+        Err(format!(
+            "unknown sink {s}, expected one of file:<path>, unix:<path> or \
+             kafka://<brokers>/<topic>"
+        ))
+    }
+}
+
+#[derive(Serialize)]
+struct SinkMessage<'a> {
+    block:   &'a MevBlock,
+    bundles: &'a [Bundle],
+}
+
+#[derive(Debug)]
+struct SinkPayload {
+    block_number: u64,
+    message:      Vec<u8>,
+}
+
+/// Handle to the running sinks. Cheap to clone, an empty set of sinks turns
+/// publishing into a no-op.
+#[derive(Debug, Clone, Default)]
+pub struct OutputSinks {
+    senders: Vec<UnboundedSender<Arc<SinkPayload>>>,
+}
+
+impl OutputSinks {
+    /// Connects to all of the configured sinks, failing if any of them can't
+    /// be reached.
+    pub async fn spawn(
+        configs: &[SinkConfig],
+        executor: &BrontesTaskExecutor,
+    ) -> eyre::Result<Self> {
+        let mut sinks = Self::default();
+        for config in configs {
+            match config {
+                SinkConfig::File(path) => {
+                    sinks.add_sink(NdJsonSink::file(path.clone()).await?, executor)
+                }
+                SinkConfig::UnixSocket(path) => {
+                    sinks.add_sink(NdJsonSink::unix_socket(path.clone()).await?, executor)
+                }
+                SinkConfig::Kafka { brokers, topic } => sinks
+                    .add_sink(KafkaSink::connect(brokers.clone(), topic.clone()).await?, executor),
+            }
+        }
+
+        Ok(sinks)
+    }
+
+    /// Spawns a task that publishes everything sent to the sink. The task
+    /// exits once all handles are dropped.
+    pub fn add_sink<S: OutputSink>(&mut self, mut sink: S, executor: &BrontesTaskExecutor) {
+        let (tx, mut rx) = unbounded_channel::<Arc<SinkPayload>>();
+        tracing::info!(target: "brontes::sinks", sink = %sink.name(), "publishing results");
+
+        executor.spawn(async move {
+            while let Some(payload) = rx.recv().await {
+                if let Err(e) = sink.publish(payload.block_number, &payload.message).await {
+                    tracing::error!(
+                        target: "brontes::sinks",
+                        sink = %sink.name(),
+                        block = payload.block_number,
+                        "failed to publish results -- {:?}",
+                        e
+                    );
+                }
+            }
+        });
+
+        self.senders.push(tx);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+    }
+
+    pub fn publish(&self, block: &MevBlock, bundles: &[Bundle]) {
+        if self.is_empty() {
+            return
+        }
+
+        let message = match serde_json::to_vec(&SinkMessage { block, bundles }) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::error!(
+                    target: "brontes::sinks",
+                    block = block.block_number,
+                    "failed to serialize results -- {:?}",
+                    e
+                );
+                return
+            }
+        };
+
+        let payload = Arc::new(SinkPayload { block_number: block.block_number, message });
+        for sender in &self.senders {
+            let _ = sender.send(payload.clone());
+        }
+    }
+}
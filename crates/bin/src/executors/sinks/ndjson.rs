@@ -0,0 +1,147 @@
+use std::{path::PathBuf, pin::Pin};
+
+use tokio::{
+    fs::OpenOptions,
+    io::{AsyncWrite, AsyncWriteExt},
+    net::UnixStream,
+};
+
+use super::OutputSink;
+
+type Writer = Pin<Box<dyn AsyncWrite + Send>>;
+
+#[derive(Debug, Clone)]
+enum Target {
+    File(PathBuf),
+    UnixSocket(PathBuf),
+}
+
+impl Target {
+    async fn connect(&self) -> eyre::Result<Writer> {
+        Ok(match self {
+            Self::File(path) => Box::pin(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await?,
+            ),
+            Self::UnixSocket(path) => Box::pin(UnixStream::connect(path).await?),
+        })
+    }
+}
+
+/// Writes every message as a single line of json to a file or unix socket.
+/// If a write fails, the sink reconnects and retries the message once, so a
+/// restarted listener picks the feed back up.
+pub struct NdJsonSink {
+    target: Target,
+    writer: Option<Writer>,
+}
+
+impl NdJsonSink {
+    pub async fn file(path: PathBuf) -> eyre::Result<Self> {
+        Self::new(Target::File(path)).await
+    }
+
+    pub async fn unix_socket(path: PathBuf) -> eyre::Result<Self> {
+        Self::new(Target::UnixSocket(path)).await
+    }
+
+    async fn new(target: Target) -> eyre::Result<Self> {
+        let writer = target.connect().await?;
+        Ok(Self { target, writer: Some(writer) })
+    }
+
+    async fn write_line(writer: &mut Writer, message: &[u8]) -> eyre::Result<()> {
+        writer.write_all(message).await?;
+        writer.write_all(b"\n").await?;
+        writer.flush().await?;
+
+        Ok(())
+    }
+}
+
+impl OutputSink for NdJsonSink {
+    fn name(&self) -> String {
+        match &self.target {
+            Target::File(path) => format!("file:{}", path.display()),
+            Target::UnixSocket(path) => format!("unix:{}", path.display()),
+        }
+    }
+
+    async fn publish(&mut self, _: u64, message: &[u8]) -> eyre::Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            match Self::write_line(&mut writer, message).await {
+                Ok(()) => {
+                    self.writer = Some(writer);
+                    return Ok(())
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        target: "brontes::sinks",
+                        sink = %self.name(),
+                        "write failed, reconnecting -- {:?}",
+                        e
+                    );
+                }
+            }
+        }
+
+        let mut writer = self.target.connect().await?;
+        Self::write_line(&mut writer, message).await?;
+        self.writer = Some(writer);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncBufReadExt, BufReader},
+        net::UnixListener,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.ndjson");
+
+        let mut sink = NdJsonSink::file(path.clone()).await.unwrap();
+        sink.publish(1, br#"{"block":1}"#).await.unwrap();
+        sink.publish(2, br#"{"block":2}"#).await.unwrap();
+
+        // a new sink appends to the existing feed
+        let mut sink = NdJsonSink::file(path.clone()).await.unwrap();
+        sink.publish(3, br#"{"block":3}"#).await.unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, "{\"block\":1}\n{\"block\":2}\n{\"block\":3}\n");
+    }
+
+    #[tokio::test]
+    async fn test_unix_socket_reconnects() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let mut sink = NdJsonSink::unix_socket(path).await.unwrap();
+        let (conn, _) = listener.accept().await.unwrap();
+        let mut lines = BufReader::new(conn).lines();
+
+        sink.publish(1, br#"{"block":1}"#).await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), r#"{"block":1}"#);
+
+        // the consumer goes away, the next message goes out over a new
+        // connection
+        drop(lines);
+        sink.publish(2, br#"{"block":2}"#).await.unwrap();
+
+        let (conn, _) = listener.accept().await.unwrap();
+        let mut lines = BufReader::new(conn).lines();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), r#"{"block":2}"#);
+    }
+}
@@ -1,10 +1,12 @@
 use std::{
+    collections::VecDeque,
     marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
     time::Duration,
 };
 
+use alloy_primitives::B256;
 use brontes_core::decoding::{Parser, TracingProvider};
 use brontes_database::{
     clickhouse::ClickhouseHandle,
@@ -12,14 +14,27 @@ use brontes_database::{
 };
 use brontes_inspect::Inspector;
 use brontes_types::MultiBlockData;
-use futures::{pin_mut, stream::FuturesUnordered, Future, StreamExt};
+use futures::{pin_mut, stream::FuturesUnordered, Future, FutureExt, StreamExt};
 use reth_tasks::shutdown::GracefulShutdown;
 use tokio::time::{interval, Interval};
-use tracing::debug;
+use tracing::{debug, warn};
 
-use super::shared::state_collector::StateCollector;
+use super::{shared::state_collector::StateCollector, sinks::OutputSinks};
 use crate::Processor;
 
+/// The max amount of processed blocks we keep the hashes of. This is the
+/// deepest reorg we are able to detect and roll back.
+const MAX_REORG_DEPTH: usize = 64;
+
+/// Builds a fresh [`StateCollector`] starting at the given block. Used to
+/// reset the classifier and pricing state after a reorg.
+pub type StateCollectorFactory<T, DB, CH> =
+    Box<dyn Fn(u64) -> StateCollector<T, DB, CH> + Send + Sync + 'static>;
+
+/// Resolves to the first orphaned block if the processed blocks are no longer
+/// on the canonical chain.
+type ReorgCheck = Pin<Box<dyn Future<Output = eyre::Result<Option<u64>>> + Send + 'static>>;
+
 pub struct TipInspector<
     T: TracingProvider,
     DB: LibmdbxReader + DBWriter,
@@ -30,10 +45,30 @@ pub struct TipInspector<
     back_from_tip:      u64,
     parser:             &'static Parser<T, DB>,
     state_collector:    StateCollector<T, DB, CH>,
+    new_collector:      StateCollectorFactory<T, DB, CH>,
     database:           &'static DB,
     inspectors:         &'static [&'static dyn Inspector<Result = P::InspectType>],
     processing_futures: FuturesUnordered<Pin<Box<dyn Future<Output = ()> + Send + 'static>>>,
+    /// block number and hash of the blocks we have finished processing
+    processed_blocks:   VecDeque<(u64, B256)>,
+    /// the first orphaned block of a detected reorg. Once all in flight work
+    /// is done, every block from here up to the current block is rolled back
+    pending_reorg:      Option<u64>,
+    rollback_future:    Option<Pin<Box<dyn Future<Output = ()> + Send + 'static>>>,
+    reorg_check:        Option<ReorgCheck>,
+    /// the last parent block we checked is still canonical before building on
+    /// top of it
+    checked_parent:     Option<u64>,
+    /// set when a parent hash mismatch is seen while a reorg check is running
+    recheck_fork:       bool,
+    /// set when a reorg check couldn't reach the node, the fork is searched for
+    /// again on the next tick
+    retry_fork_search:  bool,
+    #[cfg(not(feature = "local-reth"))]
+    tip_request:        Option<Pin<Box<dyn Future<Output = eyre::Result<u64>> + Send + 'static>>>,
     poll_interval:      Interval,
+    /// sinks the results of every processed block are published to
+    sinks:              OutputSinks,
     _p:                 PhantomData<P>,
 }
 
@@ -44,19 +79,32 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
         current_block: u64,
         back_from_tip: u64,
         state_collector: StateCollector<T, DB, CH>,
+        new_collector: StateCollectorFactory<T, DB, CH>,
         parser: &'static Parser<T, DB>,
         database: &'static DB,
         inspectors: &'static [&'static dyn Inspector<Result = P::InspectType>],
+        sinks: OutputSinks,
     ) -> Self {
         Self {
             back_from_tip,
             state_collector,
+            new_collector,
             inspectors,
             current_block,
             parser,
             processing_futures: FuturesUnordered::new(),
             database,
+            processed_blocks: VecDeque::with_capacity(MAX_REORG_DEPTH),
+            pending_reorg: None,
+            rollback_future: None,
+            reorg_check: None,
+            checked_parent: None,
+            recheck_fork: false,
+            retry_fork_search: false,
+            #[cfg(not(feature = "local-reth"))]
+            tip_request: None,
             poll_interval: interval(Duration::from_secs(3)),
+            sinks,
             _p: PhantomData,
         }
     }
@@ -80,7 +128,7 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
     }
 
     #[cfg(feature = "local-reth")]
-    fn start_block_inspector(&mut self) -> bool {
+    fn start_block_inspector(&mut self, _: &mut Context<'_>) -> bool {
         if self.state_collector.is_collecting_state() {
             return false
         }
@@ -95,15 +143,18 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
     }
 
     #[cfg(not(feature = "local-reth"))]
-    fn start_block_inspector(&mut self) -> bool {
+    fn start_block_inspector(&mut self, cx: &mut Context<'_>) -> bool {
         if self.state_collector.is_collecting_state() {
             return false
         }
 
-        let cur_block = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current()
-                .block_on(async { self.parser.get_latest_block_number().await })
-        });
+        let parser = self.parser;
+        let request = self
+            .tip_request
+            .get_or_insert_with(|| Box::pin(parser.get_latest_block_number()));
+
+        let Poll::Ready(cur_block) = request.poll_unpin(cx) else { return false };
+        self.tip_request = None;
 
         match cur_block {
             Ok(chain_tip) => chain_tip - self.back_from_tip > self.current_block,
@@ -116,12 +167,133 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
 
     fn on_price_finish(&mut self, data: MultiBlockData) {
         debug!(target:"brontes::tip_inspector","Completed DEX pricing");
+        let header = &data.get_most_recent_block().tree.header;
+        let (block, parent_hash) = (header.number, header.parent_hash);
+
+        // if the parent of this block isn't the block we processed, we are
+        // on a new fork and need to find where it branched off
+        if self
+            .processed_hash(block - 1)
+            .is_some_and(|hash| hash != parent_hash)
+            && self.pending_reorg.is_none()
+        {
+            warn!(
+                target:"brontes::tip_inspector",
+                %block,
+                "parent hash mismatch, checking for reorg"
+            );
+            if self.reorg_check.is_some() {
+                self.recheck_fork = true;
+            } else {
+                self.spawn_fork_search();
+            }
+        }
+        self.track_processed_block(block, header.hash_slow());
+
         self.processing_futures.push(Box::pin(P::process_results(
             self.database,
             self.inspectors,
             data,
+            self.sinks.clone(),
         )));
     }
+
+    fn processed_hash(&self, block: u64) -> Option<B256> {
+        self.processed_blocks
+            .iter()
+            .rev()
+            .find(|(number, _)| *number == block)
+            .map(|(_, hash)| *hash)
+    }
+
+    fn track_processed_block(&mut self, block: u64, hash: B256) {
+        if self.processed_blocks.len() == MAX_REORG_DEPTH {
+            self.processed_blocks.pop_front();
+        }
+        self.processed_blocks.push_back((block, hash));
+    }
+
+    /// searches the processed blocks for the block the current fork started at
+    fn spawn_fork_search(&mut self) {
+        let parser = self.parser;
+        let processed = self.processed_blocks.iter().copied().collect();
+
+        self.reorg_check = Some(Box::pin(find_fork_block(processed, move |block| {
+            parser.get_block_hash_for_number(block)
+        })));
+    }
+
+    /// checks if the parent of the next block we are going to process is still
+    /// the block we processed. Returns false if there is nothing to check.
+    fn spawn_parent_check(&mut self) -> bool {
+        let parent = self.current_block - 1;
+        if self.checked_parent == Some(parent) {
+            return false
+        }
+        let Some(parent_hash) = self.processed_hash(parent) else { return false };
+
+        let parser = self.parser;
+        let processed = self.processed_blocks.iter().copied().collect();
+
+        self.checked_parent = Some(parent);
+        self.reorg_check =
+            Some(Box::pin(check_canonical(parent, parent_hash, processed, move |block| {
+                parser.get_block_hash_for_number(block)
+            })));
+
+        true
+    }
+
+    fn on_reorg_check(&mut self, fork_block: eyre::Result<Option<u64>>) {
+        let fork_block = match fork_block {
+            Ok(fork_block) => fork_block,
+            Err(e) => {
+                // a search that stopped part way could roll back too few blocks, so
+                // nothing is rolled back until a search goes through
+                tracing::error!(error=%e, "reorg check failed, retrying on the next tick");
+                self.recheck_fork = false;
+                self.retry_fork_search = true;
+                return
+            }
+        };
+
+        if self.pending_reorg.is_none() {
+            self.pending_reorg = fork_block;
+        }
+
+        if std::mem::take(&mut self.recheck_fork) && self.pending_reorg.is_none() {
+            self.spawn_fork_search();
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        !self.state_collector.is_collecting_state()
+            && self.state_collector.is_idle()
+            && self.processing_futures.is_empty()
+    }
+
+    /// Removes all data for the orphaned blocks, resets the classifier and
+    /// pricing state to the fork block and restarts processing from there.
+    fn rollback_to(&mut self, fork_block: u64) {
+        let orphaned = fork_block..self.current_block;
+        tracing::info!(target:"brontes::tip_inspector", ?orphaned, "rolling back reorged blocks");
+
+        self.processed_blocks
+            .retain(|(block, _)| *block < fork_block);
+        self.state_collector.shutdown();
+        self.state_collector = (self.new_collector)(fork_block);
+        self.current_block = fork_block;
+        self.checked_parent = None;
+
+        let database = self.database;
+        self.rollback_future = Some(Box::pin(async move {
+            for block in orphaned {
+                if let Err(e) = database.rollback_block(block).await {
+                    tracing::error!(error=%e, %block, "failed to roll back reorged block");
+                }
+            }
+        }));
+    }
 }
 
 impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P: Processor> Future
@@ -132,13 +304,52 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         // given we pull the next block sync, we use this to trigger looking
         // for the next block.
-        while self.poll_interval.poll_tick(cx).is_ready() {}
+        let mut ticked = false;
+        while self.poll_interval.poll_tick(cx).is_ready() {
+            ticked = true;
+        }
+
+        if let Some(mut rollback) = self.rollback_future.take() {
+            if rollback.poll_unpin(cx).is_pending() {
+                self.rollback_future = Some(rollback);
+                return Poll::Pending
+            }
+        }
+
+        if let Some(mut check) = self.reorg_check.take() {
+            match check.poll_unpin(cx) {
+                Poll::Ready(fork_block) => self.on_reorg_check(fork_block),
+                Poll::Pending => self.reorg_check = Some(check),
+            }
+        }
+
+        if ticked && self.reorg_check.is_none() && std::mem::take(&mut self.retry_fork_search) {
+            self.spawn_fork_search();
+            cx.waker().wake_by_ref();
+        }
 
-        if self.start_block_inspector() && self.state_collector.should_process_next_block() {
-            let block = self.current_block;
-            tracing::info!(%block,"starting new tip block");
-            self.state_collector.fetch_state_for(block, 0, None);
-            self.current_block += 1;
+        if let Some(fork_block) = self.pending_reorg {
+            // wait for all in flight blocks to finish so nothing gets written after
+            // the rollback
+            if self.is_idle() {
+                self.pending_reorg = None;
+                self.rollback_to(fork_block);
+                cx.waker().wake_by_ref();
+                return Poll::Pending
+            }
+        } else if self.reorg_check.is_none()
+            && !self.retry_fork_search
+            && self.start_block_inspector(cx)
+            && self.state_collector.should_process_next_block()
+        {
+            if self.spawn_parent_check() {
+                cx.waker().wake_by_ref();
+            } else {
+                let block = self.current_block;
+                tracing::info!(%block,"starting new tip block");
+                self.state_collector.fetch_state_for(block, 0, None);
+                self.current_block += 1;
+            }
         }
 
         if let Poll::Ready(item) = self.state_collector.poll_next_unpin(cx) {
@@ -153,3 +364,176 @@ impl<T: TracingProvider, DB: DBWriter + LibmdbxReader, CH: ClickhouseHandle, P:
         Poll::Pending
     }
 }
+
+/// Checks if the processed `block` is still canonical, if it isn't, searches
+/// for the block the fork started at.
+async fn check_canonical<F, Fut>(
+    block: u64,
+    hash: B256,
+    processed: Vec<(u64, B256)>,
+    canonical_hash: F,
+) -> Option<u64>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = eyre::Result<Option<B256>>>,
+{
+    match canonical_hash(block).await? {
+        Some(canonical) if canonical == hash => Ok(None),
+        _ => {
+            warn!(
+                target:"brontes::tip_inspector",
+                %block,
+                "processed block is no longer canonical"
+            );
+            find_fork_block(processed, canonical_hash).await
+        }
+    }
+}
+
+/// Walks back through the processed blocks comparing them against the
+/// canonical chain. Returns the oldest processed block that is no longer
+/// canonical, if any. Errors if the canonical hash of one of the blocks can't
+/// be fetched, as the fork could be further back.
+async fn find_fork_block<F, Fut>(
+    processed: Vec<(u64, B256)>,
+    canonical_hash: F,
+) -> eyre::Result<Option<u64>>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = eyre::Result<Option<B256>>>,
+{
+    let mut fork_block = None;
+    for (block, hash) in processed.iter().rev() {
+        match canonical_hash(*block).await? {
+            Some(canonical) if canonical == *hash => break,
+            _ => fork_block = Some(*block),
+        }
+    }
+
+    if fork_block.is_some_and(|block| {
+        processed
+            .first()
+            .is_some_and(|(oldest, _)| *oldest == block)
+    }) {
+        tracing::error!(
+            max_depth = MAX_REORG_DEPTH,
+            "reorg is deeper than the tracked blocks, rolling back all tracked blocks"
+        );
+    }
+
+    Ok(fork_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn hash(block: u64, fork: u8) -> B256 {
+        let mut hash = B256::left_padding_from(&block.to_be_bytes());
+        hash.0[0] = fork;
+        hash
+    }
+
+    fn processed(blocks: std::ops::Range<u64>) -> Vec<(u64, B256)> {
+        blocks.map(|block| (block, hash(block, 0))).collect()
+    }
+
+    /// canonical chain that forked off the processed blocks at `fork_block`
+    fn canonical_chain(
+        blocks: std::ops::Range<u64>,
+        fork_block: Option<u64>,
+    ) -> HashMap<u64, B256> {
+        blocks
+            .map(|block| {
+                let fork = fork_block.is_some_and(|fork_block| block >= fork_block);
+                (block, hash(block, fork as u8))
+            })
+            .collect()
+    }
+
+    fn lookup(
+        chain: HashMap<u64, B256>,
+    ) -> impl Fn(u64) -> futures::future::Ready<eyre::Result<Option<B256>>> {
+        move |block| futures::future::ready(Ok(chain.get(&block).copied()))
+    }
+
+    #[tokio::test]
+    async fn test_no_reorg() {
+        let chain = canonical_chain(100..110, None);
+        let processed = processed(100..110);
+
+        assert_eq!(
+            find_fork_block(processed.clone(), lookup(chain.clone()))
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            check_canonical(109, hash(109, 0), processed, lookup(chain))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finds_oldest_orphaned_block() {
+        let chain = canonical_chain(100..110, Some(107));
+        let processed = processed(100..110);
+
+        assert_eq!(
+            find_fork_block(processed.clone(), lookup(chain.clone()))
+                .await
+                .unwrap(),
+            Some(107)
+        );
+        assert_eq!(
+            check_canonical(109, hash(109, 0), processed, lookup(chain))
+                .await
+                .unwrap(),
+            Some(107)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reorg_deeper_than_tracked_blocks() {
+        let chain = canonical_chain(90..110, Some(95));
+        let processed = processed(100..110);
+
+        assert_eq!(find_fork_block(processed, lookup(chain)).await.unwrap(), Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_missing_canonical_block_is_orphaned() {
+        // the node hasn't seen the new fork up to our height yet
+        let chain = canonical_chain(100..108, Some(106));
+        let processed = processed(100..110);
+
+        assert_eq!(find_fork_block(processed, lookup(chain)).await.unwrap(), Some(106));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_fails_search() {
+        let chain = canonical_chain(100..110, Some(105));
+        let processed = processed(100..110);
+
+        let canonical_hash = move |block: u64| {
+            let res = if block == 107 {
+                Err(eyre::eyre!("rpc error"))
+            } else {
+                Ok(chain.get(&block).copied())
+            };
+            futures::future::ready(res)
+        };
+
+        // stopping at 107 would leave 105 to 107 in the db
+        assert!(find_fork_block(processed.clone(), &canonical_hash)
+            .await
+            .is_err());
+        assert!(check_canonical(107, hash(107, 0), processed, &canonical_hash)
+            .await
+            .is_err());
+    }
+}
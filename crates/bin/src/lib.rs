@@ -23,6 +23,7 @@
 //!
 //! Please refer to the individual crate documentation for more details.
 
+pub mod api;
 pub mod cli;
 pub mod executors;
 pub mod misc;
@@ -30,3 +31,4 @@ pub use executors::*;
 pub use misc::banner;
 
 pub mod runner;
+pub use runner::{run, run_with_registry};
@@ -1,4 +1,5 @@
 use std::{
+    env,
     future::Future,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     time::Duration,
@@ -6,11 +7,65 @@ use std::{
 
 use brontes_metrics::prometheus_exporter::initialize;
 use brontes_types::{BrontesTaskExecutor, BrontesTaskManager};
+use clap::Parser;
 use futures::pin_mut;
 use metrics_process::Collector;
 use tracing::{error, info, trace};
+use tracing_subscriber::{filter::Directive, Layer};
 
-use crate::PROMETHEUS_ENDPOINT_IP;
+use crate::{
+    cli::{Args, Commands},
+    PROMETHEUS_ENDPOINT_IP,
+};
+
+/// Parses the cli arguments and runs the selected command
+pub fn run() -> eyre::Result<()> {
+    run_with_registry(|| Ok(()))
+}
+
+/// Runs the cli like [`run`], calling `register` before the arguments are
+/// parsed. Downstream binaries add their inspectors there through
+/// [`register_inspector`](brontes_inspect::registry::register_inspector) so that
+/// they can be selected with `--inspectors`.
+pub fn run_with_registry(register: impl FnOnce() -> eyre::Result<()>) -> eyre::Result<()> {
+    register()?;
+
+    let opt = Args::parse();
+    let brontes_db_path = opt
+        .brontes_db_path
+        .unwrap_or(env::var("BRONTES_DB_PATH").expect("No BRONTES_DB_PATH in .env"));
+
+    init_tracing(opt.verbosity.directive());
+
+    let metrics_port = if opt.skip_prometheus { None } else { Some(opt.metrics_port) };
+
+    match opt.command {
+        Commands::Run(command) => {
+            run_command_until_exit(metrics_port, Duration::from_secs(3600), |ctx| {
+                command.execute(brontes_db_path, ctx)
+            })
+        }
+        Commands::Database(command) => {
+            run_command_until_exit(None, Duration::from_secs(5), |ctx| {
+                command.execute(brontes_db_path, ctx)
+            })
+        }
+        Commands::Serve(command) => {
+            run_command_until_exit(None, Duration::from_secs(5), |ctx| {
+                command.execute(brontes_db_path, ctx)
+            })
+        }
+    }
+}
+
+fn init_tracing(verbosity: Directive) {
+    let layers = vec![
+        brontes_tracing::stdout(verbosity),
+        brontes_metrics::error_layer::BrontesErrorMetrics::default().boxed(),
+    ];
+
+    brontes_tracing::init(layers);
+}
 
 pub fn run_command_until_exit<F, E>(
     metrics_port: Option<u16>,
@@ -0,0 +1,61 @@
+use alloy_primitives::Address;
+use brontes_macros::discovery_impl;
+use brontes_pricing::Protocol;
+
+// Euler V2 vaults are all deployed by the EVK factory. The trailing data of the
+// proxy is `abi.encodePacked(asset, oracle, unitOfAccount)`, so the vault's
+// underlying asset is the first 20 bytes
+discovery_impl!(
+    EulerV2Discovery,
+    crate::EulerV2GenericFactory::createProxyCall,
+    0x29a56a1b8214D9Cf7c5561811750D5cBDb45CC8e,
+    |deployed_address: Address, trace_index: u64, call_data: createProxyCall, _| async move {
+        let Some(asset) = call_data.trailingData.get(..20).map(Address::from_slice) else {
+            return vec![]
+        };
+
+        vec![NormalizedNewPool {
+            trace_index,
+            protocol: Protocol::EulerV2,
+            pool_address: deployed_address,
+            tokens: vec![asset],
+        }]
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes};
+    use alloy_sol_types::SolCall;
+    use brontes_types::{constants::USDC_ADDRESS, Protocol};
+
+    use super::EulerV2Discovery;
+    use crate::{test_utils::ClassifierTestUtils, EulerV2GenericFactory, FactoryDiscovery};
+
+    #[brontes_macros::test]
+    async fn test_euler_v2_discovery() {
+        let utils = ClassifierTestUtils::new().await;
+        let vault = Address::new(hex!("797DD80692c3b2dAdabCe8e30C07fDE5307D48a9"));
+        let oracle = Address::new(hex!("83B3b76873D36A28440cF53371dF404c42497136"));
+        let unit_of_account = Address::new(hex!("0000000000000000000000000000000000000348"));
+
+        let trailing_data =
+            [USDC_ADDRESS.as_slice(), oracle.as_slice(), unit_of_account.as_slice()].concat();
+        let call_data = EulerV2GenericFactory::createProxyCall {
+            desiredImplementation: Address::ZERO,
+            upgradeable:           true,
+            trailingData:          trailing_data.into(),
+        }
+        .abi_encode();
+
+        let mut pools = EulerV2Discovery
+            .decode_create_trace(utils.get_tracing_provider(), vault, 1, Bytes::from(call_data))
+            .await;
+
+        assert_eq!(pools.len(), 1);
+        let pool = pools.remove(0);
+        assert_eq!(pool.protocol, Protocol::EulerV2);
+        assert_eq!(pool.pool_address, vault);
+        assert_eq!(pool.tokens, vec![USDC_ADDRESS]);
+    }
+}
@@ -0,0 +1,235 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    normalized_actions::NormalizedLiquidation, structured_trace::CallInfo, utils::ToScaledRational,
+    Protocol,
+};
+
+// Vaults are proxies that delegate into the liquidation module, so the event is
+// emitted by the delegate calls. The liquidator is the account the call was
+// made on behalf of through the EVC, which is only known from the event.
+//
+// The debt asset is the underlying of the vault that is set on discovery. The
+// collateral is seized as shares of the collateral vault, which have the
+// decimals of its underlying. They are converted to the underlying with the
+// vault's exchange rate in the liquidation's multi frame classification.
+action_impl!(
+    Protocol::EulerV2,
+    crate::EulerV2EVault::liquidateCall,
+    Liquidation,
+    [..Liquidate],
+    logs: true,
+    include_delegated_logs: true,
+    |
+    info: CallInfo,
+    log_data: EulerV2LiquidateCallLogs,
+    db_tx: &DB | {
+        let logs = log_data.liquidate_field?;
+        let details = db_tx.get_protocol_details(info.target_address)?;
+
+        let debt_asset = db_tx.try_fetch_token_info(details.token0)?;
+        let collateral_asset = db_tx
+            .try_fetch_token_info(db_tx.get_protocol_details(logs.collateral)?.token0)?;
+
+        let covered_debt = logs.repayAssets.to_scaled_rational(debt_asset.decimals);
+        let liquidated_collateral =
+            logs.yieldBalance.to_scaled_rational(collateral_asset.decimals);
+
+        return Ok(NormalizedLiquidation {
+            protocol: Protocol::EulerV2,
+            trace_index: info.trace_idx,
+            pool: info.target_address,
+            liquidator: logs.liquidator,
+            debtor: logs.violator,
+            collateral_asset,
+            debt_asset,
+            covered_debt,
+            liquidated_collateral,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, Log, B256, U256};
+    use alloy_sol_types::{SolCall, SolEvent};
+    use brontes_database::libmdbx::LibmdbxReader;
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{Action, NormalizedLiquidation},
+        structured_trace::TxTrace,
+        Protocol, TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use crate::{
+        test_utils::{ClassifierTestUtils, TxTraceBuilder},
+        EulerV2EVault, EulerV2GenericFactory,
+    };
+
+    const USDC_VAULT: Address = Address::new(hex!("797DD80692c3b2dAdabCe8e30C07fDE5307D48a9"));
+    const WETH_VAULT: Address = Address::new(hex!("D8b27CF359b7D15710a5BE299AF6e7Bf904984C2"));
+    const EVC: Address = Address::new(hex!("0C9a3dd6b8F28529d72d7f9cE918D493519EE383"));
+    const FACTORY: Address = Address::new(hex!("29a56a1b8214D9Cf7c5561811750D5cBDb45CC8e"));
+    const VAULT_IMPL: Address = Address::repeat_byte(0x11);
+    const LIQUIDATION_MODULE: Address = Address::repeat_byte(0x22);
+    const ORACLE: Address = Address::repeat_byte(0x44);
+    const LIQUIDATOR: Address = Address::new(hex!("80d4230c0a68fc59cb264329d3a717fcaa472a13"));
+    const VIOLATOR: Address = Address::new(hex!("e967954b9b48cb1a0079d76466e82c4d52a8f5d3"));
+
+    fn setup(classifier_utils: &ClassifierTestUtils) {
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+        classifier_utils.ensure_protocol(
+            Protocol::EulerV2,
+            WETH_VAULT,
+            WETH_ADDRESS,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+    }
+
+    /// the liquidator goes through the EVC, which calls the vault on its
+    /// behalf. The vault proxy delegate calls into its implementation, which
+    /// delegate calls into the liquidation module that emits the event. The
+    /// module values the violator's 2 collateral shares through the oracle,
+    /// which converts them to 2.1 WETH
+    fn liquidation_trace(vault: Address, block: u64) -> TxTrace {
+        let call_data: Bytes = EulerV2EVault::liquidateCall {
+            violator:        VIOLATOR,
+            collateral:      WETH_VAULT,
+            repayAssets:     U256::MAX,
+            minYieldBalance: U256::ZERO,
+        }
+        .abi_encode()
+        .into();
+
+        let log = Log {
+            address: vault,
+            data:    EulerV2EVault::Liquidate {
+                liquidator:   LIQUIDATOR,
+                violator:     VIOLATOR,
+                collateral:   WETH_VAULT,
+                repayAssets:  U256::from(2_500_000_000u64),
+                yieldBalance: U256::from(1_000_000_000_000_000_000u64),
+            }
+            .encode_log_data(),
+        };
+
+        let convert_to_assets =
+            EulerV2EVault::convertToAssetsCall { shares: U256::from(2_000_000_000_000_000_000u64) }
+                .abi_encode();
+
+        TxTraceBuilder::new(B256::with_last_byte(2))
+            .call(&[], LIQUIDATOR, EVC, Bytes::new(), vec![])
+            .call(&[0], EVC, vault, call_data.clone(), vec![])
+            .delegate_call(&[0, 0], vault, VAULT_IMPL, call_data.clone(), vec![])
+            .delegate_call(&[0, 0, 0], vault, LIQUIDATION_MODULE, call_data, vec![log])
+            .call(&[0, 0, 0, 0], vault, ORACLE, Bytes::new(), vec![])
+            .call(&[0, 0, 0, 0, 0], ORACLE, WETH_VAULT, convert_to_assets, vec![])
+            .with_output(EulerV2EVault::convertToAssetsCall::abi_encode_returns(&(U256::from(
+                2_100_000_000_000_000_000u64,
+            ),)))
+            .build(block, 0)
+    }
+
+    fn liquidation(classifier_utils: &ClassifierTestUtils, vault: Address) -> Action {
+        Action::Liquidation(NormalizedLiquidation {
+            protocol:              Protocol::EulerV2,
+            trace_index:           1,
+            pool:                  vault,
+            liquidator:            LIQUIDATOR,
+            debtor:                VIOLATOR,
+            collateral_asset:      classifier_utils.get_token_info(WETH_ADDRESS),
+            debt_asset:            classifier_utils.get_token_info(USDC_ADDRESS),
+            covered_debt:          Rational::from_signeds(2500, 1),
+            liquidated_collateral: Rational::from_signeds(21, 20),
+            msg_value:             U256::ZERO,
+        })
+    }
+
+    #[brontes_macros::test]
+    async fn test_euler_v2_liquidation() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+        classifier_utils.ensure_protocol(
+            Protocol::EulerV2,
+            USDC_VAULT,
+            USDC_ADDRESS,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        classifier_utils
+            .contains_action_in_trace(
+                liquidation_trace(USDC_VAULT, 20_000_000),
+                Header { number: 20_000_000, ..Default::default() },
+                0,
+                liquidation(&classifier_utils, USDC_VAULT),
+                TreeSearchBuilder::default().with_action(Action::is_liquidation),
+            )
+            .await;
+    }
+
+    /// a vault deployed by the factory is discovered from the create trace, and
+    /// its liquidations are classified without the vault being added by hand
+    #[brontes_macros::test]
+    async fn test_euler_v2_discovered_vault_liquidation() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+
+        let vault = Address::repeat_byte(0x33);
+        let oracle = Address::new(hex!("83B3b76873D36A28440cF53371dF404c42497136"));
+        let unit_of_account = Address::new(hex!("0000000000000000000000000000000000000348"));
+        let deployer = Address::new(hex!("D911560979B78821D7b045C79E36E9CbfC2F6C6F"));
+
+        let create_proxy = EulerV2GenericFactory::createProxyCall {
+            desiredImplementation: Address::ZERO,
+            upgradeable:           true,
+            trailingData:          [
+                USDC_ADDRESS.as_slice(),
+                oracle.as_slice(),
+                unit_of_account.as_slice(),
+            ]
+            .concat()
+            .into(),
+        }
+        .abi_encode();
+
+        let deployment = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], deployer, FACTORY, create_proxy, vec![])
+            .create(&[0], FACTORY, vault)
+            .build(20_000_000, 0);
+        classifier_utils
+            .build_tree_from_traces(
+                vec![deployment],
+                Header { number: 20_000_000, ..Default::default() },
+            )
+            .await;
+
+        let details = classifier_utils
+            .libmdbx
+            .get_protocol_details(vault)
+            .unwrap();
+        assert_eq!(details.protocol, Protocol::EulerV2);
+        assert_eq!(details.token0, USDC_ADDRESS);
+
+        classifier_utils
+            .contains_action_in_trace(
+                liquidation_trace(vault, 20_000_001),
+                Header { number: 20_000_001, ..Default::default() },
+                0,
+                liquidation(&classifier_utils, vault),
+                TreeSearchBuilder::default().with_action(Action::is_liquidation),
+            )
+            .await;
+    }
+}
@@ -0,0 +1,5 @@
+mod discovery;
+mod euler_v2;
+
+pub use discovery::*;
+pub use euler_v2::*;
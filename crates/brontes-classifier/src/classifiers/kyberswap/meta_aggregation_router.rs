@@ -0,0 +1,194 @@
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{normalized_actions::NormalizedAggregator, structured_trace::CallInfo};
+
+action_impl!(
+    Protocol::KyberSwap,
+    crate::KyberSwapMetaAggregationRouterV2::swapCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapCall,
+    _db_tx: &DB | {
+        let recipient = call_data.execution.desc.dstReceiver;
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::KyberSwap,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::KyberSwap,
+    crate::KyberSwapMetaAggregationRouterV2::swapGenericCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapGenericCall,
+    _db_tx: &DB | {
+        let recipient = call_data.execution.desc.dstReceiver;
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::KyberSwap,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::KyberSwap,
+    crate::KyberSwapMetaAggregationRouterV2::swapSimpleModeCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapSimpleModeCall,
+    _db_tx: &DB | {
+        let recipient = call_data.desc.dstReceiver;
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::KyberSwap,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, B256, U256};
+    use alloy_sol_types::SolCall;
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{Action, NormalizedAggregator, NormalizedSwap},
+        Protocol, TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use crate::{
+        test_utils::{uniswap_v2_swap, ClassifierTestUtils, TxTraceBuilder},
+        KyberSwapMetaAggregationRouterV2,
+    };
+
+    const META_AGGREGATION_ROUTER: Address =
+        Address::new(hex!("6131B5fae19EA4f9D964eAc0408E4408b66337b5"));
+    const EXECUTOR: Address = Address::new(hex!("f081470f5C6FBCCF48cC4e5B82Dd926409DcdD67"));
+    const USDC_WETH_V2: Address = Address::new(hex!("B4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+    const SENDER: Address = Address::new(hex!("f4F8845ceDe63e79De1B2c3bbA395e8547FE4283"));
+    const RECEIVER: Address = Address::new(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d"));
+
+    /// the router hands the route to its executor, which swaps 0.5 weth for
+    /// 1000 usdc on the usdc / weth uniswap v2 pool and sends it to the
+    /// receiver
+    #[brontes_macros::test]
+    async fn test_kyberswap_swap() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        classifier_utils.ensure_protocol(
+            Protocol::KyberSwap,
+            META_AGGREGATION_ROUTER,
+            Address::ZERO,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_protocol(
+            Protocol::UniswapV2,
+            USDC_WETH_V2,
+            USDC_ADDRESS,
+            Some(WETH_ADDRESS),
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+
+        let amount_in = U256::from(500_000_000_000_000_000u64);
+        let amount_out = U256::from(1_000_000_000u64);
+        let target_data = Bytes::from_static(&hex!("deadbeef"));
+
+        let call_data = KyberSwapMetaAggregationRouterV2::swapCall {
+            execution: KyberSwapMetaAggregationRouterV2::SwapExecutionParams {
+                callTarget:    EXECUTOR,
+                approveTarget: Address::ZERO,
+                targetData:    target_data.clone(),
+                desc:          KyberSwapMetaAggregationRouterV2::SwapDescriptionV2 {
+                    srcToken:        WETH_ADDRESS,
+                    dstToken:        USDC_ADDRESS,
+                    srcReceivers:    vec![],
+                    srcAmounts:      vec![],
+                    feeReceivers:    vec![],
+                    feeAmounts:      vec![],
+                    dstReceiver:     RECEIVER,
+                    amount:          amount_in,
+                    minReturnAmount: amount_out,
+                    flags:           U256::ZERO,
+                    permit:          Bytes::new(),
+                },
+                clientData:    Bytes::new(),
+            },
+        }
+        .abi_encode();
+        let (swap, swap_logs) =
+            uniswap_v2_swap(USDC_WETH_V2, EXECUTOR, RECEIVER, false, amount_in, amount_out);
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], SENDER, META_AGGREGATION_ROUTER, call_data, vec![])
+            .call(&[0], META_AGGREGATION_ROUTER, EXECUTOR, target_data, vec![])
+            .call(&[0, 0], EXECUTOR, USDC_WETH_V2, swap, swap_logs)
+            .build(19_000_000, 0);
+
+        let eq_action = Action::Aggregator(NormalizedAggregator {
+            protocol:      Protocol::KyberSwap,
+            trace_index:   0,
+            from:          SENDER,
+            to:            META_AGGREGATION_ROUTER,
+            recipient:     RECEIVER,
+            child_actions: vec![Action::Swap(NormalizedSwap {
+                protocol:    Protocol::UniswapV2,
+                trace_index: 2,
+                from:        EXECUTOR,
+                recipient:   RECEIVER,
+                pool:        USDC_WETH_V2,
+                token_in:    classifier_utils.get_token_info(WETH_ADDRESS),
+                token_out:   classifier_utils.get_token_info(USDC_ADDRESS),
+                amount_in:   Rational::from_signeds(1, 2),
+                amount_out:  Rational::from_signeds(1000, 1),
+                msg_value:   U256::ZERO,
+            })],
+            msg_value:     U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_aggregator),
+            )
+            .await;
+    }
+}
@@ -0,0 +1,3 @@
+mod meta_aggregation_router;
+
+pub use meta_aggregation_router::*;
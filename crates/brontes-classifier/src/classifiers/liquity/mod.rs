@@ -0,0 +1,3 @@
+mod trove_manager;
+
+pub use trove_manager::*;
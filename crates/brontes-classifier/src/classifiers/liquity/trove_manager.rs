@@ -0,0 +1,280 @@
+use alloy_primitives::Address;
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_macros::action_impl;
+use brontes_types::{
+    constants::LUSD_ADDRESS, db::token_info::TokenInfoWithAddress,
+    normalized_actions::NormalizedLiquidation, structured_trace::CallInfo, utils::ToScaledRational,
+    Protocol,
+};
+
+use crate::LiquityTroveManager::{Liquidation, TroveLiquidated};
+
+action_impl!(
+    Protocol::Liquity,
+    crate::LiquityTroveManager::liquidateCall,
+    Liquidation,
+    [..TroveLiquidated*, ..Liquidation],
+    logs: true,
+    |
+    info: CallInfo,
+    log_data: LiquityLiquidateCallLogs,
+    db_tx: &DB | {
+        liquity_liquidation(
+            info,
+            log_data.trove_liquidated_field?,
+            log_data.liquidation_field?,
+            db_tx,
+        )
+    }
+);
+
+action_impl!(
+    Protocol::Liquity,
+    crate::LiquityTroveManager::liquidateTrovesCall,
+    Liquidation,
+    [..TroveLiquidated*, ..Liquidation],
+    logs: true,
+    |
+    info: CallInfo,
+    log_data: LiquityLiquidateTrovesCallLogs,
+    db_tx: &DB | {
+        liquity_liquidation(
+            info,
+            log_data.trove_liquidated_field?,
+            log_data.liquidation_field?,
+            db_tx,
+        )
+    }
+);
+
+action_impl!(
+    Protocol::Liquity,
+    crate::LiquityTroveManager::batchLiquidateTrovesCall,
+    Liquidation,
+    [..TroveLiquidated*, ..Liquidation],
+    logs: true,
+    |
+    info: CallInfo,
+    log_data: LiquityBatchLiquidateTrovesCallLogs,
+    db_tx: &DB | {
+        liquity_liquidation(
+            info,
+            log_data.trove_liquidated_field?,
+            log_data.liquidation_field?,
+            db_tx,
+        )
+    }
+);
+
+/// Liquity debt is absorbed by the stability pool or redistributed to the
+/// other troves, the liquidator doesn't repay anything and only receives the
+/// gas compensation. The covered debt is the debt taken off the troves and the
+/// liquidated collateral is the eth compensation paid to the liquidator.
+///
+/// All troves liquidated in the call are merged into a single liquidation, the
+/// debtor being the first trove that was liquidated.
+fn liquity_liquidation<DB: LibmdbxReader + DBWriter>(
+    info: CallInfo,
+    troves: Vec<TroveLiquidated>,
+    liquidation: Liquidation,
+    db_tx: &DB,
+) -> eyre::Result<NormalizedLiquidation> {
+    let debtor = troves
+        .first()
+        .map(|trove| trove._borrower)
+        .unwrap_or(Address::ZERO);
+
+    let debt_asset = db_tx.try_fetch_token_info(LUSD_ADDRESS)?;
+    let collateral_asset = TokenInfoWithAddress::native_eth();
+
+    let covered_debt = liquidation
+        ._liquidatedDebt
+        .to_scaled_rational(debt_asset.decimals);
+    let liquidated_collateral = liquidation
+        ._collGasCompensation
+        .to_scaled_rational(collateral_asset.decimals);
+
+    Ok(NormalizedLiquidation {
+        protocol: Protocol::Liquity,
+        trace_index: info.trace_idx,
+        pool: info.target_address,
+        liquidator: info.msg_sender,
+        debtor,
+        collateral_asset,
+        debt_asset,
+        covered_debt,
+        liquidated_collateral,
+        msg_value: info.msg_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, Log, B256, U256};
+    use alloy_sol_types::{SolCall, SolEvent};
+    use brontes_types::{
+        constants::LUSD_ADDRESS,
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::{Action, NormalizedLiquidation},
+        structured_trace::TxTrace,
+        Protocol, TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use crate::{
+        test_utils::{ClassifierTestUtils, TxTraceBuilder},
+        LiquityTroveManager,
+    };
+
+    const TROVE_MANAGER: Address = Address::new(hex!("A39739EF8b0231DbFA0DcdA07d7e29faAbCf4bb2"));
+    const ACTIVE_POOL: Address = Address::new(hex!("DF9Eb223bAFBE5c5271415C75aeCD68C21fE3D7F"));
+    const LIQUIDATOR: Address = Address::new(hex!("D911560979B78821D7b045C79E36E9CbfC2F6C6F"));
+
+    fn setup(classifier_utils: &ClassifierTestUtils) {
+        classifier_utils.ensure_protocol(
+            Protocol::Liquity,
+            TROVE_MANAGER,
+            LUSD_ADDRESS,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress {
+            address: LUSD_ADDRESS,
+            inner:   TokenInfo { decimals: 18, symbol: "LUSD".to_string() },
+        });
+    }
+
+    fn trove_liquidated(borrower: Address, debt: u128, coll: u128) -> Log {
+        Log {
+            address: TROVE_MANAGER,
+            data:    LiquityTroveManager::TroveLiquidated {
+                _borrower:  borrower,
+                _debt:      U256::from(debt),
+                _coll:      U256::from(coll),
+                _operation: 0,
+            }
+            .encode_log_data(),
+        }
+    }
+
+    fn liquidation(debt: u128, coll: u128, coll_gas_comp: u128) -> Log {
+        Log {
+            address: TROVE_MANAGER,
+            data:    LiquityTroveManager::Liquidation {
+                _liquidatedDebt:      U256::from(debt),
+                _liquidatedColl:      U256::from(coll),
+                _collGasCompensation: U256::from(coll_gas_comp),
+                _LUSDGasCompensation: U256::from(200_000_000_000_000_000_000u128),
+            }
+            .encode_log_data(),
+        }
+    }
+
+    /// the trove manager emits the events itself and has the active pool send
+    /// the gas compensation to the liquidator
+    fn trace(call_data: Vec<u8>, logs: Vec<Log>) -> TxTrace {
+        TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], LIQUIDATOR, TROVE_MANAGER, call_data, logs)
+            .call(&[0], TROVE_MANAGER, ACTIVE_POOL, Bytes::new(), vec![])
+            .build(19_000_000, 0)
+    }
+
+    async fn assert_liquidation(
+        classifier_utils: &ClassifierTestUtils,
+        trace: TxTrace,
+        debtor: Address,
+    ) {
+        let eq_action = Action::Liquidation(NormalizedLiquidation {
+            protocol: Protocol::Liquity,
+            trace_index: 0,
+            pool: TROVE_MANAGER,
+            liquidator: LIQUIDATOR,
+            debtor,
+            collateral_asset: TokenInfoWithAddress::native_eth(),
+            debt_asset: classifier_utils.get_token_info(LUSD_ADDRESS),
+            covered_debt: Rational::from_signeds(4000, 1),
+            liquidated_collateral: Rational::from_signeds(1, 100),
+            msg_value: U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_liquidation),
+            )
+            .await;
+    }
+
+    #[brontes_macros::test]
+    async fn test_liquity_liquidation() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+
+        let borrower = Address::new(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d"));
+
+        let call_data = LiquityTroveManager::liquidateCall { _borrower: borrower }.abi_encode();
+        let logs = vec![
+            trove_liquidated(borrower, 4_000_000_000_000_000_000_000, 2_000_000_000_000_000_000),
+            liquidation(
+                4_000_000_000_000_000_000_000,
+                1_990_000_000_000_000_000,
+                10_000_000_000_000_000,
+            ),
+        ];
+
+        assert_liquidation(&classifier_utils, trace(call_data, logs), borrower).await;
+    }
+
+    #[brontes_macros::test]
+    async fn test_liquity_liquidate_troves() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+
+        let first = Address::new(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d"));
+        let second = Address::new(hex!("e967954b9b48cb1a0079d76466e82c4d52a8f5d3"));
+
+        let call_data = LiquityTroveManager::liquidateTrovesCall { _n: U256::from(2) }.abi_encode();
+        let logs = vec![
+            trove_liquidated(first, 3_000_000_000_000_000_000_000, 1_500_000_000_000_000_000),
+            trove_liquidated(second, 1_000_000_000_000_000_000_000, 500_000_000_000_000_000),
+            liquidation(
+                4_000_000_000_000_000_000_000,
+                1_990_000_000_000_000_000,
+                10_000_000_000_000_000,
+            ),
+        ];
+
+        assert_liquidation(&classifier_utils, trace(call_data, logs), first).await;
+    }
+
+    #[brontes_macros::test]
+    async fn test_liquity_batch_liquidation() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+
+        let first = Address::new(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d"));
+        let second = Address::new(hex!("e967954b9b48cb1a0079d76466e82c4d52a8f5d3"));
+
+        let call_data =
+            LiquityTroveManager::batchLiquidateTrovesCall { _troveArray: vec![first, second] }
+                .abi_encode();
+        let logs = vec![
+            trove_liquidated(first, 3_000_000_000_000_000_000_000, 1_500_000_000_000_000_000),
+            trove_liquidated(second, 1_000_000_000_000_000_000_000, 500_000_000_000_000_000),
+            liquidation(
+                4_000_000_000_000_000_000_000,
+                1_990_000_000_000_000_000,
+                10_000_000_000_000_000,
+            ),
+        ];
+
+        assert_liquidation(&classifier_utils, trace(call_data, logs), first).await;
+    }
+}
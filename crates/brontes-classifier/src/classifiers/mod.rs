@@ -41,6 +41,24 @@ pub use clipper::*;
 pub mod dodo;
 pub use dodo::*;
 
+pub mod morpho;
+pub use morpho::*;
+
+pub mod spark;
+pub use spark::*;
+
+pub mod liquity;
+pub use liquity::*;
+
+pub mod euler;
+pub use euler::*;
+
+pub mod paraswap;
+pub use paraswap::*;
+
+pub mod kyberswap;
+pub use kyberswap::*;
+
 discovery_dispatch!(
     DiscoveryClassifier,
     SushiSwapV2Discovery,
@@ -63,7 +81,8 @@ discovery_dispatch!(
     CurveCryptoSwapDiscovery,
     CurveTriCryptoDiscovery,
     BalancerV1CoreDiscovery,
-    BalancerV1SmartPoolDiscovery
+    BalancerV1SmartPoolDiscovery,
+    EulerV2Discovery
 );
 
 action_dispatch!(
@@ -81,6 +100,11 @@ action_dispatch!(
     UniswapV3MintCall,
     UniswapV3BurnCall,
     UniswapV3CollectCall,
+    UniswapV3FlashCall,
+    UniswapV4InitializeCall,
+    UniswapV4SwapCall,
+    UniswapV4ModifyLiquidityCall,
+    UniswapV4DonateCall,
     SushiSwapV3SwapCall,
     SushiSwapV3MintCall,
     SushiSwapV3BurnCall,
@@ -162,6 +186,12 @@ action_dispatch!(
     CompoundV2LiquidateBorrowCall,
     CompoundV2Initialize_0Call,
     CompoundV2Initialize_1Call,
+    MorphoBlueLiquidateCall,
+    SparkLiquidationCallCall,
+    LiquityLiquidateCall,
+    LiquityLiquidateTrovesCall,
+    LiquityBatchLiquidateTrovesCall,
+    EulerV2LiquidateCall,
     OneInchV5SwapCall,
     OneInchV5ClipperSwapCall,
     OneInchV5ClipperSwapToCall,
@@ -170,6 +200,21 @@ action_dispatch!(
     OneInchV5UnoswapToWithPermitCall,
     OneInchV5UniswapV3SwapToCall,
     OneInchV5UniswapV3SwapToWithPermitCall,
+    OneInchV6SwapCall,
+    OneInchV6UnoswapCall,
+    OneInchV6Unoswap2Call,
+    OneInchV6Unoswap3Call,
+    OneInchV6EthUnoswapCall,
+    OneInchV6EthUnoswap2Call,
+    OneInchV6EthUnoswap3Call,
+    OneInchV6UnoswapToCall,
+    OneInchV6UnoswapTo2Call,
+    OneInchV6UnoswapTo3Call,
+    OneInchV6EthUnoswapToCall,
+    OneInchV6EthUnoswapTo2Call,
+    OneInchV6EthUnoswapTo3Call,
+    OneInchV6ClipperSwapCall,
+    OneInchV6ClipperSwapToCall,
     OneInchFusionSettleOrdersCall,
     ClipperExchangeSwapCall,
     ClipperExchangeSellEthForTokenCall,
@@ -201,6 +246,26 @@ action_dispatch!(
     ZeroXFillRfqOrderCall,
     ZeroXFillOrKillLimitOrderCall,
     ZeroXFillOrKillRfqOrderCall,
+    ParaswapV5SimpleSwapCall,
+    ParaswapV5SimpleBuyCall,
+    ParaswapV5MultiSwapCall,
+    ParaswapV5MegaSwapCall,
+    ParaswapV5SwapOnUniswapCall,
+    ParaswapV5SwapOnUniswapForkCall,
+    ParaswapV5SwapOnUniswapV2ForkCall,
+    ParaswapV6SwapExactAmountInCall,
+    ParaswapV6SwapExactAmountOutCall,
+    ParaswapV6SwapExactAmountInOnUniswapV2Call,
+    ParaswapV6SwapExactAmountOutOnUniswapV2Call,
+    ParaswapV6SwapExactAmountInOnUniswapV3Call,
+    ParaswapV6SwapExactAmountOutOnUniswapV3Call,
+    ParaswapV6SwapExactAmountInOnBalancerV2Call,
+    ParaswapV6SwapExactAmountOutOnBalancerV2Call,
+    ParaswapV6SwapExactAmountInOnCurveV1Call,
+    ParaswapV6SwapExactAmountInOnCurveV2Call,
+    KyberSwapSwapCall,
+    KyberSwapSwapGenericCall,
+    KyberSwapSwapSimpleModeCall,
     DodoCreateDODOVendingMachineCall,
     DodoCreateDODOStablePoolCall,
     DodoInitDODOPrivatePoolCall,
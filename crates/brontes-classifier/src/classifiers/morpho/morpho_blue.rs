@@ -0,0 +1,216 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    normalized_actions::NormalizedLiquidation, structured_trace::CallInfo, utils::ToScaledRational,
+    Protocol,
+};
+
+// Morpho Blue is a singleton, all markets live in the same contract and are
+// only identified by the hash of their market params
+action_impl!(
+    Protocol::MorphoBlue,
+    crate::MorphoBlue::liquidateCall,
+    Liquidation,
+    [Liquidate],
+    call_data: true,
+    logs: true,
+    |
+    info: CallInfo,
+    call_data: liquidateCall,
+    log_data: MorphoBlueLiquidateCallLogs,
+    db_tx: &DB | {
+        let logs = log_data.liquidate_field?;
+        let params = call_data.marketParams;
+
+        let debt_asset = db_tx.try_fetch_token_info(params.loanToken)?;
+        let collateral_asset = db_tx.try_fetch_token_info(params.collateralToken)?;
+
+        let covered_debt = logs.repaidAssets.to_scaled_rational(debt_asset.decimals);
+        let liquidated_collateral =
+            logs.seizedAssets.to_scaled_rational(collateral_asset.decimals);
+
+        return Ok(NormalizedLiquidation {
+            protocol: Protocol::MorphoBlue,
+            trace_index: info.trace_idx,
+            pool: info.target_address,
+            liquidator: logs.caller,
+            debtor: logs.borrower,
+            collateral_asset,
+            debt_asset,
+            covered_debt,
+            liquidated_collateral,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, Log, B256, U256};
+    use alloy_sol_types::{SolCall, SolEvent};
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::{Action, NormalizedLiquidation},
+        structured_trace::TxTrace,
+        Protocol, TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use crate::{
+        test_utils::{ClassifierTestUtils, TxTraceBuilder},
+        MorphoBlue,
+    };
+
+    const MORPHO_BLUE: Address = Address::new(hex!("BBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"));
+    const WSTETH: Address = Address::new(hex!("7f39C581F595B53c5cb19bD0b3f8dA6c935E2Ca0"));
+    const LIQUIDATOR: Address = Address::new(hex!("D911560979B78821D7b045C79E36E9CbfC2F6C6F"));
+    const BOT: Address = Address::new(hex!("80d4230c0a68fc59cb264329d3a717fcaa472a13"));
+    const BORROWER: Address = Address::new(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d"));
+
+    fn setup(classifier_utils: &ClassifierTestUtils) {
+        classifier_utils.ensure_protocol(
+            Protocol::MorphoBlue,
+            MORPHO_BLUE,
+            Address::ZERO,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress {
+            address: WSTETH,
+            inner:   TokenInfo { decimals: 18, symbol: "wstETH".to_string() },
+        });
+    }
+
+    /// the liquidate call into morpho and the event it emits for a market
+    fn liquidate(
+        loan_token: Address,
+        collateral_token: Address,
+        repaid_assets: U256,
+        seized_assets: U256,
+    ) -> (Bytes, Vec<Log>) {
+        let call_data = MorphoBlue::liquidateCall {
+            marketParams: MorphoBlue::MarketParams {
+                loanToken:       loan_token,
+                collateralToken: collateral_token,
+                oracle:          hex!("2a01EB9496094dA03c4E364Def50f5aD1280AD72").into(),
+                irm:             hex!("870aC11D48B15DB9a138Cf899d20F13F79Ba00BC").into(),
+                lltv:            U256::from(945_000_000_000_000_000u64),
+            },
+            borrower:     BORROWER,
+            seizedAssets: seized_assets,
+            repaidShares: U256::ZERO,
+            data:         Bytes::new(),
+        }
+        .abi_encode();
+
+        let logs = vec![Log {
+            address: MORPHO_BLUE,
+            data:    MorphoBlue::Liquidate {
+                id:            B256::ZERO,
+                caller:        BOT,
+                borrower:      BORROWER,
+                repaidAssets:  repaid_assets,
+                repaidShares:  repaid_assets * U256::from(1_000_000),
+                seizedAssets:  seized_assets,
+                badDebtAssets: U256::ZERO,
+                badDebtShares: U256::ZERO,
+            }
+            .encode_log_data(),
+        }];
+
+        (call_data.into(), logs)
+    }
+
+    /// the liquidator goes through its bot contract, which liquidates a
+    /// position in a WETH / wstETH market and a USDC / WETH market
+    fn liquidation_trace() -> TxTrace {
+        let (weth_market, weth_market_logs) = liquidate(
+            WETH_ADDRESS,
+            WSTETH,
+            U256::from(2_250_000_000_000_000_000u64),
+            U256::from(2_000_000_000_000_000_000u64),
+        );
+        let (usdc_market, usdc_market_logs) = liquidate(
+            USDC_ADDRESS,
+            WETH_ADDRESS,
+            U256::from(3_000_000_000u64),
+            U256::from(1_000_000_000_000_000_000u64),
+        );
+
+        TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], LIQUIDATOR, BOT, Bytes::new(), vec![])
+            .call(&[0], BOT, MORPHO_BLUE, weth_market, weth_market_logs)
+            .call(&[1], BOT, MORPHO_BLUE, usdc_market, usdc_market_logs)
+            .build(19_000_000, 0)
+    }
+
+    fn header() -> Header {
+        Header { number: 19_000_000, ..Default::default() }
+    }
+
+    #[brontes_macros::test]
+    async fn test_morpho_blue_liquidation() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+
+        let eq_action = Action::Liquidation(NormalizedLiquidation {
+            protocol:              Protocol::MorphoBlue,
+            trace_index:           1,
+            pool:                  MORPHO_BLUE,
+            liquidator:            BOT,
+            debtor:                BORROWER,
+            collateral_asset:      classifier_utils.get_token_info(WSTETH),
+            debt_asset:            classifier_utils.get_token_info(WETH_ADDRESS),
+            covered_debt:          Rational::from_signeds(9, 4),
+            liquidated_collateral: Rational::from_signeds(2, 1),
+            msg_value:             U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                liquidation_trace(),
+                header(),
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_liquidation),
+            )
+            .await;
+    }
+
+    /// markets are only identified by their params, so the tokens of each
+    /// liquidation come from the call and not from the registered contract
+    #[brontes_macros::test]
+    async fn test_morpho_blue_liquidation_market_from_params() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+
+        let eq_action = Action::Liquidation(NormalizedLiquidation {
+            protocol:              Protocol::MorphoBlue,
+            trace_index:           2,
+            pool:                  MORPHO_BLUE,
+            liquidator:            BOT,
+            debtor:                BORROWER,
+            collateral_asset:      classifier_utils.get_token_info(WETH_ADDRESS),
+            debt_asset:            classifier_utils.get_token_info(USDC_ADDRESS),
+            covered_debt:          Rational::from_signeds(3000, 1),
+            liquidated_collateral: Rational::from_signeds(1, 1),
+            msg_value:             U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                liquidation_trace(),
+                header(),
+                1,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_liquidation),
+            )
+            .await;
+    }
+}
@@ -0,0 +1,424 @@
+use alloy_primitives::{Address, B256, U256};
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{normalized_actions::NormalizedAggregator, structured_trace::CallInfo};
+
+/// V6 passes addresses as `uint256` with flags packed into the upper bits
+fn unpack_address(packed: U256) -> Address {
+    Address::from_word(B256::new(packed.to_be_bytes()))
+}
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::swapCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapCall,
+    _db_tx: &DB | {
+        let recipient = call_data.desc.dstReceiver;
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::OneInchV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::unoswapCall,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::OneInchV6,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::unoswap2Call,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::OneInchV6,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::unoswap3Call,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::OneInchV6,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::ethUnoswapCall,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::OneInchV6,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::ethUnoswap2Call,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::OneInchV6,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::ethUnoswap3Call,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::OneInchV6,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::unoswapToCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: unoswapToCall,
+    _db_tx: &DB | {
+        let recipient = unpack_address(call_data.to);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::OneInchV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::unoswapTo2Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: unoswapTo2Call,
+    _db_tx: &DB | {
+        let recipient = unpack_address(call_data.to);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::OneInchV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::unoswapTo3Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: unoswapTo3Call,
+    _db_tx: &DB | {
+        let recipient = unpack_address(call_data.to);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::OneInchV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::ethUnoswapToCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: ethUnoswapToCall,
+    _db_tx: &DB | {
+        let recipient = unpack_address(call_data.to);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::OneInchV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::ethUnoswapTo2Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: ethUnoswapTo2Call,
+    _db_tx: &DB | {
+        let recipient = unpack_address(call_data.to);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::OneInchV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::ethUnoswapTo3Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: ethUnoswapTo3Call,
+    _db_tx: &DB | {
+        let recipient = unpack_address(call_data.to);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::OneInchV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::clipperSwapCall,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::OneInchV6,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV6,
+    crate::OneInchAggregationRouterV6::clipperSwapToCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: clipperSwapToCall,
+    _db_tx: &DB | {
+        let recipient = call_data.recipient;
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::OneInchV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, B256, U256};
+    use alloy_sol_types::SolCall;
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{Action, NormalizedAggregator, NormalizedSwap},
+        Protocol, TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use crate::{
+        test_utils::{uniswap_v2_swap, ClassifierTestUtils, TxTraceBuilder},
+        OneInchAggregationRouterV6,
+    };
+
+    const ROUTER_V6: Address = Address::new(hex!("111111125421cA6dc452d289314280a0f8842A65"));
+    const USDC_WETH_V2: Address = Address::new(hex!("B4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+    const SENDER: Address = Address::new(hex!("f4F8845ceDe63e79De1B2c3bbA395e8547FE4283"));
+    const RECIPIENT: Address = Address::new(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d"));
+
+    /// the router swaps 1000 usdc for 0.5 weth on the usdc / weth uniswap v2
+    /// pool, which sends the weth straight to the recipient
+    #[brontes_macros::test]
+    async fn test_one_inch_v6_unoswap_to() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        classifier_utils.ensure_protocol(
+            Protocol::OneInchV6,
+            ROUTER_V6,
+            Address::ZERO,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_protocol(
+            Protocol::UniswapV2,
+            USDC_WETH_V2,
+            USDC_ADDRESS,
+            Some(WETH_ADDRESS),
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+
+        let amount_in = U256::from(1_000_000_000u64);
+        let amount_out = U256::from(500_000_000_000_000_000u64);
+        // set a flag bit above the address to make sure it is masked off
+        let packed_to = U256::from_be_slice(RECIPIENT.as_slice()) | (U256::from(1) << 250);
+
+        let call_data = OneInchAggregationRouterV6::unoswapToCall {
+            to:        packed_to,
+            token:     U256::from_be_slice(USDC_ADDRESS.as_slice()),
+            amount:    amount_in,
+            minReturn: amount_out,
+            dex:       U256::from_be_slice(USDC_WETH_V2.as_slice()),
+        }
+        .abi_encode();
+        let (swap, swap_logs) =
+            uniswap_v2_swap(USDC_WETH_V2, ROUTER_V6, RECIPIENT, true, amount_in, amount_out);
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], SENDER, ROUTER_V6, call_data, vec![])
+            .call(&[0], ROUTER_V6, USDC_WETH_V2, swap, swap_logs)
+            .build(19_000_000, 0);
+
+        let eq_action = Action::Aggregator(NormalizedAggregator {
+            protocol:      Protocol::OneInchV6,
+            trace_index:   0,
+            from:          SENDER,
+            to:            ROUTER_V6,
+            recipient:     RECIPIENT,
+            child_actions: vec![Action::Swap(NormalizedSwap {
+                protocol:    Protocol::UniswapV2,
+                trace_index: 1,
+                from:        ROUTER_V6,
+                recipient:   RECIPIENT,
+                pool:        USDC_WETH_V2,
+                token_in:    classifier_utils.get_token_info(USDC_ADDRESS),
+                token_out:   classifier_utils.get_token_info(WETH_ADDRESS),
+                amount_in:   Rational::from_signeds(1000, 1),
+                amount_out:  Rational::from_signeds(1, 2),
+                msg_value:   U256::ZERO,
+            })],
+            msg_value:     U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_aggregator),
+            )
+            .await;
+    }
+}
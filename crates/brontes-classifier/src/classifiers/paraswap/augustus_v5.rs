@@ -0,0 +1,266 @@
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{normalized_actions::NormalizedAggregator, structured_trace::CallInfo};
+
+use super::beneficiary_or_sender;
+
+action_impl!(
+    Protocol::ParaswapV5,
+    crate::ParaswapAugustusV5::simpleSwapCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: simpleSwapCall,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.data.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV5,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV5,
+    crate::ParaswapAugustusV5::simpleBuyCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: simpleBuyCall,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.data.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV5,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV5,
+    crate::ParaswapAugustusV5::multiSwapCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: multiSwapCall,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.data.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV5,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV5,
+    crate::ParaswapAugustusV5::megaSwapCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: megaSwapCall,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.data.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV5,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV5,
+    crate::ParaswapAugustusV5::swapOnUniswapCall,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::ParaswapV5,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV5,
+    crate::ParaswapAugustusV5::swapOnUniswapForkCall,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::ParaswapV5,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV5,
+    crate::ParaswapAugustusV5::swapOnUniswapV2ForkCall,
+    Aggregator,
+    [],
+    |info: CallInfo, _db_tx: &DB| {
+        return Ok(NormalizedAggregator {
+            protocol:      Protocol::ParaswapV5,
+            trace_index:   info.trace_idx,
+            from:          info.from_address,
+            to:            info.target_address,
+            recipient:     info.msg_sender,
+            child_actions: vec![],
+            msg_value:     info.msg_value,
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, FixedBytes, B256, U256};
+    use alloy_sol_types::SolCall;
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{Action, NormalizedAggregator, NormalizedSwap},
+        Protocol, TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use crate::{
+        test_utils::{uniswap_v2_swap, ClassifierTestUtils, TxTraceBuilder},
+        ParaswapAugustusV5,
+    };
+
+    const AUGUSTUS_V5: Address = Address::new(hex!("DEF171Fe48CF0115B1d80b88dc8eAB59176FEe57"));
+    const USDC_WETH_V2: Address = Address::new(hex!("B4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+    const SENDER: Address = Address::new(hex!("f4F8845ceDe63e79De1B2c3bbA395e8547FE4283"));
+
+    /// the sender swaps 1000 usdc for 0.5 weth through augustus, which routes
+    /// the swap through the usdc / weth uniswap v2 pool
+    #[brontes_macros::test]
+    async fn test_paraswap_v5_simple_swap() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        classifier_utils.ensure_protocol(
+            Protocol::ParaswapV5,
+            AUGUSTUS_V5,
+            Address::ZERO,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_protocol(
+            Protocol::UniswapV2,
+            USDC_WETH_V2,
+            USDC_ADDRESS,
+            Some(WETH_ADDRESS),
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+
+        let amount_in = U256::from(1_000_000_000u64);
+        let amount_out = U256::from(500_000_000_000_000_000u64);
+
+        let call_data = ParaswapAugustusV5::simpleSwapCall {
+            data: ParaswapAugustusV5::SimpleData {
+                fromToken:      USDC_ADDRESS,
+                toToken:        WETH_ADDRESS,
+                fromAmount:     amount_in,
+                toAmount:       amount_out,
+                expectedAmount: amount_out,
+                callees:        vec![USDC_WETH_V2],
+                exchangeData:   Bytes::new(),
+                startIndexes:   vec![],
+                values:         vec![],
+                beneficiary:    Address::ZERO,
+                partner:        Address::ZERO,
+                feePercent:     U256::ZERO,
+                permit:         Bytes::new(),
+                deadline:       U256::MAX,
+                uuid:           FixedBytes::ZERO,
+            },
+        }
+        .abi_encode();
+        let (swap, swap_logs) =
+            uniswap_v2_swap(USDC_WETH_V2, AUGUSTUS_V5, AUGUSTUS_V5, true, amount_in, amount_out);
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], SENDER, AUGUSTUS_V5, call_data, vec![])
+            .call(&[0], AUGUSTUS_V5, USDC_WETH_V2, swap, swap_logs)
+            .build(19_000_000, 0);
+
+        // without a beneficiary the output goes to the sender
+        let eq_action = Action::Aggregator(NormalizedAggregator {
+            protocol:      Protocol::ParaswapV5,
+            trace_index:   0,
+            from:          SENDER,
+            to:            AUGUSTUS_V5,
+            recipient:     SENDER,
+            child_actions: vec![Action::Swap(NormalizedSwap {
+                protocol:    Protocol::UniswapV2,
+                trace_index: 1,
+                from:        AUGUSTUS_V5,
+                recipient:   AUGUSTUS_V5,
+                pool:        USDC_WETH_V2,
+                token_in:    classifier_utils.get_token_info(USDC_ADDRESS),
+                token_out:   classifier_utils.get_token_info(WETH_ADDRESS),
+                amount_in:   Rational::from_signeds(1000, 1),
+                amount_out:  Rational::from_signeds(1, 2),
+                msg_value:   U256::ZERO,
+            })],
+            msg_value:     U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_aggregator),
+            )
+            .await;
+    }
+}
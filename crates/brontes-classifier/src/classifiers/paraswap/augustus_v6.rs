@@ -0,0 +1,413 @@
+use alloy_primitives::{Address, B256, U256};
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{normalized_actions::NormalizedAggregator, structured_trace::CallInfo};
+
+use super::beneficiary_or_sender;
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountInCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountInCall,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.swapData.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountOutCall,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountOutCall,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.swapData.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountInOnUniswapV2Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountInOnUniswapV2Call,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.uniData.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountOutOnUniswapV2Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountOutOnUniswapV2Call,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.uniData.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountInOnUniswapV3Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountInOnUniswapV3Call,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.uniData.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountOutOnUniswapV3Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountOutOnUniswapV3Call,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.uniData.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountInOnBalancerV2Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountInOnBalancerV2Call,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(
+            balancer_beneficiary(call_data.balancerData.beneficiaryAndApproveFlag),
+            info.msg_sender,
+        );
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountOutOnBalancerV2Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountOutOnBalancerV2Call,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(
+            balancer_beneficiary(call_data.balancerData.beneficiaryAndApproveFlag),
+            info.msg_sender,
+        );
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountInOnCurveV1Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountInOnCurveV1Call,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.curveV1Data.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ParaswapV6,
+    crate::ParaswapAugustusV6::swapExactAmountInOnCurveV2Call,
+    Aggregator,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: swapExactAmountInOnCurveV2Call,
+    _db_tx: &DB | {
+        let recipient = beneficiary_or_sender(call_data.curveV2Data.beneficiary, info.msg_sender);
+        return Ok(NormalizedAggregator {
+            protocol: Protocol::ParaswapV6,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            to: info.target_address,
+            recipient,
+            child_actions: vec![],
+            msg_value: info.msg_value
+        })
+    }
+);
+
+/// The balancer entrypoints pack the approve flag into the upper bits of the
+/// beneficiary
+fn balancer_beneficiary(packed: U256) -> Address {
+    Address::from_word(B256::new(packed.to_be_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, B256, U256};
+    use alloy_sol_types::SolCall;
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{Action, NormalizedAggregator, NormalizedSwap},
+        structured_trace::TxTrace,
+        Protocol, TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use crate::{
+        test_utils::{uniswap_v2_swap, ClassifierTestUtils, TxTraceBuilder},
+        ParaswapAugustusV6,
+    };
+
+    const AUGUSTUS_V6: Address = Address::new(hex!("6A000F20005980200259B80c5102003040001068"));
+    const USDC_WETH_V2: Address = Address::new(hex!("B4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+    const BALANCER_VAULT: Address = Address::new(hex!("BA12222222228d8Ba445958a75a0704d566BF2C8"));
+    const SENDER: Address = Address::new(hex!("f4F8845ceDe63e79De1B2c3bbA395e8547FE4283"));
+    const BENEFICIARY: Address = Address::new(hex!("De74395831F3Ba9EdC7cBEE1fcB441cf24c0AF4d"));
+
+    fn setup(classifier_utils: &ClassifierTestUtils) {
+        classifier_utils.ensure_protocol(
+            Protocol::ParaswapV6,
+            AUGUSTUS_V6,
+            Address::ZERO,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_protocol(
+            Protocol::UniswapV2,
+            USDC_WETH_V2,
+            USDC_ADDRESS,
+            Some(WETH_ADDRESS),
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+    }
+
+    async fn assert_aggregator(
+        classifier_utils: &ClassifierTestUtils,
+        trace: TxTrace,
+        eq_action: Action,
+    ) {
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_aggregator),
+            )
+            .await;
+    }
+
+    /// augustus swaps 1000 usdc for 0.5 weth on the usdc / weth uniswap v2
+    /// pool and has the pool send the weth to the beneficiary
+    #[brontes_macros::test]
+    async fn test_paraswap_v6_uniswap_v2_swap() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+
+        let amount_in = U256::from(1_000_000_000u64);
+        let amount_out = U256::from(500_000_000_000_000_000u64);
+
+        let call_data = ParaswapAugustusV6::swapExactAmountInOnUniswapV2Call {
+            uniData:       ParaswapAugustusV6::UniswapV2Data {
+                srcToken:     USDC_ADDRESS,
+                destToken:    WETH_ADDRESS,
+                fromAmount:   amount_in,
+                toAmount:     amount_out,
+                quotedAmount: amount_out,
+                metadata:     B256::ZERO,
+                beneficiary:  BENEFICIARY,
+                pools:        Bytes::copy_from_slice(USDC_WETH_V2.as_slice()),
+            },
+            partnerAndFee: U256::ZERO,
+            permit:        Bytes::new(),
+        }
+        .abi_encode();
+        let (swap, swap_logs) =
+            uniswap_v2_swap(USDC_WETH_V2, AUGUSTUS_V6, BENEFICIARY, true, amount_in, amount_out);
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], SENDER, AUGUSTUS_V6, call_data, vec![])
+            .call(&[0], AUGUSTUS_V6, USDC_WETH_V2, swap, swap_logs)
+            .build(19_000_000, 0);
+
+        let eq_action = Action::Aggregator(NormalizedAggregator {
+            protocol:      Protocol::ParaswapV6,
+            trace_index:   0,
+            from:          SENDER,
+            to:            AUGUSTUS_V6,
+            recipient:     BENEFICIARY,
+            child_actions: vec![Action::Swap(NormalizedSwap {
+                protocol:    Protocol::UniswapV2,
+                trace_index: 1,
+                from:        AUGUSTUS_V6,
+                recipient:   BENEFICIARY,
+                pool:        USDC_WETH_V2,
+                token_in:    classifier_utils.get_token_info(USDC_ADDRESS),
+                token_out:   classifier_utils.get_token_info(WETH_ADDRESS),
+                amount_in:   Rational::from_signeds(1000, 1),
+                amount_out:  Rational::from_signeds(1, 2),
+                msg_value:   U256::ZERO,
+            })],
+            msg_value:     U256::ZERO,
+        });
+
+        assert_aggregator(&classifier_utils, trace, eq_action).await;
+    }
+
+    /// the balancer entrypoints pack the approve flag into the top bit of the
+    /// beneficiary
+    #[brontes_macros::test]
+    async fn test_paraswap_v6_balancer_unpacks_beneficiary() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        setup(&classifier_utils);
+
+        let packed = U256::from_be_slice(BENEFICIARY.as_slice()) | (U256::from(1) << 255);
+
+        let call_data = ParaswapAugustusV6::swapExactAmountInOnBalancerV2Call {
+            balancerData:  ParaswapAugustusV6::BalancerV2Data {
+                fromAmount:                U256::from(1_000_000_000u64),
+                toAmount:                  U256::from(1),
+                quotedAmount:              U256::from(1),
+                metadata:                  B256::ZERO,
+                beneficiaryAndApproveFlag: packed,
+            },
+            partnerAndFee: U256::ZERO,
+            permit:        Bytes::new(),
+            data:          Bytes::new(),
+        }
+        .abi_encode();
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(2))
+            .call(&[], SENDER, AUGUSTUS_V6, call_data, vec![])
+            .call(&[0], AUGUSTUS_V6, BALANCER_VAULT, Bytes::new(), vec![])
+            .build(19_000_000, 0);
+
+        let eq_action = Action::Aggregator(NormalizedAggregator {
+            protocol:      Protocol::ParaswapV6,
+            trace_index:   0,
+            from:          SENDER,
+            to:            AUGUSTUS_V6,
+            recipient:     BENEFICIARY,
+            child_actions: vec![],
+            msg_value:     U256::ZERO,
+        });
+
+        assert_aggregator(&classifier_utils, trace, eq_action).await;
+    }
+}
@@ -0,0 +1,16 @@
+use alloy_primitives::Address;
+
+mod augustus_v5;
+mod augustus_v6;
+
+pub use augustus_v5::*;
+pub use augustus_v6::*;
+
+/// Augustus sends the output to the caller when no beneficiary is set
+fn beneficiary_or_sender(beneficiary: Address, msg_sender: Address) -> Address {
+    if beneficiary.is_zero() {
+        msg_sender
+    } else {
+        beneficiary
+    }
+}
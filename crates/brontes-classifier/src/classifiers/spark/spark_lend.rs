@@ -0,0 +1,143 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    normalized_actions::NormalizedLiquidation, structured_trace::CallInfo, utils::ToScaledRational,
+    Protocol,
+};
+
+// SparkLend is an Aave V3 fork with the same pool interface. It is registered
+// under the pool proxy instead of the implementation, so the liquidation event
+// is emitted by the delegate calls of the frame
+action_impl!(
+    Protocol::Spark,
+    crate::AaveV3::liquidationCallCall,
+    Liquidation,
+    [..LiquidationCall],
+    call_data: true,
+    logs: true,
+    include_delegated_logs: true,
+    |
+    info: CallInfo,
+    call_data: liquidationCallCall,
+    log_data: SparkLiquidationCallCallLogs,
+    db_tx: &DB | {
+        let logs = log_data.liquidation_call_field?;
+
+        let debt_info = db_tx.try_fetch_token_info(call_data.debtAsset)?;
+        let collateral_info = db_tx.try_fetch_token_info(call_data.collateralAsset)?;
+
+        let covered_debt = logs.debtToCover.to_scaled_rational(debt_info.decimals);
+        let liquidated_collateral =
+            logs.liquidatedCollateralAmount.to_scaled_rational(collateral_info.decimals);
+
+        return Ok(NormalizedLiquidation {
+            protocol: Protocol::Spark,
+            trace_index: info.trace_idx,
+            pool: info.target_address,
+            liquidator: logs.liquidator,
+            debtor: call_data.user,
+            collateral_asset: collateral_info,
+            debt_asset: debt_info,
+            covered_debt,
+            liquidated_collateral,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, Log, B256, U256};
+    use alloy_sol_types::{SolCall, SolEvent};
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{Action, NormalizedLiquidation},
+        Protocol, TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use crate::{
+        test_utils::{ClassifierTestUtils, TxTraceBuilder},
+        AaveV3,
+    };
+
+    const SPARK_POOL: Address = Address::new(hex!("C13e21B648A5Ee794902342038FF3aDAB66BE987"));
+    const POOL_IMPL: Address = Address::repeat_byte(0x11);
+    const LIQUIDATION_LOGIC: Address = Address::repeat_byte(0x22);
+
+    #[brontes_macros::test]
+    async fn test_spark_liquidation() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        classifier_utils.ensure_protocol(
+            Protocol::Spark,
+            SPARK_POOL,
+            Address::ZERO,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+
+        let liquidator = Address::new(hex!("80d4230c0a68fc59cb264329d3a717fcaa472a13"));
+        let user = Address::new(hex!("e967954b9b48cb1a0079d76466e82c4d52a8f5d3"));
+
+        let call_data: Bytes = AaveV3::liquidationCallCall {
+            collateralAsset: WETH_ADDRESS,
+            debtAsset: USDC_ADDRESS,
+            user,
+            debtToCover: U256::from(1_500_000_000u64),
+            receiveAToken: false,
+        }
+        .abi_encode()
+        .into();
+
+        // emitted by the liquidation logic library the pool implementation
+        // delegate calls into, so in the context of the pool proxy
+        let log = Log {
+            address: SPARK_POOL,
+            data:    AaveV3::LiquidationCall {
+                collateralAsset: WETH_ADDRESS,
+                debtAsset: USDC_ADDRESS,
+                user,
+                debtToCover: U256::from(1_500_000_000u64),
+                liquidatedCollateralAmount: U256::from(500_000_000_000_000_000u64),
+                liquidator,
+                receiveAToken: false,
+            }
+            .encode_log_data(),
+        };
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], liquidator, SPARK_POOL, call_data.clone(), vec![])
+            .delegate_call(&[0], SPARK_POOL, POOL_IMPL, call_data, vec![])
+            .delegate_call(&[0, 0], SPARK_POOL, LIQUIDATION_LOGIC, Bytes::new(), vec![log])
+            .build(19_000_000, 0);
+
+        let eq_action = Action::Liquidation(NormalizedLiquidation {
+            protocol: Protocol::Spark,
+            trace_index: 0,
+            pool: SPARK_POOL,
+            liquidator,
+            debtor: user,
+            collateral_asset: classifier_utils.get_token_info(WETH_ADDRESS),
+            debt_asset: classifier_utils.get_token_info(USDC_ADDRESS),
+            covered_debt: Rational::from_signeds(1500, 1),
+            liquidated_collateral: Rational::from_signeds(1, 2),
+            msg_value: U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_liquidation),
+            )
+            .await;
+    }
+}
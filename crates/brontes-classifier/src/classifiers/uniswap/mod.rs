@@ -4,9 +4,12 @@ mod uniswap_v2;
 #[allow(non_snake_case)]
 mod uniswap_v3;
 #[allow(non_snake_case)]
+mod uniswap_v4;
+#[allow(non_snake_case)]
 mod uniswap_x;
 
 pub use discovery::*;
 pub use uniswap_v2::*;
 pub use uniswap_v3::*;
+pub use uniswap_v4::*;
 pub use uniswap_x::*;
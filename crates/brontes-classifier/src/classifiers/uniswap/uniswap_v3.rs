@@ -2,7 +2,9 @@ use alloy_primitives::U256;
 use brontes_macros::action_impl;
 use brontes_pricing::Protocol;
 use brontes_types::{
-    normalized_actions::{NormalizedBurn, NormalizedCollect, NormalizedMint, NormalizedSwap},
+    normalized_actions::{
+        NormalizedBurn, NormalizedCollect, NormalizedFlashLoan, NormalizedMint, NormalizedSwap,
+    },
     structured_trace::CallInfo,
     ToScaledRational,
 };
@@ -158,20 +160,83 @@ action_impl!(
         })
     }
 );
+action_impl!(
+    Protocol::UniswapV3,
+    crate::UniswapV3::flashCall,
+    FlashLoan,
+    [Flash],
+    call_data: true,
+    logs: true,
+    |
+    info: CallInfo,
+    call_data: flashCall,
+    log_data: UniswapV3FlashCallLogs,
+    db_tx: &DB
+    | {
+        let logs = log_data.flash_field?;
+        let details = db_tx.get_protocol_details_sorted(info.target_address)?;
+        let [token_0, token_1] = [details.token0, details.token1];
+
+        let t0_info = db_tx.try_fetch_token_info(token_0)?;
+        let t1_info = db_tx.try_fetch_token_info(token_1)?;
+
+        // only the tokens that are actually borrowed are part of the loan, the
+        // fee is whatever was paid back on top of the principal
+        let (assets, (amounts, fees_paid)): (Vec<_>, (Vec<_>, Vec<_>)) = [
+            (t0_info, call_data.amount0, logs.paid0),
+            (t1_info, call_data.amount1, logs.paid1),
+        ]
+        .into_iter()
+        .filter(|(_, amount, _)| *amount != U256::ZERO)
+        .map(|(token, amount, paid)| {
+            let amount_scaled = amount.to_scaled_rational(token.decimals);
+            let paid_scaled = paid.to_scaled_rational(token.decimals);
+            (token, (amount_scaled, paid_scaled))
+        })
+        .unzip();
+
+        Ok(NormalizedFlashLoan {
+            protocol: Protocol::UniswapV3,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            pool: info.target_address,
+            receiver_contract: call_data.recipient,
+            assets,
+            amounts,
+            aave_mode: None,
+            child_actions: vec![],
+            repayments: vec![],
+            fees_paid,
+            msg_value: info.msg_value
+        })
+    }
+);
 
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use alloy_primitives::{hex, Address, B256};
-    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use alloy_primitives::{hex, Address, Bytes, Log, B256};
+    use alloy_sol_types::{SolCall, SolEvent};
+    use brontes_classifier::test_utils::{uniswap_v2_swap, ClassifierTestUtils, TxTraceBuilder};
     use brontes_types::{
-        db::token_info::TokenInfoWithAddress, normalized_actions::Action, Protocol::UniswapV3,
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{Action, NormalizedTransfer},
+        Protocol::UniswapV3,
         TreeSearchBuilder,
     };
+    use malachite::{num::basic::traits::Zero, Rational};
+    use reth_primitives::Header;
 
     use super::*;
 
+    const USDC_WETH_V3: Address = Address::new(hex!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640"));
+    const USDC_WETH_V2: Address = Address::new(hex!("B4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+    const SEARCHER: Address = Address::repeat_byte(0x11);
+    const RECEIVER: Address = Address::repeat_byte(0x22);
+    const RECIPIENT: Address = Address::repeat_byte(0x33);
+
     #[brontes_macros::test]
     async fn test_univ3_swap() {
         let classifier_utils = ClassifierTestUtils::new().await;
@@ -302,4 +367,209 @@ mod tests {
             .await
             .unwrap();
     }
+
+    fn ensure_flash_pools(classifier_utils: &ClassifierTestUtils) {
+        classifier_utils.ensure_protocol(
+            UniswapV3,
+            USDC_WETH_V3,
+            USDC_ADDRESS,
+            Some(WETH_ADDRESS),
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_protocol(
+            Protocol::UniswapV2,
+            USDC_WETH_V2,
+            USDC_ADDRESS,
+            Some(WETH_ADDRESS),
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+    }
+
+    /// the receiver flash borrows `amount1` weth from the usdc / weth pool
+    /// into `recipient` and pays back `paid1` on top of it
+    fn flash(recipient: Address, amount1: U256, paid1: U256) -> (Bytes, Vec<Log>) {
+        let call_data = crate::UniswapV3::flashCall {
+            recipient,
+            amount0: U256::ZERO,
+            amount1,
+            data: Bytes::new(),
+        }
+        .abi_encode();
+
+        let logs = vec![Log {
+            address: USDC_WETH_V3,
+            data:    crate::UniswapV3::Flash {
+                sender: RECEIVER,
+                recipient,
+                amount0: U256::ZERO,
+                amount1,
+                paid0: U256::ZERO,
+                paid1,
+            }
+            .encode_log_data(),
+        }];
+
+        (call_data.into(), logs)
+    }
+
+    fn weth_flash_loan(
+        classifier_utils: &ClassifierTestUtils,
+        receiver_contract: Address,
+        child_actions: Vec<Action>,
+        repayments: Vec<NormalizedTransfer>,
+    ) -> Action {
+        Action::FlashLoan(NormalizedFlashLoan {
+            protocol: UniswapV3,
+            trace_index: 1,
+            from: RECEIVER,
+            pool: USDC_WETH_V3,
+            receiver_contract,
+            assets: vec![classifier_utils.get_token_info(WETH_ADDRESS)],
+            amounts: vec![Rational::from(10)],
+            aave_mode: None,
+            child_actions,
+            repayments,
+            fees_paid: vec![Rational::from_signeds(1, 200)],
+            msg_value: U256::ZERO,
+        })
+    }
+
+    /// only the borrowed weth is part of the loan, the usdc side of the pool
+    /// is left out
+    #[brontes_macros::test]
+    async fn test_uniswap_v3_flash() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_flash_pools(&classifier_utils);
+
+        let (flash_call, flash_logs) = flash(
+            RECEIVER,
+            U256::from(10_000_000_000_000_000_000u64),
+            U256::from(5_000_000_000_000_000u64),
+        );
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], SEARCHER, RECEIVER, Bytes::new(), vec![])
+            .call(&[0], RECEIVER, USDC_WETH_V3, flash_call, flash_logs)
+            // uniswapV3FlashCallback
+            .call(&[0, 0], USDC_WETH_V3, RECEIVER, Bytes::new(), vec![])
+            .build(19_000_000, 0);
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                weth_flash_loan(&classifier_utils, RECEIVER, vec![], vec![]),
+                TreeSearchBuilder::default().with_action(Action::is_flash_loan),
+            )
+            .await;
+    }
+
+    /// the swaps made with the borrowed weth in the callback are the child
+    /// actions of the loan
+    #[brontes_macros::test]
+    async fn test_uniswap_v3_flash_child_swap() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_flash_pools(&classifier_utils);
+
+        let (flash_call, flash_logs) = flash(
+            RECEIVER,
+            U256::from(10_000_000_000_000_000_000u64),
+            U256::from(5_000_000_000_000_000u64),
+        );
+        let (swap, swap_logs) = uniswap_v2_swap(
+            USDC_WETH_V2,
+            RECEIVER,
+            RECEIVER,
+            false,
+            U256::from(10_000_000_000_000_000_000u64),
+            U256::from(35_000_000_000u64),
+        );
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], SEARCHER, RECEIVER, Bytes::new(), vec![])
+            .call(&[0], RECEIVER, USDC_WETH_V3, flash_call, flash_logs)
+            // uniswapV3FlashCallback
+            .call(&[0, 0], USDC_WETH_V3, RECEIVER, Bytes::new(), vec![])
+            .call(&[0, 0, 0], RECEIVER, USDC_WETH_V2, swap, swap_logs)
+            .build(19_000_000, 0);
+
+        let child_swap = Action::Swap(NormalizedSwap {
+            protocol:    Protocol::UniswapV2,
+            trace_index: 3,
+            from:        RECEIVER,
+            recipient:   RECEIVER,
+            pool:        USDC_WETH_V2,
+            token_in:    classifier_utils.get_token_info(WETH_ADDRESS),
+            token_out:   classifier_utils.get_token_info(USDC_ADDRESS),
+            amount_in:   Rational::from(10),
+            amount_out:  Rational::from(35_000),
+            msg_value:   U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                weth_flash_loan(&classifier_utils, RECEIVER, vec![child_swap], vec![]),
+                TreeSearchBuilder::default().with_action(Action::is_flash_loan),
+            )
+            .await;
+    }
+
+    /// the borrowed weth is sent to a different recipient, the caller that
+    /// gets the callback is still the one paying the loan back
+    #[brontes_macros::test]
+    async fn test_uniswap_v3_flash_repaid_by_caller() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_flash_pools(&classifier_utils);
+
+        let (flash_call, flash_logs) = flash(
+            RECIPIENT,
+            U256::from(10_000_000_000_000_000_000u64),
+            U256::from(5_000_000_000_000_000u64),
+        );
+        let repay = crate::classifiers::erc20::transferCall {
+            _0: USDC_WETH_V3,
+            _1: U256::from(10_005_000_000_000_000_000u128),
+        }
+        .abi_encode();
+
+        let trace = TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], SEARCHER, RECEIVER, Bytes::new(), vec![])
+            .call(&[0], RECEIVER, USDC_WETH_V3, flash_call, flash_logs)
+            // uniswapV3FlashCallback
+            .call(&[0, 0], USDC_WETH_V3, RECEIVER, Bytes::new(), vec![])
+            .call(&[0, 0, 0], RECEIVER, WETH_ADDRESS, repay.into(), vec![])
+            .build(19_000_000, 0);
+
+        let repayment = NormalizedTransfer {
+            trace_index: 3,
+            from:        RECEIVER,
+            to:          USDC_WETH_V3,
+            token:       classifier_utils.get_token_info(WETH_ADDRESS),
+            amount:      Rational::from_signeds(2001, 200),
+            fee:         Rational::ZERO,
+            msg_value:   U256::ZERO,
+        };
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: 19_000_000, ..Default::default() },
+                0,
+                weth_flash_loan(&classifier_utils, RECIPIENT, vec![], vec![repayment]),
+                TreeSearchBuilder::default().with_action(Action::is_flash_loan),
+            )
+            .await;
+    }
 }
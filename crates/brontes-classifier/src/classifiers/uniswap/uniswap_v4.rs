@@ -0,0 +1,631 @@
+use alloy_primitives::{Log, I256, U256};
+use alloy_sol_types::SolEvent;
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    db::uniswap_v4_pool_key::{v4_currency_to_token, v4_pool_id_to_address, UniswapV4PoolKey},
+    normalized_actions::{
+        Action, NormalizedBurn, NormalizedCollect, NormalizedMint, NormalizedPoolConfigUpdate,
+        NormalizedSwap, NormalizedSwapWithFee,
+    },
+    structured_trace::CallInfo,
+    ToScaledRational,
+};
+
+use crate::UniswapV4::Initialize;
+
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4::initializeCall,
+    PoolConfigUpdate,
+    [..Initialize],
+    logs: true,
+    |info: CallInfo, log_data: UniswapV4InitializeCallLogs, _| {
+        let logs = log_data.initialize_field?;
+
+        Ok(NormalizedPoolConfigUpdate {
+            trace_index: info.trace_idx,
+            protocol: Protocol::UniswapV4,
+            pool_address: v4_pool_id_to_address(logs.id),
+            tokens: vec![
+                v4_currency_to_token(logs.currency0),
+                v4_currency_to_token(logs.currency1),
+            ],
+        })
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4::swapCall,
+    Action,
+    [..Swap],
+    call_data: true,
+    return_data: true,
+    logs: true,
+    |
+    info: CallInfo,
+    call_data: swapCall,
+    return_data: swapReturn,
+    log_data: UniswapV4SwapCallLogs,
+    db_tx: &DB| {
+        let logs = log_data.swap_field?;
+        // the returned delta is what the caller owes or is owed after the hook took
+        // its delta, the swap log is what the pool itself traded
+        let (token_0_delta, token_1_delta) = decode_balance_delta(return_data.swapDelta);
+        let (hook_0_delta, hook_1_delta) =
+            (logs.amount0 - token_0_delta, logs.amount1 - token_1_delta);
+
+        let t0_info = db_tx.try_fetch_token_info(v4_currency_to_token(call_data.key.currency0))?;
+        let t1_info = db_tx.try_fetch_token_info(v4_currency_to_token(call_data.key.currency1))?;
+
+        // deltas are from the perspective of the caller, a negative delta is what is
+        // owed to the pool
+        let (amount_in, amount_out, token_in, token_out) = if token_0_delta.is_negative() {
+            (
+                U256::from(token_0_delta.unsigned_abs()).to_scaled_rational(t0_info.decimals),
+                U256::from(token_1_delta.unsigned_abs()).to_scaled_rational(t1_info.decimals),
+                t0_info,
+                t1_info,
+            )
+        } else {
+            (
+                U256::from(token_1_delta.unsigned_abs()).to_scaled_rational(t1_info.decimals),
+                U256::from(token_0_delta.unsigned_abs()).to_scaled_rational(t0_info.decimals),
+                t1_info,
+                t0_info,
+            )
+        };
+
+        let swap = NormalizedSwap {
+            protocol: Protocol::UniswapV4,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            pool: v4_pool_id_to_address(logs.id),
+            recipient: info.from_address,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value
+        };
+
+        // a positive hook delta is what the hook took out of the swap as its fee
+        let (currency, hook_fee) = match (hook_0_delta, hook_1_delta) {
+            (fee, _) if fee > 0 => (call_data.key.currency0, fee),
+            (_, fee) if fee > 0 => (call_data.key.currency1, fee),
+            _ => return Ok(Action::Swap(swap)),
+        };
+        let fee_token = db_tx.try_fetch_token_info(v4_currency_to_token(currency))?;
+
+        Ok(Action::SwapWithFee(NormalizedSwapWithFee {
+            fee_amount: U256::from(hook_fee.unsigned_abs()).to_scaled_rational(fee_token.decimals),
+            fee_token,
+            swap,
+        }))
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4::modifyLiquidityCall,
+    Action,
+    [..ModifyLiquidity],
+    call_data: true,
+    return_data: true,
+    logs: true,
+    |
+    info: CallInfo,
+    call_data: modifyLiquidityCall,
+    return_data: modifyLiquidityReturn,
+    log_data: UniswapV4ModifyLiquidityCallLogs,
+    db_tx: &DB| {
+        let logs = log_data.modify_liquidity_field?;
+        let pool = v4_pool_id_to_address(logs.id);
+
+        let t0_info = db_tx.try_fetch_token_info(v4_currency_to_token(call_data.key.currency0))?;
+        let t1_info = db_tx.try_fetch_token_info(v4_currency_to_token(call_data.key.currency1))?;
+
+        // the caller delta includes the fees accrued by the position, so we remove
+        // them to get the principal amounts
+        let (caller_0, caller_1) = decode_balance_delta(return_data.callerDelta);
+        let (fees_0, fees_1) = decode_balance_delta(return_data.feesAccrued);
+
+        let (t0_decimals, t1_decimals) = (t0_info.decimals, t1_info.decimals);
+        let amounts = |delta_0: i128, delta_1: i128| {
+            vec![
+                U256::from(delta_0.unsigned_abs()).to_scaled_rational(t0_decimals),
+                U256::from(delta_1.unsigned_abs()).to_scaled_rational(t1_decimals),
+            ]
+        };
+        let principal = amounts(caller_0 - fees_0, caller_1 - fees_1);
+
+        // a zero liquidity delta is how fees are collected in v4
+        if call_data.params.liquidityDelta.is_zero() {
+            return Ok(Action::Collect(NormalizedCollect {
+                protocol: Protocol::UniswapV4,
+                trace_index: info.trace_idx,
+                from: info.from_address,
+                recipient: info.from_address,
+                pool,
+                amount: amounts(fees_0, fees_1),
+                token: vec![t0_info, t1_info],
+            }))
+        }
+
+        if call_data.params.liquidityDelta.is_positive() {
+            Ok(Action::Mint(NormalizedMint {
+                protocol: Protocol::UniswapV4,
+                trace_index: info.trace_idx,
+                from: info.from_address,
+                recipient: info.from_address,
+                pool,
+                token: vec![t0_info, t1_info],
+                amount: principal,
+            }))
+        } else {
+            Ok(Action::Burn(NormalizedBurn {
+                protocol: Protocol::UniswapV4,
+                trace_index: info.trace_idx,
+                from: info.from_address,
+                recipient: info.from_address,
+                pool,
+                token: vec![t0_info, t1_info],
+                amount: principal,
+            }))
+        }
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV4,
+    crate::UniswapV4::donateCall,
+    Mint,
+    [..Donate],
+    call_data: true,
+    logs: true,
+    |
+    info: CallInfo,
+    call_data: donateCall,
+    log_data: UniswapV4DonateCallLogs,
+    db_tx: &DB| {
+        let logs = log_data.donate_field?;
+        let pool = v4_pool_id_to_address(logs.id);
+
+        let t0_info = db_tx.try_fetch_token_info(v4_currency_to_token(call_data.key.currency0))?;
+        let t1_info = db_tx.try_fetch_token_info(v4_currency_to_token(call_data.key.currency1))?;
+
+        let am0 = logs.amount0.to_scaled_rational(t0_info.decimals);
+        let am1 = logs.amount1.to_scaled_rational(t1_info.decimals);
+
+        // no position is minted for the donor, the donated tokens are paid out as
+        // fees to the in range positions so the pool is the recipient
+        Ok(NormalizedMint {
+            protocol: Protocol::UniswapV4,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: pool,
+            pool,
+            token: vec![t0_info, t1_info],
+            amount: vec![am0, am1],
+        })
+    }
+);
+
+/// a `BalanceDelta` packs the delta of currency0 into the upper 128 bits and
+/// the delta of currency1 into the lower 128 bits
+fn decode_balance_delta(delta: I256) -> (i128, i128) {
+    let raw = delta.into_raw();
+    let amount0 = (raw >> 128).to::<u128>() as i128;
+    let amount1 = (raw & U256::from(u128::MAX)).to::<u128>() as i128;
+
+    (amount0, amount1)
+}
+
+/// decodes the full pool key of a pool out of the `Initialize` log. The key is
+/// stored separately from the protocol info as it is needed to go from the
+/// address we key the pool by back to its pool id
+pub fn decode_v4_pool_key(block: u64, logs: &[Log]) -> Option<UniswapV4PoolKey> {
+    logs.iter()
+        .find_map(|log| Initialize::decode_log_data(log, true).ok())
+        .map(|init| UniswapV4PoolKey {
+            pool_id:      init.id,
+            currency0:    init.currency0,
+            currency1:    init.currency1,
+            fee:          init.fee,
+            tick_spacing: init.tickSpacing,
+            hooks:        init.hooks,
+            init_block:   block,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, Bytes, B256, U160};
+    use alloy_sol_types::SolCall;
+    use brontes_classifier::test_utils::{ClassifierTestUtils, TxTraceBuilder};
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        structured_trace::TxTrace,
+        TreeSearchBuilder,
+    };
+    use malachite::Rational;
+    use reth_primitives::Header;
+
+    use super::*;
+    use crate::UniswapV4::{ModifyLiquidityParams, PoolKey, SwapParams};
+
+    const POOL_MANAGER: Address = Address::new(hex!("000000000004444c5dc75cB358380D2e3dE08A90"));
+    const POOL_ID: B256 =
+        B256::new(hex!("21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27"));
+    const ROUTER: Address = Address::repeat_byte(0x11);
+    const SENDER: Address = Address::repeat_byte(0x22);
+    const BLOCK: u64 = 21_700_000;
+
+    /// the native eth / usdc pool
+    fn pool_key() -> PoolKey {
+        PoolKey {
+            currency0:   Address::ZERO,
+            currency1:   USDC_ADDRESS,
+            fee:         500,
+            tickSpacing: 10,
+            hooks:       Address::ZERO,
+        }
+    }
+
+    fn ensure_pool_manager(classifier_utils: &ClassifierTestUtils) {
+        classifier_utils.ensure_protocol(
+            Protocol::UniswapV4,
+            POOL_MANAGER,
+            Address::ZERO,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+    }
+
+    fn encode_balance_delta(amount0: i128, amount1: i128) -> I256 {
+        I256::from_raw((U256::from(amount0 as u128) << 128) | U256::from(amount1 as u128))
+    }
+
+    /// the router calls into the pool manager from the unlock callback
+    fn pool_manager_call(call_data: Vec<u8>, return_data: Vec<u8>, logs: Vec<Log>) -> TxTrace {
+        TxTraceBuilder::new(B256::with_last_byte(1))
+            .call(&[], SENDER, ROUTER, Bytes::new(), vec![])
+            .call(&[0], ROUTER, POOL_MANAGER, call_data, logs)
+            .with_output(return_data)
+            .build(BLOCK, 0)
+    }
+
+    fn modify_liquidity(liquidity_delta: I256, caller_delta: I256, fees_accrued: I256) -> TxTrace {
+        let call_data = crate::UniswapV4::modifyLiquidityCall {
+            key:      pool_key(),
+            params:   ModifyLiquidityParams {
+                tickLower:      -200_000,
+                tickUpper:      -190_000,
+                liquidityDelta: liquidity_delta,
+                salt:           B256::ZERO,
+            },
+            hookData: Bytes::new(),
+        }
+        .abi_encode();
+        let return_data = crate::UniswapV4::modifyLiquidityCall::abi_encode_returns(&(
+            caller_delta,
+            fees_accrued,
+        ));
+
+        let logs = vec![Log {
+            address: POOL_MANAGER,
+            data:    crate::UniswapV4::ModifyLiquidity {
+                id:             POOL_ID,
+                sender:         ROUTER,
+                tickLower:      -200_000,
+                tickUpper:      -190_000,
+                liquidityDelta: liquidity_delta,
+                salt:           B256::ZERO,
+            }
+            .encode_log_data(),
+        }];
+
+        pool_manager_call(call_data, return_data, logs)
+    }
+
+    /// eth for usdc, `caller_delta` is what the router is left with after the
+    /// hook took its delta from the `amount0` / `amount1` the pool traded
+    fn swap(caller_delta: I256, amount0: i128, amount1: i128) -> TxTrace {
+        let call_data = crate::UniswapV4::swapCall {
+            key:      pool_key(),
+            params:   SwapParams {
+                zeroForOne:        true,
+                amountSpecified:   I256::try_from(-1_000_000_000_000_000_000i128).unwrap(),
+                sqrtPriceLimitX96: U160::from(4295128740u64),
+            },
+            hookData: Bytes::new(),
+        }
+        .abi_encode();
+        let return_data = crate::UniswapV4::swapCall::abi_encode_returns(&(caller_delta,));
+        let logs = vec![Log {
+            address: POOL_MANAGER,
+            data:    crate::UniswapV4::Swap {
+                id: POOL_ID,
+                sender: ROUTER,
+                amount0,
+                amount1,
+                sqrtPriceX96: U160::from(1u128 << 96),
+                liquidity: 1_000_000_000_000_000,
+                tick: -196_000,
+                fee: 500,
+            }
+            .encode_log_data(),
+        }];
+
+        pool_manager_call(call_data, return_data, logs)
+    }
+
+    fn tokens(classifier_utils: &ClassifierTestUtils) -> Vec<TokenInfoWithAddress> {
+        vec![
+            classifier_utils.get_token_info(WETH_ADDRESS),
+            classifier_utils.get_token_info(USDC_ADDRESS),
+        ]
+    }
+
+    #[test]
+    fn test_decode_balance_delta() {
+        // amount0 = -1000, amount1 = 2500
+        let raw = (U256::from((-1000i128) as u128) << 128) | U256::from(2500u128);
+        let (amount0, amount1) = decode_balance_delta(I256::from_raw(raw));
+
+        assert_eq!(amount0, -1000);
+        assert_eq!(amount1, 2500);
+    }
+
+    #[test]
+    fn test_decode_balance_delta_negative_amount1() {
+        let raw = (U256::from(42u128) << 128) | U256::from((-7i128) as u128);
+        let (amount0, amount1) = decode_balance_delta(I256::from_raw(raw));
+
+        assert_eq!(amount0, 42);
+        assert_eq!(amount1, -7);
+    }
+
+    /// the initialize log carries the pool id the pool is keyed by
+    #[brontes_macros::test]
+    async fn test_uniswap_v4_initialize() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_pool_manager(&classifier_utils);
+
+        let call_data = crate::UniswapV4::initializeCall {
+            key:          pool_key(),
+            sqrtPriceX96: U160::from(1u128 << 96),
+        }
+        .abi_encode();
+        let logs = vec![Log {
+            address: POOL_MANAGER,
+            data:    crate::UniswapV4::Initialize {
+                id:           POOL_ID,
+                currency0:    Address::ZERO,
+                currency1:    USDC_ADDRESS,
+                fee:          500,
+                tickSpacing:  10,
+                hooks:        Address::ZERO,
+                sqrtPriceX96: U160::from(1u128 << 96),
+                tick:         0,
+            }
+            .encode_log_data(),
+        }];
+
+        let eq_action = Action::PoolConfigUpdate(NormalizedPoolConfigUpdate {
+            trace_index:  1,
+            protocol:     Protocol::UniswapV4,
+            pool_address: v4_pool_id_to_address(POOL_ID),
+            tokens:       vec![WETH_ADDRESS, USDC_ADDRESS],
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                pool_manager_call(call_data, vec![], logs),
+                Header { number: BLOCK, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_pool_config_update),
+            )
+            .await;
+    }
+
+    /// eth for usdc, the caller owes the pool the eth it puts in
+    #[brontes_macros::test]
+    async fn test_uniswap_v4_swap() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_pool_manager(&classifier_utils);
+
+        let trace = swap(
+            encode_balance_delta(-1_000_000_000_000_000_000, 3_000_000_000),
+            -1_000_000_000_000_000_000,
+            3_000_000_000,
+        );
+
+        let eq_action = Action::Swap(NormalizedSwap {
+            protocol:    Protocol::UniswapV4,
+            trace_index: 1,
+            from:        ROUTER,
+            recipient:   ROUTER,
+            pool:        v4_pool_id_to_address(POOL_ID),
+            token_in:    classifier_utils.get_token_info(WETH_ADDRESS),
+            amount_in:   Rational::from(1),
+            token_out:   classifier_utils.get_token_info(USDC_ADDRESS),
+            amount_out:  Rational::from(3_000),
+            msg_value:   U256::ZERO,
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: BLOCK, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_swap),
+            )
+            .await;
+    }
+
+    #[brontes_macros::test]
+    async fn test_uniswap_v4_modify_liquidity_mint() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_pool_manager(&classifier_utils);
+
+        let trace = modify_liquidity(
+            I256::try_from(1_000_000_000_000i128).unwrap(),
+            encode_balance_delta(-1_000_000_000_000_000_000, -3_000_000_000),
+            encode_balance_delta(0, 0),
+        );
+
+        let eq_action = Action::Mint(NormalizedMint {
+            protocol:    Protocol::UniswapV4,
+            trace_index: 1,
+            from:        ROUTER,
+            recipient:   ROUTER,
+            pool:        v4_pool_id_to_address(POOL_ID),
+            token:       tokens(&classifier_utils),
+            amount:      vec![Rational::from(1), Rational::from(3_000)],
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: BLOCK, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_mint),
+            )
+            .await;
+    }
+
+    /// the fees the position accrued are paid out with the burn but aren't part
+    /// of the burned amounts
+    #[brontes_macros::test]
+    async fn test_uniswap_v4_modify_liquidity_burn() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_pool_manager(&classifier_utils);
+
+        let trace = modify_liquidity(
+            I256::try_from(-1_000_000_000_000i128).unwrap(),
+            encode_balance_delta(1_001_000_000_000_000_000, 3_003_000_000),
+            encode_balance_delta(1_000_000_000_000_000, 3_000_000),
+        );
+
+        let eq_action = Action::Burn(NormalizedBurn {
+            protocol:    Protocol::UniswapV4,
+            trace_index: 1,
+            from:        ROUTER,
+            recipient:   ROUTER,
+            pool:        v4_pool_id_to_address(POOL_ID),
+            token:       tokens(&classifier_utils),
+            amount:      vec![Rational::from(1), Rational::from(3_000)],
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: BLOCK, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_burn),
+            )
+            .await;
+    }
+
+    /// the hook takes 30 usdc out of the swap, the router only gets what is
+    /// left of the pool's output
+    #[brontes_macros::test]
+    async fn test_uniswap_v4_swap_hook_fee() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_pool_manager(&classifier_utils);
+
+        let trace = swap(
+            encode_balance_delta(-1_000_000_000_000_000_000, 2_970_000_000),
+            -1_000_000_000_000_000_000,
+            3_000_000_000,
+        );
+
+        let eq_action = Action::SwapWithFee(NormalizedSwapWithFee {
+            swap:       NormalizedSwap {
+                protocol:    Protocol::UniswapV4,
+                trace_index: 1,
+                from:        ROUTER,
+                recipient:   ROUTER,
+                pool:        v4_pool_id_to_address(POOL_ID),
+                token_in:    classifier_utils.get_token_info(WETH_ADDRESS),
+                amount_in:   Rational::from(1),
+                token_out:   classifier_utils.get_token_info(USDC_ADDRESS),
+                amount_out:  Rational::from(2_970),
+                msg_value:   U256::ZERO,
+            },
+            fee_token:  classifier_utils.get_token_info(USDC_ADDRESS),
+            fee_amount: Rational::from(30),
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                trace,
+                Header { number: BLOCK, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_swap),
+            )
+            .await;
+    }
+
+    /// donations are paid out as fees to the in range positions, the donor
+    /// doesn't get a position for them
+    #[brontes_macros::test]
+    async fn test_uniswap_v4_donate() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        ensure_pool_manager(&classifier_utils);
+
+        let call_data = crate::UniswapV4::donateCall {
+            key:      pool_key(),
+            amount0:  U256::from(1_000_000_000_000_000_000u64),
+            amount1:  U256::from(3_000_000_000u64),
+            hookData: Bytes::new(),
+        }
+        .abi_encode();
+        let return_data = crate::UniswapV4::donateCall::abi_encode_returns(&(
+            encode_balance_delta(-1_000_000_000_000_000_000, -3_000_000_000),
+        ));
+        let logs = vec![Log {
+            address: POOL_MANAGER,
+            data:    crate::UniswapV4::Donate {
+                id:      POOL_ID,
+                sender:  ROUTER,
+                amount0: U256::from(1_000_000_000_000_000_000u64),
+                amount1: U256::from(3_000_000_000u64),
+            }
+            .encode_log_data(),
+        }];
+
+        let eq_action = Action::Mint(NormalizedMint {
+            protocol:    Protocol::UniswapV4,
+            trace_index: 1,
+            from:        ROUTER,
+            recipient:   v4_pool_id_to_address(POOL_ID),
+            pool:        v4_pool_id_to_address(POOL_ID),
+            token:       tokens(&classifier_utils),
+            amount:      vec![Rational::from(1), Rational::from(3_000)],
+        });
+
+        classifier_utils
+            .contains_action_in_trace(
+                pool_manager_call(call_data, return_data, logs),
+                Header { number: BLOCK, ..Default::default() },
+                0,
+                eq_action,
+                TreeSearchBuilder::default().with_action(Action::is_mint),
+            )
+            .await;
+    }
+}
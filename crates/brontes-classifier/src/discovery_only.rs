@@ -9,6 +9,7 @@ use brontes_types::{
     structured_trace::{TraceActions, TransactionTraceWithLogs, TxTrace},
     traits::TracingProvider,
     tree::{root::NodeData, GasDetails, Node, Root},
+    Protocol,
 };
 use futures::future::join_all;
 use reth_primitives::{Address, Header};
@@ -228,6 +229,15 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
                 {
                     error!(pool=?p.pool_address,"failed to update pool config");
                 }
+
+                // v4 pools need their full key to be able to query the pool state
+                if p.protocol == Protocol::UniswapV4 {
+                    if let Some(pool_key) = decode_v4_pool_key(block, &trace.logs) {
+                        if self.libmdbx.insert_v4_pool_key(pool_key).await.is_err() {
+                            error!(pool=?p.pool_address, "failed to insert v4 pool key");
+                        }
+                    }
+                }
             }
         } else {
             self.classify_transfer(trace_index, &trace, block).await
@@ -20,6 +20,7 @@ pub mod tree_builder;
 pub use tree_builder::Classifier;
 pub mod discovery_only;
 pub mod multi_frame_classification;
+pub mod runtime;
 
 #[cfg(feature = "tests")]
 pub mod test_utils;
@@ -33,6 +34,7 @@ pub use classifiers::*;
 sol!(UniswapV2, "./classifier-abis/UniswapV2.json");
 sol!(SushiSwapV2, "./classifier-abis/SushiSwapV2.json");
 sol!(UniswapV3, "./classifier-abis/UniswapV3.json");
+sol!(UniswapV4, "./classifier-abis/UniswapV4PoolManager.json");
 sol!(SushiSwapV3, "./classifier-abis/SushiSwapV3.json");
 sol!(PancakeSwapV2, "./classifier-abis/PancakeSwapV2.json");
 sol!(PancakeSwapV3, "./classifier-abis/PancakeSwapV3.json");
@@ -55,6 +57,7 @@ sol!(MakerDssFlash, "./classifier-abis/maker/MakerDssFlash.json");
 sol!(CompoundV2CToken, "./classifier-abis/CompoundV2CToken.json");
 sol!(OneInchAggregationRouterV5, "./classifier-abis/OneInchAggregationRouterV5.json");
 sol!(OneInchFusionSettlement, "./classifier-abis/OneInchFusionSettlement.json");
+sol!(OneInchAggregationRouterV6, "./classifier-abis/OneInchAggregationRouterV6.json");
 sol!(ClipperExchange, "./classifier-abis/ClipperExchange.json");
 sol!(CowswapGPv2Settlement, "./classifier-abis/cowswap/GPv2Settlement.json");
 sol!(ZeroXUniswapFeaure, "./classifier-abis/zero-x/ZeroXUniswapFeature.json");
@@ -66,6 +69,12 @@ sol!(ZeroXLiquidityProviderFeature, "./classifier-abis/zero-x/ZeroXLiquidityProv
 sol!(ZeroXInterface, "./classifier-abis/zero-x/ZeroXInterface.json");
 sol!(DodoDPPPool, "./classifier-abis/dodo/DPPPool.json");
 sol!(DodoDSPPool, "./classifier-abis/dodo/DSPPool.json");
+sol!(MorphoBlue, "./classifier-abis/morpho/MorphoBlue.json");
+sol!(LiquityTroveManager, "./classifier-abis/liquity/TroveManager.json");
+sol!(EulerV2EVault, "./classifier-abis/euler/EVault.json");
+sol!(ParaswapAugustusV5, "./classifier-abis/paraswap/AugustusV5.json");
+sol!(ParaswapAugustusV6, "./classifier-abis/paraswap/AugustusV6.json");
+sol!(KyberSwapMetaAggregationRouterV2, "./classifier-abis/kyberswap/MetaAggregationRouterV2.json");
 
 // Discovery
 sol!(UniswapV2Factory, "./classifier-abis/UniswapV2Factory.json");
@@ -83,6 +92,7 @@ sol!(BalancerV1SmartPoolFactory, "./classifier-abis/balancer/BalancerV1CrpFactor
 sol!(DodoDVMFactory, "./classifier-abis/dodo/DVMFactory.json");
 sol!(DodoDPPFactory, "./classifier-abis/dodo/DPPFactory.json");
 sol!(DodoDSPFactory, "./classifier-abis/dodo/DSPFactory.json");
+sol!(EulerV2GenericFactory, "./classifier-abis/euler/GenericFactory.json");
 
 // Balancer Pool Interfaces
 sol! {
@@ -0,0 +1,29 @@
+use brontes_types::{
+    normalized_actions::{
+        Action, MultiCallFrameClassification, MultiFrameAction, MultiFrameRequest,
+    },
+    Protocol, TreeSearchBuilder,
+};
+
+use super::parse_child_actions;
+use crate::multi_frame_classification::MultiCallFrameClassifier;
+
+pub struct KyberSwapAgg;
+
+impl MultiCallFrameClassifier for KyberSwapAgg {
+    const KEY: [u8; 2] = [Protocol::KyberSwap as u8, MultiFrameAction::Aggregator as u8];
+
+    fn create_classifier(
+        request: MultiFrameRequest,
+    ) -> Option<MultiCallFrameClassification<Action>> {
+        Some(MultiCallFrameClassification {
+            trace_index:         request.trace_idx,
+            tree_search_builder: TreeSearchBuilder::new().with_actions([
+                Action::is_swap,
+                Action::is_transfer,
+                Action::is_eth_transfer,
+            ]),
+            parse_fn:            Box::new(parse_child_actions),
+        })
+    }
+}
@@ -1,4 +1,34 @@
+use brontes_types::normalized_actions::{Action, NodeDataIndex};
+
 pub mod one_inch;
 pub use one_inch::*;
 pub mod zero_x;
 pub use zero_x::*;
+pub mod paraswap;
+pub use paraswap::*;
+pub mod kyberswap;
+pub use kyberswap::*;
+
+/// For routers that execute the route through their own executors, every swap
+/// and transfer below the entrypoint belongs to the aggregated swap
+fn parse_child_actions(
+    this_action: &mut Action,
+    child_nodes: Vec<(NodeDataIndex, Action)>,
+) -> Vec<NodeDataIndex> {
+    let this = this_action.try_aggregator_mut().unwrap();
+    let mut prune_nodes = Vec::new();
+
+    for (trace_index, action) in child_nodes {
+        match action {
+            Action::Swap(_)
+            | Action::SwapWithFee(_)
+            | Action::Transfer(_)
+            | Action::EthTransfer(_) => {
+                this.child_actions.push(action.clone());
+                prune_nodes.push(trace_index);
+            }
+            _ => {}
+        }
+    }
+    prune_nodes
+}
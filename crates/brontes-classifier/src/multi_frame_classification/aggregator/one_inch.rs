@@ -9,6 +9,7 @@ use brontes_types::{
 use crate::multi_frame_classification::MultiCallFrameClassifier;
 
 pub struct OneInchAggregator;
+pub struct OneInchV6Aggregator;
 pub struct OneInchFusion;
 
 impl MultiCallFrameClassifier for OneInchAggregator {
@@ -31,6 +32,26 @@ impl MultiCallFrameClassifier for OneInchAggregator {
     }
 }
 
+impl MultiCallFrameClassifier for OneInchV6Aggregator {
+    const KEY: [u8; 2] = [Protocol::OneInchV6 as u8, MultiFrameAction::Aggregator as u8];
+
+    fn create_classifier(
+        request: MultiFrameRequest,
+    ) -> Option<MultiCallFrameClassification<Action>> {
+        Some(MultiCallFrameClassification {
+            trace_index:         request.trace_idx,
+            tree_search_builder: TreeSearchBuilder::new().with_actions([
+                Action::is_swap,
+                Action::is_transfer,
+                Action::is_eth_transfer,
+            ]),
+            parse_fn:            Box::new(|this_action, child_nodes| {
+                parse_1inch(this_action, child_nodes, false)
+            }),
+        })
+    }
+}
+
 const FUSION_ADDRESS: Address = Address::new(hex!("A88800CD213dA5Ae406ce248380802BD53b47647"));
 
 impl MultiCallFrameClassifier for OneInchFusion {
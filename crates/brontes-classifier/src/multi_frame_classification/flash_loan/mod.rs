@@ -4,3 +4,5 @@ pub mod maker_dss;
 pub use maker_dss::*;
 pub mod dodo;
 pub use dodo::*;
+pub mod uniswap_v3;
+pub use uniswap_v3::*;
@@ -0,0 +1,68 @@
+use alloy_sol_types::SolCall;
+use brontes_types::{
+    normalized_actions::{
+        Action, MultiCallFrameClassification, MultiFrameAction, MultiFrameRequest, NodeDataIndex,
+    },
+    utils::ToScaledRational,
+    Protocol, TreeSearchBuilder,
+};
+use reth_rpc_types::trace::parity::{Action as TraceAction, TraceOutput};
+
+use crate::{
+    multi_frame_classification::MultiCallFrameClassifier, EulerV2EVault::convertToAssetsCall,
+};
+
+pub struct EulerV2;
+
+impl MultiCallFrameClassifier for EulerV2 {
+    const KEY: [u8; 2] = [Protocol::EulerV2 as u8, MultiFrameAction::Liquidation as u8];
+
+    fn create_classifier(
+        request: MultiFrameRequest,
+    ) -> Option<MultiCallFrameClassification<Action>> {
+        Some(MultiCallFrameClassification {
+            trace_index:         request.trace_idx,
+            tree_search_builder: TreeSearchBuilder::new().with_action(Action::is_unclassified),
+            parse_fn:            Box::new(parse_euler_v2),
+        })
+    }
+}
+
+/// The collateral is seized as vault shares. To value the violator's position,
+/// the oracle router converts the violator's balance of each collateral vault
+/// to assets, with the seized collateral vault quoted last. The rate of that
+/// conversion is the one the shares were seized at.
+fn parse_euler_v2(
+    this: &mut Action,
+    child_nodes: Vec<(NodeDataIndex, Action)>,
+) -> Vec<NodeDataIndex> {
+    let this = this.try_liquidation_mut().unwrap();
+
+    let Some((shares, assets)) = child_nodes
+        .into_iter()
+        .filter_map(|(_, action)| {
+            let Action::Unclassified(trace) = action else { return None };
+            let (TraceAction::Call(call), Some(TraceOutput::Call(output))) =
+                (&trace.trace.action, &trace.trace.result)
+            else {
+                return None
+            };
+
+            let shares = convertToAssetsCall::abi_decode(&call.input, false)
+                .ok()?
+                .shares;
+            let assets = convertToAssetsCall::abi_decode_returns(&output.output, false)
+                .ok()?
+                ._0;
+
+            (!shares.is_zero()).then_some((shares, assets))
+        })
+        .last()
+    else {
+        return vec![]
+    };
+
+    this.liquidated_collateral *= assets.to_scaled_rational(0) / shares.to_scaled_rational(0);
+
+    vec![]
+}
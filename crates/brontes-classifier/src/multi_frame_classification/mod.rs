@@ -9,12 +9,15 @@ pub mod batch;
 pub mod flash_loan;
 pub mod liquidations;
 
-use aggregator::{OneInchAggregator, OneInchFusion, ZeroXAgg};
+use aggregator::{
+    KyberSwapAgg, OneInchAggregator, OneInchFusion, OneInchV6Aggregator, ParaswapV5Agg,
+    ParaswapV6Agg, ZeroXAgg,
+};
 use batch::{Cowswap, UniswapX, ZeroXBatch};
 use brontes_types::normalized_actions::{Action, MultiCallFrameClassification, MultiFrameRequest};
-use flash_loan::{BalancerV2, MakerDss};
+use flash_loan::{BalancerV2, MakerDss, UniswapV3};
 use itertools::Itertools;
-use liquidations::{AaveV2, AaveV3};
+use liquidations::{AaveV2, AaveV3, EulerV2};
 use tracing::debug;
 
 use self::flash_loan::Dodo;
@@ -38,16 +41,22 @@ pub fn parse_multi_frame_requests(
         .into_iter()
         .filter_map(|request| match request.make_key() {
             OneInchAggregator::KEY => OneInchAggregator::create_classifier(request),
+            OneInchV6Aggregator::KEY => OneInchV6Aggregator::create_classifier(request),
             OneInchFusion::KEY => OneInchFusion::create_classifier(request),
             UniswapX::KEY => UniswapX::create_classifier(request),
             Cowswap::KEY => Cowswap::create_classifier(request),
             BalancerV2::KEY => BalancerV2::create_classifier(request),
             AaveV2::KEY => AaveV2::create_classifier(request),
             AaveV3::KEY => AaveV3::create_classifier(request),
+            EulerV2::KEY => EulerV2::create_classifier(request),
             ZeroXAgg::KEY => ZeroXAgg::create_classifier(request),
             ZeroXBatch::KEY => ZeroXBatch::create_classifier(request),
+            ParaswapV5Agg::KEY => ParaswapV5Agg::create_classifier(request),
+            ParaswapV6Agg::KEY => ParaswapV6Agg::create_classifier(request),
+            KyberSwapAgg::KEY => KyberSwapAgg::create_classifier(request),
             MakerDss::KEY => MakerDss::create_classifier(request),
             Dodo::KEY => Dodo::create_classifier(request),
+            UniswapV3::KEY => UniswapV3::create_classifier(request),
             _ => {
                 debug!(?request, "no multi frame classification impl for this request");
                 None
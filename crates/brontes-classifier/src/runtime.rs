@@ -0,0 +1,798 @@
+//! Classifiers that are declared in a config file instead of with the
+//! `action_impl!` macro.
+//!
+//! Each entry points at a json abi, the selector of the function to classify
+//! and maps the decoded call and return values onto the fields of the
+//! normalized action. The file is loaded once at startup with
+//! [`load_runtime_classifiers`] and the classifiers are dispatched alongside
+//! the compiled ones. This makes it possible to support a simple protocol
+//! without recompiling brontes.
+//!
+//! ```toml
+//! [[classifier]]
+//! name      = "ExampleDexSwap"
+//! protocol  = "Unknown"
+//! addresses = ["0x0000000000000000000000000000000000000001"]
+//! abi_path  = "abis/ExampleDex.json"
+//! selector  = "0x5c11d795"
+//! action    = "swap"
+//!
+//! [classifier.fields]
+//! recipient  = "call.to"
+//! token_in   = "call.tokenIn"
+//! token_out  = "call.tokenOut"
+//! amount_in  = "call.amountIn"
+//! amount_out = "return.amountOut"
+//! ```
+//!
+//! A field is filled from one of:
+//!
+//! - `call.<name>` / `return.<name>`: a top level input or output param, by
+//!   name or by position for unnamed params
+//! - `target`, `from`, `msg_sender`: the addresses of the call frame
+//! - `msg_value`: the eth sent with the call
+//! - a literal address
+//!
+//! Swaps need `token_in`, `token_out`, `amount_in` and `amount_out`. Mints,
+//! burns and flash loans take a list of `tokens` and a matching list of
+//! `amounts`. `from` defaults to the caller, `pool` to the called contract
+//! and `recipient` / `receiver` to the caller.
+//!
+//! An entry without `addresses` applies to every contract the address to
+//! protocol table attributes to its `protocol`, so pools of a protocol that
+//! brontes already discovers are picked up without listing them one by one.
+//! Entries for a specific address take precedence over the protocol wide ones.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, OnceLock},
+};
+
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Address, FixedBytes, U256};
+use brontes_core::decoding::abi::decode_call_with_abi;
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_pricing::types::{DexPriceMsg, PoolUpdate};
+use brontes_types::{
+    normalized_actions::{
+        Action, NormalizedBurn, NormalizedFlashLoan, NormalizedMint, NormalizedSwap,
+    },
+    structured_trace::{CallFrameInfo, DecodedCallData, DecodedParams},
+    FastHashMap, Protocol, ToScaledRational,
+};
+use eyre::{eyre, WrapErr};
+use malachite::Rational;
+use serde::Deserialize;
+
+use crate::ActionCollection;
+
+static RUNTIME_CLASSIFIERS: OnceLock<RuntimeClassifiers> = OnceLock::new();
+
+/// Loads the classifiers declared in the given toml or json file. Can only be
+/// called once, before the first block is classified.
+pub fn load_runtime_classifiers(path: &Path) -> eyre::Result<()> {
+    let classifiers = RuntimeClassifiers::from_file(path)?;
+    tracing::info!(
+        classifiers = classifiers.len(),
+        path = %path.display(),
+        "loaded runtime classifiers"
+    );
+
+    RUNTIME_CLASSIFIERS
+        .set(classifiers)
+        .map_err(|_| eyre!("runtime classifiers were already loaded"))
+}
+
+/// The classifiers loaded at startup, if any
+pub fn runtime_classifiers() -> Option<&'static RuntimeClassifiers> {
+    RUNTIME_CLASSIFIERS.get()
+}
+
+#[derive(Debug, Deserialize)]
+struct RuntimeClassifierFile {
+    #[serde(rename = "classifier", default)]
+    classifiers: Vec<RuntimeClassifierConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeClassifierConfig {
+    pub name:      String,
+    /// Protocol the classified actions are attributed to
+    #[serde(default)]
+    pub protocol:  Protocol,
+    /// Contracts the classifier applies to. When empty it applies to every
+    /// contract of `protocol`
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+    /// The json abi inline
+    pub abi:       Option<String>,
+    /// Path to the json abi, relative to the config file
+    pub abi_path:  Option<PathBuf>,
+    pub selector:  FixedBytes<4>,
+    pub action:    ActionKind,
+    #[serde(default)]
+    pub fields:    FieldMappings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    Swap,
+    Mint,
+    Burn,
+    FlashLoan,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldMappings {
+    pub from:       Option<FieldSource>,
+    pub pool:       Option<FieldSource>,
+    pub recipient:  Option<FieldSource>,
+    pub receiver:   Option<FieldSource>,
+    pub token_in:   Option<FieldSource>,
+    pub token_out:  Option<FieldSource>,
+    pub amount_in:  Option<FieldSource>,
+    pub amount_out: Option<FieldSource>,
+    #[serde(default)]
+    pub tokens:     Vec<FieldSource>,
+    #[serde(default)]
+    pub amounts:    Vec<FieldSource>,
+}
+
+/// Where the value of a field comes from
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum FieldSource {
+    Call(String),
+    Return(String),
+    Target,
+    From,
+    MsgSender,
+    MsgValue,
+    Address(Address),
+}
+
+impl FromStr for FieldSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix("call.") {
+            return Ok(Self::Call(name.to_string()))
+        }
+        if let Some(name) = s.strip_prefix("return.") {
+            return Ok(Self::Return(name.to_string()))
+        }
+
+        match s {
+            "target" => Ok(Self::Target),
+            "from" => Ok(Self::From),
+            "msg_sender" => Ok(Self::MsgSender),
+            "msg_value" => Ok(Self::MsgValue),
+            _ => Address::from_str(s).map(Self::Address).map_err(|_| {
+                format!(
+                    "invalid field source {s}, expected call.<param>, return.<param>, target, \
+                     from, msg_sender, msg_value or an address"
+                )
+            }),
+        }
+    }
+}
+
+impl TryFrom<String> for FieldSource {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl FieldSource {
+    fn resolve_address(
+        &self,
+        decoded: &DecodedCallData,
+        info: &CallFrameInfo<'_>,
+    ) -> eyre::Result<Address> {
+        Ok(match self {
+            Self::Target => info.target_address,
+            Self::From => info.from_address,
+            Self::MsgSender => info.msg_sender,
+            Self::Address(address) => *address,
+            Self::MsgValue => eyre::bail!("msg_value can't be used as an address"),
+            Self::Call(_) | Self::Return(_) => {
+                let param = self.param(decoded)?;
+                Address::from_str(&param.value)
+                    .wrap_err_with(|| format!("{} is not an address", param.field_name))?
+            }
+        })
+    }
+
+    /// Signed values are taken by their magnitude, the direction is given by
+    /// the field they are mapped to.
+    fn resolve_amount(
+        &self,
+        decoded: &DecodedCallData,
+        info: &CallFrameInfo<'_>,
+    ) -> eyre::Result<U256> {
+        match self {
+            Self::MsgValue => Ok(info.msg_value),
+            Self::Call(_) | Self::Return(_) => {
+                let param = self.param(decoded)?;
+                U256::from_str(param.value.trim_start_matches('-'))
+                    .wrap_err_with(|| format!("{} is not an amount", param.field_name))
+            }
+            _ => eyre::bail!("{self:?} can't be used as an amount"),
+        }
+    }
+
+    fn param<'a>(&self, decoded: &'a DecodedCallData) -> eyre::Result<&'a DecodedParams> {
+        let (params, name) = match self {
+            Self::Call(name) => (&decoded.call_data, name),
+            Self::Return(name) => (&decoded.return_data, name),
+            _ => unreachable!("only call and return sources are decoded"),
+        };
+
+        params
+            .iter()
+            .find(|p| &p.field_name == name)
+            .or_else(|| name.parse::<usize>().ok().and_then(|i| params.get(i)))
+            .ok_or_else(|| eyre!("{} has no param {name}", decoded.function_name))
+    }
+}
+
+/// The fields of a classifier, checked against the action it produces when
+/// the config is loaded
+#[derive(Debug)]
+enum ActionMapping {
+    Swap {
+        recipient:  FieldSource,
+        token_in:   FieldSource,
+        token_out:  FieldSource,
+        amount_in:  FieldSource,
+        amount_out: FieldSource,
+    },
+    Mint {
+        recipient: FieldSource,
+        tokens:    Vec<FieldSource>,
+        amounts:   Vec<FieldSource>,
+    },
+    Burn {
+        recipient: FieldSource,
+        tokens:    Vec<FieldSource>,
+        amounts:   Vec<FieldSource>,
+    },
+    FlashLoan {
+        receiver: FieldSource,
+        tokens:   Vec<FieldSource>,
+        amounts:  Vec<FieldSource>,
+    },
+}
+
+impl ActionMapping {
+    fn new(name: &str, kind: ActionKind, fields: FieldMappings) -> eyre::Result<Self> {
+        let required = |field: Option<FieldSource>, field_name: &str| {
+            field.ok_or_else(|| eyre!("{kind:?} classifier {name} is missing {field_name}"))
+        };
+
+        if matches!(kind, ActionKind::Swap) {
+            return Ok(Self::Swap {
+                recipient:  fields.recipient.unwrap_or(FieldSource::From),
+                token_in:   required(fields.token_in, "token_in")?,
+                token_out:  required(fields.token_out, "token_out")?,
+                amount_in:  required(fields.amount_in, "amount_in")?,
+                amount_out: required(fields.amount_out, "amount_out")?,
+            })
+        }
+
+        let FieldMappings { recipient, receiver, tokens, amounts, .. } = fields;
+        if tokens.is_empty() || tokens.len() != amounts.len() {
+            eyre::bail!("{kind:?} classifier {name} needs a matching amount for every token")
+        }
+
+        Ok(match kind {
+            ActionKind::Mint => {
+                Self::Mint { recipient: recipient.unwrap_or(FieldSource::From), tokens, amounts }
+            }
+            ActionKind::Burn => {
+                Self::Burn { recipient: recipient.unwrap_or(FieldSource::From), tokens, amounts }
+            }
+            ActionKind::FlashLoan => {
+                Self::FlashLoan { receiver: receiver.unwrap_or(FieldSource::From), tokens, amounts }
+            }
+            ActionKind::Swap => unreachable!(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct RuntimeClassifier {
+    name:     String,
+    protocol: Protocol,
+    abi:      JsonAbi,
+    from:     FieldSource,
+    pool:     FieldSource,
+    action:   ActionMapping,
+}
+
+impl RuntimeClassifier {
+    fn new(config: RuntimeClassifierConfig, base_dir: &Path) -> eyre::Result<Self> {
+        let RuntimeClassifierConfig {
+            name, protocol, abi, abi_path, selector, action, fields, ..
+        } = config;
+
+        let abi = match (abi, abi_path) {
+            (Some(abi), None) => abi,
+            (None, Some(path)) => std::fs::read_to_string(base_dir.join(&path))
+                .wrap_err_with(|| format!("failed to read abi {}", path.display()))?,
+            _ => eyre::bail!("classifier {name} needs exactly one of abi or abi_path"),
+        };
+        let abi: JsonAbi = serde_json::from_str(&abi)
+            .wrap_err_with(|| format!("classifier {name} has an invalid abi"))?;
+
+        if !abi.functions().any(|f| f.selector() == selector) {
+            eyre::bail!("the abi of classifier {name} has no function with selector {selector}")
+        }
+
+        let from = fields.from.clone().unwrap_or(FieldSource::From);
+        let pool = fields.pool.clone().unwrap_or(FieldSource::Target);
+        let action = ActionMapping::new(&name, action, fields)?;
+
+        Ok(Self { name, protocol, abi, from, pool, action })
+    }
+
+    fn decode_call_trace<DB: LibmdbxReader + DBWriter>(
+        &self,
+        call_info: CallFrameInfo<'_>,
+        block: u64,
+        tx_idx: u64,
+        db_tx: &DB,
+    ) -> eyre::Result<DexPriceMsg> {
+        let output = (!call_info.return_data.is_empty()).then_some(&call_info.return_data[..]);
+        let decoded = decode_call_with_abi(&self.abi, &call_info.call_data, output)?
+            .ok_or_else(|| eyre!("call doesn't match the abi"))?;
+
+        let info = &call_info;
+        let from = self.from.resolve_address(&decoded, info)?;
+        let pool = self.pool.resolve_address(&decoded, info)?;
+
+        let tokens_and_amounts = |tokens: &[FieldSource], amounts: &[FieldSource]| {
+            tokens
+                .iter()
+                .zip(amounts)
+                .map(|(token, amount)| {
+                    let token =
+                        db_tx.try_fetch_token_info(token.resolve_address(&decoded, info)?)?;
+                    let amount = amount
+                        .resolve_amount(&decoded, info)?
+                        .to_scaled_rational(token.decimals);
+
+                    Ok((token, amount))
+                })
+                .collect::<eyre::Result<Vec<_>>>()
+                .map(|res| res.into_iter().unzip::<_, Rational, Vec<_>, Vec<_>>())
+        };
+
+        let action = match &self.action {
+            ActionMapping::Swap { recipient, token_in, token_out, amount_in, amount_out } => {
+                let token_in =
+                    db_tx.try_fetch_token_info(token_in.resolve_address(&decoded, info)?)?;
+                let token_out =
+                    db_tx.try_fetch_token_info(token_out.resolve_address(&decoded, info)?)?;
+
+                Action::Swap(NormalizedSwap {
+                    protocol: self.protocol,
+                    trace_index: info.trace_idx,
+                    from,
+                    recipient: recipient.resolve_address(&decoded, info)?,
+                    pool,
+                    amount_in: amount_in
+                        .resolve_amount(&decoded, info)?
+                        .to_scaled_rational(token_in.decimals),
+                    amount_out: amount_out
+                        .resolve_amount(&decoded, info)?
+                        .to_scaled_rational(token_out.decimals),
+                    token_in,
+                    token_out,
+                    msg_value: info.msg_value,
+                })
+            }
+            ActionMapping::Mint { recipient, tokens, amounts } => {
+                let (token, amount) = tokens_and_amounts(tokens, amounts)?;
+                Action::Mint(NormalizedMint {
+                    protocol: self.protocol,
+                    trace_index: info.trace_idx,
+                    from,
+                    recipient: recipient.resolve_address(&decoded, info)?,
+                    pool,
+                    token,
+                    amount,
+                })
+            }
+            ActionMapping::Burn { recipient, tokens, amounts } => {
+                let (token, amount) = tokens_and_amounts(tokens, amounts)?;
+                Action::Burn(NormalizedBurn {
+                    protocol: self.protocol,
+                    trace_index: info.trace_idx,
+                    from,
+                    recipient: recipient.resolve_address(&decoded, info)?,
+                    pool,
+                    token,
+                    amount,
+                })
+            }
+            ActionMapping::FlashLoan { receiver, tokens, amounts } => {
+                let (assets, amounts) = tokens_and_amounts(tokens, amounts)?;
+                Action::FlashLoan(NormalizedFlashLoan {
+                    protocol: self.protocol,
+                    trace_index: info.trace_idx,
+                    from,
+                    pool,
+                    receiver_contract: receiver.resolve_address(&decoded, info)?,
+                    assets,
+                    amounts,
+                    aave_mode: None,
+                    child_actions: vec![],
+                    repayments: vec![],
+                    fees_paid: vec![],
+                    msg_value: info.msg_value,
+                })
+            }
+        };
+
+        Ok(DexPriceMsg::Update(PoolUpdate {
+            block,
+            tx_idx,
+            logs: call_info.logs.clone().to_vec(),
+            action,
+            state_diff: None,
+        }))
+    }
+}
+
+/// All loaded classifiers, keyed by the contract or protocol and the function
+/// they classify
+#[derive(Debug, Default)]
+pub struct RuntimeClassifiers {
+    classifiers:          FastHashMap<(Address, FixedBytes<4>), Arc<RuntimeClassifier>>,
+    protocol_classifiers: FastHashMap<(Protocol, FixedBytes<4>), Arc<RuntimeClassifier>>,
+}
+
+impl RuntimeClassifiers {
+    pub fn from_file(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        let file: RuntimeClassifierFile = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&contents)?
+        } else {
+            toml::from_str(&contents)?
+        };
+
+        Self::new(file.classifiers, path.parent().unwrap_or(Path::new(".")))
+    }
+
+    pub fn new(configs: Vec<RuntimeClassifierConfig>, base_dir: &Path) -> eyre::Result<Self> {
+        let mut classifiers = FastHashMap::default();
+        let mut protocol_classifiers = FastHashMap::default();
+        for config in configs {
+            let addresses = config.addresses.clone();
+            let selector = config.selector;
+            let classifier = Arc::new(RuntimeClassifier::new(config, base_dir)?);
+
+            if addresses.is_empty() {
+                let protocol = classifier.protocol;
+                if protocol == Protocol::Unknown {
+                    eyre::bail!(
+                        "classifier {} needs either addresses or a protocol",
+                        classifier.name
+                    )
+                }
+
+                if let Some(other) =
+                    protocol_classifiers.insert((protocol, selector), classifier.clone())
+                {
+                    eyre::bail!(
+                        "{} and {} both classify {selector} on {protocol}",
+                        other.name,
+                        classifier.name
+                    )
+                }
+            }
+
+            for address in addresses {
+                if let Some(other) = classifiers.insert((address, selector), classifier.clone()) {
+                    eyre::bail!(
+                        "{} and {} both classify {selector} on {address:?}",
+                        other.name,
+                        classifier.name
+                    )
+                }
+            }
+        }
+
+        Ok(Self { classifiers, protocol_classifiers })
+    }
+
+    pub fn len(&self) -> usize {
+        self.classifiers.len() + self.protocol_classifiers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classifiers.is_empty() && self.protocol_classifiers.is_empty()
+    }
+
+    /// Whether one of the classifiers applies to the call
+    pub fn handles<DB: LibmdbxReader>(&self, call_info: &CallFrameInfo<'_>, db_tx: &DB) -> bool {
+        self.classifier_for(call_info, db_tx).is_some()
+    }
+
+    /// The classifier for the called contract, falling back to the one of the
+    /// protocol the contract belongs to. The protocol is only looked up when a
+    /// protocol wide classifier exists for the selector.
+    fn classifier_for<DB: LibmdbxReader>(
+        &self,
+        call_info: &CallFrameInfo<'_>,
+        db_tx: &DB,
+    ) -> Option<&Arc<RuntimeClassifier>> {
+        let selector = Self::selector(call_info)?;
+        if let Some(classifier) = self.classifiers.get(&(call_info.target_address, selector)) {
+            return Some(classifier)
+        }
+
+        if !self
+            .protocol_classifiers
+            .keys()
+            .any(|(_, sel)| *sel == selector)
+        {
+            return None
+        }
+
+        let protocol = db_tx.get_protocol(call_info.target_address).ok()?;
+        self.protocol_classifiers.get(&(protocol, selector))
+    }
+
+    fn selector(call_info: &CallFrameInfo<'_>) -> Option<FixedBytes<4>> {
+        (call_info.call_data.len() >= 4)
+            .then(|| FixedBytes::<4>::from_slice(&call_info.call_data[0..4]))
+    }
+}
+
+impl ActionCollection for RuntimeClassifiers {
+    fn dispatch<DB: LibmdbxReader + DBWriter>(
+        &self,
+        call_info: CallFrameInfo<'_>,
+        db_tx: &DB,
+        block: u64,
+        tx_idx: u64,
+    ) -> Option<(DexPriceMsg, Action)> {
+        let selector = Self::selector(&call_info)?;
+        let target_address = call_info.target_address;
+        let classifier = self.classifier_for(&call_info, db_tx)?;
+
+        classifier
+            .decode_call_trace(call_info, block, tx_idx, db_tx)
+            .map(|res| {
+                let action = res.get_action();
+                Some((res, action))
+            })
+            .unwrap_or_else(|e| {
+                tracing::error!(error=%e,
+                    "runtime classifier: {} failed on function sig: {} for address: {:?}",
+                    classifier.name,
+                    selector,
+                    target_address,
+                );
+                None
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+    };
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [[classifier]]
+        name      = "ExampleDexSwap"
+        addresses = ["0x0000000000000000000000000000000000000001"]
+        selector  = "0x6e81221c"
+        action    = "swap"
+        abi       = '''
+        [{
+            "type": "function",
+            "name": "swap",
+            "stateMutability": "nonpayable",
+            "inputs": [
+                { "name": "tokenIn", "type": "address" },
+                { "name": "tokenOut", "type": "address" },
+                { "name": "amountIn", "type": "uint256" },
+                { "name": "to", "type": "address" }
+            ],
+            "outputs": [{ "name": "amountOut", "type": "uint256" }]
+        }]
+        '''
+
+        [classifier.fields]
+        recipient  = "call.to"
+        token_in   = "call.tokenIn"
+        token_out  = "call.tokenOut"
+        amount_in  = "call.amountIn"
+        amount_out = "return.0"
+    "#;
+
+    /// applies to every uniswap v2 pool, none of which implement this swap
+    const PROTOCOL_CONFIG: &str = r#"
+        [[classifier]]
+        name     = "ExampleProtocolSwap"
+        protocol = "UniswapV2"
+        selector = "0xd5bcb9b5"
+        action   = "swap"
+        abi      = '''
+        [{
+            "type": "function",
+            "name": "swap",
+            "stateMutability": "nonpayable",
+            "inputs": [
+                { "name": "tokenIn", "type": "address" },
+                { "name": "tokenOut", "type": "address" },
+                { "name": "amountIn", "type": "uint256" },
+                { "name": "amountOut", "type": "uint256" },
+                { "name": "to", "type": "address" }
+            ],
+            "outputs": []
+        }]
+        '''
+
+        [classifier.fields]
+        recipient  = "call.to"
+        token_in   = "call.tokenIn"
+        token_out  = "call.tokenOut"
+        amount_in  = "call.amountIn"
+        amount_out = "call.amountOut"
+    "#;
+
+    fn load(config: &str) -> eyre::Result<RuntimeClassifiers> {
+        let file: RuntimeClassifierFile = toml::from_str(config)?;
+        RuntimeClassifiers::new(file.classifiers, Path::new("."))
+    }
+
+    #[test]
+    fn test_parse_field_source() {
+        assert_eq!("call.amountIn".parse(), Ok(FieldSource::Call("amountIn".into())));
+        assert_eq!("return.0".parse(), Ok(FieldSource::Return("0".into())));
+        assert_eq!("msg_sender".parse(), Ok(FieldSource::MsgSender));
+        assert_eq!(
+            "0x0000000000000000000000000000000000000002".parse(),
+            Ok(FieldSource::Address(Address::with_last_byte(2)))
+        );
+        assert!("amountIn".parse::<FieldSource>().is_err());
+    }
+
+    #[test]
+    fn test_load_and_resolve_swap() {
+        let classifiers = load(CONFIG).unwrap();
+        let classifier = classifiers
+            .classifiers
+            .get(&(Address::with_last_byte(1), FixedBytes(hex!("6e81221c"))))
+            .unwrap();
+
+        let ActionMapping::Swap { recipient, amount_in, amount_out, .. } = &classifier.action
+        else {
+            panic!("expected a swap mapping")
+        };
+
+        let call_data = [
+            hex!("6e81221c").to_vec(),
+            Address::with_last_byte(2).into_word().to_vec(),
+            Address::with_last_byte(3).into_word().to_vec(),
+            U256::from(100).to_be_bytes_vec(),
+            Address::with_last_byte(4).into_word().to_vec(),
+        ]
+        .concat();
+        let return_data = U256::from(99).to_be_bytes_vec();
+
+        let decoded = decode_call_with_abi(&classifier.abi, &call_data, Some(&return_data))
+            .unwrap()
+            .unwrap();
+        let info = CallFrameInfo {
+            trace_idx:      0,
+            call_data:      call_data.into(),
+            return_data:    return_data.into(),
+            target_address: Address::with_last_byte(1),
+            from_address:   Address::with_last_byte(5),
+            logs:           &[],
+            delegate_logs:  vec![],
+            msg_sender:     Address::with_last_byte(5),
+            msg_value:      U256::ZERO,
+        };
+
+        assert_eq!(recipient.resolve_address(&decoded, &info).unwrap(), Address::with_last_byte(4));
+        assert_eq!(amount_in.resolve_amount(&decoded, &info).unwrap(), U256::from(100));
+        assert_eq!(amount_out.resolve_amount(&decoded, &info).unwrap(), U256::from(99));
+        assert_eq!(classifier.pool.resolve_address(&decoded, &info).unwrap(), info.target_address);
+    }
+
+    #[test]
+    fn test_missing_swap_field() {
+        let config = CONFIG.replace("amount_out = \"return.0\"", "");
+        assert!(load(&config).is_err());
+    }
+
+    #[test]
+    fn test_missing_addresses_and_protocol() {
+        let config = PROTOCOL_CONFIG.replace("protocol = \"UniswapV2\"", "");
+        assert!(load(&config).is_err());
+    }
+
+    /// a pool that isn't listed in the config is classified through the
+    /// protocol the address to protocol table has for it
+    #[brontes_macros::test]
+    async fn test_protocol_keyed_classifier() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        let classifiers = load(PROTOCOL_CONFIG).unwrap();
+
+        let pool = Address::new(hex!("B4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+        let router = Address::repeat_byte(0x11);
+        classifier_utils.ensure_protocol(
+            Protocol::UniswapV2,
+            pool,
+            USDC_ADDRESS,
+            Some(WETH_ADDRESS),
+            None,
+            None,
+            None,
+            None,
+        );
+        classifier_utils.ensure_token(TokenInfoWithAddress::usdc());
+        classifier_utils.ensure_token(TokenInfoWithAddress::weth());
+
+        let call_data = [
+            hex!("d5bcb9b5").to_vec(),
+            WETH_ADDRESS.into_word().to_vec(),
+            USDC_ADDRESS.into_word().to_vec(),
+            U256::from(1_000_000_000_000_000_000u64).to_be_bytes_vec(),
+            U256::from(3_000_000_000u64).to_be_bytes_vec(),
+            router.into_word().to_vec(),
+        ]
+        .concat();
+
+        let info = CallFrameInfo {
+            trace_idx:      1,
+            call_data:      call_data.into(),
+            return_data:    Default::default(),
+            target_address: pool,
+            from_address:   router,
+            logs:           &[],
+            delegate_logs:  vec![],
+            msg_sender:     router,
+            msg_value:      U256::ZERO,
+        };
+        assert!(classifiers.handles(&info, classifier_utils.trace_loader.libmdbx));
+
+        let eq_action = Action::Swap(NormalizedSwap {
+            protocol: Protocol::UniswapV2,
+            trace_index: 1,
+            from: router,
+            recipient: router,
+            pool,
+            token_in: classifier_utils.get_token_info(WETH_ADDRESS),
+            amount_in: Rational::from(1),
+            token_out: classifier_utils.get_token_info(USDC_ADDRESS),
+            amount_out: Rational::from(3_000),
+            msg_value: U256::ZERO,
+        });
+
+        let (_, action) = classifiers
+            .dispatch(info, classifier_utils.trace_loader.libmdbx, 19_000_000, 0)
+            .unwrap();
+        assert_eq!(action, eq_action);
+    }
+}
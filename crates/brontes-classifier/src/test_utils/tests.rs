@@ -24,12 +24,13 @@ use brontes_types::{
         address_to_protocol_info::ProtocolInfo, dex::DexQuotes, token_info::TokenInfoWithAddress,
     },
     normalized_actions::{pool::NormalizedNewPool, NormalizedTransfer},
-    structured_trace::TraceActions,
+    structured_trace::{CallFrameInfo, TraceActions, TxTrace},
     tree::BlockTree,
     BrontesTaskManager, FastHashMap, TreeCollector, TreeSearchBuilder, UnboundedYapperReceiver,
 };
 use futures::{future::join_all, StreamExt};
 use reth_db::DatabaseError;
+use reth_primitives::Header;
 use serde_json::Value;
 use thiserror::Error;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
@@ -196,6 +197,7 @@ impl ClassifierTestUtils {
                 tx_idx: 0,
                 logs: vec![],
                 action: make_fake_transfer(*token),
+                state_diff: None,
             });
             tx.send(update).unwrap();
         });
@@ -254,6 +256,7 @@ impl ClassifierTestUtils {
                 tx_idx: 0,
                 logs: vec![],
                 action: make_fake_transfer(*token),
+                state_diff: None,
             });
             tx.send(update).unwrap();
         });
@@ -291,6 +294,7 @@ impl ClassifierTestUtils {
                 tx_idx: 0,
                 logs: vec![],
                 action: make_fake_transfer(*token),
+                state_diff: None,
             });
 
             tx.send(update).unwrap();
@@ -376,6 +380,7 @@ impl ClassifierTestUtils {
                     tx_idx: 0,
                     logs: vec![],
                     action: make_fake_transfer(*token),
+                    state_diff: None,
                 });
                 tx.send(update).unwrap();
             });
@@ -430,6 +435,7 @@ impl ClassifierTestUtils {
                 tx_idx: 0,
                 logs: vec![],
                 action: make_fake_transfer(*token),
+                state_diff: None,
             });
             tx.send(update).unwrap();
         });
@@ -537,6 +543,40 @@ impl ClassifierTestUtils {
         Ok(())
     }
 
+    /// classifies hand built traces through the full tree builder, for
+    /// actions there is no mainnet tx for
+    pub async fn build_tree_from_traces(
+        &self,
+        traces: Vec<TxTrace>,
+        header: Header,
+    ) -> BlockTree<Action> {
+        self.classifier.build_block_tree(traces, header, true).await
+    }
+
+    /// [`Self::contains_action`] over a hand built trace
+    pub async fn contains_action_in_trace(
+        &self,
+        trace: TxTrace,
+        header: Header,
+        action_number_in_tx: usize,
+        eq_action: Action,
+        tree_collect_builder: TreeSearchBuilder<Action>,
+    ) {
+        let mut tree = self.build_tree_from_traces(vec![trace], header).await;
+        assert!(!tree.tx_roots.is_empty(), "empty tree. the trace most likely reverted");
+
+        let root = tree.tx_roots.remove(0);
+        let mut actions = root.collect(&tree_collect_builder);
+        assert!(
+            actions.len() > action_number_in_tx,
+            "missing action. protocol is either missing from db or not added to dispatch, got: \
+             {actions:#?}"
+        );
+
+        let action = actions.remove(action_number_in_tx);
+        assert_eq!(eq_action, action, "got: {:#?} != given: {:#?}", action, eq_action);
+    }
+
     pub async fn has_no_actions(
         &self,
         tx_hash: TxHash,
@@ -584,6 +624,20 @@ impl ClassifierTestUtils {
         Ok(())
     }
 
+    /// runs the protocol classifier over a hand built call frame. The protocol
+    /// at the frames target address needs to be in the db
+    pub fn test_call_frame_classification(
+        &self,
+        call_info: CallFrameInfo<'_>,
+        block: u64,
+        cmp_fn: impl Fn(Option<Action>),
+    ) {
+        let dispatcher = ProtocolClassifier::default();
+        let result = dispatcher.dispatch(call_info, self.trace_loader.libmdbx, block, 0);
+
+        cmp_fn(result.map(|i| i.1));
+    }
+
     pub async fn test_discovery_classification(
         &self,
         txes: TxHash,
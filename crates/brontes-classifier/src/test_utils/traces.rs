@@ -0,0 +1,199 @@
+use alloy_primitives::{Address, Bytes, Log, B256, U256, U64};
+use alloy_sol_types::{SolCall, SolEvent};
+use brontes_types::structured_trace::{TransactionTraceWithLogs, TxTrace};
+use reth_rpc_types::trace::parity::{
+    Action as TraceAction, CallAction, CallOutput, CallType, CreateAction, CreateOutput,
+    TraceOutput, TransactionTrace,
+};
+
+/// Builds the trace of a single tx frame by frame, for running actions we have
+/// no mainnet tx for through the full tree builder. Frames are added depth
+/// first, in the order they were executed.
+pub struct TxTraceBuilder {
+    tx_hash: B256,
+    traces:  Vec<TransactionTraceWithLogs>,
+}
+
+impl TxTraceBuilder {
+    pub fn new(tx_hash: B256) -> Self {
+        Self { tx_hash, traces: vec![] }
+    }
+
+    pub fn call(
+        self,
+        trace_address: &[usize],
+        from: Address,
+        to: Address,
+        input: impl Into<Bytes>,
+        logs: Vec<Log>,
+    ) -> Self {
+        self.call_with_type(trace_address, from, to, input.into(), logs, CallType::Call)
+    }
+
+    /// delegate calls run in the context of the caller, so `from` is the
+    /// address of the caller and the logs are emitted by it
+    pub fn delegate_call(
+        self,
+        trace_address: &[usize],
+        from: Address,
+        to: Address,
+        input: impl Into<Bytes>,
+        logs: Vec<Log>,
+    ) -> Self {
+        self.call_with_type(trace_address, from, to, input.into(), logs, CallType::DelegateCall)
+    }
+
+    /// sets the return data of the last added call
+    pub fn with_output(mut self, output: impl Into<Bytes>) -> Self {
+        if let Some(TraceOutput::Call(call)) = self
+            .traces
+            .last_mut()
+            .and_then(|trace| trace.trace.result.as_mut())
+        {
+            call.output = output.into();
+        }
+        self
+    }
+
+    pub fn create(mut self, trace_address: &[usize], from: Address, created: Address) -> Self {
+        let action = TraceAction::Create(CreateAction {
+            from,
+            value: U256::ZERO,
+            gas: U64::ZERO,
+            init: Bytes::new(),
+        });
+        let output = TraceOutput::Create(CreateOutput {
+            gas_used: U64::ZERO,
+            code:     Bytes::new(),
+            address:  created,
+        });
+
+        let msg_sender = self.msg_sender(trace_address, from, false);
+        self.push(trace_address, action, output, vec![], msg_sender);
+        self
+    }
+
+    pub fn build(mut self, block: u64, tx_index: u64) -> TxTrace {
+        let trace_addresses = self
+            .traces
+            .iter()
+            .map(|trace| trace.trace.trace_address.clone())
+            .collect::<Vec<_>>();
+
+        for trace in &mut self.traces {
+            let parent = &trace.trace.trace_address;
+            trace.trace.subtraces = trace_addresses
+                .iter()
+                .filter(|address| address.len() == parent.len() + 1 && address.starts_with(parent))
+                .count();
+        }
+
+        TxTrace::new(block, self.traces, self.tx_hash, tx_index, 0, 0, true, vec![])
+    }
+
+    fn call_with_type(
+        mut self,
+        trace_address: &[usize],
+        from: Address,
+        to: Address,
+        input: Bytes,
+        logs: Vec<Log>,
+        call_type: CallType,
+    ) -> Self {
+        let msg_sender = self.msg_sender(trace_address, from, call_type == CallType::DelegateCall);
+        let action = TraceAction::Call(CallAction {
+            from,
+            to,
+            value: U256::ZERO,
+            gas: U64::ZERO,
+            input,
+            call_type,
+        });
+        let output = TraceOutput::Call(CallOutput { gas_used: U64::ZERO, output: Bytes::new() });
+
+        self.push(trace_address, action, output, logs, msg_sender);
+        self
+    }
+
+    /// the msg.sender of a delegate call is the one of the frame it was made
+    /// from
+    fn msg_sender(&self, trace_address: &[usize], from: Address, is_delegate: bool) -> Address {
+        if !is_delegate {
+            return from
+        }
+
+        trace_address
+            .split_last()
+            .and_then(|(_, parent)| {
+                self.traces
+                    .iter()
+                    .find(|trace| trace.trace.trace_address == parent)
+            })
+            .map(|parent| parent.msg_sender)
+            .unwrap_or(from)
+    }
+
+    fn push(
+        &mut self,
+        trace_address: &[usize],
+        action: TraceAction,
+        output: TraceOutput,
+        logs: Vec<Log>,
+        msg_sender: Address,
+    ) {
+        let trace_idx = self.traces.len() as u64;
+        self.traces.push(TransactionTraceWithLogs {
+            trace: TransactionTrace {
+                action,
+                error: None,
+                result: Some(output),
+                subtraces: 0,
+                trace_address: trace_address.to_vec(),
+            },
+            logs,
+            msg_sender,
+            trace_idx,
+            decoded_data: None,
+        });
+    }
+}
+
+/// The call data of a swap on a uniswap v2 pool and the swap log it emits.
+/// Swaps token0 for token1 if `zero_for_one`, the other way around otherwise
+pub fn uniswap_v2_swap(
+    pool: Address,
+    sender: Address,
+    recipient: Address,
+    zero_for_one: bool,
+    amount_in: U256,
+    amount_out: U256,
+) -> (Bytes, Vec<Log>) {
+    let (amount0_in, amount1_in, amount0_out, amount1_out) = if zero_for_one {
+        (amount_in, U256::ZERO, U256::ZERO, amount_out)
+    } else {
+        (U256::ZERO, amount_in, amount_out, U256::ZERO)
+    };
+
+    let call_data = crate::UniswapV2::swapCall {
+        amount0Out: amount0_out,
+        amount1Out: amount1_out,
+        to:         recipient,
+        data:       Bytes::new(),
+    }
+    .abi_encode();
+
+    let logs = vec![Log {
+        address: pool,
+        data:    crate::UniswapV2::Swap {
+            sender,
+            amount0In: amount0_in,
+            amount1In: amount1_in,
+            amount0Out: amount0_out,
+            amount1Out: amount1_out,
+            to: recipient,
+        }
+        .encode_log_data(),
+    }];
+
+    (call_data.into(), logs)
+}
@@ -9,7 +9,7 @@ use brontes_types::{
         NormalizedEthTransfer, NormalizedTransfer,
     },
     tree::root::NodeData,
-    ToScaledRational,
+    FastHashSet, Protocol, ToScaledRational,
 };
 use malachite::{num::basic::traits::Zero, Rational};
 
@@ -35,8 +35,8 @@ use utils::{decode_transfer, get_coinbase_transfer};
 
 use self::erc20::try_decode_transfer;
 use crate::{
-    classifiers::*, multi_frame_classification::parse_multi_frame_requests, ActionCollection,
-    FactoryDiscoveryDispatch,
+    classifiers::*, multi_frame_classification::parse_multi_frame_requests,
+    runtime::runtime_classifiers, ActionCollection, FactoryDiscoveryDispatch,
 };
 
 //TODO: Document this module
@@ -120,6 +120,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 tx_idx: root_count as u64,
                 logs: vec![],
                 action: Action::EthTransfer(NormalizedEthTransfer::default()),
+                state_diff: None,
             }))
             .unwrap();
 
@@ -246,6 +247,20 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                         tx_root.insert(node, classification);
                     }
 
+                    // lets the pools sync from the storage they were left with, if the
+                    // trace recorded it. the diff is the state at the end of the tx, so
+                    // it only belongs to the last update of each pool
+                    let mut synced_pools = FastHashSet::default();
+                    for update in pool_updates.iter_mut().rev() {
+                        if let DexPriceMsg::Update(update) = update {
+                            update.state_diff = update
+                                .get_pool_address_for_pricing()
+                                .filter(|pool| synced_pools.insert(*pool))
+                                .and_then(|pool| trace.state_diff_for(pool))
+                                .cloned();
+                        }
+                    }
+
                     // Here we reverse the requests to ensure that we always classify the most
                     // nested action & its children first. This is to prevent the
                     // case where we classify a parent action where its children also require
@@ -386,9 +401,16 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
             }
         }
 
-        if let Some(results) =
-            ProtocolClassifier::default().dispatch(call_info, self.libmdbx, block, tx_idx)
+        // classifiers loaded from the config take precedence for the calls they are
+        // declared for
+        let results = match runtime_classifiers()
+            .filter(|runtime| runtime.handles(&call_info, self.libmdbx))
         {
+            Some(runtime) => runtime.dispatch(call_info, self.libmdbx, block, tx_idx),
+            None => ProtocolClassifier::default().dispatch(call_info, self.libmdbx, block, tx_idx),
+        };
+
+        if let Some(results) = results {
             if results.1.is_new_pool() {
                 let Action::NewPool(p) = &results.1 else { unreachable!() };
                 self.insert_new_pool(block, p).await;
@@ -402,6 +424,15 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 {
                     error!(pool=?p.pool_address,"failed to update pool config");
                 }
+
+                // v4 pools need their full key to be able to query the pool state
+                if p.protocol == Protocol::UniswapV4 {
+                    if let Some(pool_key) = decode_v4_pool_key(block, &trace.logs) {
+                        if self.libmdbx.insert_v4_pool_key(pool_key).await.is_err() {
+                            error!(pool=?p.pool_address, "failed to insert v4 pool key");
+                        }
+                    }
+                }
             }
 
             (vec![results.0], vec![results.1])
@@ -486,6 +517,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                         tx_idx,
                         logs: vec![],
                         action: Action::Transfer(transfer.clone()),
+                        state_diff: None,
                     })],
                     result,
                 ))
@@ -515,6 +547,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                                 tx_idx,
                                 logs: vec![],
                                 action: Action::Transfer(transfer.clone()),
+                                state_diff: None,
                             })],
                             vec![Action::Transfer(transfer)],
                         ))
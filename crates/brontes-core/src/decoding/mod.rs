@@ -9,6 +9,7 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use self::parser::TraceParser;
 
+pub mod abi;
 #[cfg(feature = "dyn-decode")]
 mod dyn_decode;
 
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use alloy_provider::{Provider, RootProvider};
-use alloy_rpc_types::AnyReceiptEnvelope;
+use alloy_rpc_types::{AnyReceiptEnvelope, Block};
 use alloy_transport_http::Http;
 use brontes_types::{structured_trace::TxTrace, traits::TracingProvider};
 use itertools::Itertools;
@@ -108,34 +108,7 @@ impl TracingProvider for LocalProvider {
             .await?
             .ok_or_else(err)?;
 
-        let header = Header {
-            number:                   block.header.number.ok_or_else(err)?,
-            base_fee_per_gas:         block.header.base_fee_per_gas.map(|f| f as u64),
-            mix_hash:                 block.header.mix_hash.ok_or_else(err)?,
-            withdrawals_root:         block.header.withdrawals_root,
-            parent_beacon_block_root: block.header.parent_beacon_block_root,
-            nonce:                    block
-                .header
-                .nonce
-                .map(|i| u64::from_be_bytes(*i))
-                .ok_or_else(err)?,
-            gas_used:                 block.header.gas_used as u64,
-            gas_limit:                block.header.gas_limit as u64,
-            timestamp:                block.header.timestamp,
-            difficulty:               block.header.difficulty,
-            state_root:               block.header.state_root,
-            parent_hash:              block.header.parent_hash,
-            receipts_root:            block.header.receipts_root,
-            transactions_root:        block.header.transactions_root,
-            logs_bloom:               block.header.logs_bloom,
-            extra_data:               block.header.extra_data,
-            blob_gas_used:            block.header.blob_gas_used.map(|f| f as u64),
-            excess_blob_gas:          block.header.excess_blob_gas.map(|f| f as u64),
-            ommers_hash:              block.header.uncles_hash,
-            beneficiary:              block.header.miner,
-        };
-
-        Ok(Some(header))
+        rpc_block_to_header(block).map(Some)
     }
 
     async fn get_storage(
@@ -171,3 +144,35 @@ impl TracingProvider for LocalProvider {
         Ok(Some(bytecode))
     }
 }
+
+/// converts a rpc block into the reth header
+pub(crate) fn rpc_block_to_header(block: Block) -> eyre::Result<Header> {
+    let err = || eyre::eyre!("failed to unwrap option");
+
+    Ok(Header {
+        number:                   block.header.number.ok_or_else(err)?,
+        base_fee_per_gas:         block.header.base_fee_per_gas.map(|f| f as u64),
+        mix_hash:                 block.header.mix_hash.ok_or_else(err)?,
+        withdrawals_root:         block.header.withdrawals_root,
+        parent_beacon_block_root: block.header.parent_beacon_block_root,
+        nonce:                    block
+            .header
+            .nonce
+            .map(|i| u64::from_be_bytes(*i))
+            .ok_or_else(err)?,
+        gas_used:                 block.header.gas_used as u64,
+        gas_limit:                block.header.gas_limit as u64,
+        timestamp:                block.header.timestamp,
+        difficulty:               block.header.difficulty,
+        state_root:               block.header.state_root,
+        parent_hash:              block.header.parent_hash,
+        receipts_root:            block.header.receipts_root,
+        transactions_root:        block.header.transactions_root,
+        logs_bloom:               block.header.logs_bloom,
+        extra_data:               block.header.extra_data,
+        blob_gas_used:            block.header.blob_gas_used.map(|f| f as u64),
+        excess_blob_gas:          block.header.excess_blob_gas.map(|f| f as u64),
+        ommers_hash:              block.header.uncles_hash,
+        beneficiary:              block.header.miner,
+    })
+}
@@ -0,0 +1,244 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tracing::debug;
+
+/// base delay before retrying a failed request. doubles on every attempt up
+/// to 64x
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+/// JSON-RPC error codes nodes and providers answer with when we are rate
+/// limited. `-32005` is the standard limit exceeded code, some providers use
+/// the http status code instead
+const RETRYABLE_ERROR_CODES: [i64; 2] = [-32005, 429];
+
+#[derive(Debug, Serialize)]
+struct JsonRpcRequest<'a> {
+    jsonrpc: &'static str,
+    id:      u64,
+    method:  &'a str,
+    params:  Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    id:     u64,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error:  Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code:    i64,
+    message: String,
+}
+
+impl JsonRpcResponse {
+    fn into_result(self, method: &str) -> eyre::Result<Value> {
+        if let Some(err) = self.error {
+            eyre::bail!("{method} failed with code {}: {}", err.code, err.message)
+        }
+
+        Ok(self.result.unwrap_or(Value::Null))
+    }
+}
+
+/// Minimal JSON-RPC over http client. Every http request, batched or not, takes
+/// a permit so that we never have more than `max_concurrent_requests` in flight
+/// against the node. Transport errors, rate limits and server errors are
+/// retried with an exponential backoff. The permit is released while backing
+/// off so the other requests can go through.
+#[derive(Debug)]
+pub struct JsonRpcClient {
+    http:    reqwest::Client,
+    url:     String,
+    retries: u8,
+    permits: Semaphore,
+    ids:     AtomicU64,
+}
+
+impl JsonRpcClient {
+    pub fn new(url: String, max_concurrent_requests: usize, retries: u8) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url,
+            retries,
+            permits: Semaphore::new(max_concurrent_requests.max(1)),
+            ids: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn request<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> eyre::Result<R> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0",
+            id: self.ids.fetch_add(1, Ordering::Relaxed),
+            method,
+            params,
+        };
+        let response: JsonRpcResponse = self.send_with_retry(&request).await?;
+
+        Ok(serde_json::from_value(response.into_result(method)?)?)
+    }
+
+    /// sends all calls in a single http request. The results are returned in
+    /// the same order as the calls
+    pub async fn batch_request(&self, calls: Vec<(&str, Value)>) -> eyre::Result<Vec<Value>> {
+        let first_id = self.ids.fetch_add(calls.len() as u64, Ordering::Relaxed);
+        let methods = calls.iter().map(|(method, _)| *method).collect::<Vec<_>>();
+
+        let requests = calls
+            .into_iter()
+            .enumerate()
+            .map(|(i, (method, params))| JsonRpcRequest {
+                jsonrpc: "2.0",
+                id: first_id + i as u64,
+                method,
+                params,
+            })
+            .collect::<Vec<_>>();
+
+        let mut responses: Vec<JsonRpcResponse> = self.send_with_retry(&requests).await?;
+        if responses.len() != methods.len() {
+            eyre::bail!("expected {} batch responses, got {}", methods.len(), responses.len())
+        }
+        // nodes are free to answer a batch in any order
+        responses.sort_unstable_by_key(|res| res.id);
+
+        responses
+            .into_iter()
+            .zip(methods)
+            .map(|(res, method)| res.into_result(method))
+            .collect()
+    }
+
+    async fn send_with_retry<B: Serialize, R: RetryableResponse>(
+        &self,
+        body: &B,
+    ) -> eyre::Result<R> {
+        let mut attempts = 0;
+        loop {
+            let permit = self.permits.acquire().await?;
+            let res = self.send::<B, R>(body).await;
+            drop(permit);
+
+            match res {
+                Ok(res) if attempts < self.retries && res.is_retryable() => {
+                    debug!(target: "brontes::rpc", %attempts, "rpc request rate limited");
+                }
+                Ok(res) => return Ok(res),
+                Err(e) if attempts < self.retries && is_retryable_error(&e) => {
+                    debug!(target: "brontes::rpc", err=%e, %attempts, "rpc request failed");
+                }
+                Err(e) => return Err(e),
+            }
+
+            tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempts.min(6) as u32)).await;
+            attempts += 1;
+        }
+    }
+
+    async fn send<B: Serialize, R: DeserializeOwned>(&self, body: &B) -> eyre::Result<R> {
+        let response = self
+            .http
+            .post(&self.url)
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+}
+
+/// Transport errors, rate limits and server errors can go through on a retry,
+/// the other client errors and responses we can't decode never will
+fn is_retryable_error(err: &eyre::Report) -> bool {
+    let Some(err) = err.downcast_ref::<reqwest::Error>() else { return false };
+    match err.status() {
+        Some(status) => is_retryable_status(status),
+        None => !err.is_decode() && !err.is_builder(),
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Responses that can carry a JSON-RPC error worth retrying the request for
+trait RetryableResponse: DeserializeOwned {
+    fn is_retryable(&self) -> bool;
+}
+
+impl RetryableResponse for JsonRpcResponse {
+    fn is_retryable(&self) -> bool {
+        self.error
+            .as_ref()
+            .is_some_and(|err| RETRYABLE_ERROR_CODES.contains(&err.code))
+    }
+}
+
+/// a batch is retried as a whole if any of its calls was rate limited
+impl RetryableResponse for Vec<JsonRpcResponse> {
+    fn is_retryable(&self) -> bool {
+        self.iter().any(RetryableResponse::is_retryable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(json: Value) -> JsonRpcResponse {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_rate_limits_are_retryable() {
+        let ok = response(serde_json::json!({ "id": 0, "result": "0x1" }));
+        let limited = response(serde_json::json!({
+            "id": 1,
+            "error": { "code": -32005, "message": "limit exceeded" }
+        }));
+        let too_many = response(serde_json::json!({
+            "id": 2,
+            "error": { "code": 429, "message": "too many requests" }
+        }));
+        let reverted = response(serde_json::json!({
+            "id": 3,
+            "error": { "code": -32000, "message": "execution reverted" }
+        }));
+
+        assert!(!RetryableResponse::is_retryable(&ok));
+        assert!(RetryableResponse::is_retryable(&limited));
+        assert!(RetryableResponse::is_retryable(&too_many));
+        assert!(!RetryableResponse::is_retryable(&reverted));
+        // one rate limited call retries the whole batch
+        assert!(RetryableResponse::is_retryable(&vec![ok, limited]));
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_other_errors_are_not_retried() {
+        assert!(!is_retryable_error(&eyre::eyre!("invalid response")));
+    }
+}
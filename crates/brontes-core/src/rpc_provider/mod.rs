@@ -0,0 +1,272 @@
+//! A [`TracingProvider`] that works against any archive node over JSON-RPC.
+//!
+//! Traces are built from either `trace_replayBlockTransactions` (reth, erigon,
+//! nethermind) or `debug_traceBlockByNumber` with the `callTracer` (geth and
+//! most hosted providers) and translated into our [`TxTrace`] format.
+mod client;
+pub mod translate;
+
+use std::{fmt::Display, str::FromStr};
+
+use alloy_rpc_types::{AnyReceiptEnvelope, Block};
+use brontes_types::{structured_trace::TxTrace, traits::TracingProvider};
+use reth_primitives::{
+    Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue, TxHash,
+    B256, U64,
+};
+use reth_rpc_types::{
+    state::StateOverride, BlockOverrides, Log, TransactionReceipt, TransactionRequest,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+pub use self::client::JsonRpcClient;
+use self::translate::{
+    geth_to_tx_trace, parity_to_tx_trace, GethBlockTrace, ParityBlockTrace, RpcReceiptMeta,
+};
+use crate::local_provider::rpc_block_to_header;
+
+/// Which tracing namespace the node exposes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RpcTraceMode {
+    /// `trace_replayBlockTransactions`. Parity traces don't include logs, so
+    /// the receipt logs are matched back to the frames that emitted them
+    #[default]
+    Parity,
+    /// `debug_traceBlockByNumber` with the `callTracer` and logs enabled. The
+    /// `callTracer` doesn't report state changes, so the traces have no state
+    /// diff
+    Geth,
+}
+
+impl FromStr for RpcTraceMode {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "parity" | "trace" => Ok(Self::Parity),
+            "geth" | "debug" => Ok(Self::Geth),
+            _ => Err(eyre::eyre!("unknown rpc trace mode {s}, expected parity or geth")),
+        }
+    }
+}
+
+impl Display for RpcTraceMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parity => write!(f, "parity"),
+            Self::Geth => write!(f, "geth"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RpcTracingProvider {
+    client:     JsonRpcClient,
+    trace_mode: RpcTraceMode,
+}
+
+impl RpcTracingProvider {
+    pub fn new(
+        url: String,
+        trace_mode: RpcTraceMode,
+        max_concurrent_requests: usize,
+        retries: u8,
+    ) -> Self {
+        Self { client: JsonRpcClient::new(url, max_concurrent_requests, retries), trace_mode }
+    }
+
+    pub fn trace_mode(&self) -> RpcTraceMode {
+        self.trace_mode
+    }
+
+    /// fetches the block traces along with the receipts in a single batch. The
+    /// receipts give us the tx index, status and for parity traces the logs
+    async fn fetch_block_traces(&self, block_id: BlockId) -> eyre::Result<Option<Vec<TxTrace>>> {
+        let call_tracer = json!({ "tracer": "callTracer", "tracerConfig": { "withLog": true } });
+        let trace_call = match (self.trace_mode, block_id) {
+            (RpcTraceMode::Parity, _) => {
+                ("trace_replayBlockTransactions", json!([block_id, ["trace", "stateDiff"]]))
+            }
+            (RpcTraceMode::Geth, BlockId::Hash(hash)) => {
+                ("debug_traceBlockByHash", json!([hash.block_hash, call_tracer]))
+            }
+            (RpcTraceMode::Geth, BlockId::Number(number)) => {
+                ("debug_traceBlockByNumber", json!([number, call_tracer]))
+            }
+        };
+
+        let mut res = self
+            .client
+            .batch_request(vec![trace_call, ("eth_getBlockReceipts", json!([block_id]))])
+            .await?;
+        let (receipts, traces) = (res.pop().unwrap_or_default(), res.pop().unwrap_or_default());
+        if traces.is_null() || receipts.is_null() {
+            return Ok(None)
+        }
+
+        let receipts: Vec<RpcReceiptMeta> = serde_json::from_value(receipts)?;
+        let traces = match self.trace_mode {
+            RpcTraceMode::Parity => {
+                let traces: Vec<ParityBlockTrace> = serde_json::from_value(traces)?;
+                Self::check_trace_count(traces.len(), receipts.len())?;
+                traces
+                    .into_iter()
+                    .zip(receipts)
+                    .map(|(trace, receipt)| parity_to_tx_trace(trace, receipt))
+                    .collect()
+            }
+            RpcTraceMode::Geth => {
+                let traces: Vec<GethBlockTrace> = serde_json::from_value(traces)?;
+                Self::check_trace_count(traces.len(), receipts.len())?;
+                traces
+                    .into_iter()
+                    .zip(receipts)
+                    .map(|(trace, receipt)| geth_to_tx_trace(trace, receipt))
+                    .collect()
+            }
+        };
+
+        Ok(Some(traces))
+    }
+
+    fn check_trace_count(traces: usize, receipts: usize) -> eyre::Result<()> {
+        if traces != receipts {
+            eyre::bail!("got {traces} transaction traces for {receipts} receipts")
+        }
+        Ok(())
+    }
+
+    fn block_param(block_number: Option<u64>) -> BlockId {
+        match block_number {
+            Some(number) => BlockId::Number(BlockNumberOrTag::Number(number)),
+            None => BlockId::Number(BlockNumberOrTag::Latest),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcTxLocation {
+    block_number:      Option<U64>,
+    transaction_index: Option<U64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcBlockHash {
+    hash: B256,
+}
+
+#[async_trait::async_trait]
+impl TracingProvider for RpcTracingProvider {
+    async fn eth_call(
+        &self,
+        request: TransactionRequest,
+        block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> eyre::Result<Bytes> {
+        let mut params = vec![json!(request), json!(block_number.unwrap_or(BlockId::latest()))];
+        if state_overrides.is_some() || block_overrides.is_some() {
+            params.push(
+                state_overrides
+                    .map(|s| json!(s))
+                    .unwrap_or_else(|| json!({})),
+            );
+        }
+        if let Some(block_overrides) = block_overrides {
+            params.push(json!(block_overrides));
+        }
+
+        self.client.request("eth_call", Value::Array(params)).await
+    }
+
+    async fn block_hash_for_id(&self, block_num: u64) -> eyre::Result<Option<B256>> {
+        let block: Option<RpcBlockHash> = self
+            .client
+            .request("eth_getBlockByNumber", json!([BlockNumberOrTag::Number(block_num), false]))
+            .await?;
+
+        Ok(block.map(|b| b.hash))
+    }
+
+    #[cfg(feature = "local-reth")]
+    fn best_block_number(&self) -> eyre::Result<u64> {
+        unreachable!("rpc provider should only be used without the local-reth feature flag")
+    }
+
+    #[cfg(not(feature = "local-reth"))]
+    async fn best_block_number(&self) -> eyre::Result<u64> {
+        let number: U64 = self.client.request("eth_blockNumber", json!([])).await?;
+        Ok(number.to())
+    }
+
+    async fn replay_block_transactions(
+        &self,
+        block_id: BlockId,
+    ) -> eyre::Result<Option<Vec<TxTrace>>> {
+        self.fetch_block_traces(block_id).await
+    }
+
+    async fn block_receipts(
+        &self,
+        number: BlockNumberOrTag,
+    ) -> eyre::Result<Option<Vec<TransactionReceipt<AnyReceiptEnvelope<Log>>>>> {
+        self.client
+            .request("eth_getBlockReceipts", json!([number]))
+            .await
+    }
+
+    async fn header_by_number(&self, number: BlockNumber) -> eyre::Result<Option<Header>> {
+        let block: Option<Block> = self
+            .client
+            .request("eth_getBlockByNumber", json!([BlockNumberOrTag::Number(number), false]))
+            .await?;
+
+        block.map(rpc_block_to_header).transpose()
+    }
+
+    async fn block_and_tx_index(&self, hash: TxHash) -> eyre::Result<(u64, usize)> {
+        let tx: Option<RpcTxLocation> = self
+            .client
+            .request("eth_getTransactionByHash", json!([hash]))
+            .await?;
+        let err = || eyre::eyre!("failed to unwrap option");
+        let tx = tx.ok_or_else(err)?;
+
+        Ok((
+            tx.block_number.ok_or_else(err)?.to(),
+            tx.transaction_index.ok_or_else(err)?.to::<u64>() as usize,
+        ))
+    }
+
+    async fn get_storage(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+        storage_key: B256,
+    ) -> eyre::Result<Option<StorageValue>> {
+        let value: B256 = self
+            .client
+            .request(
+                "eth_getStorageAt",
+                json!([address, storage_key, Self::block_param(block_number)]),
+            )
+            .await?;
+
+        Ok(Some(StorageValue::from_be_bytes(value.0)))
+    }
+
+    async fn get_bytecode(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+    ) -> eyre::Result<Option<Bytecode>> {
+        let bytes: Bytes = self
+            .client
+            .request("eth_getCode", json!([address, Self::block_param(block_number)]))
+            .await?;
+
+        Ok(Some(Bytecode::new_raw(bytes)))
+    }
+}
@@ -0,0 +1,588 @@
+//! Translation of the traces returned by the `trace_` (parity) and `debug_`
+//! (geth) namespaces into our [`TxTrace`] format.
+
+use alloy_primitives::{Address, Bytes, Log, LogData, B256, U128, U256, U64};
+use brontes_types::structured_trace::{
+    AccountStateDiff, BalanceDiff, StorageSlotDiff, TransactionTraceWithLogs, TxTrace,
+};
+use reth_rpc_types::trace::parity::{
+    Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput, Delta,
+    SelfdestructAction, StateDiff, TraceOutput, TransactionTrace,
+};
+use serde::Deserialize;
+
+/// the error parity style tracers use for a reverted frame
+const PARITY_REVERT_ERROR: &str = "Reverted";
+/// the error geth uses for a reverted frame
+const GETH_REVERT_ERROR: &str = "execution reverted";
+
+/// The parts of a transaction receipt that we need to build the trace.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcReceiptMeta {
+    pub transaction_hash:    B256,
+    pub transaction_index:   U64,
+    pub block_number:        U64,
+    pub gas_used:            U128,
+    /// missing on nodes that predate london
+    #[serde(default)]
+    pub effective_gas_price: U128,
+    /// missing for pre-byzantium receipts
+    #[serde(default)]
+    pub status:              Option<U64>,
+    #[serde(default)]
+    pub logs:                Vec<RpcLog>,
+}
+
+impl RpcReceiptMeta {
+    pub fn is_success(&self) -> Option<bool> {
+        self.status.map(|status| status == U64::from(1))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcLog {
+    pub address: Address,
+    #[serde(default)]
+    pub topics:  Vec<B256>,
+    #[serde(default)]
+    pub data:    Bytes,
+}
+
+impl From<RpcLog> for Log {
+    fn from(log: RpcLog) -> Self {
+        Log { address: log.address, data: LogData::new_unchecked(log.topics, log.data) }
+    }
+}
+
+/// A single frame of the geth `callTracer` with `withLog` enabled.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GethCallFrame {
+    #[serde(rename = "type")]
+    pub typ:      String,
+    pub from:     Address,
+    #[serde(default)]
+    pub to:       Option<Address>,
+    #[serde(default)]
+    pub value:    Option<U256>,
+    #[serde(default)]
+    pub gas:      U64,
+    #[serde(default)]
+    pub gas_used: U64,
+    #[serde(default)]
+    pub input:    Bytes,
+    #[serde(default)]
+    pub output:   Option<Bytes>,
+    #[serde(default)]
+    pub error:    Option<String>,
+    #[serde(default)]
+    pub calls:    Vec<GethCallFrame>,
+    #[serde(default)]
+    pub logs:     Vec<RpcLog>,
+}
+
+/// An entry of `debug_traceBlockByNumber`. Older geth versions don't return
+/// the tx hash, in which case we use the one from the receipt.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GethBlockTrace {
+    #[serde(default)]
+    pub tx_hash: Option<B256>,
+    pub result:  GethCallFrame,
+}
+
+/// An entry of `trace_replayBlockTransactions`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParityBlockTrace {
+    pub trace:            Vec<TransactionTrace>,
+    #[serde(default)]
+    pub state_diff:       Option<StateDiff>,
+    #[serde(default)]
+    pub transaction_hash: Option<B256>,
+}
+
+/// The `callTracer` doesn't report state changes, so geth traces come without
+/// a state diff.
+pub fn geth_to_tx_trace(trace: GethBlockTrace, receipt: RpcReceiptMeta) -> TxTrace {
+    let mut traces = Vec::new();
+    flatten_geth_frame(trace.result, vec![], false, &mut traces);
+
+    let is_success = receipt.is_success().unwrap_or_else(|| {
+        traces
+            .first()
+            .map(|t| t.trace.error.is_none())
+            .unwrap_or(true)
+    });
+
+    TxTrace::new(
+        receipt.block_number.to(),
+        traces,
+        trace.tx_hash.unwrap_or(receipt.transaction_hash),
+        receipt.transaction_index.to(),
+        receipt.gas_used.to(),
+        receipt.effective_gas_price.to(),
+        is_success,
+        vec![],
+    )
+}
+
+pub fn parity_to_tx_trace(trace: ParityBlockTrace, receipt: RpcReceiptMeta) -> TxTrace {
+    let logs = attribute_logs(&trace.trace, receipt.logs.iter().cloned().map(Log::from));
+
+    let mut traces: Vec<TransactionTraceWithLogs> = Vec::with_capacity(trace.trace.len());
+    for (idx, (tx_trace, logs)) in trace.trace.into_iter().zip(logs).enumerate() {
+        let msg_sender = msg_sender(&tx_trace.action, &traces);
+        traces.push(TransactionTraceWithLogs {
+            trace: tx_trace,
+            logs,
+            msg_sender,
+            trace_idx: idx as u64,
+            decoded_data: None,
+        });
+    }
+
+    let is_success = receipt.is_success().unwrap_or_else(|| {
+        traces
+            .first()
+            .map(|t| t.trace.error.is_none())
+            .unwrap_or(true)
+    });
+
+    TxTrace::new(
+        receipt.block_number.to(),
+        traces,
+        trace.transaction_hash.unwrap_or(receipt.transaction_hash),
+        receipt.transaction_index.to(),
+        receipt.gas_used.to(),
+        receipt.effective_gas_price.to(),
+        is_success,
+        trace
+            .state_diff
+            .map(translate_state_diff)
+            .unwrap_or_default(),
+    )
+}
+
+/// the parity state diff is keyed by address, so the accounts come out sorted
+fn translate_state_diff(diff: StateDiff) -> Vec<AccountStateDiff> {
+    diff.0
+        .into_iter()
+        .filter_map(|(address, account)| {
+            let balance =
+                pre_and_post(account.balance).map(|(pre, post)| BalanceDiff { pre, post });
+            let mut storage = account
+                .storage
+                .into_iter()
+                .filter_map(|(slot, value)| {
+                    let (pre, post) = pre_and_post(value)?;
+                    Some(StorageSlotDiff {
+                        slot: U256::from_be_bytes(slot.0),
+                        pre:  U256::from_be_bytes(pre.0),
+                        post: U256::from_be_bytes(post.0),
+                    })
+                })
+                .collect::<Vec<_>>();
+            storage.sort_unstable_by_key(|slot| slot.slot);
+
+            (balance.is_some() || !storage.is_empty()).then_some(AccountStateDiff {
+                address,
+                balance,
+                storage,
+            })
+        })
+        .collect()
+}
+
+/// created and destroyed accounts are diffed against the default value
+fn pre_and_post<T: Default>(delta: Delta<T>) -> Option<(T, T)> {
+    match delta {
+        Delta::Unchanged => None,
+        Delta::Added(post) => Some((T::default(), post)),
+        Delta::Removed(pre) => Some((pre, T::default())),
+        Delta::Changed(changed) => Some((changed.from, changed.to)),
+    }
+}
+
+/// flattens the call tree depth first, which is the order parity traces are
+/// returned in
+fn flatten_geth_frame(
+    frame: GethCallFrame,
+    trace_address: Vec<usize>,
+    parent_reverted: bool,
+    out: &mut Vec<TransactionTraceWithLogs>,
+) {
+    let reverted = parent_reverted || frame.error.is_some();
+    let error = frame.error.map(|err| {
+        if err == GETH_REVERT_ERROR {
+            PARITY_REVERT_ERROR.to_string()
+        } else {
+            err
+        }
+    });
+    let value = frame.value.unwrap_or_default();
+
+    let action = match frame.typ.as_str() {
+        "CREATE" | "CREATE2" => Action::Create(CreateAction {
+            from: frame.from,
+            value,
+            gas: frame.gas,
+            init: frame.input,
+        }),
+        "SELFDESTRUCT" => Action::Selfdestruct(SelfdestructAction {
+            address:        frame.from,
+            refund_address: frame.to.unwrap_or_default(),
+            balance:        value,
+        }),
+        typ => Action::Call(CallAction {
+            from: frame.from,
+            to: frame.to.unwrap_or_default(),
+            value,
+            gas: frame.gas,
+            input: frame.input,
+            call_type: match typ {
+                "STATICCALL" => CallType::StaticCall,
+                "DELEGATECALL" => CallType::DelegateCall,
+                "CALLCODE" => CallType::CallCode,
+                _ => CallType::Call,
+            },
+        }),
+    };
+
+    // same as parity, errors that are not reverts and selfdestructs have no
+    // output
+    let result = match &action {
+        Action::Selfdestruct(_) => None,
+        _ if error.as_ref().is_some_and(|err| err != PARITY_REVERT_ERROR) => None,
+        Action::Create(_) => Some(TraceOutput::Create(CreateOutput {
+            gas_used: frame.gas_used,
+            code:     frame.output.unwrap_or_default(),
+            address:  frame.to.unwrap_or_default(),
+        })),
+        _ => Some(TraceOutput::Call(CallOutput {
+            gas_used: frame.gas_used,
+            output:   frame.output.unwrap_or_default(),
+        })),
+    };
+
+    let msg_sender = msg_sender(&action, out);
+    let trace_idx = out.len() as u64;
+    // logs of reverted frames never make it into the receipt
+    let logs = if reverted { vec![] } else { frame.logs.into_iter().map(Log::from).collect() };
+
+    out.push(TransactionTraceWithLogs {
+        trace: TransactionTrace {
+            action,
+            error,
+            result,
+            trace_address: trace_address.clone(),
+            subtraces: frame.calls.len(),
+        },
+        logs,
+        msg_sender,
+        trace_idx,
+        decoded_data: None,
+    });
+
+    for (i, call) in frame.calls.into_iter().enumerate() {
+        let mut child_address = trace_address.clone();
+        child_address.push(i);
+        flatten_geth_frame(call, child_address, reverted, out);
+    }
+}
+
+/// the msg.sender of a frame. For delegate calls this is the msg.sender of
+/// the closest frame above it that isn't a delegate call.
+fn msg_sender(action: &Action, prev: &[TransactionTraceWithLogs]) -> Address {
+    match action {
+        Action::Call(call) if call.call_type == CallType::DelegateCall => prev
+            .iter()
+            .rev()
+            .find(|n| match &n.trace.action {
+                Action::Call(c) => c.call_type != CallType::DelegateCall,
+                Action::Create(_) => true,
+                _ => false,
+            })
+            .map(|n| n.msg_sender)
+            .unwrap_or(call.from),
+        Action::Call(call) => call.from,
+        Action::Create(call) => call.from,
+        Action::Reward(call) => call.author,
+        Action::Selfdestruct(call) => call.address,
+    }
+}
+
+/// Parity traces don't include logs, so we assign the receipt logs to the
+/// frames that emitted them. Logs are emitted in execution order so we walk
+/// them alongside the depth first frames, preferring in order the frame that
+/// emitted the last log, the next frame to execute and then the frames the
+/// last emitter returns to. When a contract is called multiple times in a row
+/// this can pick the wrong frame, the geth tracer doesn't have this issue.
+fn attribute_logs(traces: &[TransactionTrace], logs: impl Iterator<Item = Log>) -> Vec<Vec<Log>> {
+    let mut frame_logs = vec![vec![]; traces.len()];
+    if traces.is_empty() {
+        return frame_logs
+    }
+
+    let emitter = traces
+        .iter()
+        .map(|trace| match &trace.action {
+            // delegate calls emit logs from the callers context
+            Action::Call(c)
+                if matches!(c.call_type, CallType::DelegateCall | CallType::CallCode) =>
+            {
+                Some(c.from)
+            }
+            Action::Call(c) => Some(c.to),
+            Action::Create(_) => match &trace.result {
+                Some(TraceOutput::Create(o)) => Some(o.address),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let reverted = traces
+        .iter()
+        .map(|trace| {
+            traces.iter().any(|other| {
+                other.error.is_some() && trace.trace_address.starts_with(&other.trace_address)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let is_parent = |parent: usize, child: usize| {
+        let (p, c) = (&traces[parent].trace_address, &traces[child].trace_address);
+        p.len() < c.len() && c.starts_with(p)
+    };
+
+    let mut last: Option<usize> = None;
+    for log in logs {
+        let can_emit = |i: &usize| !reverted[*i] && emitter[*i] == Some(log.address);
+
+        let frame = last
+            .filter(can_emit)
+            .or_else(|| (last.map(|l| l + 1).unwrap_or_default()..traces.len()).find(can_emit))
+            .or_else(|| {
+                let last = last?;
+                (0..last)
+                    .rev()
+                    .filter(|i| is_parent(*i, last))
+                    .find(can_emit)
+            })
+            .or_else(|| (0..traces.len()).find(can_emit))
+            .unwrap_or_default();
+
+        frame_logs[frame].push(log);
+        last = Some(frame);
+    }
+
+    frame_logs
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::I256;
+
+    use super::*;
+
+    fn receipt(logs: Vec<RpcLog>) -> RpcReceiptMeta {
+        RpcReceiptMeta {
+            transaction_hash: B256::with_last_byte(1),
+            transaction_index: U64::from(3),
+            block_number: U64::from(100),
+            gas_used: U128::from(21_000),
+            effective_gas_price: U128::from(30_000_000_000u64),
+            status: Some(U64::from(1)),
+            logs,
+        }
+    }
+
+    fn log(address: Address) -> RpcLog {
+        RpcLog { address, topics: vec![], data: Bytes::new() }
+    }
+
+    #[test]
+    fn test_receipt_gas_is_carried_over() {
+        let json = serde_json::json!({
+            "transactionHash": B256::with_last_byte(1),
+            "transactionIndex": "0x3",
+            "blockNumber": "0x64",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x6fc23ac00",
+            "status": "0x1",
+            "logs": []
+        });
+        let receipt: RpcReceiptMeta = serde_json::from_value(json).unwrap();
+
+        let geth = GethBlockTrace {
+            tx_hash: None,
+            result:  GethCallFrame {
+                typ:      "CALL".to_string(),
+                from:     Address::with_last_byte(1),
+                to:       Some(Address::with_last_byte(2)),
+                value:    None,
+                gas:      U64::from(30_000),
+                gas_used: U64::from(21_000),
+                input:    Bytes::new(),
+                output:   None,
+                error:    None,
+                calls:    vec![],
+                logs:     vec![],
+            },
+        };
+        let parity = ParityBlockTrace {
+            trace:            vec![],
+            state_diff:       None,
+            transaction_hash: None,
+        };
+
+        for tx in [geth_to_tx_trace(geth, receipt.clone()), parity_to_tx_trace(parity, receipt)] {
+            assert_eq!(tx.gas_used, 21_000);
+            assert_eq!(tx.effective_price, 30_000_000_000);
+            assert!(tx.state_diff.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_geth_frames_are_flattened_depth_first() {
+        let json = serde_json::json!({
+            "txHash": B256::with_last_byte(1),
+            "result": {
+                "type": "CALL",
+                "from": Address::with_last_byte(1),
+                "to": Address::with_last_byte(2),
+                "value": "0x0",
+                "gas": "0x1000",
+                "gasUsed": "0x100",
+                "input": "0x",
+                "calls": [
+                    {
+                        "type": "DELEGATECALL",
+                        "from": Address::with_last_byte(2),
+                        "to": Address::with_last_byte(3),
+                        "gas": "0x100",
+                        "gasUsed": "0x10",
+                        "input": "0x",
+                        "logs": [
+                            { "address": Address::with_last_byte(2), "topics": [], "data": "0x" }
+                        ]
+                    },
+                    {
+                        "type": "CALL",
+                        "from": Address::with_last_byte(2),
+                        "to": Address::with_last_byte(4),
+                        "gas": "0x100",
+                        "gasUsed": "0x10",
+                        "input": "0x",
+                        "error": "execution reverted",
+                        "logs": [
+                            { "address": Address::with_last_byte(4), "topics": [], "data": "0x" }
+                        ]
+                    }
+                ]
+            }
+        });
+        let trace: GethBlockTrace = serde_json::from_value(json).unwrap();
+        let tx = geth_to_tx_trace(trace, receipt(vec![]));
+
+        assert_eq!(tx.trace.len(), 3);
+        assert_eq!(tx.block_number, 100);
+        assert_eq!(tx.tx_index, 3);
+        assert_eq!(tx.trace[0].trace.subtraces, 2);
+        assert_eq!(tx.trace[2].trace.trace_address, vec![1]);
+        // delegate calls inherit the msg.sender of the calling frame
+        assert_eq!(tx.trace[1].msg_sender, Address::with_last_byte(1));
+        assert_eq!(tx.trace[1].logs.len(), 1);
+        // reverted frames keep their output but lose their logs
+        assert_eq!(tx.trace[2].trace.error.as_deref(), Some(PARITY_REVERT_ERROR));
+        assert!(tx.trace[2].trace.result.is_some());
+        assert!(tx.trace[2].logs.is_empty());
+    }
+
+    #[test]
+    fn test_parity_logs_are_attributed_to_emitting_frames() {
+        let call = |to: Address, trace_address: Vec<usize>, subtraces: usize| TransactionTrace {
+            action: Action::Call(CallAction {
+                from: Address::with_last_byte(1),
+                to,
+                value: U256::ZERO,
+                gas: U64::ZERO,
+                input: Bytes::new(),
+                call_type: CallType::Call,
+            }),
+            error: None,
+            result: Some(TraceOutput::Call(CallOutput {
+                gas_used: U64::ZERO,
+                output:   Bytes::new(),
+            })),
+            trace_address,
+            subtraces,
+        };
+        let (router, token) = (Address::with_last_byte(2), Address::with_last_byte(3));
+
+        let trace = ParityBlockTrace {
+            trace:            vec![call(router, vec![], 1), call(token, vec![0], 0)],
+            state_diff:       None,
+            transaction_hash: None,
+        };
+        // token transfer and approval, then the router event after the call returns
+        let tx = parity_to_tx_trace(trace, receipt(vec![log(token), log(token), log(router)]));
+
+        assert_eq!(tx.tx_hash, B256::with_last_byte(1));
+        assert_eq!(tx.trace[0].logs.len(), 1);
+        assert_eq!(tx.trace[1].logs.len(), 2);
+    }
+
+    #[test]
+    fn test_parity_state_diff_only_keeps_changes() {
+        let (sender, pool) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let json = serde_json::json!({
+            "trace": [],
+            "stateDiff": {
+                (sender.to_string()): {
+                    "balance": { "*": { "from": "0x10", "to": "0x4" } },
+                    "code": "=",
+                    "nonce": { "*": { "from": "0x1", "to": "0x2" } },
+                    "storage": {}
+                },
+                (pool.to_string()): {
+                    "balance": "=",
+                    "code": "=",
+                    "nonce": "=",
+                    "storage": {
+                        (B256::with_last_byte(8).to_string()): {
+                            "*": { "from": B256::with_last_byte(5), "to": B256::with_last_byte(7) }
+                        },
+                        (B256::with_last_byte(3).to_string()): { "+": B256::with_last_byte(1) }
+                    }
+                },
+                (Address::with_last_byte(3).to_string()): {
+                    "balance": "=",
+                    "code": "=",
+                    "nonce": { "*": { "from": "0x1", "to": "0x2" } },
+                    "storage": {}
+                }
+            }
+        });
+        let trace: ParityBlockTrace = serde_json::from_value(json).unwrap();
+        let tx = parity_to_tx_trace(trace, receipt(vec![]));
+
+        // nonce only changes aren't recorded
+        assert_eq!(tx.state_diff.len(), 2);
+        assert_eq!(
+            tx.eth_balance_deltas().collect::<Vec<_>>(),
+            vec![(sender, I256::try_from(-12).unwrap())]
+        );
+
+        let pool = tx.state_diff_for(pool).unwrap();
+        assert!(pool.balance.is_none());
+        assert_eq!(pool.storage.len(), 2);
+        assert_eq!(
+            pool.slot(U256::from(3)),
+            Some(&StorageSlotDiff { slot: U256::from(3), pre: U256::ZERO, post: U256::from(1) })
+        );
+        assert_eq!(pool.slot(U256::from(8)).unwrap().post, U256::from(7));
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::{OnceLock, RwLock},
+};
+
+use alloy_rpc_types::AnyReceiptEnvelope;
+use brontes_database::libmdbx::{
+    fixtures::{BlockFixture, FixtureIndex},
+    LibmdbxReadWriter,
+};
+use brontes_types::{structured_trace::TxTrace, traits::TracingProvider, FastHashMap};
+use reth_primitives::{
+    Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue, TxHash,
+    B256,
+};
+use reth_rpc_types::{
+    state::StateOverride, BlockOverrides, Log, TransactionReceipt, TransactionRequest,
+};
+
+static FIXTURES: OnceLock<Fixtures> = OnceLock::new();
+
+/// The fixtures recorded with `brontes db record-fixtures`. Read from
+/// `BRONTES_TEST_FIXTURES`, or `fixtures/` in the repository root
+pub fn fixtures() -> &'static Fixtures {
+    FIXTURES.get_or_init(|| {
+        let dir = env::var("BRONTES_TEST_FIXTURES")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures"));
+        let index = FixtureIndex::load(&dir).unwrap_or_else(|e| {
+            tracing::warn!(dir = %dir.display(), "failed to load fixture index -- {:?}", e);
+            FixtureIndex::default()
+        });
+
+        Fixtures { dir, index, db: OnceLock::new(), imported: RwLock::default() }
+    })
+}
+
+pub struct Fixtures {
+    dir:      PathBuf,
+    index:    FixtureIndex,
+    /// The temp db the fixtures are imported into. Unset when the tests run
+    /// against `BRONTES_TEST_DB_PATH`, so a fixture never overwrites its rows
+    db:       OnceLock<&'static LibmdbxReadWriter>,
+    /// Headers of the fixtures that were imported into the temp db
+    imported: RwLock<FastHashMap<u64, Header>>,
+}
+
+impl Fixtures {
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether no fixture with transactions was recorded
+    pub fn is_empty(&self) -> bool {
+        self.index.txs.is_empty()
+    }
+
+    pub(crate) fn set_db(&self, db: &'static LibmdbxReadWriter) {
+        if self.db.set(db).is_err() {
+            panic!("fixture db was already set")
+        }
+    }
+
+    /// Imports the fixture of the block into the temp db. Returns false if the
+    /// tests run against a configured db or no fixture was recorded for the
+    /// block.
+    pub fn import(&self, block: u64) -> eyre::Result<bool> {
+        let Some(db) = self.db.get() else { return Ok(false) };
+        if self.is_imported(block) {
+            return Ok(true)
+        }
+        let Some(fixture) = BlockFixture::read(&self.dir, block)? else { return Ok(false) };
+
+        db.import_fixture(&fixture)?;
+        tracing::info!(%block, "imported fixture");
+        self.imported.write().unwrap().insert(block, fixture.header);
+
+        Ok(true)
+    }
+
+    pub fn is_imported(&self, block: u64) -> bool {
+        self.imported.read().unwrap().contains_key(&block)
+    }
+
+    pub fn header(&self, block: u64) -> Option<Header> {
+        self.imported.read().unwrap().get(&block).cloned()
+    }
+
+    pub fn block_and_tx_index(&self, tx_hash: TxHash) -> Option<(u64, usize)> {
+        self.index.get(&tx_hash)
+    }
+}
+
+/// Answers the header and transaction lookups of recorded blocks from the
+/// fixtures and forwards everything else to the node, if there is one.
+pub struct FixtureProvider {
+    node: Option<Box<dyn TracingProvider>>,
+}
+
+impl FixtureProvider {
+    pub fn new(node: Option<Box<dyn TracingProvider>>) -> Self {
+        Self { node }
+    }
+
+    fn node(&self) -> eyre::Result<&dyn TracingProvider> {
+        self.node
+            .as_deref()
+            .ok_or_else(|| eyre::eyre!("no fixture recorded for the request and no node to query"))
+    }
+}
+
+#[async_trait::async_trait]
+impl TracingProvider for FixtureProvider {
+    async fn eth_call(
+        &self,
+        request: TransactionRequest,
+        block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> eyre::Result<Bytes> {
+        self.node()?
+            .eth_call(request, block_number, state_overrides, block_overrides)
+            .await
+    }
+
+    async fn block_hash_for_id(&self, block_num: u64) -> eyre::Result<Option<B256>> {
+        if let Some(header) = fixtures().header(block_num) {
+            return Ok(Some(header.hash_slow()))
+        }
+
+        self.node()?.block_hash_for_id(block_num).await
+    }
+
+    #[cfg(feature = "local-reth")]
+    fn best_block_number(&self) -> eyre::Result<u64> {
+        self.node()?.best_block_number()
+    }
+
+    #[cfg(not(feature = "local-reth"))]
+    async fn best_block_number(&self) -> eyre::Result<u64> {
+        self.node()?.best_block_number().await
+    }
+
+    async fn replay_block_transactions(
+        &self,
+        block_id: BlockId,
+    ) -> eyre::Result<Option<Vec<TxTrace>>> {
+        self.node()?.replay_block_transactions(block_id).await
+    }
+
+    async fn block_receipts(
+        &self,
+        number: BlockNumberOrTag,
+    ) -> eyre::Result<Option<Vec<TransactionReceipt<AnyReceiptEnvelope<Log>>>>> {
+        self.node()?.block_receipts(number).await
+    }
+
+    async fn header_by_number(&self, number: BlockNumber) -> eyre::Result<Option<Header>> {
+        if let Some(header) = fixtures().header(number) {
+            return Ok(Some(header))
+        }
+
+        self.node()?.header_by_number(number).await
+    }
+
+    async fn block_and_tx_index(&self, hash: TxHash) -> eyre::Result<(u64, usize)> {
+        if let Some(res) = fixtures().block_and_tx_index(hash) {
+            return Ok(res)
+        }
+
+        self.node()?.block_and_tx_index(hash).await
+    }
+
+    async fn get_storage(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+        storage_key: B256,
+    ) -> eyre::Result<Option<StorageValue>> {
+        self.node()?
+            .get_storage(block_number, address, storage_key)
+            .await
+    }
+
+    async fn get_bytecode(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+    ) -> eyre::Result<Option<Bytecode>> {
+        self.node()?.get_bytecode(block_number, address).await
+    }
+}
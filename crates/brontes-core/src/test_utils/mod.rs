@@ -45,6 +45,9 @@ use tokio::{
 use tracing::Level;
 use tracing_subscriber::filter::Directive;
 
+mod fixtures;
+pub use fixtures::{fixtures, FixtureProvider, Fixtures};
+
 use crate::decoding::parser::TraceParser;
 #[cfg(not(feature = "local-reth"))]
 use crate::local_provider::LocalProvider;
@@ -78,6 +81,7 @@ impl TraceLoader {
         &self,
         block: u64,
     ) -> Result<(BlockHash, Vec<TxTrace>, Header), TraceLoaderError> {
+        fixtures().import(block)?;
         if let Some(traces) = self.tracing_provider.clone().execute_block(block).await {
             Ok(traces)
         } else {
@@ -95,6 +99,7 @@ impl TraceLoader {
         block: u64,
         pricing: bool,
     ) -> Result<Metadata, TraceLoaderError> {
+        fixtures().import(block)?;
         if pricing {
             if let Ok(res) = self.test_metadata_with_pricing(block, USDT_ADDRESS) {
                 Ok(res)
@@ -447,8 +452,9 @@ pub async fn get_db_handle(handle: Handle) -> &'static LibmdbxReadWriter {
         .get_or_init(|| async {
             let _ = dotenv::dotenv();
             init_tracing();
-            let brontes_db_path =
-                env::var("BRONTES_TEST_DB_PATH").expect("No BRONTES_TEST_DB_PATH in .env");
+            let Ok(brontes_db_path) = env::var("BRONTES_TEST_DB_PATH") else {
+                return init_fixture_db()
+            };
 
             let this = &*Box::leak(Box::new(
                 LibmdbxReadWriter::init_db_tests(&brontes_db_path).unwrap_or_else(|e| {
@@ -473,6 +479,35 @@ pub async fn get_db_handle(handle: Handle) -> &'static LibmdbxReadWriter {
         .await
 }
 
+/// Without `BRONTES_TEST_DB_PATH` the tests run against a fresh db in the temp
+/// dir that only holds the recorded fixtures. Fixtures are only ever imported
+/// into this db, never into a configured test db.
+fn init_fixture_db() -> &'static LibmdbxReadWriter {
+    let fixtures = fixtures();
+    if fixtures.is_empty() {
+        panic!(
+            "No BRONTES_TEST_DB_PATH in .env and no fixtures with transactions in {}, record them \
+             with `brontes db record-fixtures`",
+            fixtures.dir().display()
+        )
+    }
+
+    let path = env::temp_dir().join(format!("brontes-fixture-db-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    tracing::warn!(
+        path = %path.display(),
+        "no BRONTES_TEST_DB_PATH in .env, running against the recorded fixtures only"
+    );
+
+    let this = &*Box::leak(Box::new(LibmdbxReadWriter::init_db_tests(&path).unwrap_or_else(|e| {
+        panic!("failed to open fixture db path {}, err={}", path.display(), e)
+    })));
+    fixtures.set_db(this);
+
+    this
+}
+
 /// will trigger a update if a test with a new highest block is written
 /// or if any of the 3 critical tables are empty
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -586,7 +621,7 @@ pub async fn init_trace_parser(
         static_files,
     );
     handle.spawn(executor);
-    let tracer = Box::new(client) as Box<dyn TracingProvider>;
+    let tracer = Box::new(FixtureProvider::new(Some(Box::new(client)))) as Box<dyn TracingProvider>;
 
     TraceParser::new(libmdbx, Arc::new(tracer), Arc::new(metrics_tx)).await
 }
@@ -598,10 +633,13 @@ pub async fn init_trace_parser(
     libmdbx: &'static LibmdbxReadWriter,
     _max_tasks: u32,
 ) -> TraceParser<Box<dyn TracingProvider>, LibmdbxReadWriter> {
-    let db_endpoint = env::var("RETH_ENDPOINT").expect("No db Endpoint in .env");
-    let db_port = env::var("RETH_PORT").expect("No DB port.env");
-    let url = format!("{db_endpoint}:{db_port}");
-    let tracer = Box::new(LocalProvider::new(url, 15)) as Box<dyn TracingProvider>;
+    // without a node only blocks with a recorded fixture can be loaded
+    let node = env::var("RETH_ENDPOINT").ok().map(|db_endpoint| {
+        let db_port = env::var("RETH_PORT").expect("No DB port.env");
+        let url = format!("{db_endpoint}:{db_port}");
+        Box::new(LocalProvider::new(url, 15)) as Box<dyn TracingProvider>
+    });
+    let tracer = Box::new(FixtureProvider::new(node)) as Box<dyn TracingProvider>;
 
     TraceParser::new(libmdbx, Arc::new(tracer), Arc::new(metrics_tx)).await
 }
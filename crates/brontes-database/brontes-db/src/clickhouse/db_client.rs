@@ -18,6 +18,7 @@ use brontes_types::{
         },
         dex::{DexQuotes, DexQuotesWithBlockNumber},
         metadata::{BlockMetadata, BlockMetadataInner, Metadata},
+        mev_block::MevBlockWithClassified,
         normalized_actions::TransactionRoot,
         searcher::SearcherInfo,
         token_info::{TokenInfo, TokenInfoWithAddress},
@@ -25,7 +26,7 @@ use brontes_types::{
     mev::{Bundle, BundleData, MevBlock},
     normalized_actions::Action,
     structured_trace::TxTrace,
-    BlockTree, Protocol,
+    BlockTree, FastHashMap, Protocol,
 };
 use clickhouse::error::Error::{BadResponse, Custom, Network};
 use db_interfaces::{
@@ -41,12 +42,18 @@ use eyre::Result;
 use itertools::Itertools;
 use reth_primitives::{BlockHash, TxHash};
 use serde::{Deserialize, Serialize};
-use tokio::{sync::mpsc::UnboundedSender, time::Duration};
+use tokio::{
+    sync::{mpsc::UnboundedSender, oneshot},
+    time::Duration,
+};
 use tracing::{debug, error, warn};
 
 use super::{
-    cex_config::CexDownloadConfig, dbms::*, ClickhouseHandle, MOST_VOLUME_PAIR_EXCHANGE,
-    P2P_OBSERVATIONS, PRIVATE_FLOW, RAW_CEX_QUOTES, RAW_CEX_TRADES,
+    cex_config::CexDownloadConfig,
+    dbms::*,
+    split_db::{ClickhouseBuffered, RollbackRequest},
+    ClickhouseHandle, MOST_VOLUME_PAIR_EXCHANGE, P2P_OBSERVATIONS, PRIVATE_FLOW, RAW_CEX_QUOTES,
+    RAW_CEX_TRADES,
 };
 #[cfg(feature = "local-clickhouse")]
 use super::{BLOCK_TIMES, CEX_SYMBOLS};
@@ -61,13 +68,72 @@ use crate::{
 const SECONDS_TO_US: f64 = 1_000_000.0;
 const MAX_MARKOUT_TIME: f64 = 300.0;
 
+/// tables a mev block and its bundles are written to
+pub const MEV_BLOCK_TABLES: [&str; 12] = [
+    "mev.mev_blocks",
+    "mev.bundle_header",
+    "mev.searcher_tx",
+    "mev.cex_dex",
+    "mev.cex_dex_quotes",
+    "mev.liquidations",
+    "mev.jit_sandwich",
+    "mev.jit",
+    "mev.sandwiches",
+    "mev.atomic_arbs",
+    "mev.custom",
+    "brontes.block_analysis",
+];
+
+/// all tables we write to that are keyed by block number
+pub(crate) const BLOCK_INDEXED_TABLES: [&str; 14] = [
+    "mev.mev_blocks",
+    "mev.bundle_header",
+    "mev.searcher_tx",
+    "mev.cex_dex",
+    "mev.cex_dex_quotes",
+    "mev.liquidations",
+    "mev.jit_sandwich",
+    "mev.jit",
+    "mev.sandwiches",
+    "mev.atomic_arbs",
+    "mev.custom",
+    "brontes.dex_price_mapping",
+    "brontes.block_analysis",
+    "brontes.tree",
+];
+
+/// deletes the rows of `block` from each of the given tables. Without a run id
+/// the rows of every run are deleted, which is only meant for reorgs
+pub(crate) async fn delete_block_rows(
+    client: &ClickhouseClient<BrontesClickhouseTables>,
+    tables: &[&str],
+    block: u64,
+    run_id: Option<u64>,
+) -> eyre::Result<()> {
+    let run_filter = run_id
+        .map(|run_id| format!(" AND run_id = {run_id}"))
+        .unwrap_or_default();
+    for table in tables {
+        let query = format!(
+            "ALTER TABLE {table} ON CLUSTER eth_cluster0 DELETE WHERE block_number = \
+             {block}{run_filter}"
+        );
+        client.execute_remote(&query, &()).await?;
+    }
+
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct Clickhouse {
-    pub tip:                 bool,
-    pub run_id:              u64,
-    pub client:              ClickhouseClient<BrontesClickhouseTables>,
-    pub cex_download_config: CexDownloadConfig,
-    pub buffered_insert_tx:  Option<UnboundedSender<Vec<BrontesClickhouseData>>>,
+    pub tip:                  bool,
+    pub run_id:               u64,
+    pub client:               ClickhouseClient<BrontesClickhouseTables>,
+    pub cex_download_config:  CexDownloadConfig,
+    pub buffered_insert_tx:   Option<UnboundedSender<Vec<BrontesClickhouseData>>>,
+    /// set together with `buffered_insert_tx` so rollbacks are ordered after
+    /// the buffered inserts of the block
+    pub buffered_rollback_tx: Option<UnboundedSender<RollbackRequest>>,
 }
 
 impl Clickhouse {
@@ -79,7 +145,14 @@ impl Clickhouse {
         run_id: Option<u64>,
     ) -> Self {
         let client = config.build();
-        let mut this = Self { client, cex_download_config, buffered_insert_tx, tip, run_id: 0 };
+        let mut this = Self {
+            client,
+            cex_download_config,
+            buffered_insert_tx,
+            buffered_rollback_tx: None,
+            tip,
+            run_id: 0,
+        };
 
         this.run_id = if let Some(run_id) = run_id {
             run_id
@@ -167,26 +240,9 @@ impl Clickhouse {
                     .collect(),
             )?;
 
-            bundle_data.into_iter().try_for_each(|data| {
-                match data {
-                    BundleData::Sandwich(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                    BundleData::AtomicArb(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                    BundleData::JitSandwich(s) => {
-                        tx.send(vec![(s, self.tip, self.run_id).into()])?
-                    }
-                    BundleData::Jit(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                    BundleData::CexDex(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                    BundleData::CexDexQuote(s) => {
-                        tx.send(vec![(s, self.tip, self.run_id).into()])?
-                    }
-                    BundleData::Liquidation(s) => {
-                        tx.send(vec![(s, self.tip, self.run_id).into()])?
-                    }
-                    BundleData::Unknown(s) => tx.send(vec![(s, self.tip, self.run_id).into()])?,
-                };
-
-                Ok(()) as eyre::Result<()>
-            })?;
+            bundle_data
+                .into_iter()
+                .try_for_each(|data| tx.send(vec![self.bundle_data_row(data)]))?;
         }
 
         Ok(())
@@ -279,6 +335,100 @@ impl Clickhouse {
         Ok(())
     }
 
+    /// deletes all rows brontes has written for the given block across the
+    /// block indexed tables. When writes are buffered, the rows of the block
+    /// can still be sitting in the buffer, so the delete is sent through it
+    /// and only runs once they have been written.
+    pub async fn rollback_block(&self, block: u64) -> eyre::Result<()> {
+        if let Some(tx) = self.buffered_rollback_tx.as_ref() {
+            let (done, rx) = oneshot::channel();
+            tx.send(RollbackRequest { block, done })
+                .map_err(|_| eyre::eyre!("clickhouse writer has shut down"))?;
+            return rx.await?
+        }
+
+        // a reorged block is invalid for every run
+        delete_block_rows(&self.client, &BLOCK_INDEXED_TABLES, block, None).await
+    }
+
+    /// compares the given mev blocks, as stored in libmdbx, with the rows
+    /// written to clickhouse under the run id of this client and returns every
+    /// table that is missing rows for one of the blocks
+    pub async fn find_block_gaps(
+        &self,
+        blocks: &[MevBlockWithClassified],
+    ) -> eyre::Result<Vec<BlockGap>> {
+        let Some((start_block, end_block)) = blocks
+            .iter()
+            .map(|b| b.block.block_number)
+            .minmax()
+            .into_option()
+        else {
+            return Ok(vec![])
+        };
+
+        let mut found = FastHashMap::default();
+        for table in MEV_BLOCK_TABLES {
+            let query = format!(
+                "SELECT block_number, count() AS rows FROM {table} WHERE block_number >= ? AND \
+                 block_number <= ? AND run_id = ? GROUP BY block_number"
+            );
+            let counts: Vec<BlockRowCount> = self
+                .query_many_with_retry(query, &(start_block, end_block, self.run_id))
+                .await?;
+
+            found.insert(
+                table,
+                counts
+                    .into_iter()
+                    .map(|c| (c.block_number, c.rows))
+                    .collect::<FastHashMap<_, _>>(),
+            );
+        }
+
+        Ok(diff_block_rows(blocks, |table, block| {
+            found
+                .get(table)
+                .and_then(|counts| counts.get(&block))
+                .copied()
+                .unwrap_or_default()
+        }))
+    }
+
+    /// writes the mev block to clickhouse under the run id of this client,
+    /// bypassing the insert buffer. The rows previously written for the block
+    /// are deleted first so that a repair can safely be rerun. The rows other
+    /// runs wrote for the block are left alone.
+    pub async fn repair_mev_block(&self, block: MevBlockWithClassified) -> eyre::Result<()> {
+        let MevBlockWithClassified { block, mev } = block;
+        delete_block_rows(&self.client, &MEV_BLOCK_TABLES, block.block_number, Some(self.run_id))
+            .await?;
+
+        let analysis = BlockAnalysis::new(&block, &mev);
+        let mut rows: Vec<BrontesClickhouseData> =
+            vec![(block, self.tip, self.run_id).into(), (analysis, self.tip, self.run_id).into()];
+        for bundle in mev {
+            rows.push((bundle.header, self.tip, self.run_id).into());
+            rows.push(self.bundle_data_row(bundle.data));
+        }
+
+        ClickhouseBuffered::insert_unbuffered(self.client.clone(), rows).await
+    }
+
+    fn bundle_data_row(&self, data: BundleData) -> BrontesClickhouseData {
+        match data {
+            BundleData::Sandwich(s) => (s, self.tip, self.run_id).into(),
+            BundleData::AtomicArb(s) => (s, self.tip, self.run_id).into(),
+            BundleData::JitSandwich(s) => (s, self.tip, self.run_id).into(),
+            BundleData::Jit(s) => (s, self.tip, self.run_id).into(),
+            BundleData::CexDex(s) => (s, self.tip, self.run_id).into(),
+            BundleData::CexDexQuote(s) => (s, self.tip, self.run_id).into(),
+            BundleData::Liquidation(s) => (s, self.tip, self.run_id).into(),
+            BundleData::Unknown(s) => (s, self.tip, self.run_id).into(),
+            BundleData::Custom(s) => (s, self.tip, self.run_id).into(),
+        }
+    }
+
     async fn query_many_with_retry<Q, P>(
         &self,
         query: impl AsRef<str> + Send,
@@ -992,6 +1142,100 @@ impl ClickhouseCritTableCount {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, clickhouse::Row)]
+struct BlockRowCount {
+    block_number: u64,
+    rows:         u64,
+}
+
+/// A block for which a table holds fewer rows than brontes produced for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockGap {
+    pub block_number: u64,
+    pub table:        &'static str,
+    pub expected:     u64,
+    pub found:        u64,
+}
+
+impl BlockGap {
+    /// true if none of the block's rows made it into the table
+    pub fn is_missing(&self) -> bool {
+        self.found == 0
+    }
+}
+
+impl std::fmt::Display for BlockGap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "block {} {} {}: expected {} rows, found {}",
+            self.block_number,
+            if self.is_missing() { "missing from" } else { "partially in" },
+            self.table,
+            self.expected,
+            self.found
+        )
+    }
+}
+
+/// the table a bundle's data is written to
+pub fn bundle_data_table(data: &BundleData) -> &'static str {
+    match data {
+        BundleData::Sandwich(_) => "mev.sandwiches",
+        BundleData::AtomicArb(_) => "mev.atomic_arbs",
+        BundleData::JitSandwich(_) => "mev.jit_sandwich",
+        BundleData::Jit(_) => "mev.jit",
+        BundleData::CexDex(_) => "mev.cex_dex",
+        BundleData::CexDexQuote(_) => "mev.cex_dex_quotes",
+        BundleData::Liquidation(_) => "mev.liquidations",
+        BundleData::Unknown(_) => "mev.searcher_tx",
+        BundleData::Custom(_) => "mev.custom",
+    }
+}
+
+/// the amount of rows brontes writes to each of the [`MEV_BLOCK_TABLES`] for
+/// the block
+pub fn expected_block_rows(block: &MevBlockWithClassified) -> FastHashMap<&'static str, u64> {
+    let mut rows = MEV_BLOCK_TABLES
+        .into_iter()
+        .map(|table| (table, 0))
+        .collect::<FastHashMap<_, _>>();
+
+    *rows.get_mut("mev.mev_blocks").unwrap() += 1;
+    *rows.get_mut("brontes.block_analysis").unwrap() += 1;
+    *rows.get_mut("mev.bundle_header").unwrap() += block.mev.len() as u64;
+    for bundle in &block.mev {
+        *rows.get_mut(bundle_data_table(&bundle.data)).unwrap() += 1;
+    }
+
+    rows
+}
+
+/// returns the tables that hold fewer rows for a block than expected. `found`
+/// gives the amount of rows a table holds for a block
+pub fn diff_block_rows(
+    blocks: &[MevBlockWithClassified],
+    found: impl Fn(&'static str, u64) -> u64,
+) -> Vec<BlockGap> {
+    blocks
+        .iter()
+        .flat_map(|block| {
+            let block_number = block.block.block_number;
+            expected_block_rows(block)
+                .into_iter()
+                .map(|(table, expected)| BlockGap {
+                    block_number,
+                    table,
+                    expected,
+                    found: found(table, block_number),
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|gap| gap.found < gap.expected)
+        .sorted_by(|a, b| (a.block_number, a.table).cmp(&(b.block_number, b.table)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -1011,7 +1255,7 @@ mod tests {
             NormalizedBurn, NormalizedLiquidation, NormalizedMint, NormalizedSwap,
         },
         pair::Pair,
-        FastHashMap, GasDetails,
+        FastHashMap, GasDetails, UnboundedYapperReceiver,
     };
     use db_interfaces::{
         clickhouse::{dbms::ClickhouseDBMS, test_utils::ClickhouseTestClient},
@@ -1408,4 +1652,117 @@ mod tests {
             .run_test_with_test_db(tables, |db| Box::pin(run_all(db)))
             .await;
     }
+
+    #[brontes_macros::test]
+    async fn test_rollback_waits_for_buffered_rows() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (rollback_tx, rollback_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(
+            ClickhouseBuffered::new(
+                UnboundedYapperReceiver::new(rx, 1500, "rollback test".to_string()),
+                clickhouse_config(),
+                5000,
+                800,
+                None,
+            )
+            .with_rollbacks(rollback_rx),
+        );
+
+        let mut clickhouse = Clickhouse::new_default(None).await;
+        clickhouse.buffered_insert_tx = Some(tx);
+        clickhouse.buffered_rollback_tx = Some(rollback_tx);
+
+        let block_number = u32::MAX as u64;
+        let block = MevBlockWithClassified {
+            block: MevBlock { block_number, ..Default::default() },
+            mev:   vec![],
+        };
+        // well below the buffer size, so the row is still buffered when the
+        // rollback is sent
+        clickhouse
+            .save_mev_blocks(block_number, block.block.clone(), vec![])
+            .await
+            .unwrap();
+
+        // a rollback flushes the buffer before deleting
+        clickhouse.rollback_block(block_number + 1).await.unwrap();
+        let gaps = clickhouse.find_block_gaps(&[block.clone()]).await.unwrap();
+        assert!(!gaps.iter().any(|gap| gap.table == "mev.mev_blocks"));
+
+        clickhouse.rollback_block(block_number).await.unwrap();
+
+        // deletes are applied as mutations in the background
+        let mut removed = false;
+        for _ in 0..10 {
+            let gaps = clickhouse.find_block_gaps(&[block.clone()]).await.unwrap();
+            removed = gaps
+                .iter()
+                .any(|gap| gap.table == "mev.mev_blocks" && gap.is_missing());
+            if removed {
+                break
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        assert!(removed, "buffered row was written after the rollback");
+    }
+
+    #[brontes_macros::test]
+    async fn test_repair_keeps_rows_of_other_runs() {
+        let run = Clickhouse::new_default(Some(u64::MAX - 1)).await;
+        let other_run = Clickhouse::new_default(Some(u64::MAX)).await;
+
+        let block_number = u32::MAX as u64 - 1;
+        let block = MevBlockWithClassified {
+            block: MevBlock { block_number, ..Default::default() },
+            mev:   vec![],
+        };
+        run.repair_mev_block(block.clone()).await.unwrap();
+        other_run.repair_mev_block(block.clone()).await.unwrap();
+
+        // deletes are applied as mutations in the background
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        let gaps = run.find_block_gaps(&[block.clone()]).await.unwrap();
+        assert!(
+            !gaps.iter().any(|gap| gap.is_missing()),
+            "repairing the block in another run deleted the rows of this run"
+        );
+
+        run.rollback_block(block_number).await.unwrap();
+    }
+
+    #[test]
+    fn test_diff_block_rows() {
+        let block = MevBlockWithClassified {
+            block: MevBlock { block_number: 10, ..Default::default() },
+            mev:   vec![
+                Bundle {
+                    header: BundleHeader::default(),
+                    data:   BundleData::Sandwich(Sandwich::default()),
+                },
+                Bundle {
+                    header: BundleHeader::default(),
+                    data:   BundleData::Sandwich(Sandwich::default()),
+                },
+            ],
+        };
+
+        let found = |table: &str, _| match table {
+            "mev.mev_blocks" | "brontes.block_analysis" | "mev.bundle_header" => 2,
+            "mev.sandwiches" => 1,
+            _ => 0,
+        };
+        assert_eq!(
+            diff_block_rows(&[block.clone()], found),
+            vec![BlockGap {
+                block_number: 10,
+                table:        "mev.sandwiches",
+                expected:     2,
+                found:        1,
+            }]
+        );
+
+        let gaps = diff_block_rows(&[block], |_, _| 0);
+        assert_eq!(gaps.len(), 4);
+        assert!(gaps.iter().all(BlockGap::is_missing));
+    }
 }
@@ -24,6 +24,7 @@ clickhouse_dbms!(
         MevJit,
         MevSandwiches,
         MevAtomic_Arbs,
+        MevCustom,
         BrontesToken_Info,
         EthereumPools,
         BrontesTree,
@@ -125,6 +126,13 @@ remote_clickhouse_table!(
     "crates/brontes-database/brontes-db/src/clickhouse/tables/"
 );
 
+remote_clickhouse_table!(
+    BrontesClickhouseTables,
+    [Mev, Custom],
+    DbDataWithRunId<CustomMev>,
+    "crates/brontes-database/brontes-db/src/clickhouse/tables/"
+);
+
 remote_clickhouse_table!(
     BrontesClickhouseTables,
     [Brontes, Token_Info],
@@ -240,6 +248,7 @@ db_types!(
     (JitLiquidity, MevJit, true),
     (Sandwich, MevSandwiches, true),
     (AtomicArb, MevAtomic_Arbs, true),
+    (CustomMev, MevCustom, true),
     (TokenInfoWithAddress, BrontesToken_Info, false),
     (ProtocolInfoClickhouse, EthereumPools, false),
     (TransactionRoot, BrontesTree, true),
@@ -0,0 +1,883 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fmt::Debug,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alloy_primitives::{Address, TxHash};
+use arrow::{
+    array::{Array, AsArray, Float64Array, Int64Array, StringArray},
+    compute::cast,
+    csv::{reader::Format, ReaderBuilder},
+    datatypes::{DataType, Float64Type, Int64Type},
+    record_batch::RecordBatch,
+};
+use brontes_types::{
+    db::{
+        block_times::BlockTimes,
+        cex::{
+            quotes::{CexPriceMap, CexQuotesConverter, RawCexQuotes},
+            trades::{CexTradesConverter, RawCexTrades, TradeType},
+            BestCexPerPair, CexExchange, CexSymbols,
+        },
+        metadata::{BlockMetadata, BlockMetadataInner, Metadata},
+    },
+    pair::Pair,
+    FastHashMap,
+};
+use chrono::NaiveDate;
+use clickhouse::DbRow;
+use flate2::read::GzDecoder;
+use itertools::Itertools;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use regex::Regex;
+use reth_primitives::BlockHash;
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use super::{cex_config::CexDownloadConfig, ClickhouseHandle, ClickhouseHttpClient};
+use crate::{
+    libmdbx::{cex_utils::CexRangeOrArbitrary, determine_eth_prices, types::LibmdbxData},
+    BlockInfo, BlockInfoData, CexPriceData, CexTradesData, CompressedTable, Table,
+};
+
+const SECONDS_TO_US: u64 = 1_000_000;
+const DAY_US: u64 = 86_400 * SECONDS_TO_US;
+/// quotes are loaded this far around each block so that the markout windows
+/// are covered, same as the clickhouse backend
+const QUOTES_WINDOW_US: u64 = 300 * SECONDS_TO_US;
+/// trades are bucketed +- 6 seconds around the block by the trades converter
+const TRADES_WINDOW_US: u64 = 6 * SECONDS_TO_US;
+/// rows used to infer the schema of the csv dumps
+const CSV_SCHEMA_INFER_ROWS: usize = 1000;
+
+const TRADES_DIR: &str = "trades";
+const QUOTES_DIR: &str = "quotes";
+const SYMBOLS_FILE: &str = "symbols.csv";
+const BLOCK_METADATA_FILE: &str = "block_metadata.jsonl";
+
+/// A [`ClickhouseHandle`] that serves cex data and block metadata from local
+/// market data archives instead of the Sorella clickhouse. The data directory
+/// is laid out as:
+///
+/// - `trades/`: Tardis `trades` dumps (`exchange, symbol, timestamp, side,
+///   price, amount`) as `.csv`, `.csv.gz` or `.parquet`
+/// - `quotes/`: Tardis `quotes` dumps (`exchange, symbol, timestamp,
+///   ask_amount, ask_price, bid_price, bid_amount`) in the same formats
+/// - `symbols.csv`: maps the exchange symbols to token addresses (`exchange,
+///   symbol, token0, token1`)
+/// - `block_metadata.jsonl`: one [`BlockMetadataRecord`] per line
+///
+/// Timestamps are in microseconds. Files whose name contains a `YYYY-MM-DD`
+/// date, as the Tardis downloads do, are skipped when the day is outside of
+/// the queried blocks. Tables other than the block info have no local source
+/// and are returned empty.
+pub struct ClickhouseFileClient {
+    data_dir:            PathBuf,
+    cex_download_config: CexDownloadConfig,
+    symbols:             Vec<CexSymbols>,
+    block_info:          BTreeMap<u64, BlockMetadataInner>,
+}
+
+/// A line of the block metadata file. Everything but the block hash and
+/// timestamp is optional as it is only known when the relay and p2p data is
+/// archived as well
+#[derive(Debug, Deserialize)]
+pub struct BlockMetadataRecord {
+    pub block_number:           u64,
+    pub block_hash:             BlockHash,
+    pub block_timestamp:        u64,
+    #[serde(default)]
+    pub relay_timestamp:        Option<u64>,
+    #[serde(default)]
+    pub p2p_timestamp:          Option<u64>,
+    #[serde(default)]
+    pub proposer_fee_recipient: Option<Address>,
+    #[serde(default)]
+    pub proposer_mev_reward:    Option<u128>,
+    #[serde(default)]
+    pub private_flow:           Vec<TxHash>,
+}
+
+impl From<BlockMetadataRecord> for BlockMetadataInner {
+    fn from(value: BlockMetadataRecord) -> Self {
+        Self {
+            block_hash:             value.block_hash.into(),
+            block_timestamp:        value.block_timestamp,
+            relay_timestamp:        value.relay_timestamp,
+            p2p_timestamp:          value.p2p_timestamp,
+            proposer_fee_recipient: value.proposer_fee_recipient,
+            proposer_mev_reward:    value.proposer_mev_reward,
+            private_flow:           value.private_flow,
+        }
+    }
+}
+
+impl ClickhouseFileClient {
+    pub fn new(
+        data_dir: impl Into<PathBuf>,
+        cex_download_config: CexDownloadConfig,
+    ) -> eyre::Result<Self> {
+        let data_dir = data_dir.into();
+        let symbols = load_symbols(&data_dir.join(SYMBOLS_FILE))?;
+        let block_info = load_block_metadata(&data_dir.join(BLOCK_METADATA_FILE))?;
+
+        debug!(
+            symbols = symbols.len(),
+            blocks = block_info.len(),
+            ?data_dir,
+            "loaded local cex data"
+        );
+
+        Ok(Self { data_dir, cex_download_config, symbols, block_info })
+    }
+
+    /// the block times the cex data is bucketed into. same as the clickhouse
+    /// backend, arbitrary blocks are padded by the run time window, and so
+    /// are ranges when loading trades
+    fn block_times(
+        &self,
+        range_or_arbitrary: &CexRangeOrArbitrary,
+        pad_range: bool,
+    ) -> Vec<BlockTimes> {
+        let (pre, post) = self.cex_download_config.run_time_window;
+        let to_block_time = |(block_number, meta): (&u64, &BlockMetadataInner)| BlockTimes {
+            block_number: *block_number,
+            timestamp:    meta.block_timestamp * SECONDS_TO_US,
+        };
+
+        match range_or_arbitrary {
+            CexRangeOrArbitrary::Range(start, end) => {
+                let (start, end) = if pad_range {
+                    (start.saturating_sub(pre), end + post)
+                } else {
+                    (*start, *end)
+                };
+                self.block_info
+                    .range(start..end)
+                    .map(to_block_time)
+                    .collect()
+            }
+            CexRangeOrArbitrary::Arbitrary(blocks) => blocks
+                .iter()
+                .flat_map(|block| block.saturating_sub(pre)..=block + post)
+                .unique()
+                .filter_map(|block| self.block_info.get_key_value(&block))
+                .map(to_block_time)
+                .collect(),
+            CexRangeOrArbitrary::Timestamp { block_number, block_timestamp } => {
+                vec![BlockTimes {
+                    block_number: *block_number,
+                    timestamp:    block_timestamp * SECONDS_TO_US,
+                }]
+            }
+        }
+    }
+
+    async fn load_rows<R, F>(
+        &self,
+        dir: &str,
+        windows: Vec<(u64, u64)>,
+        parse: F,
+    ) -> eyre::Result<Vec<R>>
+    where
+        R: Send + 'static,
+        F: Fn(&RecordBatch) -> eyre::Result<Vec<(u64, CexExchange, R)>> + Send + 'static,
+    {
+        let dir = self.data_dir.join(dir);
+        let exchanges = self.cex_download_config.exchanges_to_use.clone();
+
+        tokio::task::spawn_blocking(move || load_rows_from_dir(&dir, &windows, &exchanges, parse))
+            .await?
+    }
+
+    async fn load_trades(&self, windows: Vec<(u64, u64)>) -> eyre::Result<Vec<RawCexTrades>> {
+        self.load_rows(TRADES_DIR, windows, parse_trades).await
+    }
+
+    async fn load_quotes(&self, windows: Vec<(u64, u64)>) -> eyre::Result<Vec<RawCexQuotes>> {
+        self.load_rows(QUOTES_DIR, windows, parse_quotes).await
+    }
+}
+
+impl ClickhouseHandle for ClickhouseFileClient {
+    async fn get_metadata(
+        &self,
+        block_num: u64,
+        block_timestamp: u64,
+        block_hash: BlockHash,
+        _: Vec<TxHash>,
+        quote_asset: Address,
+    ) -> eyre::Result<Metadata> {
+        let block_meta = self.block_info.get(&block_num).cloned().unwrap_or_else(|| {
+            warn!(block_num, "no local block metadata, relay & p2p data will be missing");
+            BlockMetadataInner::make_new(block_hash, block_timestamp, None, None, vec![])
+        });
+
+        let cex_quotes = self
+            .get_cex_prices(CexRangeOrArbitrary::Timestamp {
+                block_number: block_num,
+                block_timestamp,
+            })
+            .await?
+            .pop()
+            .map(|quotes| quotes.value)
+            .unwrap_or_else(|| {
+                warn!(block_num, "no local cex quotes found for block");
+                CexPriceMap::default()
+            });
+
+        let eth_price = determine_eth_prices(
+            &cex_quotes,
+            block_meta.block_timestamp * SECONDS_TO_US,
+            quote_asset,
+        );
+
+        Ok(BlockMetadata::new(
+            block_num,
+            block_meta.block_hash,
+            block_meta.block_timestamp,
+            block_meta.relay_timestamp,
+            block_meta.p2p_timestamp,
+            block_meta.proposer_fee_recipient,
+            block_meta.proposer_mev_reward,
+            eth_price.unwrap_or_default(),
+            block_meta.private_flow.into_iter().collect(),
+        )
+        .into_metadata(cex_quotes, None, None, None))
+    }
+
+    async fn get_cex_prices(
+        &self,
+        range_or_arbitrary: CexRangeOrArbitrary,
+    ) -> eyre::Result<Vec<CexPriceData>> {
+        let block_times = self.block_times(&range_or_arbitrary, false);
+        if block_times.is_empty() {
+            eyre::bail!("No block times found");
+        }
+
+        let windows = time_windows(&block_times, QUOTES_WINDOW_US);
+        let quotes = self.load_quotes(windows.clone()).await?;
+        // the venues are ranked by their traded volume over the same period
+        let symbol_rank = symbol_rank(&self.load_trades(windows).await?);
+
+        debug!(quotes = quotes.len(), "loaded local cex quotes");
+
+        let price_converter =
+            CexQuotesConverter::new(block_times, self.symbols.clone(), quotes, symbol_rank);
+
+        Ok(price_converter
+            .convert_to_prices()
+            .into_iter()
+            .map(|(block_num, price_map)| CexPriceData::new(block_num, price_map))
+            .collect())
+    }
+
+    async fn get_cex_trades(
+        &self,
+        range_or_arbitrary: CexRangeOrArbitrary,
+    ) -> eyre::Result<Vec<CexTradesData>> {
+        let block_times = self.block_times(&range_or_arbitrary, true);
+        if block_times.is_empty() {
+            eyre::bail!("No block times found");
+        }
+
+        let trades = self
+            .load_trades(time_windows(&block_times, TRADES_WINDOW_US))
+            .await?;
+
+        debug!(trades = trades.len(), "loaded local cex trades");
+
+        let trades_converter = CexTradesConverter::new(block_times, self.symbols.clone(), trades);
+
+        Ok(trades_converter
+            .convert_to_trades()
+            .into_iter()
+            .map(|(block_num, trade_map)| CexTradesData::new(block_num, trade_map))
+            .collect())
+    }
+
+    async fn query_many_range<T, D>(&self, start_block: u64, end_block: u64) -> eyre::Result<Vec<D>>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+        D: LibmdbxData<T> + DbRow + for<'de> Deserialize<'de> + Send + Debug + Unpin + 'static,
+    {
+        Ok(self.block_info_for::<T, D>(self.block_info.range(start_block..end_block)))
+    }
+
+    async fn query_many_arbitrary<T, D>(&self, range: &'static [u64]) -> eyre::Result<Vec<D>>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+        D: LibmdbxData<T> + DbRow + for<'de> Deserialize<'de> + Send + Debug + Unpin + 'static,
+    {
+        Ok(self.block_info_for::<T, D>(
+            range
+                .iter()
+                .filter_map(|block| self.block_info.get_key_value(block)),
+        ))
+    }
+
+    async fn query_many<T, D>(&self) -> eyre::Result<Vec<D>>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+        D: LibmdbxData<T> + DbRow + for<'de> Deserialize<'de> + Send + Debug + Unpin + 'static,
+    {
+        Ok(self.block_info_for::<T, D>(self.block_info.iter()))
+    }
+}
+
+impl ClickhouseFileClient {
+    /// the block info table is the only one that is kept locally, everything
+    /// else has to come from the db snapshot
+    fn block_info_for<'a, T, D>(
+        &self,
+        blocks: impl Iterator<Item = (&'a u64, &'a BlockMetadataInner)>,
+    ) -> Vec<D>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+        D: LibmdbxData<T> + 'static,
+    {
+        if T::NAME != BlockInfo::NAME {
+            debug!(table = T::NAME, "no local data for table");
+            return vec![]
+        }
+
+        let data: Box<dyn Any> = Box::new(
+            blocks
+                .map(|(block, meta)| BlockInfoData::new(*block, meta.clone()))
+                .collect::<Vec<_>>(),
+        );
+
+        match data.downcast::<Vec<D>>() {
+            Ok(data) => *data,
+            Err(_) => {
+                debug!(table = T::NAME, "block info requested as another row type");
+                vec![]
+            }
+        }
+    }
+}
+
+/// Either the Sorella clickhouse api or local files, picked at startup
+/// depending on what the user has configured
+pub enum ClickhouseSource {
+    Http(ClickhouseHttpClient),
+    Files(ClickhouseFileClient),
+}
+
+impl ClickhouseHandle for ClickhouseSource {
+    async fn get_metadata(
+        &self,
+        block_num: u64,
+        block_timestamp: u64,
+        block_hash: BlockHash,
+        tx_hashes_in_block: Vec<TxHash>,
+        quote_asset: Address,
+    ) -> eyre::Result<Metadata> {
+        match self {
+            Self::Http(client) => {
+                client
+                    .get_metadata(
+                        block_num,
+                        block_timestamp,
+                        block_hash,
+                        tx_hashes_in_block,
+                        quote_asset,
+                    )
+                    .await
+            }
+            Self::Files(client) => {
+                client
+                    .get_metadata(
+                        block_num,
+                        block_timestamp,
+                        block_hash,
+                        tx_hashes_in_block,
+                        quote_asset,
+                    )
+                    .await
+            }
+        }
+    }
+
+    async fn get_cex_prices(
+        &self,
+        range_or_arbitrary: CexRangeOrArbitrary,
+    ) -> eyre::Result<Vec<CexPriceData>> {
+        match self {
+            Self::Http(client) => client.get_cex_prices(range_or_arbitrary).await,
+            Self::Files(client) => client.get_cex_prices(range_or_arbitrary).await,
+        }
+    }
+
+    async fn get_cex_trades(
+        &self,
+        range_or_arbitrary: CexRangeOrArbitrary,
+    ) -> eyre::Result<Vec<CexTradesData>> {
+        match self {
+            Self::Http(client) => client.get_cex_trades(range_or_arbitrary).await,
+            Self::Files(client) => client.get_cex_trades(range_or_arbitrary).await,
+        }
+    }
+
+    async fn query_many_range<T, D>(&self, start_block: u64, end_block: u64) -> eyre::Result<Vec<D>>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+        D: LibmdbxData<T> + DbRow + for<'de> Deserialize<'de> + Send + Debug + Unpin + 'static,
+    {
+        match self {
+            Self::Http(client) => {
+                client
+                    .query_many_range::<T, D>(start_block, end_block)
+                    .await
+            }
+            Self::Files(client) => {
+                client
+                    .query_many_range::<T, D>(start_block, end_block)
+                    .await
+            }
+        }
+    }
+
+    async fn query_many_arbitrary<T, D>(&self, range: &'static [u64]) -> eyre::Result<Vec<D>>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+        D: LibmdbxData<T> + DbRow + for<'de> Deserialize<'de> + Send + Debug + Unpin + 'static,
+    {
+        match self {
+            Self::Http(client) => client.query_many_arbitrary::<T, D>(range).await,
+            Self::Files(client) => client.query_many_arbitrary::<T, D>(range).await,
+        }
+    }
+
+    async fn query_many<T, D>(&self) -> eyre::Result<Vec<D>>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+        D: LibmdbxData<T> + DbRow + for<'de> Deserialize<'de> + Send + Debug + Unpin + 'static,
+    {
+        match self {
+            Self::Http(client) => client.query_many::<T, D>().await,
+            Self::Files(client) => client.query_many::<T, D>().await,
+        }
+    }
+}
+
+fn load_symbols(path: &Path) -> eyre::Result<Vec<CexSymbols>> {
+    let mut symbols = Vec::new();
+    for batch in read_record_batches(path)? {
+        let exchange = string_column(&batch, "exchange")?;
+        let symbol = string_column(&batch, "symbol")?;
+        let token0 = string_column(&batch, "token0")?;
+        let token1 = string_column(&batch, "token1")?;
+
+        for i in 0..batch.num_rows() {
+            symbols.push(CexSymbols {
+                exchange:     exchange.value(i).into(),
+                symbol_pair:  normalize_symbol(symbol.value(i)),
+                address_pair: Pair(token0.value(i).parse()?, token1.value(i).parse()?),
+            });
+        }
+    }
+
+    Ok(symbols)
+}
+
+fn load_block_metadata(path: &Path) -> eyre::Result<BTreeMap<u64, BlockMetadataInner>> {
+    let file = File::open(path)
+        .map_err(|e| eyre::eyre!("failed to open block metadata {}: {e}", path.display()))?;
+
+    BufReader::new(file)
+        .lines()
+        .filter(|line| line.as_ref().map(|l| !l.trim().is_empty()).unwrap_or(true))
+        .map(|line| {
+            let record: BlockMetadataRecord = serde_json::from_str(&line?)?;
+            Ok::<_, eyre::Report>((record.block_number, record.into()))
+        })
+        .collect()
+}
+
+/// loads the rows of every file in the directory that fall into one of the
+/// time windows and are from one of the given exchanges, sorted by timestamp
+fn load_rows_from_dir<R, F>(
+    dir: &Path,
+    windows: &[(u64, u64)],
+    exchanges: &[CexExchange],
+    parse: F,
+) -> eyre::Result<Vec<R>>
+where
+    F: Fn(&RecordBatch) -> eyre::Result<Vec<(u64, CexExchange, R)>>,
+{
+    if !dir.exists() {
+        warn!(?dir, "local cex data directory doesn't exist");
+        return Ok(vec![])
+    }
+
+    let date_regex = Regex::new(r"\d{4}-\d{2}-\d{2}").unwrap();
+    let files = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|path| path.is_file())
+        .filter(|path| file_in_windows(path, &date_regex, windows))
+        .sorted();
+
+    let mut rows = Vec::new();
+    for file in files {
+        for batch in read_record_batches(&file)? {
+            rows.extend(
+                parse(&batch)?
+                    .into_iter()
+                    .filter(|(timestamp, exchange, _)| {
+                        exchanges.contains(exchange) && in_windows(*timestamp, windows)
+                    }),
+            );
+        }
+    }
+
+    Ok(rows
+        .into_iter()
+        .sorted_by_key(|(timestamp, ..)| *timestamp)
+        .map(|(.., row)| row)
+        .collect())
+}
+
+fn parse_trades(batch: &RecordBatch) -> eyre::Result<Vec<(u64, CexExchange, RawCexTrades)>> {
+    let exchange = string_column(batch, "exchange")?;
+    let symbol = string_column(batch, "symbol")?;
+    let timestamp = timestamp_column(batch)?;
+    let side = string_column(batch, "side")?;
+    let price = f64_column(batch, "price")?;
+    let amount = f64_column(batch, "amount")?;
+
+    Ok((0..batch.num_rows())
+        .filter(|i| timestamp.is_valid(*i) && price.is_valid(*i) && amount.is_valid(*i))
+        .map(|i| {
+            let trade = RawCexTrades {
+                exchange:   exchange.value(i).into(),
+                trade_type: TradeType::Taker,
+                symbol:     normalize_symbol(symbol.value(i)),
+                timestamp:  timestamp.value(i) as u64,
+                side:       side.value(i).to_string(),
+                price:      price.value(i),
+                amount:     amount.value(i),
+            };
+            (trade.timestamp, trade.exchange, trade)
+        })
+        .collect())
+}
+
+fn parse_quotes(batch: &RecordBatch) -> eyre::Result<Vec<(u64, CexExchange, RawCexQuotes)>> {
+    let exchange = string_column(batch, "exchange")?;
+    let symbol = string_column(batch, "symbol")?;
+    let timestamp = timestamp_column(batch)?;
+    let ask_amount = f64_column(batch, "ask_amount")?;
+    let ask_price = f64_column(batch, "ask_price")?;
+    let bid_price = f64_column(batch, "bid_price")?;
+    let bid_amount = f64_column(batch, "bid_amount")?;
+
+    // tardis leaves a side empty when the book has no level on it
+    Ok((0..batch.num_rows())
+        .filter(|i| {
+            [&ask_amount, &ask_price, &bid_price, &bid_amount]
+                .iter()
+                .all(|column| column.is_valid(*i))
+                && timestamp.is_valid(*i)
+        })
+        .map(|i| {
+            let quote = RawCexQuotes {
+                exchange:   exchange.value(i).into(),
+                symbol:     normalize_symbol(symbol.value(i)),
+                timestamp:  timestamp.value(i) as u64,
+                ask_amount: ask_amount.value(i),
+                ask_price:  ask_price.value(i),
+                bid_price:  bid_price.value(i),
+                bid_amount: bid_amount.value(i),
+            };
+            (quote.timestamp, quote.exchange, quote)
+        })
+        .collect())
+}
+
+/// ranks the exchanges of each symbol by their traded notional, the local
+/// equivalent of the monthly volume ranking of the clickhouse backend
+fn symbol_rank(trades: &[RawCexTrades]) -> Vec<BestCexPerPair> {
+    let timestamp = trades.first().map(|t| t.timestamp).unwrap_or_default();
+
+    trades
+        .iter()
+        .fold(FastHashMap::<(&str, CexExchange), f64>::default(), |mut acc, trade| {
+            *acc.entry((trade.symbol.as_str(), trade.exchange))
+                .or_default() += trade.price * trade.amount;
+            acc
+        })
+        .into_iter()
+        .into_group_map_by(|((symbol, _), _)| *symbol)
+        .into_iter()
+        .map(|(symbol, volumes)| BestCexPerPair {
+            symbol: symbol.to_string(),
+            exchange: volumes
+                .into_iter()
+                .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+                .map(|((_, exchange), _)| exchange)
+                .collect(),
+            timestamp,
+        })
+        .collect()
+}
+
+/// the symbols are normalized the same way the clickhouse queries do so that
+/// `BTC-USD` and `BTCUSD` map to the same pair
+fn normalize_symbol(symbol: &str) -> String {
+    symbol.replace(['/', '-', '_'], "").to_uppercase()
+}
+
+/// merges the padded block times into sorted, non overlapping windows
+fn time_windows(block_times: &[BlockTimes], padding: u64) -> Vec<(u64, u64)> {
+    block_times
+        .iter()
+        .map(|b| (b.timestamp.saturating_sub(padding), b.timestamp + padding))
+        .sorted()
+        .coalesce(|a, b| if b.0 <= a.1 { Ok((a.0, a.1.max(b.1))) } else { Err((a, b)) })
+        .collect()
+}
+
+fn in_windows(timestamp: u64, windows: &[(u64, u64)]) -> bool {
+    let idx = windows.partition_point(|(start, _)| *start <= timestamp);
+    idx > 0 && timestamp < windows[idx - 1].1
+}
+
+/// tardis dumps are split by day, so we can skip whole files whose date isn't
+/// in any of the windows. files without a date are always read
+fn file_in_windows(path: &Path, date_regex: &Regex, windows: &[(u64, u64)]) -> bool {
+    let Some(date) = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| date_regex.find(name))
+        .and_then(|date| NaiveDate::parse_from_str(date.as_str(), "%Y-%m-%d").ok())
+    else {
+        return true
+    };
+
+    let start = date
+        .and_hms_opt(0, 0, 0)
+        .map(|d| d.and_utc().timestamp() as u64 * SECONDS_TO_US)
+        .unwrap_or_default();
+    let end = start + DAY_US;
+
+    windows.iter().any(|(s, e)| *s < end && start < *e)
+}
+
+fn read_record_batches(path: &Path) -> eyre::Result<Vec<RecordBatch>> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+
+    if name.ends_with(".parquet") {
+        return Ok(ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?
+            .build()?
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    let open = || -> eyre::Result<Box<dyn Read>> {
+        let file =
+            File::open(path).map_err(|e| eyre::eyre!("failed to open {}: {e}", path.display()))?;
+        Ok(if name.ends_with(".gz") { Box::new(GzDecoder::new(file)) } else { Box::new(file) })
+    };
+
+    let format = Format::default().with_header(true);
+    let (schema, _) = format.infer_schema(open()?, Some(CSV_SCHEMA_INFER_ROWS))?;
+
+    Ok(ReaderBuilder::new(Arc::new(schema))
+        .with_format(format)
+        .build(open()?)?
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+fn column(batch: &RecordBatch, name: &str, data_type: &DataType) -> eyre::Result<Arc<dyn Array>> {
+    let column = batch
+        .column_by_name(name)
+        .ok_or_else(|| eyre::eyre!("local cex data is missing the {name} column"))?;
+
+    Ok(cast(column, data_type)?)
+}
+
+fn string_column(batch: &RecordBatch, name: &str) -> eyre::Result<StringArray> {
+    Ok(column(batch, name, &DataType::Utf8)?
+        .as_string::<i32>()
+        .clone())
+}
+
+fn f64_column(batch: &RecordBatch, name: &str) -> eyre::Result<Float64Array> {
+    Ok(column(batch, name, &DataType::Float64)?
+        .as_primitive::<Float64Type>()
+        .clone())
+}
+
+/// parquet files can store the timestamp as a microsecond timestamp type
+/// instead of a plain integer, both cast to the raw microseconds
+fn timestamp_column(batch: &RecordBatch) -> eyre::Result<Int64Array> {
+    Ok(column(batch, "timestamp", &DataType::Int64)?
+        .as_primitive::<Int64Type>()
+        .clone())
+}
+
+#[cfg(test)]
+pub mod test {
+    use brontes_types::constants::USDT_ADDRESS;
+
+    use super::*;
+    use crate::CexPrice;
+
+    fn write_data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join(TRADES_DIR)).unwrap();
+        std::fs::create_dir_all(dir.join(QUOTES_DIR)).unwrap();
+
+        std::fs::write(
+            dir.join(SYMBOLS_FILE),
+            concat!(
+                "exchange,symbol,token0,token1\n",
+                "binance,ETHUSDT,0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2,",
+                "0xdAC17F958D2ee523a2206206994597C13D831ec7\n",
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(BLOCK_METADATA_FILE),
+            concat!(
+                "{\"block_number\":18500000,",
+                "\"block_hash\":",
+                "\"0x6b4b6b3f9f1c87a6d2a3d4f2d5a5ad1a1e2a8d1b5f3bb4e7b6bd1b0a9c74e3e1\",",
+                "\"block_timestamp\":1699000000,\"relay_timestamp\":1699000000500}\n",
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(TRADES_DIR)
+                .join("binance_trades_2023-11-03_ETHUSDT.csv"),
+            concat!(
+                "exchange,symbol,timestamp,local_timestamp,id,side,price,amount\n",
+                "binance,ETHUSDT,1699000001000000,1699000001000100,1,buy,1800.5,2.0\n",
+                "binance,ETHUSDT,1699000100000000,1699000100000100,2,sell,1801.0,1.0\n",
+            ),
+        )
+        .unwrap();
+        // outside of the block's day, should never be read
+        std::fs::write(
+            dir.join(TRADES_DIR)
+                .join("binance_trades_2023-11-05_ETHUSDT.csv"),
+            "not,a,valid\ncsv\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(QUOTES_DIR)
+                .join("binance_quotes_2023-11-03_ETHUSDT.csv"),
+            concat!(
+                "exchange,symbol,timestamp,local_timestamp,ask_amount,ask_price,bid_price,",
+                "bid_amount\n",
+                "binance,ETHUSDT,1698999999000000,1698999999000100,5.0,1800.6,1800.4,4.0\n",
+                "binance,ETHUSDT,1699000002000000,1699000002000100,,,1800.3,3.0\n",
+            ),
+        )
+        .unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn test_time_windows() {
+        let block_times = vec![
+            BlockTimes { block_number: 2, timestamp: 30 },
+            BlockTimes { block_number: 1, timestamp: 10 },
+            BlockTimes { block_number: 3, timestamp: 100 },
+        ];
+        let windows = time_windows(&block_times, 12);
+
+        assert_eq!(windows, vec![(0, 42), (88, 112)]);
+        assert!(in_windows(41, &windows));
+        assert!(!in_windows(42, &windows));
+        assert!(in_windows(88, &windows));
+        assert!(!in_windows(112, &windows));
+    }
+
+    #[brontes_macros::test]
+    async fn test_local_cex_trades() {
+        let dir = write_data_dir("brontes_local_cex_trades");
+        let client = ClickhouseFileClient::new(&dir, CexDownloadConfig::default()).unwrap();
+
+        let trades = client
+            .get_cex_trades(CexRangeOrArbitrary::Range(18500000, 18500001))
+            .await
+            .unwrap();
+
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].key, 18500000);
+        let exchange_trades = trades[0].value.0.get(&CexExchange::Binance).unwrap();
+        // only the trade within 6 seconds of the block is bucketed into it
+        assert_eq!(exchange_trades.values().map(|t| t.len()).sum::<usize>(), 1);
+    }
+
+    #[brontes_macros::test]
+    async fn test_local_metadata() {
+        let dir = write_data_dir("brontes_local_metadata");
+        let client = ClickhouseFileClient::new(&dir, CexDownloadConfig::default()).unwrap();
+
+        let metadata = client
+            .get_metadata(
+                18500000,
+                1699000000,
+                Default::default(),
+                Default::default(),
+                USDT_ADDRESS,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(metadata.block_num, 18500000);
+        assert_eq!(metadata.relay_timestamp, Some(1699000000500));
+        // the quote with an empty ask side is dropped, leaving the one before the block
+        // for every time boundary
+        let quotes = metadata
+            .cex_quotes
+            .quotes
+            .get(&CexExchange::Binance)
+            .unwrap();
+        assert!(quotes
+            .values()
+            .flatten()
+            .all(|quote| quote.timestamp == 1698999999000000));
+    }
+
+    #[brontes_macros::test]
+    async fn test_local_block_info() {
+        let dir = write_data_dir("brontes_local_block_info");
+        let client = ClickhouseFileClient::new(&dir, CexDownloadConfig::default()).unwrap();
+
+        let block_info = client
+            .query_many_range::<BlockInfo, BlockInfoData>(18500000, 18500001)
+            .await
+            .unwrap();
+        assert_eq!(block_info.len(), 1);
+        assert_eq!(block_info[0].key, 18500000);
+
+        // everything but the block info has to come from the db snapshot
+        assert!(client
+            .query_many::<CexPrice, CexPriceData>()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
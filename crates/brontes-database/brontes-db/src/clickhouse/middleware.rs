@@ -7,12 +7,16 @@ use brontes_types::{
         address_to_protocol_info::ProtocolInfo,
         block_analysis::BlockAnalysis,
         builder::BuilderInfo,
-        dex::DexQuotes,
+        cex::{quotes::CexPriceMap, trades::CexTradeMap},
+        dex::{DexQuoteWithIndex, DexQuotes},
+        fund::FundInfo,
         metadata::Metadata,
         mev_block::MevBlockWithClassified,
+        pricing_snapshot::PricingGraphSnapshot,
         searcher::SearcherInfo,
         token_info::TokenInfoWithAddress,
         traits::{DBWriter, LibmdbxReader, ProtocolCreatedRange},
+        uniswap_v4_pool_key::UniswapV4PoolKey,
     },
     mev::{Bundle, MevBlock},
     normalized_actions::Action,
@@ -166,6 +170,12 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
 
         self.inner().save_traces(block, traces).await
     }
+
+    async fn rollback_block(&self, block: u64) -> eyre::Result<()> {
+        self.client.rollback_block(block).await?;
+
+        self.inner().rollback_block(block).await
+    }
 }
 
 impl<I: LibmdbxInit> LibmdbxInit for ClickhouseMiddleware<I> {
@@ -317,6 +327,14 @@ impl<I: LibmdbxInit> LibmdbxReader for ClickhouseMiddleware<I> {
         self.inner.fetch_all_builder_info()
     }
 
+    fn try_fetch_fund_info(&self, name: &str) -> eyre::Result<Option<FundInfo>> {
+        self.inner.try_fetch_fund_info(name)
+    }
+
+    fn fetch_all_fund_info(&self) -> eyre::Result<Vec<(String, FundInfo)>> {
+        self.inner.fetch_all_fund_info()
+    }
+
     //TODO: JOE
     fn try_fetch_mev_blocks(
         &self,
@@ -382,9 +400,68 @@ impl<I: LibmdbxInit> LibmdbxReader for ClickhouseMiddleware<I> {
         self.inner.get_protocol_details(address)
     }
 
+    fn try_fetch_v4_pool_key(&self, pool: Address) -> eyre::Result<Option<UniswapV4PoolKey>> {
+        self.inner.try_fetch_v4_pool_key(pool)
+    }
+
+    fn fetch_all_v4_pool_keys(&self) -> eyre::Result<Vec<(Address, UniswapV4PoolKey)>> {
+        self.inner.fetch_all_v4_pool_keys()
+    }
+
+    fn try_fetch_latest_pricing_snapshot(
+        &self,
+        block: u64,
+    ) -> eyre::Result<Option<(u64, PricingGraphSnapshot)>> {
+        self.inner.try_fetch_latest_pricing_snapshot(block)
+    }
+
     fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>> {
         self.inner.load_trace(block_num)
     }
+
+    fn fetch_dex_price_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, DexQuoteWithIndex)>> {
+        self.inner.fetch_dex_price_range(start_block, end_block)
+    }
+
+    fn fetch_cex_price_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, CexPriceMap)>> {
+        self.inner.fetch_cex_price_range(start_block, end_block)
+    }
+
+    fn fetch_cex_trades_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, CexTradeMap)>> {
+        self.inner.fetch_cex_trades_range(start_block, end_block)
+    }
+
+    fn fetch_trace_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, Vec<TxTrace>)>> {
+        self.inner.fetch_trace_range(start_block, end_block)
+    }
+
+    fn fetch_mev_block_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<MevBlockWithClassified>> {
+        self.inner.fetch_mev_block_range(start_block, end_block)
+    }
+
+    fn fetch_all_token_info(&self) -> eyre::Result<Vec<TokenInfoWithAddress>> {
+        self.inner.fetch_all_token_info()
+    }
 }
 
 pub struct ReadOnlyMiddleware<I: DBWriter> {
@@ -494,6 +571,32 @@ impl<I: DBWriter + Send + Sync> DBWriter for ReadOnlyMiddleware<I> {
     async fn save_traces(&self, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
         self.client.save_traces(block, traces.clone()).await
     }
+
+    /// pool keys only live in libmdbx, which we don't write to in read only
+    /// mode
+    async fn insert_v4_pool_key(&self, _pool_key: UniswapV4PoolKey) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// snapshots only live in libmdbx, which we don't write to in read only
+    /// mode
+    async fn save_pricing_snapshot(
+        &self,
+        _block: u64,
+        _snapshot: PricingGraphSnapshot,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// the fund registry only lives in libmdbx, which we don't write to in read
+    /// only mode
+    async fn write_fund_info(&self, _name: String, _info: FundInfo) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    async fn rollback_block(&self, block: u64) -> eyre::Result<()> {
+        self.client.rollback_block(block).await
+    }
 }
 
 impl<I: LibmdbxInit> LibmdbxInit for ReadOnlyMiddleware<I> {
@@ -639,6 +742,14 @@ impl<I: LibmdbxInit> LibmdbxReader for ReadOnlyMiddleware<I> {
         self.inner.fetch_all_builder_info()
     }
 
+    fn try_fetch_fund_info(&self, name: &str) -> eyre::Result<Option<FundInfo>> {
+        self.inner.try_fetch_fund_info(name)
+    }
+
+    fn fetch_all_fund_info(&self) -> eyre::Result<Vec<(String, FundInfo)>> {
+        self.inner.fetch_all_fund_info()
+    }
+
     //TODO: JOE
     fn try_fetch_mev_blocks(
         &self,
@@ -704,7 +815,66 @@ impl<I: LibmdbxInit> LibmdbxReader for ReadOnlyMiddleware<I> {
         self.inner.get_protocol_details(address)
     }
 
+    fn try_fetch_v4_pool_key(&self, pool: Address) -> eyre::Result<Option<UniswapV4PoolKey>> {
+        self.inner.try_fetch_v4_pool_key(pool)
+    }
+
+    fn fetch_all_v4_pool_keys(&self) -> eyre::Result<Vec<(Address, UniswapV4PoolKey)>> {
+        self.inner.fetch_all_v4_pool_keys()
+    }
+
+    fn try_fetch_latest_pricing_snapshot(
+        &self,
+        block: u64,
+    ) -> eyre::Result<Option<(u64, PricingGraphSnapshot)>> {
+        self.inner.try_fetch_latest_pricing_snapshot(block)
+    }
+
     fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>> {
         self.inner.load_trace(block_num)
     }
+
+    fn fetch_dex_price_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, DexQuoteWithIndex)>> {
+        self.inner.fetch_dex_price_range(start_block, end_block)
+    }
+
+    fn fetch_cex_price_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, CexPriceMap)>> {
+        self.inner.fetch_cex_price_range(start_block, end_block)
+    }
+
+    fn fetch_cex_trades_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, CexTradeMap)>> {
+        self.inner.fetch_cex_trades_range(start_block, end_block)
+    }
+
+    fn fetch_trace_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, Vec<TxTrace>)>> {
+        self.inner.fetch_trace_range(start_block, end_block)
+    }
+
+    fn fetch_mev_block_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<MevBlockWithClassified>> {
+        self.inner.fetch_mev_block_range(start_block, end_block)
+    }
+
+    fn fetch_all_token_info(&self) -> eyre::Result<Vec<TokenInfoWithAddress>> {
+        self.inner.fetch_all_token_info()
+    }
 }
@@ -18,6 +18,10 @@ pub use split_db::*;
 pub mod http_client;
 #[cfg(not(feature = "local-clickhouse"))]
 pub use http_client::*;
+#[cfg(not(feature = "local-clickhouse"))]
+pub mod local_files;
+#[cfg(not(feature = "local-clickhouse"))]
+pub use local_files::*;
 
 #[cfg(feature = "local-clickhouse")]
 mod middleware;
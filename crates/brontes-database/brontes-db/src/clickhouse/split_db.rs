@@ -9,14 +9,28 @@ use db_interfaces::{
     clickhouse::{client::ClickhouseClient, config::ClickhouseConfig},
     Database,
 };
-use futures::{stream::FuturesUnordered, Future, StreamExt};
+use futures::{stream::FuturesUnordered, Future, FutureExt, StreamExt};
 use reth_tasks::shutdown::GracefulShutdown;
-use tokio::task::JoinError;
+use tokio::{
+    sync::{mpsc::UnboundedReceiver, oneshot},
+    task::JoinError,
+};
 
-use crate::clickhouse::dbms::*;
+use crate::clickhouse::{
+    db_client::{delete_block_rows, BLOCK_INDEXED_TABLES},
+    dbms::*,
+};
 
 type InsertFut = Pin<Box<dyn Future<Output = Result<eyre::Result<()>, JoinError>> + Send>>;
 
+/// Deletes all rows of an orphaned block. Sent to the [`ClickhouseBuffered`]
+/// so the delete runs after the rows of the block that are still buffered
+/// have been written.
+pub struct RollbackRequest {
+    pub block: u64,
+    pub done:  oneshot::Sender<eyre::Result<()>>,
+}
+
 pub struct ClickhouseBuffered {
     client:            ClickhouseClient<BrontesClickhouseTables>,
     rx:                UnboundedYapperReceiver<Vec<BrontesClickhouseData>>,
@@ -27,6 +41,10 @@ pub struct ClickhouseBuffered {
     /// if none, will always write to db. if some. will only start writing if
     heart_rate:        Option<HeartRateMonitor>,
     skip:              bool,
+    rollback_rx:       Option<UnboundedReceiver<RollbackRequest>>,
+    /// while a rollback is running no new inserts are started, so rows of the
+    /// re-processed block can't be deleted by it
+    rollback:          Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
 }
 
 impl ClickhouseBuffered {
@@ -46,20 +64,22 @@ impl ClickhouseBuffered {
             skip: heart_rate.is_some(),
             heart_rate,
             futs: FuturesUnordered::default(),
+            rollback_rx: None,
+            rollback: None,
         }
     }
 
+    pub fn with_rollbacks(mut self, rollback_rx: UnboundedReceiver<RollbackRequest>) -> Self {
+        self.rollback_rx = Some(rollback_rx);
+        self
+    }
+
     fn handle_incoming(&mut self, value: Vec<BrontesClickhouseData>) {
         let enum_kind = value.first().as_ref().unwrap().data.get_db_enum();
-        let mut force_insert = false;
+        let force_insert = self.buffer(value);
 
         let entry = self.value_map.entry(enum_kind.clone()).or_default();
 
-        entry.extend(value.into_iter().map(|value| {
-            force_insert |= value.force_insert;
-            value.data
-        }));
-
         let size = if enum_kind.is_big() { self.buffer_size_big } else { self.buffer_size_small };
 
         if entry.len() >= size || force_insert {
@@ -72,6 +92,79 @@ impl ClickhouseBuffered {
         }
     }
 
+    /// adds the rows to the buffer of their table, returns true if one of them
+    /// requires the buffer to be flushed
+    fn buffer(&mut self, value: Vec<BrontesClickhouseData>) -> bool {
+        let enum_kind = value.first().as_ref().unwrap().data.get_db_enum();
+        let mut force_insert = false;
+
+        self.value_map
+            .entry(enum_kind)
+            .or_default()
+            .extend(value.into_iter().map(|value| {
+                force_insert |= value.force_insert;
+                value.data
+            }));
+
+        force_insert
+    }
+
+    /// Flushes the buffer and waits for all in flight inserts before deleting
+    /// the rows of the block.
+    fn start_rollback(&mut self, request: RollbackRequest) {
+        // rows sent before the rollback request can still be in the channel
+        while let Ok(value) = self.rx.try_recv() {
+            if !value.is_empty() && !self.skip {
+                self.buffer(value);
+            }
+        }
+
+        let buffered = std::mem::take(&mut self.value_map)
+            .into_iter()
+            .filter(|(_, rows)| !rows.is_empty())
+            .collect::<Vec<_>>();
+        let mut in_flight = std::mem::take(&mut self.futs);
+        let client = self.client.clone();
+
+        self.rollback = Some(Box::pin(async move {
+            while let Some(res) = in_flight.next().await {
+                if let Err(e) = res {
+                    tracing::error!(target: "brontes", "error writing to clickhouse {:?}", e);
+                }
+            }
+            for (table, rows) in buffered {
+                if let Err(e) = Self::insert(client.clone(), rows, table).await {
+                    tracing::error!(target: "brontes", "error writing to clickhouse {:?}", e);
+                }
+            }
+
+            let res = delete_block_rows(&client, &BLOCK_INDEXED_TABLES, request.block, None).await;
+            let _ = request.done.send(res);
+        }));
+    }
+
+    /// Inserts the rows straight away, bypassing the buffer. Used to backfill
+    /// rows that never made it into clickhouse.
+    pub async fn insert_unbuffered(
+        client: ClickhouseClient<BrontesClickhouseTables>,
+        rows: Vec<BrontesClickhouseData>,
+    ) -> eyre::Result<()> {
+        let mut tables: FastHashMap<BrontesClickhouseTables, Vec<BrontesClickhouseTableDataTypes>> =
+            FastHashMap::default();
+        for row in rows {
+            tables
+                .entry(row.data.get_db_enum())
+                .or_default()
+                .push(row.data);
+        }
+
+        for (table, data) in tables {
+            Self::insert(client.clone(), data, table).await?;
+        }
+
+        Ok(())
+    }
+
     async fn insert(
         client: ClickhouseClient<BrontesClickhouseTables>,
         data: Vec<BrontesClickhouseTableDataTypes>,
@@ -114,6 +207,7 @@ impl ClickhouseBuffered {
             (MevJit_Sandwich, JitLiquiditySandwich),
             (MevSandwiches, Sandwich),
             (MevAtomic_Arbs, AtomicArb),
+            (MevCustom, CustomMev),
             (MevLiquidations, Liquidation),
             (BrontesDex_Price_Mapping, DexQuotesWithBlockNumber),
             (BrontesToken_Info, TokenInfoWithAddress),
@@ -204,6 +298,13 @@ impl Future for ClickhouseBuffered {
         let mut work = 128;
 
         loop {
+            if let Some(rollback) = this.rollback.as_mut() {
+                if rollback.poll_unpin(cx).is_pending() {
+                    return Poll::Pending
+                }
+                this.rollback = None;
+            }
+
             if let Some(hr) = this.heart_rate.as_mut() {
                 match hr.poll_next_unpin(cx) {
                     Poll::Ready(Some(val)) => {
@@ -238,6 +339,13 @@ impl Future for ClickhouseBuffered {
                 }
             }
 
+            if let Some(Poll::Ready(Some(request))) =
+                this.rollback_rx.as_mut().map(|rx| rx.poll_recv(cx))
+            {
+                this.start_rollback(request);
+                continue
+            }
+
             work -= 1;
             if work == 0 {
                 cx.waker().wake_by_ref();
@@ -20,6 +20,11 @@ CREATE TABLE mev.atomic_arbs ON CLUSTER eth_cluster0
         `effective_gas_price` UInt128
     ),
     `arb_type` String,
+    `refund_recipient` Nullable(String),
+    `refund_tx_hash` Nullable(String),
+    `refund_type` Nullable(String),
+    `refund_amount_eth` Nullable(Float64),
+    `refund_amount_usd` Nullable(Float64),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedMergeTree('/clickhouse/eth_cluster0/tables/all/mev/atomic_arbs', '{replica}')
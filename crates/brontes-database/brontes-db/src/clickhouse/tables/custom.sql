@@ -0,0 +1,19 @@
+CREATE TABLE mev.custom ON CLUSTER eth_cluster0
+(
+    `inspector` String,
+    `block_number` UInt64,
+    `tx_hashes` Array(String),
+    `gas_details` Nested(
+        `tx_hash` String,
+        `coinbase_transfer` Nullable(UInt128),
+        `priority_fee` UInt128,
+        `gas_used` UInt128,
+        `effective_gas_price` UInt128
+    ),
+    `protocols` Array(String),
+    `payload` String,
+    `run_id` UInt64
+) 
+ENGINE = ReplicatedMergeTree('/clickhouse/eth_cluster0/tables/all/mev/custom', '{replica}')
+PRIMARY KEY (`block_number`, `inspector`)
+ORDER BY (`block_number`, `inspector`)
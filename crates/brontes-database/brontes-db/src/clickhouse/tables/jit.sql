@@ -49,6 +49,7 @@ CREATE TABLE mev.jit ON CLUSTER eth_cluster0
         `gas_used` UInt128,
         `effective_gas_price` UInt128
     ),
+    `fees_earned_usd` Float64,
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedMergeTree('/clickhouse/eth_cluster0/tables/all/mev/jit', '{replica}')
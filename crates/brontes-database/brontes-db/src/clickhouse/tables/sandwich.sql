@@ -57,6 +57,14 @@ CREATE TABLE mev.sandwiches ON CLUSTER eth_cluster0
         `gas_used` UInt128,
         `effective_gas_price` UInt128
     ),
+    `victim_loss` Nested(
+        `tx_hash` String,
+        `victim_eoa` String,
+        `token` String,
+        `amount_out` UInt256,
+        `counterfactual_amount_out` UInt256,
+        `amount_lost_usd` Float64
+    ),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedMergeTree('/clickhouse/eth_cluster0/tables/all/mev/sandwiches', '{replica}')
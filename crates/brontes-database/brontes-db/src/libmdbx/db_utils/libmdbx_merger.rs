@@ -27,12 +27,16 @@ pub fn merge_libmdbx_dbs(
             InitializedState,
             PoolCreationBlocks,
             TxTraces,
+            TxStateDiffs,
             AddressMeta,
             SearcherEOAs,
             SearcherContracts,
             Builder,
             AddressToProtocolInfo,
             TokenDecimals,
+            UniswapV4PoolKeys,
+            PricingSnapshots,
+            Funds,
             DexPrice
             );
 
@@ -107,7 +107,9 @@ impl LibmdbxPartitioner {
                     MevBlocks,
                     InitializedState,
                     PoolCreationBlocks,
-                    TxTraces
+                    TxTraces,
+                    TxStateDiffs,
+                    PricingSnapshots
                 );
                 // manually dex pricing
                 self.parent_db
@@ -129,7 +131,9 @@ impl LibmdbxPartitioner {
             SearcherContracts,
             Builder,
             AddressToProtocolInfo,
-            TokenDecimals
+            TokenDecimals,
+            UniswapV4PoolKeys,
+            Funds
         );
 
         Ok(())
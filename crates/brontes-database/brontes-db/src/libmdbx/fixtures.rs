@@ -0,0 +1,418 @@
+//! Self contained snapshots of everything the classifier and inspectors read
+//! from libmdbx for a single block.
+//!
+//! A fixture holds the raw rows of the tables for the block together with its
+//! header, so tests can run against a fresh database without a node or
+//! clickhouse. Fixtures are stored as zstd compressed json, one file per block,
+//! next to an index that maps transaction hashes to their block.
+
+use std::{
+    collections::BTreeMap,
+    ops::RangeBounds,
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::{Address, Bytes, TxHash};
+use brontes_libmdbx::RO;
+use brontes_types::{
+    constants::{USDT_ADDRESS, WETH_ADDRESS},
+    db::dex::make_filter_key_range,
+    structured_trace::TraceActions,
+    FastHashSet,
+};
+use eyre::eyre;
+use reth_db::{
+    cursor::DbCursorRO,
+    table::{Compress, Encode},
+    transaction::DbTx,
+};
+use reth_primitives::Header;
+use serde::{Deserialize, Serialize};
+
+use super::{tables::*, CompressedLibmdbxTx, LibmdbxReadWriter};
+use crate::CompressedTable;
+
+/// Extension of the fixture files
+pub const FIXTURE_EXTENSION: &str = "json.zst";
+/// Name of the transaction index in the fixture directory
+pub const FIXTURE_INDEX: &str = "index.json";
+/// Cex trades are loaded in a window around the block, this covers the window
+/// of the test utils
+const CEX_TRADES_WINDOW: u64 = 10;
+const ZSTD_LEVEL: i32 = 9;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureTable {
+    pub table: Tables,
+    /// Encoded keys and compressed values, as they are stored in libmdbx
+    pub rows:  Vec<(Bytes, Bytes)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockFixture {
+    pub block:     u64,
+    pub header:    Header,
+    /// Hashes of the transactions in the block, by index
+    pub tx_hashes: Vec<TxHash>,
+    pub tables:    Vec<FixtureTable>,
+}
+
+impl BlockFixture {
+    pub fn path(dir: &Path, block: u64) -> PathBuf {
+        dir.join(format!("{block}.{FIXTURE_EXTENSION}"))
+    }
+
+    /// Reads the fixture for the block, if one was recorded
+    pub fn read(dir: &Path, block: u64) -> eyre::Result<Option<Self>> {
+        let path = Self::path(dir, block);
+        if !path.exists() {
+            return Ok(None)
+        }
+
+        let compressed = std::fs::read(&path)?;
+        let json = zstd::stream::decode_all(compressed.as_slice())?;
+
+        Ok(Some(serde_json::from_slice(&json)?))
+    }
+
+    /// Writes the fixture to the directory, returning the path of the file
+    pub fn write(&self, dir: &Path) -> eyre::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+
+        let path = Self::path(dir, self.block);
+        let json = serde_json::to_vec(self)?;
+        std::fs::write(&path, zstd::stream::encode_all(json.as_slice(), ZSTD_LEVEL)?)?;
+
+        Ok(path)
+    }
+}
+
+/// Maps the transactions of all recorded fixtures to their block and index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureIndex {
+    pub txs: BTreeMap<TxHash, (u64, usize)>,
+}
+
+impl FixtureIndex {
+    pub fn load(dir: &Path) -> eyre::Result<Self> {
+        let path = dir.join(FIXTURE_INDEX);
+        if !path.exists() {
+            return Ok(Self::default())
+        }
+
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, dir: &Path) -> eyre::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(FIXTURE_INDEX), serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, fixture: &BlockFixture) {
+        self.txs.extend(
+            fixture
+                .tx_hashes
+                .iter()
+                .enumerate()
+                .map(|(idx, hash)| (*hash, (fixture.block, idx))),
+        );
+    }
+
+    pub fn get(&self, tx_hash: &TxHash) -> Option<(u64, usize)> {
+        self.txs.get(tx_hash).copied()
+    }
+}
+
+impl LibmdbxReadWriter {
+    /// Collects the traces, metadata, dex quotes and cex data of the block,
+    /// along with the protocol and token info of every address touched in the
+    /// block. Fails if the block hasn't been traced yet.
+    pub fn record_fixture(&self, block: u64, header: Header) -> eyre::Result<BlockFixture> {
+        self.db.view_db(|tx| {
+            let mut traces = tx
+                .get::<TxTraces>(block)?
+                .and_then(|inner| inner.traces)
+                .ok_or_else(|| eyre!("no traces for block {block}"))?;
+            traces.sort_by_key(|trace| trace.tx_index);
+
+            let mut addresses = FastHashSet::default();
+            addresses.extend([USDT_ADDRESS, WETH_ADDRESS, header.beneficiary]);
+            for trace in traces.iter().flat_map(|tx| &tx.trace) {
+                addresses.extend([trace.get_from_addr(), trace.get_to_address(), trace.msg_sender]);
+                addresses.extend(trace.logs.iter().map(|log| log.address));
+            }
+
+            // the tokens of the touched pools aren't necessarily touched themselves
+            let mut tokens = addresses.clone();
+            for address in &addresses {
+                if let Some(info) = tx.get::<AddressToProtocolInfo>(*address)? {
+                    tokens.extend(info.get_tokens());
+                    tokens.extend(info.curve_lp_token);
+                }
+            }
+
+            let (dex_start, dex_end) = make_filter_key_range(block);
+            let cex_window = block.saturating_sub(CEX_TRADES_WINDOW)..=block + CEX_TRADES_WINDOW;
+
+            let tables = vec![
+                raw_rows::<TxTraces, _>(tx, block..=block)?,
+                raw_rows::<TxStateDiffs, _>(tx, block..=block)?,
+                raw_rows::<BlockInfo, _>(tx, block..=block)?,
+                raw_rows::<CexPrice, _>(tx, block..=block)?,
+                raw_rows::<DexPrice, _>(tx, dex_start..=dex_end)?,
+                raw_rows::<CexTrades, _>(tx, cex_window.clone())?,
+                raw_rows::<InitializedState, _>(tx, cex_window)?,
+                raw_keys::<AddressToProtocolInfo>(tx, &addresses)?,
+                raw_keys::<TokenDecimals>(tx, &tokens)?,
+                raw_keys::<AddressMeta>(tx, &addresses)?,
+                raw_keys::<Builder>(tx, &[header.beneficiary].into_iter().collect())?,
+            ];
+
+            let tx_hashes = traces.iter().map(|trace| trace.tx_hash).collect();
+
+            Ok(BlockFixture { block, header, tx_hashes, tables })
+        })
+    }
+
+    /// Writes the rows of the fixture into the database, overwriting any
+    /// existing entries
+    pub fn import_fixture(&self, fixture: &BlockFixture) -> eyre::Result<()> {
+        let tx = self.db.rw_tx()?;
+
+        for FixtureTable { table, rows } in &fixture.tables {
+            for (key, value) in rows {
+                let value = value.to_vec();
+                match table {
+                    Tables::TxTraces => tx.put_bytes::<TxTraces>(key, value)?,
+                    Tables::TxStateDiffs => tx.put_bytes::<TxStateDiffs>(key, value)?,
+                    Tables::BlockInfo => tx.put_bytes::<BlockInfo>(key, value)?,
+                    Tables::CexPrice => tx.put_bytes::<CexPrice>(key, value)?,
+                    Tables::DexPrice => tx.put_bytes::<DexPrice>(key, value)?,
+                    Tables::CexTrades => tx.put_bytes::<CexTrades>(key, value)?,
+                    Tables::InitializedState => tx.put_bytes::<InitializedState>(key, value)?,
+                    Tables::AddressToProtocolInfo => {
+                        tx.put_bytes::<AddressToProtocolInfo>(key, value)?
+                    }
+                    Tables::TokenDecimals => tx.put_bytes::<TokenDecimals>(key, value)?,
+                    Tables::AddressMeta => tx.put_bytes::<AddressMeta>(key, value)?,
+                    Tables::Builder => tx.put_bytes::<Builder>(key, value)?,
+                    table => eyre::bail!("{table} isn't part of a fixture"),
+                }
+            }
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+}
+
+fn raw_rows<T, R>(tx: &CompressedLibmdbxTx<RO>, range: R) -> eyre::Result<FixtureTable>
+where
+    T: CompressedTable,
+    T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+    R: RangeBounds<T::Key>,
+{
+    let rows =
+        tx.0.new_cursor::<T>()?
+            .walk_range(range)?
+            .map(|row| {
+                let (key, value) = row?;
+                Ok(raw_row::<T>(key, value))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+    Ok(FixtureTable { table: table_of::<T>(), rows })
+}
+
+fn raw_keys<T>(
+    tx: &CompressedLibmdbxTx<RO>,
+    keys: &FastHashSet<Address>,
+) -> eyre::Result<FixtureTable>
+where
+    T: CompressedTable<Key = Address>,
+    T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+{
+    let mut keys = keys.iter().copied().collect::<Vec<_>>();
+    keys.sort_unstable();
+
+    let mut rows = Vec::new();
+    for key in keys {
+        if let Some(value) = tx.0.get::<T>(key)? {
+            rows.push(raw_row::<T>(key, value));
+        }
+    }
+
+    Ok(FixtureTable { table: table_of::<T>(), rows })
+}
+
+fn raw_row<T: CompressedTable>(key: T::Key, value: T::Value) -> (Bytes, Bytes)
+where
+    T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+{
+    let value: Vec<u8> = value.compress().into();
+    (Bytes::copy_from_slice(key.encode().as_ref()), value.into())
+}
+
+fn table_of<T: CompressedTable>() -> Tables
+where
+    T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+{
+    T::NAME.parse().expect("every table is part of `Tables`")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{b256, U256, U64};
+    use brontes_pricing::Protocol;
+    use brontes_types::{
+        db::{
+            address_to_protocol_info::ProtocolInfo,
+            token_info::TokenInfo,
+            traces::{TxStateDiffsInner, TxTracesInner},
+            traits::LibmdbxReader,
+        },
+        structured_trace::{AccountStateDiff, BalanceDiff, TransactionTraceWithLogs, TxTrace},
+    };
+    use reth_rpc_types::trace::parity::{
+        Action, CallAction, CallOutput, CallType, TraceOutput, TransactionTrace,
+    };
+
+    use super::*;
+
+    const BLOCK: u64 = 10;
+    const EOA: Address = Address::with_last_byte(1);
+    const POOL: Address = Address::with_last_byte(2);
+    const TOKEN: Address = Address::with_last_byte(3);
+    const UNTOUCHED: Address = Address::with_last_byte(4);
+    const TX_HASH: TxHash =
+        b256!("00000000000000000000000000000000000000000000000000000000000000aa");
+
+    /// a tx of the eoa calling the pool, which holds weth and a token that
+    /// isn't touched in the block. The eoa pays for gas
+    fn tx_trace() -> TxTrace {
+        let trace = TransactionTraceWithLogs {
+            trace:        TransactionTrace {
+                action:        Action::Call(CallAction {
+                    from:      EOA,
+                    to:        POOL,
+                    value:     U256::ZERO,
+                    gas:       U64::ZERO,
+                    input:     Bytes::new(),
+                    call_type: CallType::Call,
+                }),
+                error:         None,
+                result:        Some(TraceOutput::Call(CallOutput {
+                    gas_used: U64::ZERO,
+                    output:   Bytes::new(),
+                })),
+                subtraces:     0,
+                trace_address: vec![],
+            },
+            logs:         vec![],
+            msg_sender:   EOA,
+            trace_idx:    0,
+            decoded_data: None,
+        };
+
+        let state_diff = vec![AccountStateDiff {
+            address: EOA,
+            balance: Some(BalanceDiff { pre: U256::from(1_000_000), post: U256::from(979_000) }),
+            storage: vec![],
+        }];
+
+        TxTrace::new(BLOCK, vec![trace], TX_HASH, 0, 21_000, 1, true, state_diff)
+    }
+
+    fn pool_info() -> ProtocolInfo {
+        ProtocolInfo {
+            protocol: Protocol::UniswapV2,
+            token0: WETH_ADDRESS,
+            token1: TOKEN,
+            ..Default::default()
+        }
+    }
+
+    fn source_db(dir: &tempfile::TempDir) -> LibmdbxReadWriter {
+        let db = LibmdbxReadWriter::init_db_tests(dir.path()).unwrap();
+
+        let traces = vec![tx_trace()];
+        db.db
+            .write_table::<TxStateDiffs, TxStateDiffsData>(&[TxStateDiffsData::new(
+                BLOCK,
+                TxStateDiffsInner::from_traces(&traces),
+            )])
+            .unwrap();
+        db.db
+            .write_table::<TxTraces, TxTracesData>(&[TxTracesData::new(
+                BLOCK,
+                TxTracesInner::new(Some(traces)),
+            )])
+            .unwrap();
+        db.db
+            .write_table::<AddressToProtocolInfo, AddressToProtocolInfoData>(&[
+                AddressToProtocolInfoData::new(POOL, pool_info()),
+            ])
+            .unwrap();
+        db.db
+            .write_table::<TokenDecimals, TokenDecimalsData>(&[
+                TokenDecimalsData::new(WETH_ADDRESS, TokenInfo::new(18, "WETH".to_string())),
+                TokenDecimalsData::new(TOKEN, TokenInfo::new(6, "TOKEN".to_string())),
+                TokenDecimalsData::new(UNTOUCHED, TokenInfo::new(9, "UNTOUCHED".to_string())),
+            ])
+            .unwrap();
+
+        db
+    }
+
+    #[test]
+    fn test_record_and_import_round_trip() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let fixture_dir = tempfile::tempdir().unwrap();
+        let target_dir = tempfile::tempdir().unwrap();
+
+        let header = Header { number: BLOCK, ..Default::default() };
+        let recorded = source_db(&source_dir)
+            .record_fixture(BLOCK, header.clone())
+            .unwrap();
+        recorded.write(fixture_dir.path()).unwrap();
+
+        let mut index = FixtureIndex::default();
+        index.insert(&recorded);
+        index.save(fixture_dir.path()).unwrap();
+        assert_eq!(
+            FixtureIndex::load(fixture_dir.path())
+                .unwrap()
+                .get(&TX_HASH),
+            Some((BLOCK, 0))
+        );
+
+        let fixture = BlockFixture::read(fixture_dir.path(), BLOCK)
+            .unwrap()
+            .expect("fixture was written");
+        assert_eq!(fixture.header, header);
+        assert_eq!(fixture.tx_hashes, vec![TX_HASH]);
+        assert!(BlockFixture::read(fixture_dir.path(), BLOCK + 1)
+            .unwrap()
+            .is_none());
+
+        let db = LibmdbxReadWriter::init_db_tests(target_dir.path()).unwrap();
+        db.import_fixture(&fixture).unwrap();
+
+        // the state diff is stored apart from the trace and joined back on load
+        let stored = db
+            .db
+            .view_db(|tx| tx.get::<TxTraces>(BLOCK).map_err(Into::into))
+            .unwrap()
+            .and_then(|inner| inner.traces)
+            .unwrap();
+        assert!(stored[0].state_diff.is_empty());
+        assert_eq!(db.load_trace(BLOCK).unwrap(), vec![tx_trace()]);
+        assert_eq!(db.get_protocol_details(POOL).unwrap(), pool_info());
+        assert_eq!(db.try_fetch_token_info(WETH_ADDRESS).unwrap().decimals, 18);
+        // the tokens of touched pools are recorded even if they aren't touched
+        assert_eq!(db.try_fetch_token_info(TOKEN).unwrap().decimals, 6);
+        assert!(db.try_fetch_token_info(UNTOUCHED).is_err());
+    }
+}
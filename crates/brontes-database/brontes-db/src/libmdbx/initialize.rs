@@ -6,6 +6,7 @@ use brontes_types::{
     db::{
         address_metadata::{AddressMetadata, ContractInfo, Socials},
         builder::BuilderInfo,
+        fund::{FundInfo, FundRegistry},
         searcher::SearcherInfo,
         traits::{DBWriter, LibmdbxReader},
     },
@@ -115,10 +116,11 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
     }
 
     pub async fn load_config(&self) -> eyre::Result<()> {
+        let funds = self.load_fund_config_data().await;
         join!(
             self.load_classifier_config_data(),
-            self.load_searcher_config_data(),
-            self.load_builder_config_data(),
+            self.load_searcher_config_data(&funds),
+            self.load_builder_config_data(&funds),
             self.load_address_metadata_config(),
         );
 
@@ -393,7 +395,44 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
         }
     }
 
-    async fn load_builder_config_data(&self) {
+    /// loads the `funds` registry of the searcher and builder configs and
+    /// merges it with the funds that are already stored. The returned registry
+    /// is used to resolve the fund labels of the configs to their canonical
+    /// name
+    async fn load_fund_config_data(&self) -> FundRegistry {
+        let mut funds: FastHashMap<String, FundInfo> = self
+            .libmdbx
+            .fetch_all_fund_info()
+            .expect("Failed to query fund table")
+            .into_iter()
+            .collect();
+
+        for config_file in [SEARCHER_CONFIG_FILE, BUILDER_CONFIG_FILE] {
+            let mut fund_config_path = workspace_dir();
+            fund_config_path.push(config_file);
+
+            let fund_config_str =
+                std::fs::read_to_string(fund_config_path).expect("Failed to read fund config file");
+
+            let fund_config: FundConfig =
+                toml::from_str(&fund_config_str).expect("Failed to parse fund TOML");
+
+            for (name, fund_info) in fund_config.funds {
+                funds.entry(name).or_default().merge(fund_info);
+            }
+        }
+
+        for (name, fund_info) in &funds {
+            self.libmdbx
+                .write_fund_info(name.clone(), fund_info.clone())
+                .await
+                .expect("Failed to write fund info");
+        }
+
+        funds.into_iter().collect()
+    }
+
+    async fn load_builder_config_data(&self, funds: &FundRegistry) {
         let mut builder_config_path = workspace_dir();
         builder_config_path.push(BUILDER_CONFIG_FILE);
 
@@ -404,7 +443,8 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
             toml::from_str(&builder_config_str).expect("Failed to parse builder TOML");
 
         // Process builders
-        for (address_str, builder_info) in builder_config.builders {
+        for (address_str, mut builder_info) in builder_config.builders {
+            builder_info.fund = builder_info.fund.map(|fund| funds.resolve(&fund));
             let address: Address = address_str
                 .parse()
                 .unwrap_or_else(|_| panic!("Failed to parse address '{}'", address_str));
@@ -428,7 +468,7 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
         }
     }
 
-    async fn load_searcher_config_data(&self) {
+    async fn load_searcher_config_data(&self, funds: &FundRegistry) {
         let mut searcher_config_path = workspace_dir();
 
         searcher_config_path.push(SEARCHER_CONFIG_FILE);
@@ -440,7 +480,8 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
             toml::from_str(&searcher_config_str).expect("Failed to parse searcher TOML");
 
         // Process SearcherEOAs
-        for (address_str, searcher_info) in searcher_config.searcher_eoas {
+        for (address_str, mut searcher_info) in searcher_config.searcher_eoas {
+            searcher_info.fund = funds.resolve(&searcher_info.fund);
             let address = address_str
                 .parse()
                 .unwrap_or_else(|_| panic!("Failed to parse address '{}'", address_str));
@@ -464,7 +505,8 @@ impl<TP: TracingProvider, CH: ClickhouseHandle> LibmdbxInitializer<TP, CH> {
             }
         }
         // Process SearcherContracts
-        for (address_str, searcher_info) in searcher_config.searcher_contracts {
+        for (address_str, mut searcher_info) in searcher_config.searcher_contracts {
+            searcher_info.fund = funds.resolve(&searcher_info.fund);
             let address = address_str.parse().unwrap();
             let existing_info = self.libmdbx.try_fetch_searcher_contract_info(address);
 
@@ -544,6 +586,13 @@ struct BuilderConfig {
     builders: FastHashMap<String, BuilderInfo>,
 }
 
+/// The `funds` section shared by the searcher and builder configs
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct FundConfig {
+    #[serde(default)]
+    funds: FastHashMap<String, FundInfo>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct SearcherConfig {
     searcher_eoas:      FastHashMap<String, SearcherInfo>,
@@ -0,0 +1,68 @@
+//! Keeps track of the archived layout version of every table in a
+//! `table_layouts.json` file inside the database directory, so that a database
+//! written with an older layout of a table drops the entries it can no longer
+//! decode instead of misreading them.
+
+use std::{collections::BTreeMap, path::Path};
+
+use eyre::Context;
+
+use super::tables::Tables;
+
+const LAYOUT_FILE: &str = "table_layouts.json";
+
+/// Returns the tables whose entries were written with an older layout than
+/// [`Tables::layout_version`]. Databases without a layout file predate the
+/// versioning, so all of their tables are on the first layout.
+pub(crate) fn outdated_tables(path: &Path) -> eyre::Result<Vec<Tables>> {
+    let versions = read_layout_versions(path)?;
+
+    Ok(Tables::ALL
+        .into_iter()
+        .filter(|table| {
+            versions.get(table.name()).copied().unwrap_or(1) < table.layout_version()
+        })
+        .collect())
+}
+
+/// Records the current layout of all tables
+pub(crate) fn write_layout_versions(path: &Path) -> eyre::Result<()> {
+    let versions = Tables::ALL
+        .iter()
+        .map(|table| (table.name().to_string(), table.layout_version()))
+        .collect::<BTreeMap<_, _>>();
+
+    let file = path.join(LAYOUT_FILE);
+    std::fs::write(&file, serde_json::to_vec_pretty(&versions)?)
+        .wrap_err_with(|| format!("Could not write table layouts to {}", file.display()))
+}
+
+fn read_layout_versions(path: &Path) -> eyre::Result<BTreeMap<String, u16>> {
+    let file = path.join(LAYOUT_FILE);
+    if !file.exists() {
+        return Ok(BTreeMap::new())
+    }
+
+    let versions = std::fs::read(&file)
+        .wrap_err_with(|| format!("Could not read table layouts from {}", file.display()))?;
+
+    Ok(serde_json::from_slice(&versions)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outdated_tables() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let outdated = outdated_tables(dir.path()).unwrap();
+        assert!(outdated.contains(&Tables::MevBlocks));
+        assert!(outdated.contains(&Tables::SearcherEOAs));
+        assert!(!outdated.contains(&Tables::TokenDecimals));
+
+        write_layout_versions(dir.path()).unwrap();
+        assert!(outdated_tables(dir.path()).unwrap().is_empty());
+    }
+}
@@ -10,16 +10,19 @@ use brontes_types::{
         address_to_protocol_info::ProtocolInfo,
         builder::BuilderInfo,
         cex::{quotes::CexPriceMap, trades::CexTradeMap},
-        dex::{make_filter_key_range, DexPrices, DexQuotes},
+        dex::{decompose_key, make_filter_key_range, DexPrices, DexQuoteWithIndex, DexQuotes},
+        fund::FundInfo,
         initialized_state::{
             InitializedStateMeta, CEX_QUOTES_FLAG, CEX_TRADES_FLAG, DATA_NOT_PRESENT_NOT_AVAILABLE,
             DATA_PRESENT, DEX_PRICE_FLAG, META_FLAG,
         },
         metadata::{BlockMetadata, BlockMetadataInner, Metadata},
         mev_block::MevBlockWithClassified,
+        pricing_snapshot::PricingGraphSnapshot,
         searcher::SearcherInfo,
         token_info::{TokenInfo, TokenInfoWithAddress},
         traits::{DBWriter, LibmdbxReader},
+        uniswap_v4_pool_key::UniswapV4PoolKey,
     },
     mev::{Bundle, MevBlock},
     normalized_actions::Action,
@@ -127,6 +130,26 @@ impl LibmdbxReadWriter {
         })
     }
 
+    /// Opens an existing database without a writer. Writes fail as the write
+    /// channel is closed.
+    pub fn init_db_read_only<P: AsRef<Path>>(
+        path: P,
+        log_level: Option<LogLevel>,
+        metrics: bool,
+    ) -> eyre::Result<Self> {
+        // 5 gb total
+        let memory_per_table_mb = 1_000;
+        let (tx, _) = unbounded_channel();
+        let db = Arc::new(Libmdbx::init_db_read_only(path, log_level)?);
+
+        Ok(Self {
+            db,
+            tx,
+            metrics: metrics.then(LibmdbxMetrics::default),
+            cache: ReadWriteCache::new(memory_per_table_mb, metrics),
+        })
+    }
+
     pub fn init_db_tests<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
         // 5 gb total
         let memory_per_table_mb = 1_000;
@@ -423,12 +446,18 @@ impl LibmdbxReader for LibmdbxReadWriter {
     #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"load_trace")]
     fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>> {
         self.db.view_db(|tx| {
-            tx.get::<TxTraces>(block_num)?
+            let mut traces = tx
+                .get::<TxTraces>(block_num)?
                 .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))
                 .map(|i| {
                     i.traces
                         .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))
-                })?
+                })??;
+            if let Some(state_diffs) = tx.get::<TxStateDiffs>(block_num)? {
+                state_diffs.apply(&mut traces);
+            }
+
+            Ok(traces)
         })
     }
 
@@ -830,6 +859,30 @@ impl LibmdbxReader for LibmdbxReadWriter {
         )
     }
 
+    fn try_fetch_fund_info(&self, name: &str) -> eyre::Result<Option<FundInfo>> {
+        self.db
+            .view_db(|tx| tx.get::<Funds>(name.to_string()).map_err(ErrReport::from))
+    }
+
+    #[instrument(level = "error", skip_all)]
+    fn fetch_all_fund_info(&self) -> eyre::Result<Vec<(String, FundInfo)>> {
+        self.db.export_db(
+            None,
+            |start_key, tx| {
+                let mut cur = tx.cursor_read::<Funds>()?;
+                if let Some(key) = start_key {
+                    let _ = cur.seek(key);
+                } else {
+                    // move to first entry and make sure .next() is first
+                    let _ = cur.first();
+                    let _ = cur.prev();
+                }
+                Ok(cur)
+            },
+            |cursor| Ok(cursor.next().map(|inner| inner.map(|i| (i.0, i.1)))?),
+        )
+    }
+
     #[instrument(level = "error", skip_all)]
     fn try_fetch_mev_blocks(
         &self,
@@ -857,6 +910,20 @@ impl LibmdbxReader for LibmdbxReadWriter {
         )
     }
 
+    fn fetch_mev_block_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<MevBlockWithClassified>> {
+        self.db.view_db(|tx| {
+            tx.cursor_read::<MevBlocks>()?
+                .walk_range(start_block..=end_block)?
+                .map(|row| row.map(|(_, block)| block))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Into::into)
+        })
+    }
+
     #[instrument(level = "error", skip_all)]
     fn fetch_all_mev_blocks(
         &self,
@@ -897,6 +964,139 @@ impl LibmdbxReader for LibmdbxReadWriter {
             |cursor| Ok(cursor.next().map(|inner| inner.map(|i| (i.0, i.1)))?),
         )
     }
+
+    fn try_fetch_v4_pool_key(&self, pool: Address) -> eyre::Result<Option<UniswapV4PoolKey>> {
+        self.db
+            .view_db(|tx| tx.get::<UniswapV4PoolKeys>(pool).map_err(ErrReport::from))
+    }
+
+    #[instrument(level = "error", skip_all)]
+    fn fetch_all_v4_pool_keys(&self) -> eyre::Result<Vec<(Address, UniswapV4PoolKey)>> {
+        self.db.export_db(
+            None,
+            |start_key, tx| {
+                let mut cur = tx.cursor_read::<UniswapV4PoolKeys>()?;
+                if let Some(key) = start_key {
+                    let _ = cur.seek(key);
+                } else {
+                    // move to first entry and make sure .next() is first
+                    let _ = cur.first();
+                    let _ = cur.prev();
+                }
+                Ok(cur)
+            },
+            |cursor| Ok(cursor.next().map(|inner| inner.map(|i| (i.0, i.1)))?),
+        )
+    }
+
+    fn try_fetch_latest_pricing_snapshot(
+        &self,
+        block: u64,
+    ) -> eyre::Result<Option<(u64, PricingGraphSnapshot)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<PricingSnapshots>()?;
+            // seek lands on the first snapshot at or after the block, so step back
+            // when it isn't an exact match
+            let snapshot = match cursor.seek(block)? {
+                Some((key, snapshot)) if key == block => Some((key, snapshot)),
+                Some(_) => cursor.prev()?,
+                None => cursor.last()?,
+            };
+
+            Ok(snapshot)
+        })
+    }
+
+    fn fetch_dex_price_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, DexQuoteWithIndex)>> {
+        let start_key = make_filter_key_range(start_block).0;
+        let end_key = make_filter_key_range(end_block).1;
+
+        self.db.view_db(|tx| {
+            tx.cursor_read::<DexPrice>()?
+                .walk_range(start_key..=end_key)?
+                .map(|row| row.map(|(key, quote)| (decompose_key(key).0, quote)))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Into::into)
+        })
+    }
+
+    fn fetch_cex_price_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, CexPriceMap)>> {
+        self.db.view_db(|tx| {
+            tx.cursor_read::<CexPrice>()?
+                .walk_range(start_block..=end_block)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Into::into)
+        })
+    }
+
+    fn fetch_cex_trades_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, CexTradeMap)>> {
+        self.db.view_db(|tx| {
+            tx.cursor_read::<CexTrades>()?
+                .walk_range(start_block..=end_block)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Into::into)
+        })
+    }
+
+    fn fetch_trace_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, Vec<TxTrace>)>> {
+        self.db.view_db(|tx| {
+            let mut state_diffs = tx
+                .cursor_read::<TxStateDiffs>()?
+                .walk_range(start_block..=end_block)?
+                .collect::<Result<FastHashMap<_, _>, _>>()?;
+
+            tx.cursor_read::<TxTraces>()?
+                .walk_range(start_block..=end_block)?
+                .filter_map_ok(|(block, inner)| {
+                    let mut traces = inner.traces?;
+                    if let Some(diffs) = state_diffs.remove(&block) {
+                        diffs.apply(&mut traces);
+                    }
+                    Some((block, traces))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Into::into)
+        })
+    }
+
+    #[instrument(level = "error", skip_all)]
+    fn fetch_all_token_info(&self) -> eyre::Result<Vec<TokenInfoWithAddress>> {
+        self.db.export_db(
+            None,
+            |start_key, tx| {
+                let mut cur = tx.cursor_read::<TokenDecimals>()?;
+                if let Some(key) = start_key {
+                    let _ = cur.seek(key);
+                } else {
+                    // move to first entry and make sure .next() is first
+                    let _ = cur.first();
+                    let _ = cur.prev();
+                }
+                Ok(cur)
+            },
+            |cursor| {
+                Ok(cursor.next().map(|inner| {
+                    inner.map(|(address, inner)| TokenInfoWithAddress { address, inner })
+                })?)
+            },
+        )
+    }
 }
 
 impl DBWriter for LibmdbxReadWriter {
@@ -1054,12 +1254,32 @@ impl DBWriter for LibmdbxReadWriter {
         )?)
     }
 
+    async fn insert_v4_pool_key(&self, pool_key: UniswapV4PoolKey) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::V4PoolKey { pool_key: Box::new(pool_key) }.stamp())?)
+    }
+
+    async fn save_pricing_snapshot(
+        &self,
+        block: u64,
+        snapshot: PricingGraphSnapshot,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::PricingSnapshot { block, snapshot: Box::new(snapshot) }.stamp())?)
+    }
+
     async fn save_traces(&self, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
         Ok(self
             .tx
             .send(WriterMessage::Traces { block, traces }.stamp())?)
     }
 
+    async fn rollback_block(&self, block: u64) -> eyre::Result<()> {
+        Ok(self.tx.send(WriterMessage::Rollback { block }.stamp())?)
+    }
+
     async fn write_builder_info(
         &self,
         builder_address: Address,
@@ -1071,6 +1291,12 @@ impl DBWriter for LibmdbxReadWriter {
         )?)
     }
 
+    async fn write_fund_info(&self, name: String, info: FundInfo) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::FundInfo { name, info: Box::new(info) }.stamp())?)
+    }
+
     /// only for internal functionality (i.e. clickhouse)
     async fn insert_tree(&self, _tree: BlockTree<Action>) -> eyre::Result<()> {
         Ok(())
@@ -1333,3 +1559,84 @@ pub fn tables_to_initialize(data: InitializedStateMeta) -> Vec<(Tables, bool)> {
         (Tables::CexTrades, data.is_initialized(CEX_TRADES_FLAG)),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{
+        db::leaderboard::{LeaderboardEntity, LeaderboardSort},
+        mev::{BundleHeader, MevType},
+    };
+
+    use super::*;
+
+    /// db holding blocks 10 to 12 with an atomic arb of the same searcher in
+    /// each
+    fn test_db(dir: &tempfile::TempDir) -> LibmdbxReadWriter {
+        let db = LibmdbxReadWriter::init_db_tests(dir.path()).unwrap();
+
+        let rows = (10..=12)
+            .map(|block| {
+                let bundle = Bundle {
+                    header: BundleHeader {
+                        block_number: block,
+                        eoa: Address::with_last_byte(1),
+                        mev_type: MevType::AtomicArb,
+                        profit_usd: 1.0,
+                        ..Default::default()
+                    },
+                    data:   Default::default(),
+                };
+                MevBlocksData::new(
+                    block,
+                    MevBlockWithClassified {
+                        block: MevBlock { block_number: block, ..Default::default() },
+                        mev:   vec![bundle],
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        db.db
+            .write_table::<MevBlocks, MevBlocksData>(&rows)
+            .unwrap();
+
+        db
+    }
+
+    #[test]
+    fn test_fetch_mev_block_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = test_db(&dir);
+
+        let blocks = |start, end| {
+            db.fetch_mev_block_range(start, end)
+                .unwrap()
+                .into_iter()
+                .map(|block| block.block.block_number)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(blocks(10, 10), vec![10]);
+        assert_eq!(blocks(10, 12), vec![10, 11, 12]);
+        assert_eq!(blocks(12, 20), vec![12]);
+        assert!(blocks(13, 20).is_empty());
+    }
+
+    #[test]
+    fn test_fetch_leaderboard_includes_first_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = test_db(&dir);
+
+        let bundle_count = |start, end| {
+            db.fetch_leaderboard(start, end)
+                .unwrap()
+                .top(LeaderboardEntity::Searcher, None, LeaderboardSort::Profit, 1)
+                .first()
+                .map(|row| row.bundle_count)
+                .unwrap_or_default()
+        };
+
+        assert_eq!(bundle_count(10, 10), 1);
+        assert_eq!(bundle_count(10, 11), 2);
+        assert_eq!(bundle_count(10, 12), 3);
+    }
+}
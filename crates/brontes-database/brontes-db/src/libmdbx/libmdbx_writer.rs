@@ -12,13 +12,16 @@ use brontes_types::{
         address_metadata::AddressMetadata,
         address_to_protocol_info::ProtocolInfo,
         builder::BuilderInfo,
-        dex::{make_key, DexQuoteWithIndex, DexQuotes},
-        initialized_state::{DATA_PRESENT, DEX_PRICE_FLAG, TRACE_FLAG},
+        dex::{make_filter_key_range, make_key, DexQuoteWithIndex, DexQuotes},
+        fund::FundInfo,
+        initialized_state::{DATA_NOT_PRESENT_UNKNOWN, DATA_PRESENT, DEX_PRICE_FLAG, TRACE_FLAG},
         mev_block::MevBlockWithClassified,
         pool_creation_block::PoolsToAddresses,
+        pricing_snapshot::PricingGraphSnapshot,
         searcher::SearcherInfo,
         token_info::TokenInfo,
-        traces::TxTracesInner,
+        traces::{TxStateDiffsInner, TxTracesInner},
+        uniswap_v4_pool_key::UniswapV4PoolKey,
     },
     mev::{Bundle, MevBlock},
     structured_trace::TxTrace,
@@ -86,6 +89,10 @@ pub enum WriterMessage {
         address:  Address,
         metadata: Box<AddressMetadata>,
     },
+    FundInfo {
+        name: String,
+        info: Box<FundInfo>,
+    },
     Pool {
         block:           u64,
         address:         Address,
@@ -93,10 +100,20 @@ pub enum WriterMessage {
         curve_lp_token:  Option<Address>,
         classifier_name: Protocol,
     },
+    V4PoolKey {
+        pool_key: Box<UniswapV4PoolKey>,
+    },
+    PricingSnapshot {
+        block:    u64,
+        snapshot: Box<PricingGraphSnapshot>,
+    },
     Traces {
         block:  u64,
         traces: Vec<TxTrace>,
     },
+    Rollback {
+        block: u64,
+    },
     Init(InitTables, Arc<Notify>),
 }
 
@@ -214,6 +231,10 @@ init!(
     MevBlocks,
     SearcherEOAs,
     SearcherContracts,
+    UniswapV4PoolKeys,
+    PricingSnapshots,
+    TxStateDiffs,
+    Funds,
     InitializedState
 );
 
@@ -242,10 +263,22 @@ impl LibmdbxWriter {
                 self.insert_pool(block, address, &tokens, curve_lp_token, classifier_name)?;
                 "pool"
             }
+            WriterMessage::V4PoolKey { pool_key } => {
+                self.insert_v4_pool_key(*pool_key)?;
+                "v4poolkey"
+            }
+            WriterMessage::PricingSnapshot { block, snapshot } => {
+                self.save_pricing_snapshot(block, *snapshot)?;
+                "pricingsnapshot"
+            }
             WriterMessage::Traces { block, traces } => {
                 self.save_traces(block, traces)?;
                 "traces"
             }
+            WriterMessage::Rollback { block } => {
+                self.rollback_block(block)?;
+                "rollback"
+            }
             WriterMessage::DexQuotes { block_number, quotes } => {
                 self.write_dex_quotes(block_number, quotes)?;
                 "dexquotes"
@@ -266,6 +299,10 @@ impl LibmdbxWriter {
                 self.write_address_meta(address, *metadata)?;
                 "addressmeta"
             }
+            WriterMessage::FundInfo { name, info } => {
+                self.write_fund_info(name, *info)?;
+                "fundinfo"
+            }
             WriterMessage::SearcherInfo {
                 eoa_address,
                 contract_address,
@@ -514,8 +551,39 @@ impl LibmdbxWriter {
         })
     }
 
+    #[instrument(target = "libmdbx_read_write::insert_v4_pool_key", skip_all, level = "warn")]
+    fn insert_v4_pool_key(&self, pool_key: UniswapV4PoolKey) -> eyre::Result<()> {
+        let data = UniswapV4PoolKeysData::new(pool_key.pool_address(), pool_key);
+        self.instrumented_write::<UniswapV4PoolKeys, UniswapV4PoolKeysData>(&[data])
+            .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
+    #[instrument(target = "libmdbx_read_write::save_pricing_snapshot", skip_all, level = "warn")]
+    fn save_pricing_snapshot(
+        &self,
+        block: u64,
+        snapshot: PricingGraphSnapshot,
+    ) -> eyre::Result<()> {
+        let data = PricingSnapshotsData::new(block, snapshot);
+        self.instrumented_write::<PricingSnapshots, PricingSnapshotsData>(&[data])
+            .expect("libmdbx write failure");
+
+        Ok(())
+    }
+
     #[instrument(target = "libmdbx_read_write::save_traces", skip_all, level = "warn")]
     fn save_traces(&mut self, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
+        let state_diffs = TxStateDiffsInner::from_traces(&traces);
+        if !state_diffs.is_empty() {
+            self.instrumented_write::<TxStateDiffs, TxStateDiffsData>(&[TxStateDiffsData::new(
+                block,
+                state_diffs,
+            )])
+            .expect("libmdbx write failure");
+        }
+
         let data = TxTracesData::new(block, TxTracesInner { traces: Some(traces) }).into_key_val();
         let (key, value) = Self::convert_into_save_bytes(data);
 
@@ -530,6 +598,35 @@ impl LibmdbxWriter {
         self.init_state_updating(block, TRACE_FLAG)
     }
 
+    /// deletes the mev, trace and dex price entries for a block that is no
+    /// longer canonical. Pending batched inserts are flushed first so that we
+    /// never write data for the block after it has been removed
+    #[instrument(target = "libmdbx_read_write::rollback_block", skip_all, level = "warn")]
+    fn rollback_block(&mut self, block: u64) -> eyre::Result<()> {
+        self.insert_remaining();
+
+        let tx = self.db.rw_tx()?;
+        tx.delete::<MevBlocks>(block, None)?;
+        tx.delete::<TxTraces>(block, None)?;
+        tx.delete::<TxStateDiffs>(block, None)?;
+
+        let (start_key, end_key) = make_filter_key_range(block);
+        let mut cursor = tx.cursor_write::<DexPrice>()?;
+        let mut walker = cursor.walk_range(start_key..=end_key)?;
+        while walker.next().transpose()?.is_some() {
+            walker.delete_current()?;
+        }
+
+        if let Some(mut state) = tx.get::<InitializedState>(block)? {
+            state.set(TRACE_FLAG, DATA_NOT_PRESENT_UNKNOWN);
+            state.set(DEX_PRICE_FLAG, DATA_NOT_PRESENT_UNKNOWN);
+            tx.put::<InitializedState>(block, state)?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
     #[instrument(target = "libmdbx_read_write::write_builder_info", skip_all, level = "warn")]
     fn write_builder_info(
         &self,
@@ -542,6 +639,14 @@ impl LibmdbxWriter {
         Ok(())
     }
 
+    #[instrument(target = "libmdbx_read_write::write_fund_info", skip_all, level = "warn")]
+    fn write_fund_info(&self, name: String, info: FundInfo) -> eyre::Result<()> {
+        let data = FundsData::new(name, info);
+        self.instrumented_write::<Funds, FundsData>(&[data])
+            .expect("libmdbx write failure");
+        Ok(())
+    }
+
     #[instrument(target = "libmdbx_read_write::init_state_updating", skip_all, level = "warn")]
     fn init_state_updating(&mut self, block: u64, flag: u16) -> eyre::Result<()> {
         let tx = self.db.ro_tx()?;
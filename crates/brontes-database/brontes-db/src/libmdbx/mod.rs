@@ -14,9 +14,11 @@ pub mod cache_middleware;
 pub use cache_middleware::*;
 
 pub mod cex_utils;
+pub mod fixtures;
 pub mod libmdbx_writer;
 
 pub mod initialize;
+mod layout;
 mod libmdbx_read_write;
 use brontes_libmdbx::{RO, RW};
 use env::{DatabaseArguments, DatabaseEnv, DatabaseEnvKind};
@@ -35,7 +37,7 @@ use reth_db::{
 };
 use reth_interfaces::db::LogLevel;
 use tables::*;
-use tracing::info;
+use tracing::{info, warn};
 
 use self::{
     cursor::CompressedCursor,
@@ -70,7 +72,8 @@ impl Libmdbx {
     /// path. Creates tables if necessary. Opens in read/write mode.
     pub fn init_db<P: AsRef<Path>>(path: P, log_level: Option<LogLevel>) -> eyre::Result<Self> {
         let rpath = path.as_ref();
-        if is_database_empty(rpath) {
+        let is_new = is_database_empty(rpath);
+        if is_new {
             std::fs::create_dir_all(rpath).wrap_err_with(|| {
                 format!("Could not create database directory {}", rpath.display())
             })?;
@@ -100,10 +103,47 @@ impl Libmdbx {
 
         let this = Self(db);
         this.create_tables()?;
+        if !is_new {
+            this.clear_outdated_tables(rpath)?;
+        }
+        layout::write_layout_versions(rpath)?;
 
         Ok(this)
     }
 
+    /// Opens up an existing database in read only mode. Tables aren't created,
+    /// so the database needs to have been opened read/write before.
+    pub fn init_db_read_only<P: AsRef<Path>>(
+        path: P,
+        log_level: Option<LogLevel>,
+    ) -> eyre::Result<Self> {
+        let rpath = path.as_ref();
+        if is_database_empty(rpath) {
+            eyre::bail!("no database found at {}", rpath.display());
+        }
+        // the version file can't be created without write access
+        match check_db_version_file(rpath) {
+            Ok(_) | Err(DatabaseVersionError::MissingFile) => (),
+            Err(err) => return Err(err.into()),
+        }
+        let outdated = layout::outdated_tables(rpath)?;
+        if !outdated.is_empty() {
+            eyre::bail!(
+                "tables {} were written with an older layout, open the database in read/write \
+                 mode to clear them",
+                outdated.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        let db = DatabaseEnv::open(
+            rpath,
+            DatabaseEnvKind::RO,
+            DatabaseArguments::new(ClientVersion::default()).with_log_level(log_level),
+        )?;
+
+        Ok(Self(db))
+    }
+
     /// Creates all the defined tables, opens if already created
     fn create_tables(&self) -> Result<(), DatabaseError> {
         let tx = CompressedLibmdbxTx::new_rw_tx(&self.0)?;
@@ -117,6 +157,22 @@ impl Libmdbx {
         Ok(())
     }
 
+    /// Clears the tables whose values changed their archived layout since the
+    /// database was written, as their entries can't be decoded anymore. They
+    /// are refilled by rerunning brontes over the range.
+    fn clear_outdated_tables(&self, path: &Path) -> eyre::Result<()> {
+        for table in layout::outdated_tables(path)? {
+            warn!(
+                target: "brontes::init",
+                %table,
+                "table was written with an older layout, clearing it"
+            );
+            table.clear(self)?;
+        }
+
+        Ok(())
+    }
+
     /// Clears a table in the database
     pub fn clear_table<T>(&self) -> eyre::Result<()>
     where
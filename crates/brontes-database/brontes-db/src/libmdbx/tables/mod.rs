@@ -17,6 +17,7 @@ use brontes_types::{
         },
         clickhouse_serde::tx_trace::tx_traces_inner,
         dex::{DexKey, DexQuoteWithIndex, DexQuoteWithIndexRedefined},
+        fund::{FundInfo, FundInfoRedefined},
         initialized_state::{
             InitializedStateMeta, CEX_QUOTES_FLAG, CEX_TRADES_FLAG, DEX_PRICE_FLAG, META_FLAG,
             TRACE_FLAG,
@@ -24,10 +25,14 @@ use brontes_types::{
         metadata::{BlockMetadataInner, BlockMetadataInnerRedefined},
         mev_block::{MevBlockWithClassified, MevBlockWithClassifiedRedefined},
         pool_creation_block::{PoolsToAddresses, PoolsToAddressesRedefined},
+        pricing_snapshot::{PricingGraphSnapshot, PricingGraphSnapshotRedefined},
         searcher::{SearcherInfo, SearcherInfoRedefined},
         token_info::TokenInfo,
-        traces::{TxTracesInner, TxTracesInnerRedefined},
+        traces::{
+            TxStateDiffsInner, TxStateDiffsInnerRedefined, TxTracesInner, TxTracesInnerRedefined,
+        },
         traits::LibmdbxReader,
+        uniswap_v4_pool_key::{UniswapV4PoolKey, UniswapV4PoolKeyRedefined},
     },
     serde_utils::*,
     traits::TracingProvider,
@@ -40,7 +45,7 @@ use serde_with::serde_as;
 
 use crate::{
     clickhouse::ClickhouseHandle,
-    libmdbx::{types::ReturnKV, utils::protocol_info, LibmdbxData, LibmdbxReadWriter},
+    libmdbx::{types::ReturnKV, utils::protocol_info, Libmdbx, LibmdbxData, LibmdbxReadWriter},
     parquet::ParquetExporter,
 };
 mod const_sql;
@@ -58,7 +63,7 @@ use super::{
     CompressedTable,
 };
 
-pub const NUM_TABLES: usize = 14;
+pub const NUM_TABLES: usize = 18;
 
 macro_rules! tables {
     ($($table:ident),*) => {
@@ -108,6 +113,14 @@ macro_rules! tables {
                 Ok(())
             }
 
+            /// Drops all entries of the given table
+            pub fn clear(&self, db: &Libmdbx) -> eyre::Result<()> {
+                match self {
+                    $(
+                        Tables::$table => db.clear_table::<$table>(),
+                    )*
+                }
+            }
         }
 
         impl Display for Tables {
@@ -276,7 +289,13 @@ impl Tables {
                     )
                     .await
             }
-            Tables::SearcherEOAs | Tables::SearcherContracts | Tables::InitializedState => Ok(()),
+            Tables::SearcherEOAs
+            | Tables::SearcherContracts
+            | Tables::InitializedState
+            | Tables::UniswapV4PoolKeys
+            | Tables::PricingSnapshots
+            | Tables::TxStateDiffs
+            | Tables::Funds => Ok(()),
             _ => unimplemented!("'initialize_table' not implemented for {:?}", self),
         }
     }
@@ -373,6 +392,11 @@ impl Tables {
             Self::MevBlocks => exporter.export_mev_blocks().await,
             Self::SearcherContracts | Self::SearcherEOAs => exporter.export_searcher_info().await,
             Self::Builder => exporter.export_builder_info().await,
+            Self::TokenDecimals => exporter.export_token_info().await,
+            Self::DexPrice => exporter.export_dex_price().await,
+            Self::CexPrice => exporter.export_cex_price().await,
+            Self::CexTrades => exporter.export_cex_trades().await,
+            Self::TxTraces => exporter.export_tx_traces().await,
             _ => unreachable!("Parquet export not yet supported for this table"),
         }
     }
@@ -537,9 +561,32 @@ tables!(
     SearcherEOAs,
     SearcherContracts,
     InitializedState,
-    CexTrades
+    CexTrades,
+    UniswapV4PoolKeys,
+    PricingSnapshots,
+    Funds,
+    TxStateDiffs
 );
 
+impl Tables {
+    /// Version of the archived layout of the table's values. Needs to be bumped
+    /// whenever a value type changes its rkyv layout, so that databases written
+    /// with the old layout drop the entries they can no longer decode when
+    /// they are opened, see [`Libmdbx::init_db`].
+    pub const fn layout_version(&self) -> u16 {
+        match self {
+            // funds became registry names instead of a fixed enum, sandwiches record
+            // the simulated loss of their victims and atomic arbs can be flash loan
+            // funded
+            Tables::SearcherEOAs
+            | Tables::SearcherContracts
+            | Tables::Builder
+            | Tables::MevBlocks => 2,
+            _ => 1,
+        }
+    }
+}
+
 /// Must be in this order when defining
 /// Table {
 ///     Data {
@@ -852,6 +899,25 @@ compressed_table!(
     }
 );
 
+compressed_table!(
+    Table TxStateDiffs {
+        #[serde_as]
+        Data {
+            key: u64,
+            value: TxStateDiffsInner,
+            compressed_value: TxStateDiffsInnerRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
 compressed_table!(
     Table AddressMeta {
         Data {
@@ -1031,3 +1097,61 @@ compressed_table!(
         }
     }
 );
+
+compressed_table!(
+    Table UniswapV4PoolKeys {
+        #[serde_as]
+        Data {
+            #[serde(with = "address_string")]
+            key: Address,
+            value: UniswapV4PoolKey,
+            compressed_value: UniswapV4PoolKeyRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table PricingSnapshots {
+        #[serde_as]
+        Data {
+            key: u64,
+            value: PricingGraphSnapshot,
+            compressed_value: PricingGraphSnapshotRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table Funds {
+        #[serde_as]
+        Data {
+            key: String,
+            value: FundInfo,
+            compressed_value: FundInfoRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
@@ -23,8 +23,12 @@ pub fn builder_info_to_record_batch(
             .collect_vec(),
     );
 
-    let fund_array =
-        get_string_array_from_owned(builder_info.iter().map(|info| info.1.fund).collect_vec());
+    let fund_array = get_string_array_from_owned(
+        builder_info
+            .iter()
+            .map(|info| info.1.fund.clone())
+            .collect_vec(),
+    );
 
     let pub_keys_array = get_list_string_array_from_owned(
         builder_info
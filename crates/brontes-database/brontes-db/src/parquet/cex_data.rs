@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use arrow::{
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use brontes_types::{
+    db::cex::{quotes::CexPriceMap, trades::CexTradeMap},
+    ToFloatNearest,
+};
+use itertools::Itertools;
+
+use super::utils::{
+    build_float64_array, build_record_batch, build_string_array, build_uint64_array,
+};
+
+/// Flattens the cex quotes into one row per block, exchange, pair & quote
+pub fn cex_price_to_record_batch(
+    cex_prices: Vec<(u64, CexPriceMap)>,
+) -> Result<RecordBatch, ArrowError> {
+    let rows = cex_prices
+        .iter()
+        .flat_map(|(block, map)| {
+            map.quotes.iter().flat_map(move |(exchange, pairs)| {
+                pairs.iter().flat_map(move |(pair, quotes)| {
+                    quotes
+                        .iter()
+                        .map(move |quote| (*block, exchange, pair, quote))
+                })
+            })
+        })
+        .collect_vec();
+
+    let block_number_array = build_uint64_array(rows.iter().map(|row| row.0).collect());
+    let exchange_array = build_string_array(rows.iter().map(|row| row.1.to_string()).collect());
+    let token0_array = build_string_array(rows.iter().map(|row| row.2 .0.to_string()).collect());
+    let token1_array = build_string_array(rows.iter().map(|row| row.2 .1.to_string()).collect());
+    let timestamp_array = build_uint64_array(rows.iter().map(|row| row.3.timestamp).collect());
+    let bid_price_array = build_float64_array(
+        rows.iter()
+            .map(|row| row.3.price.0.clone().to_float())
+            .collect(),
+    );
+    let ask_price_array = build_float64_array(
+        rows.iter()
+            .map(|row| row.3.price.1.clone().to_float())
+            .collect(),
+    );
+    let bid_amount_array = build_float64_array(
+        rows.iter()
+            .map(|row| row.3.amount.0.clone().to_float())
+            .collect(),
+    );
+    let ask_amount_array = build_float64_array(
+        rows.iter()
+            .map(|row| row.3.amount.1.clone().to_float())
+            .collect(),
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new("exchange", DataType::Utf8, false),
+        Field::new("token0", DataType::Utf8, false),
+        Field::new("token1", DataType::Utf8, false),
+        Field::new("timestamp", DataType::UInt64, false),
+        Field::new("bid_price", DataType::Float64, false),
+        Field::new("ask_price", DataType::Float64, false),
+        Field::new("bid_amount", DataType::Float64, false),
+        Field::new("ask_amount", DataType::Float64, false),
+    ]);
+
+    build_record_batch(
+        schema,
+        vec![
+            Arc::new(block_number_array),
+            Arc::new(exchange_array),
+            Arc::new(token0_array),
+            Arc::new(token1_array),
+            Arc::new(timestamp_array),
+            Arc::new(bid_price_array),
+            Arc::new(ask_price_array),
+            Arc::new(bid_amount_array),
+            Arc::new(ask_amount_array),
+        ],
+    )
+}
+
+/// Flattens the cex trades into one row per block, exchange, pair & trade
+pub fn cex_trades_to_record_batch(
+    cex_trades: Vec<(u64, CexTradeMap)>,
+) -> Result<RecordBatch, ArrowError> {
+    let rows = cex_trades
+        .iter()
+        .flat_map(|(block, map)| {
+            map.0.iter().flat_map(move |(exchange, pairs)| {
+                pairs.iter().flat_map(move |(pair, trades)| {
+                    trades
+                        .iter()
+                        .map(move |trade| (*block, exchange, pair, trade))
+                })
+            })
+        })
+        .collect_vec();
+
+    let block_number_array = build_uint64_array(rows.iter().map(|row| row.0).collect());
+    let exchange_array = build_string_array(rows.iter().map(|row| row.1.to_string()).collect());
+    let token0_array = build_string_array(rows.iter().map(|row| row.2 .0.to_string()).collect());
+    let token1_array = build_string_array(rows.iter().map(|row| row.2 .1.to_string()).collect());
+    let timestamp_array = build_uint64_array(rows.iter().map(|row| row.3.timestamp).collect());
+    let price_array = build_float64_array(
+        rows.iter()
+            .map(|row| row.3.price.clone().to_float())
+            .collect(),
+    );
+    let amount_array = build_float64_array(
+        rows.iter()
+            .map(|row| row.3.amount.clone().to_float())
+            .collect(),
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new("exchange", DataType::Utf8, false),
+        Field::new("token0", DataType::Utf8, false),
+        Field::new("token1", DataType::Utf8, false),
+        Field::new("timestamp", DataType::UInt64, false),
+        Field::new("price", DataType::Float64, false),
+        Field::new("amount", DataType::Float64, false),
+    ]);
+
+    build_record_batch(
+        schema,
+        vec![
+            Arc::new(block_number_array),
+            Arc::new(exchange_array),
+            Arc::new(token0_array),
+            Arc::new(token1_array),
+            Arc::new(timestamp_array),
+            Arc::new(price_array),
+            Arc::new(amount_array),
+        ],
+    )
+}
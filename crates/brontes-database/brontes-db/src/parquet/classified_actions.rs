@@ -0,0 +1,309 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, BooleanArray},
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use brontes_types::{
+    normalized_actions::{
+        Action, NormalizedBurn, NormalizedLiquidation, NormalizedMint, NormalizedSwap,
+        NormalizedTransfer,
+    },
+    structured_trace::TxTrace,
+    tree::{BlockTree, GasDetails, Root},
+    TreeSearchBuilder,
+};
+use itertools::Itertools;
+
+use super::{
+    normalized_actions::{
+        burns::get_normalized_burn_list_array, gas_details::get_gas_details_array,
+        liquidations::get_normalized_liquidation_list_array, mints::get_normalized_mint_list_array,
+        swaps::get_normalized_swap_list_array, transfers::get_normalized_transfer_list_array,
+    },
+    utils::{build_record_batch, build_string_array, build_uint64_array},
+};
+
+/// The classified actions of a single transaction, split by action type
+#[derive(Debug, Default)]
+pub struct ClassifiedTx {
+    pub block_number: u64,
+    pub tx_index:     u64,
+    pub tx_hash:      String,
+    pub from:         String,
+    pub to:           String,
+    pub is_private:   bool,
+    pub gas_details:  GasDetails,
+    pub swaps:        Vec<NormalizedSwap>,
+    pub mints:        Vec<NormalizedMint>,
+    pub burns:        Vec<NormalizedBurn>,
+    pub transfers:    Vec<NormalizedTransfer>,
+    pub liquidations: Vec<NormalizedLiquidation>,
+}
+
+impl ClassifiedTx {
+    pub fn from_root(block_number: u64, root: &Root<Action>) -> Self {
+        let mut tx = Self {
+            block_number,
+            tx_index: root.position as u64,
+            tx_hash: root.tx_hash.to_string(),
+            from: root.get_from_address().to_string(),
+            to: root.try_get_to_address().unwrap_or_default().to_string(),
+            is_private: root.private,
+            gas_details: root.gas_details,
+            ..Default::default()
+        };
+
+        let search = TreeSearchBuilder::default().with_actions([
+            Action::is_swap,
+            Action::is_mint,
+            Action::is_burn,
+            Action::is_transfer,
+            Action::is_liquidation,
+            Action::is_aggregator,
+            Action::is_batch,
+        ]);
+        root.collect(&search)
+            .into_iter()
+            .for_each(|action| tx.push_action(action));
+
+        tx
+    }
+
+    /// aggregators and batches are flattened into the actions they are made of
+    fn push_action(&mut self, action: Action) {
+        match action {
+            Action::Swap(swap) => self.swaps.push(swap),
+            Action::SwapWithFee(swap) => self.swaps.push(swap.swap),
+            Action::Mint(mint) => self.mints.push(mint),
+            Action::Burn(burn) => self.burns.push(burn),
+            Action::Transfer(transfer) => self.transfers.push(transfer),
+            Action::Liquidation(liquidation) => self.liquidations.push(liquidation),
+            Action::Aggregator(aggregator) => aggregator
+                .child_actions
+                .into_iter()
+                .for_each(|action| self.push_action(action)),
+            Action::Batch(batch) => self.swaps.extend(batch.user_swaps),
+            _ => {}
+        }
+    }
+}
+
+/// Flattens the classified block trees into one row per transaction, with a
+/// list column for each action type.
+pub fn classified_actions_to_record_batch(
+    trees: Vec<BlockTree<Action>>,
+) -> Result<RecordBatch, ArrowError> {
+    let txes = trees
+        .iter()
+        .flat_map(|tree| {
+            tree.tx_roots
+                .iter()
+                .map(|root| ClassifiedTx::from_root(tree.header.number, root))
+        })
+        .collect_vec();
+
+    classified_txes_to_record_batch(txes)
+}
+
+pub fn classified_txes_to_record_batch(txes: Vec<ClassifiedTx>) -> Result<RecordBatch, ArrowError> {
+    let block_number_array = build_uint64_array(txes.iter().map(|tx| tx.block_number).collect());
+    let tx_index_array = build_uint64_array(txes.iter().map(|tx| tx.tx_index).collect());
+    let tx_hash_array = build_string_array(txes.iter().map(|tx| tx.tx_hash.clone()).collect());
+    let from_array = build_string_array(txes.iter().map(|tx| tx.from.clone()).collect());
+    let to_array = build_string_array(txes.iter().map(|tx| tx.to.clone()).collect());
+    let is_private_array = BooleanArray::from(txes.iter().map(|tx| tx.is_private).collect_vec());
+    let gas_details_array = get_gas_details_array(txes.iter().map(|tx| tx.gas_details).collect());
+
+    let swaps_array = get_normalized_swap_list_array(
+        txes.iter()
+            .map(|tx| tx.swaps.iter().collect_vec())
+            .collect_vec(),
+    );
+    let mints_array = get_normalized_mint_list_array(
+        txes.iter()
+            .map(|tx| tx.mints.iter().collect_vec())
+            .collect_vec(),
+    );
+    let burns_array = get_normalized_burn_list_array(txes.iter().map(|tx| &tx.burns).collect());
+    let transfers_array =
+        get_normalized_transfer_list_array(txes.iter().map(|tx| &tx.transfers).collect());
+    let liquidations_array = get_normalized_liquidation_list_array(
+        txes.iter()
+            .map(|tx| tx.liquidations.iter().collect_vec())
+            .collect_vec(),
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new("tx_index", DataType::UInt64, false),
+        Field::new("tx_hash", DataType::Utf8, false),
+        Field::new("from", DataType::Utf8, false),
+        Field::new("to", DataType::Utf8, false),
+        Field::new("is_private", DataType::Boolean, false),
+        Field::new("gas_details", gas_details_array.data_type().clone(), false),
+        Field::new("swaps", swaps_array.data_type().clone(), false),
+        Field::new("mints", mints_array.data_type().clone(), false),
+        Field::new("burns", burns_array.data_type().clone(), false),
+        Field::new("transfers", transfers_array.data_type().clone(), false),
+        Field::new("liquidations", liquidations_array.data_type().clone(), false),
+    ]);
+
+    build_record_batch(
+        schema,
+        vec![
+            Arc::new(block_number_array),
+            Arc::new(tx_index_array),
+            Arc::new(tx_hash_array),
+            Arc::new(from_array),
+            Arc::new(to_array),
+            Arc::new(is_private_array),
+            Arc::new(gas_details_array),
+            Arc::new(swaps_array),
+            Arc::new(mints_array),
+            Arc::new(burns_array),
+            Arc::new(transfers_array),
+            Arc::new(liquidations_array),
+        ],
+    )
+}
+
+/// Classifies the traces of a block into its block tree. The classifier
+/// depends on this crate, so the exporter is handed one by the caller.
+#[async_trait::async_trait]
+pub trait TraceClassifier: Send + Sync + 'static {
+    async fn classify_block(
+        &self,
+        block: u64,
+        traces: Vec<TxTrace>,
+    ) -> eyre::Result<BlockTree<Action>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use alloy_primitives::{hex, Address, B256, U256};
+    use arrow::array::{AsArray, ListArray};
+    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use brontes_types::{
+        db::token_info::TokenInfoWithAddress, normalized_actions::NormalizedAggregator, Protocol,
+    };
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+    use crate::parquet::sync_write_parquet;
+
+    fn list_len(batch: &RecordBatch, column: &str, row: usize) -> usize {
+        batch
+            .column_by_name(column)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap()
+            .value(row)
+            .len()
+    }
+
+    #[brontes_macros::test]
+    async fn test_classified_swap_is_exported() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        let tx =
+            B256::from(hex!("057f1d5b3ddabec1b8d78ac7181f562f755669494514f94a767247af800339b1"));
+        let tree = classifier_utils.build_tree_tx(tx).await.unwrap();
+        let block_number = tree.header.number;
+
+        let batch = classified_actions_to_record_batch(vec![tree]).unwrap();
+
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(
+            batch
+                .column_by_name("tx_hash")
+                .unwrap()
+                .as_string::<i32>()
+                .value(0),
+            tx.to_string()
+        );
+        assert_eq!(
+            batch
+                .column_by_name("block_number")
+                .unwrap()
+                .as_primitive::<arrow::datatypes::UInt64Type>()
+                .value(0),
+            block_number
+        );
+        assert_eq!(list_len(&batch, "swaps", 0), 1);
+        assert_eq!(list_len(&batch, "liquidations", 0), 0);
+    }
+
+    #[test]
+    fn test_aggregator_actions_are_flattened() {
+        let swap = NormalizedSwap {
+            token_in: TokenInfoWithAddress::weth(),
+            token_out: TokenInfoWithAddress::usdc(),
+            ..Default::default()
+        };
+        let transfer = NormalizedTransfer {
+            token: TokenInfoWithAddress::usdc(),
+            to: Address::with_last_byte(1),
+            ..Default::default()
+        };
+
+        let mut tx = ClassifiedTx::default();
+        tx.push_action(Action::Aggregator(NormalizedAggregator {
+            child_actions: vec![
+                Action::Swap(swap.clone()),
+                Action::Swap(swap.clone()),
+                Action::Transfer(transfer.clone()),
+            ],
+            protocol:      Protocol::ParaswapV5,
+            trace_index:   0,
+            from:          Address::ZERO,
+            to:            Address::ZERO,
+            recipient:     Address::ZERO,
+            msg_value:     U256::ZERO,
+        }));
+        tx.push_action(Action::Transfer(transfer));
+
+        assert_eq!(tx.swaps, vec![swap.clone(), swap]);
+        assert_eq!(tx.transfers.len(), 2);
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let txes = vec![
+            ClassifiedTx {
+                block_number: 10,
+                tx_index: 0,
+                swaps: vec![NormalizedSwap::default(); 2],
+                ..Default::default()
+            },
+            ClassifiedTx {
+                block_number: 11,
+                tx_index: 4,
+                transfers: vec![NormalizedTransfer::default()],
+                ..Default::default()
+            },
+        ];
+        let batch = classified_txes_to_record_batch(txes).unwrap();
+
+        let path = std::env::temp_dir()
+            .join(format!("brontes-classified-actions-{}.parquet", std::process::id()));
+        sync_write_parquet(batch.clone(), path.clone()).unwrap();
+
+        let read = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(read, vec![batch.clone()]);
+        assert_eq!(list_len(&batch, "swaps", 0), 2);
+        assert_eq!(list_len(&batch, "swaps", 1), 0);
+        assert_eq!(list_len(&batch, "transfers", 1), 1);
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Float64Array, UInt64Array},
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use brontes_types::db::leaderboard::LeaderboardRow;
+use itertools::Itertools;
+
+use super::utils::{
+    build_float64_array, build_record_batch, build_string_array, build_uint64_array,
+};
+
+pub fn leaderboard_to_record_batch(rows: &[&LeaderboardRow]) -> Result<RecordBatch, ArrowError> {
+    let entity_type_array = build_string_array(
+        rows.iter()
+            .map(|row| row.entity_type.to_string())
+            .collect_vec(),
+    );
+    let entity_array = build_string_array(rows.iter().map(|row| row.entity.clone()).collect_vec());
+    let mev_type_array =
+        build_string_array(rows.iter().map(|row| row.mev_type_name()).collect_vec());
+    let bundle_count_array = build_uint64_array(rows.iter().map(|row| row.bundle_count).collect());
+    let profit_array = build_float64_array(rows.iter().map(|row| row.profit_usd).collect());
+    let revenue_array = build_float64_array(rows.iter().map(|row| row.revenue_usd).collect());
+    let bribe_array = build_float64_array(rows.iter().map(|row| row.bribe_usd).collect());
+    let bribe_share_array = build_float64_array(rows.iter().map(|row| row.bribe_share).collect());
+    let win_rate_array = build_float64_array(rows.iter().map(|row| row.win_rate).collect());
+    let blocks_built_array =
+        UInt64Array::from(rows.iter().map(|row| row.blocks_built).collect_vec());
+    let builder_profit_array =
+        Float64Array::from(rows.iter().map(|row| row.builder_profit_usd).collect_vec());
+
+    let schema = Schema::new(vec![
+        Field::new("entity_type", DataType::Utf8, false),
+        Field::new("entity", DataType::Utf8, false),
+        Field::new("mev_type", DataType::Utf8, false),
+        Field::new("bundle_count", DataType::UInt64, false),
+        Field::new("profit_usd", DataType::Float64, false),
+        Field::new("revenue_usd", DataType::Float64, false),
+        Field::new("bribe_usd", DataType::Float64, false),
+        Field::new("bribe_share", DataType::Float64, false),
+        Field::new("win_rate", DataType::Float64, false),
+        Field::new("blocks_built", DataType::UInt64, true),
+        Field::new("builder_profit_usd", DataType::Float64, true),
+    ]);
+
+    build_record_batch(
+        schema,
+        vec![
+            Arc::new(entity_type_array),
+            Arc::new(entity_array),
+            Arc::new(mev_type_array),
+            Arc::new(bundle_count_array),
+            Arc::new(profit_array),
+            Arc::new(revenue_array),
+            Arc::new(bribe_array),
+            Arc::new(bribe_share_array),
+            Arc::new(win_rate_array),
+            Arc::new(blocks_built_array),
+            Arc::new(builder_profit_array),
+        ],
+    )
+}
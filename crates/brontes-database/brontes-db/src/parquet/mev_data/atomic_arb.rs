@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use arrow::{
-    array::Array,
+    array::{Array, Float64Array},
     datatypes::{DataType, Field, Schema},
     error::ArrowError,
     record_batch::RecordBatch,
@@ -41,11 +41,51 @@ pub fn atomic_arb_to_record_batch(atomic_arbs: Vec<AtomicArb>) -> Result<RecordB
             .collect_vec(),
     );
 
+    let refund_recipient_array = get_string_array_from_owned(
+        atomic_arbs
+            .iter()
+            .map(|arb| arb.refund.as_ref().map(|r| r.recipient.to_string()))
+            .collect_vec(),
+    );
+
+    let refund_tx_hash_array = get_string_array_from_owned(
+        atomic_arbs
+            .iter()
+            .map(|arb| arb.refund.as_ref().map(|r| r.tx_hash.to_string()))
+            .collect_vec(),
+    );
+
+    let refund_type_array = get_string_array_from_owned(
+        atomic_arbs
+            .iter()
+            .map(|arb| arb.refund.as_ref().map(|r| r.refund_type.to_string()))
+            .collect_vec(),
+    );
+
+    let refund_amount_eth_array = Float64Array::from(
+        atomic_arbs
+            .iter()
+            .map(|arb| arb.refund.as_ref().map(|r| r.amount_eth))
+            .collect_vec(),
+    );
+
+    let refund_amount_usd_array = Float64Array::from(
+        atomic_arbs
+            .iter()
+            .map(|arb| arb.refund.as_ref().map(|r| r.amount_usd))
+            .collect_vec(),
+    );
+
     let schema = Schema::new(vec![
         Field::new("tx_hash", DataType::Utf8, false),
         Field::new("swaps", swaps_array.data_type().clone(), false),
         Field::new("gas_details", gas_details_array.data_type().clone(), false),
         Field::new("arb_type", DataType::Utf8, false),
+        Field::new("refund_recipient", DataType::Utf8, true),
+        Field::new("refund_tx_hash", DataType::Utf8, true),
+        Field::new("refund_type", DataType::Utf8, true),
+        Field::new("refund_amount_eth", DataType::Float64, true),
+        Field::new("refund_amount_usd", DataType::Float64, true),
     ]);
 
     RecordBatch::try_new(
@@ -55,6 +95,11 @@ pub fn atomic_arb_to_record_batch(atomic_arbs: Vec<AtomicArb>) -> Result<RecordB
             Arc::new(swaps_array),
             Arc::new(gas_details_array),
             Arc::new(arb_type_array),
+            Arc::new(refund_recipient_array),
+            Arc::new(refund_tx_hash_array),
+            Arc::new(refund_type_array),
+            Arc::new(refund_amount_eth_array),
+            Arc::new(refund_amount_usd_array),
         ],
     )
 }
@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use arrow::{
-    array::Array,
-    datatypes::{Field, Schema},
+    array::{Array, Float64Array},
+    datatypes::{DataType, Field, Schema},
     error::ArrowError,
     record_batch::RecordBatch,
 };
@@ -86,6 +86,13 @@ pub fn jit_to_record_batch(jit_liquidity: Vec<JitLiquidity>) -> Result<RecordBat
             .collect(),
     );
 
+    let fees_earned_usd_array = Float64Array::from(
+        jit_liquidity
+            .iter()
+            .map(|jls| jls.fees_earned_usd)
+            .collect_vec(),
+    );
+
     let schema = Schema::new(vec![
         Field::new("frontrun_tx_hashes", frontrun_tx_hash_array.data_type().clone(), false),
         Field::new("frontrun_mints", mints_array.data_type().clone(), false),
@@ -95,6 +102,7 @@ pub fn jit_to_record_batch(jit_liquidity: Vec<JitLiquidity>) -> Result<RecordBat
         Field::new("backrun_tx_hash", backrun_tx_hash_array.data_type().clone(), false),
         Field::new("backrun_burns", burns_array.data_type().clone(), false),
         Field::new("backrun_gas_details", backrun_gas_details_array.data_type().clone(), false),
+        Field::new("fees_earned_usd", DataType::Float64, false),
     ]);
 
     RecordBatch::try_new(
@@ -108,6 +116,7 @@ pub fn jit_to_record_batch(jit_liquidity: Vec<JitLiquidity>) -> Result<RecordBat
             Arc::new(backrun_tx_hash_array),
             Arc::new(burns_array),
             Arc::new(backrun_gas_details_array),
+            Arc::new(fees_earned_usd_array),
         ],
     )
 }
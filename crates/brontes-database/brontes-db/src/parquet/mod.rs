@@ -1,11 +1,12 @@
 use std::{
     fs::File,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use arrow::record_batch::RecordBatch;
+use arrow::{error::ArrowError, record_batch::RecordBatch};
 use brontes_types::{
-    db::traits::LibmdbxReader,
+    db::{leaderboard::LeaderboardRow, traits::LibmdbxReader},
     mev::{BundleData, MevType},
 };
 use chrono::Local;
@@ -24,24 +25,44 @@ use crate::Tables;
 mod address_meta;
 mod builder;
 mod bundle_header;
+mod cex_data;
+mod classified_actions;
+mod dex_price;
+mod leaderboard;
 mod mev_block;
 mod mev_data;
 mod normalized_actions;
 mod searcher;
+mod token_info;
 pub mod utils;
 
 use address_meta::address_metadata_to_record_batch;
 use builder::builder_info_to_record_batch;
 use bundle_header::bundle_headers_to_record_batch;
+use cex_data::{cex_price_to_record_batch, cex_trades_to_record_batch};
+pub use classified_actions::{
+    classified_actions_to_record_batch, classified_txes_to_record_batch, ClassifiedTx,
+    TraceClassifier,
+};
+use dex_price::dex_price_to_record_batch;
+use leaderboard::leaderboard_to_record_batch;
 use mev_block::mev_block_to_record_batch;
 use mev_data::*;
 use searcher::searcher_info_to_record_batch;
+use token_info::token_info_to_record_batch;
+
+/// Amount of blocks that are written to a single file for the tables that are
+/// keyed by block number
+pub const DEFAULT_PARTITION_SIZE: u64 = 1_000;
 
 pub struct ParquetExporter<DB: LibmdbxReader> {
-    pub start_block:   Option<u64>,
-    pub end_block:     Option<u64>,
-    pub base_dir_path: Option<String>,
-    pub db:            &'static DB,
+    pub start_block:    Option<u64>,
+    pub end_block:      Option<u64>,
+    pub base_dir_path:  Option<String>,
+    pub partition_size: u64,
+    pub db:             &'static DB,
+    /// needed to export the classified actions of the tx traces
+    pub classifier:     Option<Arc<dyn TraceClassifier>>,
 }
 
 impl<DB> ParquetExporter<DB>
@@ -54,7 +75,24 @@ where
         base_dir_path: Option<String>,
         db: &'static DB,
     ) -> Self {
-        Self { start_block, end_block, base_dir_path, db }
+        Self {
+            start_block,
+            end_block,
+            base_dir_path,
+            partition_size: DEFAULT_PARTITION_SIZE,
+            db,
+            classifier: None,
+        }
+    }
+
+    pub fn with_classifier(mut self, classifier: Arc<dyn TraceClassifier>) -> Self {
+        self.classifier = Some(classifier);
+        self
+    }
+
+    pub fn with_partition_size(mut self, partition_size: u64) -> Self {
+        self.partition_size = partition_size.max(1);
+        self
     }
 
     pub async fn export_mev_blocks(&self) -> Result<(), Error> {
@@ -334,6 +372,174 @@ where
 
         Ok(())
     }
+
+    pub async fn export_token_info(&self) -> Result<(), Error> {
+        let token_info = self
+            .db
+            .fetch_all_token_info()
+            .wrap_err("Failed to query token decimals table")?;
+
+        if token_info.is_empty() {
+            error!("Token decimals table is empty.");
+            return Err(Error::msg("No token info"))
+        }
+
+        let token_info_batch = token_info_to_record_batch(token_info)
+            .wrap_err("Failed to convert Token Info to record batch")?;
+
+        write_parquet(
+            token_info_batch,
+            get_path(self.base_dir_path.clone(), Tables::TokenDecimals, None)?,
+        )
+        .await
+        .wrap_err("Failed to write token info to parquet file")?;
+
+        Ok(())
+    }
+
+    pub async fn export_dex_price(&self) -> Result<(), Error> {
+        self.export_partitioned(
+            Tables::DexPrice,
+            |db, start, end| db.fetch_dex_price_range(start, end),
+            dex_price_to_record_batch,
+        )
+        .await
+    }
+
+    pub async fn export_cex_price(&self) -> Result<(), Error> {
+        self.export_partitioned(
+            Tables::CexPrice,
+            |db, start, end| db.fetch_cex_price_range(start, end),
+            cex_price_to_record_batch,
+        )
+        .await
+    }
+
+    pub async fn export_cex_trades(&self) -> Result<(), Error> {
+        self.export_partitioned(
+            Tables::CexTrades,
+            |db, start, end| db.fetch_cex_trades_range(start, end),
+            cex_trades_to_record_batch,
+        )
+        .await
+    }
+
+    /// Exports the classified actions of every transaction, one row per
+    /// transaction. The stored traces are classified block by block as the
+    /// block trees themselves aren't stored in libmdbx.
+    pub async fn export_tx_traces(&self) -> Result<(), Error> {
+        let table = Tables::TxTraces;
+        let Some(classifier) = self.classifier.clone() else {
+            return Err(Error::msg(format!("A trace classifier is required to export {table}")))
+        };
+
+        let mut written_partitions = 0;
+        for (start, end) in self.block_partitions(table)? {
+            let db = self.db;
+            let traces = tokio::task::spawn_blocking(move || db.fetch_trace_range(start, end))
+                .await?
+                .wrap_err_with(|| format!("Failed to fetch {table} for blocks {start}-{end}"))?;
+            if traces.is_empty() {
+                continue
+            }
+
+            let mut trees = Vec::with_capacity(traces.len());
+            for (block, block_traces) in traces {
+                trees.push(
+                    classifier
+                        .classify_block(block, block_traces)
+                        .await
+                        .wrap_err_with(|| format!("Failed to classify block {block}"))?,
+                );
+            }
+
+            let base_dir_path = self.base_dir_path.clone();
+            tokio::task::spawn_blocking(move || {
+                let batch = classified_actions_to_record_batch(trees)
+                    .wrap_err_with(|| format!("Failed to convert {table} to record batch"))?;
+                sync_write_parquet(batch, get_partition_path(base_dir_path, table, start, end)?)
+            })
+            .await??;
+
+            written_partitions += 1;
+        }
+
+        if written_partitions == 0 {
+            error!("No {} data fetched for the given range.", table);
+            return Err(Error::msg(format!("No {table} data fetched for the given range.")))
+        }
+
+        Ok(())
+    }
+
+    /// Exports a table that is keyed by block number into one file per block
+    /// partition. Partitions are loaded one at a time so that the memory usage
+    /// stays bounded by the partition size.
+    async fn export_partitioned<T, F, B>(
+        &self,
+        table: Tables,
+        fetch: F,
+        to_record_batch: B,
+    ) -> Result<(), Error>
+    where
+        T: Send + 'static,
+        F: Fn(&DB, u64, u64) -> Result<Vec<T>> + Send + Copy + 'static,
+        B: Fn(Vec<T>) -> Result<RecordBatch, ArrowError> + Send + Copy + 'static,
+    {
+        let mut written_partitions = 0;
+
+        for (start, end) in self.block_partitions(table)? {
+            let db = self.db;
+            let base_dir_path = self.base_dir_path.clone();
+
+            let written = tokio::task::spawn_blocking(move || {
+                let rows = fetch(db, start, end).wrap_err_with(|| {
+                    format!("Failed to fetch {table} for blocks {start}-{end}")
+                })?;
+                if rows.is_empty() {
+                    return Ok(false)
+                }
+
+                let batch = to_record_batch(rows)
+                    .wrap_err_with(|| format!("Failed to convert {table} to record batch"))?;
+                sync_write_parquet(batch, get_partition_path(base_dir_path, table, start, end)?)?;
+
+                Ok(true)
+            })
+            .await??;
+
+            if written {
+                written_partitions += 1;
+            }
+        }
+
+        if written_partitions == 0 {
+            error!("No {} data fetched for the given range.", table);
+            return Err(Error::msg(format!("No {table} data fetched for the given range.")))
+        }
+
+        Ok(())
+    }
+
+    /// Splits the block range into partitions that are aligned to the
+    /// partition size, so that exports of overlapping ranges write to the same
+    /// files
+    fn block_partitions(&self, table: Tables) -> Result<Vec<(u64, u64)>> {
+        let (Some(start_block), Some(end_block)) = (self.start_block, self.end_block) else {
+            return Err(Error::msg(format!("A start and end block are required to export {table}")))
+        };
+
+        let mut partitions = Vec::new();
+        let mut start = start_block;
+        while start <= end_block {
+            let end =
+                (start - start % self.partition_size + self.partition_size - 1).min(end_block);
+            partitions.push((start, end));
+            start = end + 1;
+        }
+
+        Ok(partitions)
+    }
 }
 
 async fn write_parquet(record_batch: RecordBatch, file_path: PathBuf) -> Result<()> {
@@ -381,6 +587,14 @@ fn sync_write_parquet(record_batch: RecordBatch, file_path: PathBuf) -> Result<(
     Ok(())
 }
 
+/// Writes the leaderboard rows to a single parquet file
+pub fn write_leaderboard(rows: &[&LeaderboardRow], file_path: PathBuf) -> Result<()> {
+    let batch = leaderboard_to_record_batch(rows)
+        .wrap_err("Failed to convert leaderboard to record batch")?;
+
+    sync_write_parquet(batch, file_path)
+}
+
 pub fn get_path(
     custom_path: Option<String>,
     batch_type: Tables,
@@ -402,6 +616,24 @@ pub fn get_path(
     create_file_path(path)
 }
 
+/// Path of a block range partition, i.e.
+/// "data_exports/dex_price/18000000-18000999.parquet"
+pub fn get_partition_path(
+    custom_path: Option<String>,
+    batch_type: Tables,
+    start_block: u64,
+    end_block: u64,
+) -> Result<PathBuf> {
+    let base_path = custom_path
+        .as_deref()
+        .unwrap_or("../brontes-notebook/data/brontes-exports");
+
+    let dir_path = PathBuf::from(base_path).join(batch_type.get_default_path());
+    std::fs::create_dir_all(&dir_path)?;
+
+    Ok(dir_path.join(format!("{start_block}-{end_block}.parquet")))
+}
+
 pub fn create_file_path<P: AsRef<Path>>(base_dir: P) -> Result<PathBuf> {
     let now = Local::now();
     let date_str = now.format("%m-%d").to_string();
@@ -424,6 +656,11 @@ impl Tables {
             Tables::SearcherEOAs => DEFAULT_SEARCHER_INFO_DIR,
             Tables::SearcherContracts => DEFAULT_SEARCHER_INFO_DIR,
             Tables::Builder => DEFAULT_BUILDER_INFO_DIR,
+            Tables::TokenDecimals => DEFAULT_TOKEN_INFO_DIR,
+            Tables::DexPrice => DEFAULT_DEX_PRICE_DIR,
+            Tables::CexPrice => DEFAULT_CEX_PRICE_DIR,
+            Tables::CexTrades => DEFAULT_CEX_TRADES_DIR,
+            Tables::TxTraces => DEFAULT_TX_TRACES_DIR,
             _ => panic!("Unsupported table type"),
         }
     }
@@ -433,3 +670,8 @@ pub const DEFAULT_BLOCK_DIR: &str = "mev";
 pub const DEFAULT_METADATA_DIR: &str = "address_metadata";
 pub const DEFAULT_SEARCHER_INFO_DIR: &str = "searcher_info";
 pub const DEFAULT_BUILDER_INFO_DIR: &str = "builder-info";
+pub const DEFAULT_TOKEN_INFO_DIR: &str = "token_info";
+pub const DEFAULT_DEX_PRICE_DIR: &str = "dex_price";
+pub const DEFAULT_CEX_PRICE_DIR: &str = "cex_price";
+pub const DEFAULT_CEX_TRADES_DIR: &str = "cex_trades";
+pub const DEFAULT_TX_TRACES_DIR: &str = "tx_traces";
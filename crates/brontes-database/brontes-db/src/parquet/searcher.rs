@@ -7,7 +7,7 @@ use arrow::{
     error::ArrowError,
     record_batch::RecordBatch,
 };
-use brontes_types::db::searcher::{Fund, SearcherInfo};
+use brontes_types::db::searcher::SearcherInfo;
 use itertools::Itertools;
 
 use super::utils::{build_string_array, get_list_string_array, get_string_array_from_owned};
@@ -28,9 +28,7 @@ pub fn searcher_info_to_record_batch(
         eoa_info
             .iter()
             .chain(&contract_info)
-            .map(
-                |info| if info.1.fund == Fund::None { None } else { Some(info.1.fund.to_string()) },
-            )
+            .map(|info| if info.1.fund.is_none() { None } else { Some(info.1.fund.to_string()) })
             .collect_vec(),
     );
 
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::UInt8Array,
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use brontes_types::db::token_info::TokenInfoWithAddress;
+use itertools::Itertools;
+
+use super::utils::{build_record_batch, build_string_array};
+
+pub fn token_info_to_record_batch(
+    token_info: Vec<TokenInfoWithAddress>,
+) -> Result<RecordBatch, ArrowError> {
+    let address_array = build_string_array(
+        token_info
+            .iter()
+            .map(|info| info.address.to_string())
+            .collect(),
+    );
+    let symbol_array =
+        build_string_array(token_info.iter().map(|info| info.symbol.clone()).collect());
+    let decimals_array =
+        UInt8Array::from(token_info.iter().map(|info| info.decimals).collect_vec());
+
+    let schema = Schema::new(vec![
+        Field::new("address", DataType::Utf8, false),
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("decimals", DataType::UInt8, false),
+    ]);
+
+    build_record_batch(
+        schema,
+        vec![Arc::new(address_array), Arc::new(symbol_array), Arc::new(decimals_array)],
+    )
+}
@@ -84,7 +84,7 @@ pub fn atomic_dedup_fn(
             }
         };
 
-        if atomic_data.arb_type == AtomicArbType::Triangle {
+        if matches!(atomic_data.arb_type, AtomicArbType::Triangle | AtomicArbType::FlashLoanArb) {
             return false;
         }
         // if the cex dex has a higher value. then use that.
@@ -109,9 +109,11 @@ define_mev_precedence!(
     Unknown, SearcherTx => CexDexQuotes;
     Unknown, SearcherTx => CexDexTrades;
     Unknown, SearcherTx => AtomicArb;
+    Unknown, SearcherTx, AtomicArb => MultiBlockJit;
     Unknown, SearcherTx, AtomicArb => Jit;
     Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades  => Liquidation;
-    Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades  => Sandwich;
+    Unknown, SearcherTx, AtomicArb, CexDexQuotes, CexDexTrades => MultiBlockSandwich;
+    Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades, MultiBlockSandwich  => Sandwich;
     Unknown, SearcherTx, AtomicArb, Jit, CexDexQuotes, CexDexTrades=> JitCexDex;
     Unknown, SearcherTx, AtomicArb, CexDexQuotes, CexDexTrades, Jit, Sandwich => JitSandwich;
 );
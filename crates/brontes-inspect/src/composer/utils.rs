@@ -156,7 +156,10 @@ pub fn filter_and_count_bundles(
         let filtered_bundles: Vec<Bundle> = bundles
             .into_iter()
             .filter(|bundle| {
-                if matches!(mev_type, MevType::Sandwich | MevType::AtomicArb) {
+                if matches!(
+                    mev_type,
+                    MevType::Sandwich | MevType::MultiBlockSandwich | MevType::AtomicArb
+                ) {
                     bundle.header.profit_usd > 0.0 || bundle.header.no_pricing_calculated
                 } else {
                     true
@@ -181,17 +184,23 @@ pub fn filter_and_count_bundles(
 
 fn update_mev_count(mev_count: &mut MevCount, mev_type: MevType, count: u64) {
     match mev_type {
-        MevType::Sandwich => mev_count.sandwich_count = Some(count),
+        // multi block sandwiches are counted with the regular ones
+        MevType::Sandwich | MevType::MultiBlockSandwich => {
+            mev_count.sandwich_count = Some(mev_count.sandwich_count.unwrap_or_default() + count)
+        }
         MevType::CexDexTrades => mev_count.cex_dex_trade_count = Some(count),
         MevType::CexDexQuotes => mev_count.cex_dex_quote_count = Some(count),
         MevType::JitCexDex => mev_count.jit_cex_dex_count = Some(count),
         MevType::CexDexRfq => mev_count.cex_dex_rfq_count = Some(count),
-        MevType::Jit => mev_count.jit_count = Some(count),
+        // multi block jits are counted with the regular ones
+        MevType::Jit | MevType::MultiBlockJit => {
+            mev_count.jit_count = Some(mev_count.jit_count.unwrap_or_default() + count)
+        }
         MevType::JitSandwich => mev_count.jit_sandwich_count = Some(count),
         MevType::AtomicArb => mev_count.atomic_backrun_count = Some(count),
         MevType::Liquidation => mev_count.liquidation_count = Some(count),
         MevType::SearcherTx => mev_count.searcher_tx_count = Some(count),
-        MevType::Unknown => (),
+        MevType::Custom | MevType::Unknown => (),
     }
 }
 
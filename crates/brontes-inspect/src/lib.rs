@@ -38,6 +38,9 @@
 //! Each inspector implements the `Inspector` trait and provides its own
 //! implementation of the `inspect_block` method.
 //!
+//! Inspectors defined in other crates can be added at runtime through the
+//! [`registry`], without having to extend the [`Inspectors`] enum.
+//!
 //! ## Composer
 //!
 //! The `Composer` is a special type of inspector that combines the results of
@@ -60,6 +63,7 @@
 pub mod composer;
 pub mod discovery;
 pub mod mev_inspectors;
+pub mod registry;
 use brontes_metrics::inspectors::OutlierMetrics;
 use mev_inspectors::searcher_activity::SearcherActivity;
 pub use mev_inspectors::*;
@@ -81,9 +85,9 @@ use brontes_types::{
     MultiBlockData,
 };
 use cex_dex::{markout::CexDexMarkoutInspector, quotes::CexDexQuotesInspector};
-use jit::JitCexDex;
+use jit::{JitCexDex, MultiBlockJitInspector};
 use liquidations::LiquidationInspector;
-use sandwich::SandwichInspector;
+use sandwich::{MultiBlockSandwichInspector, SandwichInspector, VictimSimulator};
 
 use crate::jit::jit_liquidity::JitInspector;
 
@@ -112,11 +116,18 @@ pub enum Inspectors {
     SearcherActivity,
     CexDexMarkout,
     JitCexDex,
+    MultiBlockSandwich,
+    MultiBlockJit,
 }
 
-type DynMevInspector = &'static (dyn Inspector<Result = Vec<Bundle>> + 'static);
+pub type DynMevInspector = &'static (dyn Inspector<Result = Vec<Bundle>> + 'static);
 
 impl Inspectors {
+    /// Inspectors that only run when explicitly selected
+    pub const fn is_opt_in(&self) -> bool {
+        matches!(self, Self::MultiBlockSandwich | Self::MultiBlockJit)
+    }
+
     pub fn init_mev_inspector<DB: LibmdbxReader>(
         &self,
         quote_token: Address,
@@ -124,6 +135,7 @@ impl Inspectors {
         cex_exchanges: &[CexExchange],
         trade_config: CexDexTradeConfig,
         metrics: Option<OutlierMetrics>,
+        victim_simulator: Option<VictimSimulator>,
     ) -> DynMevInspector {
         match &self {
             Self::AtomicArb => {
@@ -140,9 +152,10 @@ impl Inspectors {
                 trade_config.quote_offset_from_block_us,
                 metrics,
             )) as DynMevInspector,
-            Self::Sandwich => {
-                static_object(SandwichInspector::new(quote_token, db, metrics)) as DynMevInspector
-            }
+            Self::Sandwich => static_object(
+                SandwichInspector::new(quote_token, db, metrics)
+                    .with_victim_simulator(victim_simulator),
+            ) as DynMevInspector,
             Self::Liquidations => {
                 static_object(LiquidationInspector::new(quote_token, db, metrics))
                     as DynMevInspector
@@ -167,6 +180,14 @@ impl Inspectors {
                 ),
                 jit:     JitInspector::new(quote_token, db, metrics),
             }) as DynMevInspector,
+            Self::MultiBlockSandwich => {
+                static_object(MultiBlockSandwichInspector::new(quote_token, db, metrics))
+                    as DynMevInspector
+            }
+            Self::MultiBlockJit => {
+                static_object(MultiBlockJitInspector::new(quote_token, db, metrics))
+                    as DynMevInspector
+            }
         }
     }
 }
@@ -1,17 +1,23 @@
 use std::sync::Arc;
 
+use alloy_primitives::hex;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
-    constants::{get_stable_type, is_euro_stable, is_gold_stable, is_usd_stable, StableType},
+    constants::{
+        get_stable_type, is_euro_stable, is_gold_stable, is_usd_stable, StableType, WETH_ADDRESS,
+    },
     db::dex::PriceAt,
-    mev::{AtomicArb, AtomicArbType, Bundle, BundleData, MevType},
+    mev::{
+        AtomicArb, AtomicArbType, Bundle, BundleData, BundleHeader, MevType, OfaRefund, RefundType,
+    },
     normalized_actions::{
-        accounting::ActionAccounting, Action, NormalizedEthTransfer, NormalizedSwap,
-        NormalizedTransfer,
+        accounting::ActionAccounting, Action, NormalizedEthTransfer, NormalizedFlashLoan,
+        NormalizedSwap, NormalizedTransfer,
     },
-    BlockData, FastHashSet, IntoZip, MultiBlockData, ToFloatNearest, TreeBase, TreeCollector,
-    TreeSearchBuilder, TxInfo,
+    structured_trace::TraceActions,
+    BlockData, FastHashSet, IntoZip, MultiBlockData, ToFloatNearest, ToScaledRational, TreeBase,
+    TreeCollector, TreeSearchBuilder, TxInfo,
 };
 use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
@@ -22,6 +28,8 @@ use crate::{
 };
 
 const MAX_PRICE_DIFF: Rational = Rational::const_from_unsigneds(99995, 100000);
+/// `uniswapV2Call(address,uint256,uint256,bytes)`
+const UNISWAP_V2_CALL: [u8; 4] = hex!("10d1e85c");
 
 // figure out why
 pub struct AtomicArbInspector<'db, DB: LibmdbxReader> {
@@ -60,22 +68,35 @@ impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
                     Action::is_transfer,
                     Action::is_eth_transfer,
                     Action::is_nested_action,
+                    is_flash_swap_callback,
                 ]))
                 .t_full_map(|(tree, v)| {
                     let (tx_hashes, v): (Vec<_>, Vec<_>) = v.unzip();
                     (
                         tree.get_tx_info_batch(&tx_hashes, self.utils.db),
                         v.into_iter().map(|v| {
-                            self.utils
-                                .flatten_nested_actions_default(v.into_iter())
-                                .collect::<Vec<_>>()
+                            // the loans need to be grabbed before they are flattened so that
+                            // the principal can be netted out of the deltas
+                            let flash_loans = v
+                                .iter()
+                                .filter_map(|a| a.try_flash_loan_ref().cloned())
+                                .collect::<Vec<_>>();
+                            let flash_swap_pools =
+                                v.iter().filter_map(flash_swap_pool).collect::<Vec<_>>();
+
+                            (
+                                (flash_loans, flash_swap_pools),
+                                self.utils
+                                    .flatten_nested_actions_default(v.into_iter())
+                                    .collect::<Vec<_>>(),
+                            )
                         }),
                     )
                 })
                 .into_zip()
                 .filter_map(|(info, action)| {
                     let info = info??;
-                    let actions = action?;
+                    let ((flash_loans, flash_swap_pools), actions) = action?;
 
                     self.process_swaps(
                         data.per_block_data
@@ -84,6 +105,8 @@ impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
                             .collect_vec(),
                         info,
                         metadata.clone(),
+                        flash_loans,
+                        flash_swap_pools,
                         actions
                             .into_iter()
                             .split_actions::<(Vec<_>, Vec<_>, Vec<_>), _>((
@@ -109,10 +132,13 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
         trees: Vec<Arc<BlockTree<Action>>>,
         info: TxInfo,
         metadata: Arc<Metadata>,
+        flash_loans: Vec<NormalizedFlashLoan>,
+        flash_swap_pools: Vec<Address>,
         data: (Vec<NormalizedSwap>, Vec<NormalizedTransfer>, Vec<NormalizedEthTransfer>),
     ) -> Option<Bundle> {
         tracing::trace!(?info, "trying atomic");
         let (mut swaps, transfers, eth_transfers) = data;
+        let transfers = net_flash_loan_principal(&flash_loans, transfers);
         let mev_addresses: FastHashSet<Address> = info.collect_address_set_for_accounting();
 
         let mut ignore_addresses = mev_addresses.clone();
@@ -124,6 +150,7 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
         swaps.extend(self.utils.try_create_swaps(&transfers, ignore_addresses));
 
         let possible_arb_type = self.is_possible_arb(&swaps)?;
+        let outflows = searcher_eth_outflows(&mev_addresses, &transfers, &eth_transfers);
 
         let account_deltas = transfers
             .into_iter()
@@ -195,23 +222,35 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
                 && is_profitable
                 || self.is_long_tail(&info, requirement_multiplier) & !has_dex_price)
                 .then_some(profit),
+            AtomicArbType::FlashLoanArb => {
+                unreachable!("arbs are only tagged as flash loan funded once they are built")
+            }
         }?;
 
         // given we have a atomic arb now, we will go and try to find the trigger
         // transaction that lead to this arb.
-        let trigger_tx = self.find_trigger_tx(&info, trees, &swaps);
+        let tree = trees.last().cloned().unwrap();
+        let trigger = self.find_trigger_tx(&info, trees, &swaps);
+        let refund = trigger
+            .as_ref()
+            .and_then(|trigger| self.find_ofa_refund(&info, trigger, &outflows, &tree, &metadata));
 
         let backrun = AtomicArb {
             block_number: metadata.block_num,
-            trigger_tx,
+            trigger_tx: trigger.map(|t| t.tx_hash).unwrap_or_default(),
+            refund: refund.clone(),
             tx_hash: info.tx_hash,
             gas_details: info.gas_details,
             swaps,
-            arb_type: possible_arb_type,
+            arb_type: if flash_loans.is_empty() && flash_swap_pools.is_empty() {
+                possible_arb_type
+            } else {
+                AtomicArbType::FlashLoanArb
+            },
         };
         let data = BundleData::AtomicArb(backrun);
 
-        let header = self.utils.build_bundle_header(
+        let mut header = self.utils.build_bundle_header(
             vec![account_deltas],
             vec![info.tx_hash],
             &info,
@@ -231,6 +270,10 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
             },
         );
 
+        if let Some(refund) = &refund {
+            net_ofa_refund(&mut header, refund);
+        }
+
         Some(Bundle { header, data })
     }
 
@@ -241,7 +284,7 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
         arb_info: &TxInfo,
         mut trees: Vec<Arc<BlockTree<Action>>>,
         swaps: &[NormalizedSwap],
-    ) -> B256 {
+    ) -> Option<TriggerTx> {
         let this_tree = trees.pop().unwrap();
 
         trees
@@ -307,8 +350,82 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
                         .unwrap_or(false)
                 })
             })
-            .map(|root| root.tx_hash)
-            .unwrap_or_default()
+            .map(|root| TriggerTx {
+                tx_hash: root.tx_hash,
+                eoa:     root.get_from_address(),
+                private: root.private,
+            })
+    }
+
+    /// Looks for a refund to the originator of the trigger tx. Searchers that
+    /// refund the user themselves do so in the arb, otherwise the refund is
+    /// paid by the builder out of the searcher's bid in a payout at the end
+    /// of the block, which is how MEV-Share and MEV Blocker settle.
+    fn find_ofa_refund(
+        &self,
+        info: &TxInfo,
+        trigger: &TriggerTx,
+        outflows: &[(Address, Rational)],
+        tree: &BlockTree<Action>,
+        metadata: &Metadata,
+    ) -> Option<OfaRefund> {
+        // searchers trigger their own arbs as well
+        if trigger.eoa == info.eoa {
+            return None
+        }
+
+        let (refund_type, tx_hash, amount) = if let Some(amount) = outflows
+            .iter()
+            .filter(|(to, _)| *to == trigger.eoa)
+            .map(|(_, amount)| amount.clone())
+            .reduce(|a, b| a + b)
+        {
+            (RefundType::Searcher, info.tx_hash, amount)
+        } else {
+            // order flow auctions only refund private flow that landed in the same
+            // block as the backrun
+            let coinbase = tree.header.beneficiary;
+            if !trigger.private
+                || trigger.eoa == coinbase
+                || metadata.proposer_fee_recipient == Some(trigger.eoa)
+                || !tree
+                    .tx_roots
+                    .iter()
+                    .any(|root| root.tx_hash == trigger.tx_hash)
+            {
+                return None
+            }
+
+            tree.tx_roots
+                .iter()
+                .skip(info.tx_index as usize + 1)
+                .filter(|root| root.get_from_address() == coinbase)
+                .find_map(|root| {
+                    let amount = root
+                        .collect(&TreeSearchBuilder::default().with_action(Action::is_eth_transfer))
+                        .into_iter()
+                        .filter_map(Action::try_eth_transfer)
+                        .chain(root.total_msg_value_transfers.iter().cloned())
+                        .filter(|t| t.to == trigger.eoa)
+                        .map(|t| t.value.to_scaled_rational(18))
+                        .reduce(|a, b| a + b)?;
+
+                    Some((RefundType::Builder, root.tx_hash, amount))
+                })?
+        };
+
+        if amount <= Rational::ZERO {
+            return None
+        }
+
+        let amount_usd = &amount * metadata.get_eth_price(self.utils.quote);
+        Some(OfaRefund {
+            recipient: trigger.eoa,
+            tx_hash,
+            refund_type,
+            amount_eth: amount.to_float(),
+            amount_usd: amount_usd.to_float(),
+        })
     }
 
     fn is_possible_arb(&self, swaps: &[NormalizedSwap]) -> Option<AtomicArbType> {
@@ -395,6 +512,111 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
     }
 }
 
+/// The transaction that created the opportunity the arb captures
+struct TriggerTx {
+    tx_hash: B256,
+    /// originator of the trigger tx, the user an order flow auction refunds
+    eoa:     Address,
+    private: bool,
+}
+
+/// Books the refund paid to the user against the arb. A refund the searcher
+/// pays in the arb is already an outflow in the account deltas. A builder
+/// refund is paid out of the searcher's bid, so that part of the bribe went to
+/// the user rather than the builder and is moved from the bribe to the refund.
+fn net_ofa_refund(header: &mut BundleHeader, refund: &OfaRefund) {
+    if refund.refund_type != RefundType::Builder {
+        return
+    }
+
+    let revenue = header.profit_usd + header.bribe_usd;
+    let refund_usd = refund.amount_usd.min(header.bribe_usd);
+    header.bribe_usd -= refund_usd;
+
+    if !header.no_pricing_calculated {
+        header.profit_usd = revenue - header.bribe_usd - refund_usd;
+    }
+}
+
+/// Pool that flash swapped to the searcher, if the action is the
+/// `uniswapV2Call` callback a Uniswap V2 style pool makes to the recipient of a
+/// swap called with data. The pool sends the output before it is paid, so the
+/// output funds the rest of the arb.
+fn flash_swap_pool(action: &Action) -> Option<Address> {
+    let Action::Unclassified(trace) = action else { return None };
+    trace
+        .get_calldata()
+        .starts_with(&UNISWAP_V2_CALL)
+        .then(|| trace.get_from_addr())
+}
+
+fn is_flash_swap_callback(action: &Action) -> bool {
+    flash_swap_pool(action).is_some()
+}
+
+/// ETH and WETH sent out by the searcher in the arb, by recipient
+fn searcher_eth_outflows(
+    mev_addresses: &FastHashSet<Address>,
+    transfers: &[NormalizedTransfer],
+    eth_transfers: &[NormalizedEthTransfer],
+) -> Vec<(Address, Rational)> {
+    eth_transfers
+        .iter()
+        .filter(|t| !t.coinbase_transfer && mev_addresses.contains(&t.from))
+        .map(|t| (t.to, t.value.to_scaled_rational(18)))
+        .chain(
+            transfers
+                .iter()
+                .filter(|t| t.token.address == WETH_ADDRESS && mev_addresses.contains(&t.from))
+                .map(|t| (t.to, t.amount.clone())),
+        )
+        .collect()
+}
+
+/// Removes the flash loan principal from the transfers of the arb. The
+/// disbursement to the receiver is dropped and the repayment is cut down to the
+/// fee paid on top of the principal, otherwise the borrowed amount shows up in
+/// the deltas of the searcher and gets picked up as swaps.
+fn net_flash_loan_principal(
+    flash_loans: &[NormalizedFlashLoan],
+    mut transfers: Vec<NormalizedTransfer>,
+) -> Vec<NormalizedTransfer> {
+    for loan in flash_loans {
+        for (asset, principal) in loan.assets.iter().zip(&loan.amounts) {
+            if let Some(i) = transfers.iter().position(|t| {
+                t.token == *asset && t.to == loan.receiver_contract && &t.amount == principal
+            }) {
+                transfers.remove(i);
+            }
+
+            // the classifier picks out the repayments, which don't have to come from
+            // the receiver. Aave doesn't record them, there the last transfer out of
+            // the receiver that covers the loan is the repayment
+            let Some(i) = transfers.iter().rposition(|t| {
+                t.token == *asset
+                    && &t.amount >= principal
+                    && if loan.repayments.is_empty() {
+                        t.from == loan.receiver_contract
+                    } else {
+                        loan.repayments
+                            .iter()
+                            .any(|repayment| repayment.trace_index == t.trace_index)
+                    }
+            }) else {
+                continue
+            };
+
+            if &transfers[i].amount == principal {
+                transfers.remove(i);
+            } else {
+                transfers[i].amount -= principal;
+            }
+        }
+    }
+
+    transfers
+}
+
 fn identify_arb_sequence(swaps: &[NormalizedSwap]) -> Option<AtomicArbType> {
     let start_token = &swaps.first().unwrap().token_in.symbol;
     let end_token = &swaps.last().unwrap().token_out.symbol;
@@ -436,14 +658,140 @@ pub fn is_stable_pair(token_in: &str, token_out: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use alloy_primitives::hex;
-    use brontes_types::constants::USDT_ADDRESS;
+    use alloy_primitives::{hex, Address, U256};
+    use brontes_types::{
+        constants::USDT_ADDRESS,
+        db::token_info::TokenInfoWithAddress,
+        mev::{AtomicArbType, BundleData, BundleHeader, OfaRefund, RefundType},
+        normalized_actions::{NormalizedFlashLoan, NormalizedTransfer},
+        Protocol,
+    };
+    use malachite::Rational;
 
+    use super::{net_flash_loan_principal, net_ofa_refund};
     use crate::{
         test_utils::{InspectorTestUtils, InspectorTxRunConfig, USDC_ADDRESS, WETH_ADDRESS},
         Inspectors,
     };
 
+    #[test]
+    fn test_net_flash_loan_principal() {
+        let pool = Address::with_last_byte(1);
+        let receiver = Address::with_last_byte(2);
+        let dex = Address::with_last_byte(3);
+
+        let transfer = |from, to, token: TokenInfoWithAddress, amount: i64| NormalizedTransfer {
+            from,
+            to,
+            token,
+            amount: Rational::from(amount),
+            ..Default::default()
+        };
+
+        let loan = NormalizedFlashLoan {
+            protocol: Protocol::AaveV3,
+            trace_index: 0,
+            from: receiver,
+            pool,
+            receiver_contract: receiver,
+            assets: vec![TokenInfoWithAddress::weth()],
+            amounts: vec![Rational::from(100)],
+            aave_mode: None,
+            child_actions: vec![],
+            repayments: vec![],
+            fees_paid: vec![],
+            msg_value: U256::ZERO,
+        };
+
+        let transfers = vec![
+            transfer(pool, receiver, TokenInfoWithAddress::weth(), 100),
+            transfer(receiver, dex, TokenInfoWithAddress::weth(), 100),
+            transfer(dex, receiver, TokenInfoWithAddress::usdc(), 350_000),
+            transfer(receiver, dex, TokenInfoWithAddress::usdc(), 350_000),
+            transfer(dex, receiver, TokenInfoWithAddress::weth(), 102),
+            transfer(receiver, pool, TokenInfoWithAddress::weth(), 101),
+        ];
+
+        let netted = net_flash_loan_principal(&[loan], transfers.clone());
+
+        // disbursement is dropped and only the fee is left of the repayment
+        assert_eq!(netted.len(), 5);
+        assert_eq!(netted[0], transfers[1]);
+        assert_eq!(netted[4], transfer(receiver, pool, TokenInfoWithAddress::weth(), 1));
+    }
+
+    #[test]
+    fn test_net_flash_loan_principal_repaid_by_caller() {
+        let pool = Address::with_last_byte(1);
+        let recipient = Address::with_last_byte(2);
+        let dex = Address::with_last_byte(3);
+        let caller = Address::with_last_byte(4);
+
+        let transfer = |trace_index, from, to, amount: i64| NormalizedTransfer {
+            trace_index,
+            from,
+            to,
+            token: TokenInfoWithAddress::weth(),
+            amount: Rational::from(amount),
+            ..Default::default()
+        };
+
+        let transfers = vec![
+            transfer(1, pool, recipient, 100),
+            transfer(2, recipient, dex, 100),
+            transfer(3, dex, recipient, 102),
+            transfer(4, recipient, caller, 102),
+            transfer(5, caller, pool, 101),
+        ];
+
+        // the pool calls back the caller, which repays the loan sent to the recipient
+        let loan = NormalizedFlashLoan {
+            protocol: Protocol::UniswapV3,
+            trace_index: 0,
+            from: caller,
+            pool,
+            receiver_contract: recipient,
+            assets: vec![TokenInfoWithAddress::weth()],
+            amounts: vec![Rational::from(100)],
+            aave_mode: None,
+            child_actions: vec![],
+            repayments: vec![transfers[4].clone()],
+            fees_paid: vec![],
+            msg_value: U256::ZERO,
+        };
+
+        let netted = net_flash_loan_principal(&[loan], transfers.clone());
+
+        assert_eq!(netted.len(), 4);
+        // the recipient handing the proceeds to the caller is left as is
+        assert_eq!(netted[2], transfers[3]);
+        assert_eq!(netted[3], transfer(5, caller, pool, 1));
+    }
+
+    #[test]
+    fn test_net_ofa_refund() {
+        let header = BundleHeader { profit_usd: 60.0, bribe_usd: 40.0, ..Default::default() };
+        let refund =
+            |refund_type, amount_usd| OfaRefund { refund_type, amount_usd, ..Default::default() };
+
+        // the builder paid the user 30 out of the 40 bid, so the builder only kept 10
+        let mut builder = header.clone();
+        net_ofa_refund(&mut builder, &refund(RefundType::Builder, 30.0));
+        assert_eq!(builder.bribe_usd, 10.0);
+        assert_eq!(builder.profit_usd, 60.0);
+
+        // a refund can't take more out of the bid than was bid
+        let mut capped = header.clone();
+        net_ofa_refund(&mut capped, &refund(RefundType::Builder, 50.0));
+        assert_eq!(capped.bribe_usd, 0.0);
+        assert_eq!(capped.profit_usd, 60.0);
+
+        // searcher refunds are part of the deltas already
+        let mut searcher = header.clone();
+        net_ofa_refund(&mut searcher, &refund(RefundType::Searcher, 30.0));
+        assert_eq!(searcher, header);
+    }
+
     #[brontes_macros::test]
     async fn test_backrun() {
         let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 0.5).await;
@@ -476,6 +824,33 @@ mod tests {
         inspector_util.run_inspector(config, None).await.unwrap();
     }
 
+    /// arb funded by a weth flash loan from balancer, the principal isn't
+    /// picked up as a swap and the arb is tagged as a flash loan arb
+    #[brontes_macros::test]
+    async fn test_flash_loan_funded_arb() {
+        let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 0.5).await;
+
+        let tx = hex!("0feed8bde2117cc166264dfeebfdec0cf6dc6655325fb94bd90f00688f8c463a").into();
+        let config = InspectorTxRunConfig::new(Inspectors::AtomicArb)
+            .with_mev_tx_hashes(vec![tx])
+            .with_dex_prices()
+            .needs_tokens(vec![
+                WETH_ADDRESS,
+                hex!("cd5fe23c85820f7b72d0926fc9b05b43e359b7ee").into(),
+            ]);
+
+        let bundles = inspector_util.inspect(config).await.unwrap();
+        assert_eq!(bundles.len(), 1, "found: {bundles:#?}");
+
+        let BundleData::AtomicArb(arb) = &bundles[0].data else {
+            panic!("expected an atomic arb, found: {:#?}", bundles[0].data)
+        };
+        assert_eq!(arb.arb_type, AtomicArbType::FlashLoanArb);
+        assert!(arb.swaps.iter().all(
+            |swap| swap.pool != Address::new(hex!("ba12222222228d8ba445958a75a0704d566bf2c8"))
+        ));
+    }
+
     #[brontes_macros::test]
     async fn test_not_false_positive_uni_router() {
         let inspector_util = InspectorTestUtils::new(USDC_ADDRESS, 0.5).await;
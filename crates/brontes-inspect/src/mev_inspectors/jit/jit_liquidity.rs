@@ -104,7 +104,7 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             .collect::<Vec<_>>()
     }
 
-    fn get_searcher_actions<'a>(
+    pub(super) fn get_searcher_actions<'a>(
         &self,
         i: impl Iterator<Item = &'a TxHash>,
         tree: Arc<BlockTree<Action>>,
@@ -117,6 +117,7 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
                         TreeSearchBuilder::default().with_actions([
                             Action::is_mint,
                             Action::is_burn,
+                            Action::is_collect,
                             Action::is_transfer,
                             Action::is_eth_transfer,
                             Action::is_nested_action,
@@ -272,6 +273,9 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             },
         );
 
+        let fees_earned_usd =
+            self.fees_earned_usd(&burns, &collect, info_set.last()?.tx_index, &metadata);
+
         let jit_details = self.build_jit_type(
             hashes,
             gas_details,
@@ -282,12 +286,13 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             victim_hashes,
             victim_gas_details,
             &victim_actions,
+            fees_earned_usd,
         )?;
 
         Some(vec![Bundle { header, data: BundleData::Jit(jit_details) }])
     }
 
-    fn build_jit_type(
+    pub(super) fn build_jit_type(
         &self,
         mut hashes: Vec<TxHash>,
         mut gas_details: Vec<GasDetails>,
@@ -298,6 +303,7 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
         victim_hashes: Vec<TxHash>,
         victim_gas_details: Vec<GasDetails>,
         victim_actions: &[Vec<Action>],
+        fees_earned_usd: f64,
     ) -> Option<JitLiquidity> {
         let victim_swaps = victim_actions
             .iter()
@@ -338,10 +344,37 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
                         .collect_vec()
                 })
                 .unwrap_or(burns),
+            fees_earned_usd,
         })
     }
 
-    fn ensure_valid_structure(
+    /// Collects pay out the burned liquidity together with the fees owed to
+    /// the position, so whatever is collected on top of the burned amounts are
+    /// the fees it earned. Protocols without a collect (v2) fold the fees into
+    /// the burn and report zero.
+    pub(super) fn fees_earned_usd(
+        &self,
+        burns: &[NormalizedBurn],
+        collect: &[NormalizedCollect],
+        tx_index: u64,
+        metadata: &Arc<Metadata>,
+    ) -> f64 {
+        collected_fees(burns, collect)
+            .into_iter()
+            .filter_map(|(token, amount)| {
+                self.utils.get_token_value_dex(
+                    tx_index as usize,
+                    PriceAt::After,
+                    token,
+                    &amount,
+                    metadata,
+                )
+            })
+            .fold(Rational::ZERO, |acc, fee| acc + fee)
+            .to_float()
+    }
+
+    pub(super) fn ensure_valid_structure(
         &self,
         mints: &[NormalizedMint],
         burns: &[NormalizedBurn],
@@ -604,7 +637,7 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             .collect_vec()
     }
 
-    fn get_bribes(&self, price: Arc<Metadata>, gas: &[GasDetails]) -> Rational {
+    pub(super) fn get_bribes(&self, price: Arc<Metadata>, gas: &[GasDetails]) -> Rational {
         let bribe = gas.iter().map(|gas| gas.gas_paid()).sum::<u128>();
 
         price.get_gas_price_usd(bribe, self.utils.quote)
@@ -653,7 +686,7 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
         results
     }
 
-    fn get_victim_actions(
+    pub(super) fn get_victim_actions(
         &self,
         victims: Vec<Vec<TxHash>>,
         tree: Arc<BlockTree<Action>>,
@@ -687,6 +720,34 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
     }
 }
 
+/// Amount collected on top of the burned liquidity per token, see
+/// [`JitInspector::fees_earned_usd`]
+pub(super) fn collected_fees(
+    burns: &[NormalizedBurn],
+    collect: &[NormalizedCollect],
+) -> FastHashMap<Address, Rational> {
+    let mut fees: FastHashMap<Address, Rational> = FastHashMap::default();
+    for c in collect {
+        for (token, amount) in c.token.iter().zip(&c.amount) {
+            *fees.entry(token.address).or_insert(Rational::ZERO) += amount;
+        }
+    }
+
+    for burn in burns
+        .iter()
+        .filter(|b| collect.iter().any(|c| c.pool == b.pool))
+    {
+        for (token, amount) in burn.token.iter().zip(&burn.amount) {
+            if let Some(fee) = fees.get_mut(&token.address) {
+                *fee -= amount;
+            }
+        }
+    }
+
+    fees.retain(|_, amount| amount > &Rational::ZERO);
+    fees
+}
+
 #[cfg(test)]
 mod tests {
     use alloy_primitives::hex;
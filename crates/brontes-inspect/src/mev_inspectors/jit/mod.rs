@@ -1,7 +1,9 @@
 pub mod jit_cex_dex;
 pub mod jit_liquidity;
+pub mod multi_block;
 
 mod types;
 
 pub use jit_cex_dex::JitCexDex;
 pub use jit_liquidity::JitInspector;
+pub use multi_block::MultiBlockJitInspector;
@@ -0,0 +1,624 @@
+use std::sync::Arc;
+
+use alloy_primitives::{Address, B256};
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_metrics::inspectors::OutlierMetrics;
+use brontes_types::{
+    collect_address_set_for_accounting,
+    db::dex::PriceAt,
+    mev::{Bundle, BundleData, MevType},
+    normalized_actions::{accounting::ActionAccounting, Action},
+    tree::BlockTree,
+    ActionIter, BlockData, FastHashMap, FastHashSet, MultiBlockData, ToFloatNearest,
+    TreeSearchBuilder,
+};
+use itertools::Itertools;
+use malachite::{num::basic::traits::Zero, Rational};
+
+use super::JitInspector;
+use crate::{Inspector, MAX_PROFIT, MIN_PROFIT};
+
+/// amount of blocks a position can stay open for, including the block it is
+/// burned in
+const BLOCK_WINDOW: usize = 3;
+/// max amount of victims we allow while the position is open
+const MAX_VICTIMS: usize = 10;
+
+/// Detects jit liquidity where the position is minted at the end of one of the
+/// previous blocks and burned in the most recent block, after the large swaps
+/// it was placed for went through. Positions are matched on the minting eoa
+/// and the pool. Found bundles are emitted as [`MevType::MultiBlockJit`]; jits
+/// that mint and burn in the same block are left to the [`JitInspector`].
+pub struct MultiBlockJitInspector<'db, DB: LibmdbxReader> {
+    inner: JitInspector<'db, DB>,
+}
+
+impl<'db, DB: LibmdbxReader> MultiBlockJitInspector<'db, DB> {
+    pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
+        Self { inner: JitInspector::new(quote, db, metrics) }
+    }
+}
+
+impl<DB: LibmdbxReader> Inspector for MultiBlockJitInspector<'_, DB> {
+    type Result = Vec<Bundle>;
+
+    fn block_window(&self) -> usize {
+        BLOCK_WINDOW
+    }
+
+    fn get_id(&self) -> &str {
+        "MultiBlockJit"
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.inner.utils.quote
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        let blocks = data.per_block_data.as_slice();
+        if blocks.len() < 2
+            || blocks
+                .windows(2)
+                .any(|w| w[0].block_number() + 1 != w[1].block_number())
+        {
+            return vec![]
+        }
+
+        self.inner
+            .utils
+            .get_metrics()
+            .map(|m| m.run_inspector(MevType::MultiBlockJit, || self.inspect_block_inner(blocks)))
+            .unwrap_or_else(|| self.inspect_block_inner(blocks))
+    }
+}
+
+impl<DB: LibmdbxReader> MultiBlockJitInspector<'_, DB> {
+    fn inspect_block_inner(&self, blocks: &[BlockData]) -> Vec<Bundle> {
+        tracing::trace!("starting multi block jit");
+        let summaries = blocks
+            .iter()
+            .map(|block| self.summarize_block(block.tree.clone()))
+            .collect_vec();
+
+        get_possible_multi_block_jits(&summaries)
+            .into_iter()
+            .filter_map(|pj| self.calculate_jit(blocks, pj))
+            .collect()
+    }
+
+    /// Collects the pools each tx of the block adds liquidity to, removes it
+    /// from or swaps on.
+    fn summarize_block(&self, tree: Arc<BlockTree<Action>>) -> Vec<TxPools> {
+        let search_args = TreeSearchBuilder::default().with_actions([
+            Action::is_mint,
+            Action::is_burn,
+            Action::is_collect,
+            Action::is_swap,
+            Action::is_nested_action,
+        ]);
+
+        tree.tx_roots
+            .iter()
+            .filter(|root| !root.get_root_action().is_revert())
+            .map(|root| {
+                let mut tx = TxPools {
+                    tx_hash: root.tx_hash,
+                    eoa:     root.head.address,
+                    mints:   FastHashSet::default(),
+                    burns:   FastHashSet::default(),
+                    swaps:   FastHashSet::default(),
+                };
+
+                self.inner
+                    .utils
+                    .flatten_nested_actions(root.collect(&search_args).into_iter(), &|action| {
+                        action.is_mint()
+                            || action.is_burn()
+                            || action.is_collect()
+                            || action.is_swap()
+                    })
+                    .for_each(|action| match action {
+                        Action::Mint(mint) => {
+                            tx.mints.insert(mint.pool);
+                        }
+                        Action::Burn(burn) => {
+                            tx.burns.insert(burn.pool);
+                        }
+                        Action::Collect(collect) => {
+                            tx.burns.insert(collect.pool);
+                        }
+                        action if action.is_swap() => {
+                            tx.swaps.insert(action.force_swap().pool);
+                        }
+                        _ => {}
+                    });
+
+                tx
+            })
+            .collect()
+    }
+
+    fn calculate_jit(&self, blocks: &[BlockData], pj: PossibleMultiBlockJit) -> Option<Bundle> {
+        let utils = &self.inner.utils;
+        let mint_block = &blocks[pj.mint_block];
+        let curr = blocks.last()?;
+
+        let mint_info = mint_block.tree.get_tx_info(pj.mint_tx, utils.db)?;
+        let burn_info = curr.tree.get_tx_info(pj.burn_tx, utils.db)?;
+        let victim_info = pj
+            .victims
+            .iter()
+            .map(|(block, tx_hash)| blocks[*block].tree.get_tx_info(*tx_hash, utils.db))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut searcher_actions = self
+            .inner
+            .get_searcher_actions([pj.mint_tx].iter(), mint_block.tree.clone());
+        searcher_actions.extend(
+            self.inner
+                .get_searcher_actions([pj.burn_tx].iter(), curr.tree.clone()),
+        );
+
+        let executor_contract = curr.tree.get_root(pj.burn_tx)?.get_to_address();
+        let mut victim_actions = vec![];
+        for (i, block) in blocks.iter().enumerate() {
+            let victims = pj
+                .victims
+                .iter()
+                .filter(|(victim_block, _)| *victim_block == i)
+                .map(|(_, tx_hash)| *tx_hash)
+                .collect_vec();
+            if victims.is_empty() {
+                continue
+            }
+
+            victim_actions.extend(self.inner.get_victim_actions(
+                vec![victims],
+                block.tree.clone(),
+                executor_contract,
+            )?);
+        }
+
+        // grab all mints and burns
+        let ((mints, burns, collect), rem): ((Vec<_>, Vec<_>, Vec<_>), Vec<_>) = searcher_actions
+            .into_iter()
+            .flatten()
+            .action_split_out((Action::try_mint, Action::try_burn, Action::try_collect));
+
+        if mints.is_empty() || (burns.is_empty() && collect.is_empty()) {
+            tracing::trace!("missing mints & burns");
+            return None
+        }
+        self.inner
+            .ensure_valid_structure(&mints, &burns, &victim_actions)?;
+
+        let info_set = [mint_info, burn_info];
+        let burn_info = &info_set[1];
+        let mev_addresses: FastHashSet<Address> = collect_address_set_for_accounting(&info_set);
+
+        let deltas = rem
+            .into_iter()
+            .filter(|f| f.is_transfer() || f.is_eth_transfer())
+            .chain(
+                info_set
+                    .iter()
+                    .flat_map(|info| info.get_total_eth_value())
+                    .cloned()
+                    .map(Action::from),
+            )
+            .account_for_actions();
+
+        let (rev, mut has_dex_price) = if let Some(rev) = utils.get_deltas_usd(
+            burn_info.tx_index,
+            PriceAt::After,
+            &mev_addresses,
+            &deltas,
+            curr.metadata.clone(),
+            true,
+        ) {
+            (rev, true)
+        } else {
+            (Rational::ZERO, false)
+        };
+
+        let (hashes, gas_details): (Vec<_>, Vec<_>) = info_set
+            .iter()
+            .map(|info| info.clone().split_to_storage_info())
+            .unzip();
+
+        let (victim_hashes, victim_gas_details): (Vec<_>, Vec<_>) = victim_info
+            .into_iter()
+            .map(|info| info.split_to_storage_info())
+            .unzip();
+
+        // gas of each tx is priced with the block it landed in
+        let bribe = self
+            .inner
+            .get_bribes(mint_block.metadata.clone(), &gas_details[..1])
+            + self
+                .inner
+                .get_bribes(curr.metadata.clone(), &gas_details[1..]);
+
+        let mut profit = Some(rev - bribe)
+            .filter(|_| has_dex_price)
+            .unwrap_or_default();
+
+        if profit >= MAX_PROFIT || profit <= MIN_PROFIT {
+            has_dex_price = false;
+            profit = Rational::ZERO;
+        }
+
+        let bundle_hashes = std::iter::once(hashes[0])
+            .chain(victim_hashes.iter().copied())
+            .chain(std::iter::once(hashes[1]))
+            .collect_vec();
+
+        let header = utils.build_bundle_header(
+            vec![deltas],
+            bundle_hashes,
+            burn_info,
+            profit.to_float(),
+            &gas_details,
+            curr.metadata.clone(),
+            MevType::MultiBlockJit,
+            !has_dex_price,
+            |this, token, amount| {
+                this.get_token_value_dex(
+                    burn_info.tx_index as usize,
+                    PriceAt::Average,
+                    token,
+                    &amount,
+                    &curr.metadata,
+                )
+            },
+        );
+
+        let fees_earned_usd =
+            self.inner
+                .fees_earned_usd(&burns, &collect, burn_info.tx_index, &curr.metadata);
+
+        let jit_details = self.inner.build_jit_type(
+            hashes,
+            gas_details,
+            curr.block_number(),
+            mints,
+            burns,
+            collect,
+            victim_hashes,
+            victim_gas_details,
+            &victim_actions,
+            fees_earned_usd,
+        )?;
+        tracing::debug!("{:#?}\n{:#?}", header, jit_details);
+
+        Some(Bundle { header, data: BundleData::Jit(jit_details) })
+    }
+}
+
+#[derive(Debug)]
+struct TxPools {
+    tx_hash: B256,
+    eoa:     Address,
+    mints:   FastHashSet<Address>,
+    /// pools liquidity is burned or collected from
+    burns:   FastHashSet<Address>,
+    swaps:   FastHashSet<Address>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PossibleMultiBlockJit {
+    /// index of the block the mint landed in
+    mint_block: usize,
+    mint_tx:    B256,
+    burn_tx:    B256,
+    /// swaps on the pool from other eoas while the position was open, as
+    /// (block index, tx hash)
+    victims:    Vec<(usize, B256)>,
+}
+
+/// Pairs the first burn of an eoa on a pool in the most recent block with the
+/// last mint the eoa made on that pool in the previous blocks of the window.
+/// Positions that are burned before the most recent block or that are topped
+/// up within it are skipped.
+fn get_possible_multi_block_jits(blocks: &[Vec<TxPools>]) -> Vec<PossibleMultiBlockJit> {
+    let Some((curr, prev)) = blocks.split_last() else { return vec![] };
+    let curr_block = prev.len();
+
+    // (eoa, pool) -> (block index, tx index) of the mint
+    let mut open: FastHashMap<(Address, Address), (usize, usize)> = FastHashMap::default();
+    for (block, txes) in prev.iter().enumerate() {
+        for (i, tx) in txes.iter().enumerate() {
+            for pool in &tx.burns {
+                open.remove(&(tx.eoa, *pool));
+            }
+            for pool in &tx.mints {
+                open.insert((tx.eoa, *pool), (block, i));
+            }
+        }
+    }
+
+    // (mint block, mint tx index, burn tx index) -> pools
+    let mut pairs: FastHashMap<(usize, usize, usize), FastHashSet<Address>> =
+        FastHashMap::default();
+    for (i, tx) in curr.iter().enumerate() {
+        for pool in &tx.burns {
+            if let Some((block, mint)) = open.remove(&(tx.eoa, *pool)) {
+                pairs.entry((block, mint, i)).or_default().insert(*pool);
+            }
+        }
+        // minted and burned within the same block, handled by the single
+        // block jit inspector
+        for pool in &tx.mints {
+            open.remove(&(tx.eoa, *pool));
+        }
+    }
+
+    pairs
+        .into_iter()
+        .sorted_by_key(|(key, _)| *key)
+        .filter_map(|((mint_block, mint_idx, burn_idx), pools)| {
+            let lp = curr[burn_idx].eoa;
+            let victims = (mint_block..=curr_block)
+                .flat_map(|block| {
+                    let txes = &blocks[block];
+                    let start = if block == mint_block { mint_idx + 1 } else { 0 };
+                    let end = if block == curr_block { burn_idx } else { txes.len() };
+
+                    txes[start..end]
+                        .iter()
+                        .filter(|tx| tx.eoa != lp && !tx.swaps.is_disjoint(&pools))
+                        .map(move |tx| (block, tx.tx_hash))
+                })
+                .collect_vec();
+
+            if victims.is_empty() || victims.len() > MAX_VICTIMS {
+                return None
+            }
+
+            Some(PossibleMultiBlockJit {
+                mint_block,
+                mint_tx: blocks[mint_block][mint_idx].tx_hash,
+                burn_tx: curr[burn_idx].tx_hash,
+                victims,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{NormalizedBurn, NormalizedCollect},
+    };
+
+    use super::*;
+    use crate::{
+        mev_inspectors::jit::jit_liquidity::collected_fees,
+        test_utils::{InspectorTestUtils, InspectorTxRunConfig},
+        Inspectors,
+    };
+
+    const LP: Address = Address::with_last_byte(1);
+    const TRADER: Address = Address::with_last_byte(2);
+    const POOL: Address = Address::with_last_byte(10);
+    const OTHER_POOL: Address = Address::with_last_byte(11);
+
+    /// the txes of the blur jit, in block order
+    const BLUR_JIT: [B256; 4] = [
+        B256::new(hex!("70a315ed0b31138a0b841d9760dc6d4595414e50fecb60f05e031880f0d9398f")),
+        B256::new(hex!("590edbb9e1046405a2a3586208e1e9384b8eca93dcbf03e9216da53ca8f94a6d")),
+        B256::new(hex!("ab001a0981e3da3d057c1b0c939a988d4d7cc98a903c66699feb59fc028ffe77")),
+        B256::new(hex!("b420b67fab4f1902bcd1284934d9610631b9da9e616780dbcc85d7c815b50896")),
+    ];
+
+    fn tx(
+        hash: u8,
+        eoa: Address,
+        mints: &[Address],
+        burns: &[Address],
+        swaps: &[Address],
+    ) -> TxPools {
+        TxPools {
+            tx_hash: B256::with_last_byte(hash),
+            eoa,
+            mints: mints.iter().copied().collect(),
+            burns: burns.iter().copied().collect(),
+            swaps: swaps.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn test_mint_and_burn_in_consecutive_blocks() {
+        let blocks = vec![
+            vec![tx(1, TRADER, &[], &[], &[POOL]), tx(2, LP, &[POOL], &[], &[])],
+            vec![
+                tx(3, TRADER, &[], &[], &[POOL]),
+                tx(4, TRADER, &[], &[], &[OTHER_POOL]),
+                tx(5, LP, &[], &[POOL], &[]),
+                tx(6, TRADER, &[], &[], &[POOL]),
+            ],
+        ];
+
+        // only the swaps on the pool while the position was open are victims
+        assert_eq!(
+            get_possible_multi_block_jits(&blocks),
+            vec![PossibleMultiBlockJit {
+                mint_block: 0,
+                mint_tx:    B256::with_last_byte(2),
+                burn_tx:    B256::with_last_byte(5),
+                victims:    vec![(1, B256::with_last_byte(3))],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_victims_across_the_window() {
+        let blocks = vec![
+            vec![tx(1, LP, &[POOL], &[], &[]), tx(2, TRADER, &[], &[], &[POOL])],
+            vec![tx(3, TRADER, &[], &[], &[POOL]), tx(4, LP, &[], &[], &[POOL])],
+            vec![tx(5, LP, &[], &[POOL], &[])],
+        ];
+
+        // the lp's own swap isn't a victim
+        assert_eq!(
+            get_possible_multi_block_jits(&blocks),
+            vec![PossibleMultiBlockJit {
+                mint_block: 0,
+                mint_tx:    B256::with_last_byte(1),
+                burn_tx:    B256::with_last_byte(5),
+                victims:    vec![(0, B256::with_last_byte(2)), (1, B256::with_last_byte(3))],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_no_multi_block_jit() {
+        let victim = || tx(2, TRADER, &[], &[], &[POOL]);
+
+        // minted and burned in the most recent block
+        let same_block = vec![
+            vec![],
+            vec![tx(1, LP, &[POOL], &[], &[]), victim(), tx(3, LP, &[], &[POOL], &[])],
+        ];
+        // burned before the most recent block
+        let closed = vec![
+            vec![tx(1, LP, &[POOL], &[], &[]), victim()],
+            vec![tx(3, LP, &[], &[POOL], &[])],
+            vec![tx(4, TRADER, &[], &[], &[POOL])],
+        ];
+        // no swaps while the position was open
+        let no_victims = vec![
+            vec![tx(1, LP, &[POOL], &[], &[])],
+            vec![tx(2, TRADER, &[], &[], &[OTHER_POOL]), tx(3, LP, &[], &[POOL], &[])],
+        ];
+        // the position is burned by another eoa
+        let other_eoa = vec![
+            vec![tx(1, LP, &[POOL], &[], &[])],
+            vec![victim(), tx(3, TRADER, &[], &[POOL], &[])],
+        ];
+        // topped up in the most recent block before the burn
+        let topped_up = vec![
+            vec![tx(1, LP, &[POOL], &[], &[])],
+            vec![tx(3, LP, &[POOL], &[], &[]), victim(), tx(4, LP, &[], &[POOL], &[])],
+        ];
+
+        for blocks in [same_block, closed, no_victims, other_eoa, topped_up] {
+            assert!(get_possible_multi_block_jits(&blocks).is_empty(), "{blocks:#?}");
+        }
+    }
+
+    #[test]
+    fn test_fees_from_collect() {
+        let weth = TokenInfoWithAddress::weth();
+        let usdc = TokenInfoWithAddress::usdc();
+
+        let burn = NormalizedBurn {
+            pool: POOL,
+            token: vec![weth.clone(), usdc.clone()],
+            amount: vec![Rational::from(9), Rational::from(100)],
+            ..Default::default()
+        };
+        // collected over the blocks the position was open for, on top of the
+        // burned liquidity
+        let collect = NormalizedCollect {
+            pool: POOL,
+            token: vec![weth.clone(), usdc.clone()],
+            amount: vec![Rational::from(10), Rational::from(100)],
+            ..Default::default()
+        };
+        // collected without burning, everything is fees
+        let other_collect = NormalizedCollect {
+            pool: OTHER_POOL,
+            token: vec![usdc],
+            amount: vec![Rational::from(5)],
+            ..Default::default()
+        };
+
+        let fees = collected_fees(&[burn.clone()], &[collect.clone()]);
+        assert_eq!(fees.len(), 1);
+        assert_eq!(fees[&WETH_ADDRESS], Rational::from(1));
+
+        let fees = collected_fees(&[burn], &[collect, other_collect]);
+        assert_eq!(fees.len(), 2);
+        assert_eq!(fees[&WETH_ADDRESS], Rational::from(1));
+        assert_eq!(fees[&USDC_ADDRESS], Rational::from(5));
+
+        // v2 style exits fold the fees into the burn
+        assert!(collected_fees(&[], &[]).is_empty());
+    }
+
+    fn blur_jit_config(inspector: Inspectors) -> InspectorTxRunConfig {
+        InspectorTxRunConfig::new(inspector)
+            .with_dex_prices()
+            .needs_tokens(vec![
+                hex!("95ad61b0a150d79219dcf64e1e6cc01f0b64c4ce").into(),
+                WETH_ADDRESS,
+            ])
+    }
+
+    /// the blur jit with its position left open over the block boundary is
+    /// found with the same victims and fees as the single block jit
+    #[brontes_macros::test]
+    async fn test_multi_block_jit() {
+        let test_utils = InspectorTestUtils::new(USDC_ADDRESS, 2.0).await;
+
+        let single_block = test_utils
+            .inspect(blur_jit_config(Inspectors::Jit).with_mev_tx_hashes(BLUR_JIT.to_vec()))
+            .await
+            .unwrap();
+        let [Bundle { data: BundleData::Jit(single_jit), header: single_header }] =
+            single_block.as_slice()
+        else {
+            panic!("expected a single jit, got {single_block:#?}")
+        };
+
+        // the position is minted at the end of the first block and burned in the
+        // second one, after the victims
+        let split = BLUR_JIT
+            .iter()
+            .position(|tx| *tx == single_jit.frontrun_mint_tx_hash)
+            .unwrap()
+            + 1;
+        let config = blur_jit_config(Inspectors::MultiBlockJit).with_split_block_tx_hashes(vec![
+            BLUR_JIT[..split].to_vec(),
+            BLUR_JIT[split..].to_vec(),
+        ]);
+
+        let multi_block = test_utils.inspect(config).await.unwrap();
+        let [Bundle { data: BundleData::Jit(jit), header }] = multi_block.as_slice() else {
+            panic!("expected a single jit, got {multi_block:#?}")
+        };
+
+        assert_eq!(header.mev_type, MevType::MultiBlockJit);
+        assert_eq!(jit.frontrun_mint_tx_hash, single_jit.frontrun_mint_tx_hash);
+        assert_eq!(jit.victim_swaps_tx_hashes, single_jit.victim_swaps_tx_hashes);
+        assert_eq!(jit.backrun_burn_tx_hash, single_jit.backrun_burn_tx_hash);
+        assert!(jit.fees_earned_usd > 0.0, "no fees earned: {jit:#?}");
+        assert!(
+            (jit.fees_earned_usd - single_jit.fees_earned_usd).abs() < 2.0,
+            "{} != {}",
+            jit.fees_earned_usd,
+            single_jit.fees_earned_usd
+        );
+        assert!(
+            (header.profit_usd - single_header.profit_usd).abs() < 2.0,
+            "{} != {}",
+            header.profit_usd,
+            single_header.profit_usd
+        );
+    }
+
+    /// a jit that mints and burns within one block is left to the single block
+    /// inspector
+    #[brontes_macros::test]
+    async fn test_single_block_jit_is_not_multi_block() {
+        let test_utils = InspectorTestUtils::new(USDC_ADDRESS, 2.0).await;
+        let config = blur_jit_config(Inspectors::MultiBlockJit)
+            .with_split_block_tx_hashes(vec![vec![], BLUR_JIT.to_vec()]);
+
+        test_utils.assert_no_mev(config).await.unwrap();
+    }
+}
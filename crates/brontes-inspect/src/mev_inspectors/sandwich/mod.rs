@@ -5,22 +5,27 @@ use std::{
 
 use alloy_primitives::TxHash;
 use tracing::trace;
+mod multi_block;
+mod simulation;
 mod types;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
     db::dex::PriceAt,
-    mev::{Bundle, BundleData, MevType, Sandwich},
+    mev::{Bundle, BundleData, MevType, Sandwich, SimulatedVictimLoss},
     normalized_actions::{
         accounting::ActionAccounting, Action, NormalizedSwap, NormalizedTransfer,
     },
     tree::{collect_address_set_for_accounting, BlockTree, GasDetails},
     ActionIter, BlockData, FastHashMap, FastHashSet, IntoZipTree, MultiBlockData, ToFloatNearest,
-    TreeBase, TreeCollector, TreeIter, TreeSearchBuilder, TxInfo, UnzipPadded,
+    ToScaledRational, TreeBase, TreeCollector, TreeIter, TreeSearchBuilder, TxInfo, UnzipPadded,
 };
 use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
+pub use multi_block::MultiBlockSandwichInspector;
 use reth_primitives::{Address, B256};
+use simulation::SimulatedVictim;
+pub use simulation::VictimSimulator;
 use types::{PossibleSandwich, PossibleSandwichWithTxInfo};
 
 use super::MAX_PROFIT;
@@ -37,12 +42,23 @@ const MAX_PRICE_DIFF: Rational = Rational::const_from_unsigneds(99995, 100000);
 const MAX_NON_SWAP_FRONTRUN: Rational = Rational::const_from_unsigned(5000);
 
 pub struct SandwichInspector<'db, DB: LibmdbxReader> {
-    utils: SharedInspectorUtils<'db, DB>,
+    utils:            SharedInspectorUtils<'db, DB>,
+    victim_simulator: Option<VictimSimulator>,
 }
 
 impl<'db, DB: LibmdbxReader> SandwichInspector<'db, DB> {
     pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
-        Self { utils: SharedInspectorUtils::new(quote, db, metrics) }
+        Self {
+            utils:            SharedInspectorUtils::new(quote, db, metrics),
+            victim_simulator: None,
+        }
+    }
+
+    /// Measures the loss of each victim by re-executing it without the
+    /// frontruns, see [`VictimSimulator`]
+    pub fn with_victim_simulator(mut self, victim_simulator: Option<VictimSimulator>) -> Self {
+        self.victim_simulator = victim_simulator;
+        self
     }
 }
 
@@ -421,6 +437,20 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
 
         let victim_swaps = victim_swaps.into_iter().map(|(s, _)| s).collect_vec();
 
+        let victim_loss = self
+            .victim_simulator
+            .as_ref()
+            .map(|simulator| {
+                self.simulate_victim_loss(
+                    simulator,
+                    &frontrun_tx_hash,
+                    &victim_info,
+                    &victim_swaps,
+                    &metadata,
+                )
+            })
+            .unwrap_or_default();
+
         let sandwich = Sandwich {
             block_number: metadata.block_num,
             frontrun_tx_hash,
@@ -432,12 +462,98 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             backrun_tx_hash: backrun_info.tx_hash,
             backrun_swaps: back_run_swaps,
             backrun_gas_details: backrun_info.gas_details,
+            victim_loss,
         };
         tracing::debug!("{:#?}\n{:#?}", header, sandwich);
 
         Some(vec![Bundle { header, data: BundleData::Sandwich(sandwich) }])
     }
 
+    /// Re-executes every victim with and without the frontruns and prices the
+    /// difference in the amount the recipient of the victim's last swap
+    /// received. Victims that can't be re-executed are estimated by quoting
+    /// their last swap through the pre-frontrun pool at the execution price
+    fn simulate_victim_loss(
+        &self,
+        simulator: &VictimSimulator,
+        frontrun_tx_hash: &[B256],
+        victim_info: &[Vec<TxInfo>],
+        victim_swaps: &[Vec<NormalizedSwap>],
+        metadata: &Arc<Metadata>,
+    ) -> Vec<SimulatedVictimLoss> {
+        let victims = victim_info
+            .iter()
+            .flatten()
+            .zip(victim_swaps)
+            .filter_map(|(info, swaps)| {
+                let swap = swaps.last()?;
+                Some(SimulatedVictim {
+                    tx_hash:   info.tx_hash,
+                    token_out: swap.token_out.address,
+                    recipient: swap.recipient,
+                })
+            })
+            .collect_vec();
+
+        let mut simulations = simulator
+            .simulate_victims(self.utils.db, metadata.block_num, frontrun_tx_hash, &victims)
+            .unwrap_or_else(|e| {
+                tracing::warn!(err=%e, block=metadata.block_num, "failed to simulate victims");
+                vec![]
+            });
+
+        let unsimulated = victim_info
+            .iter()
+            .flatten()
+            .zip(victim_swaps)
+            .filter(|(info, _)| !simulations.iter().any(|sim| sim.tx_hash == info.tx_hash))
+            .filter_map(|(info, swaps)| Some((info, swaps.last()?)))
+            .collect_vec();
+        if !unsimulated.is_empty() {
+            simulations.extend(simulator.estimate_victims(
+                self.utils.db,
+                metadata.block_num,
+                frontrun_tx_hash,
+                &unsimulated,
+            ));
+        }
+
+        simulations
+            .into_iter()
+            .map(|sim| {
+                let amount_lost_usd = self
+                    .utils
+                    .db
+                    .try_fetch_token_info(sim.token)
+                    .ok()
+                    .and_then(|info| {
+                        let lost = sim
+                            .counterfactual_amount_out
+                            .to_scaled_rational(info.decimals)
+                            - sim.amount_out.to_scaled_rational(info.decimals);
+                        self.utils.get_token_value_dex(
+                            sim.tx_index as usize,
+                            PriceAt::Average,
+                            sim.token,
+                            &lost,
+                            metadata,
+                        )
+                    })
+                    .map(|usd| usd.to_float())
+                    .unwrap_or_default();
+
+                SimulatedVictimLoss {
+                    tx_hash: sim.tx_hash,
+                    victim_eoa: sim.victim_eoa,
+                    token: sim.token,
+                    amount_out: sim.amount_out,
+                    counterfactual_amount_out: sim.counterfactual_amount_out,
+                    amount_lost_usd,
+                }
+            })
+            .collect()
+    }
+
     /// For the given set of possible sandwich data.
     /// Calls with two different revisions.
     ///     1) front shrink
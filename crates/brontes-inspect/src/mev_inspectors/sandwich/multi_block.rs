@@ -0,0 +1,512 @@
+use std::sync::Arc;
+
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_metrics::inspectors::OutlierMetrics;
+use brontes_types::{
+    db::dex::PriceAt,
+    mev::{Bundle, BundleData, MevType, Sandwich},
+    normalized_actions::{accounting::ActionAccounting, Action},
+    tree::{collect_address_set_for_accounting, BlockTree, Root},
+    ActionIter, BlockData, FastHashMap, FastHashSet, MultiBlockData, ToFloatNearest,
+    TreeSearchBuilder,
+};
+use itertools::Itertools;
+use malachite::{num::basic::traits::Zero, Rational};
+use reth_primitives::{Address, B256};
+
+use super::{SandwichInspector, MAX_NON_SWAP_FRONTRUN, MAX_PRICE_DIFF};
+use crate::{Inspector, MAX_PROFIT, MIN_PROFIT};
+
+/// max amount of victims we allow across both blocks
+const MAX_VICTIMS: usize = 30;
+
+/// Detects sandwiches where the frontrun lands at the end of block N and the
+/// backrun at the start of block N + 1. This mostly happens when the same
+/// builder wins consecutive slots. Found bundles are emitted as
+/// [`MevType::MultiBlockSandwich`] so they can be reported separately from
+/// the single block sandwiches.
+pub struct MultiBlockSandwichInspector<'db, DB: LibmdbxReader> {
+    inner: SandwichInspector<'db, DB>,
+}
+
+impl<'db, DB: LibmdbxReader> MultiBlockSandwichInspector<'db, DB> {
+    pub fn new(quote: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
+        Self { inner: SandwichInspector::new(quote, db, metrics) }
+    }
+}
+
+impl<DB: LibmdbxReader> Inspector for MultiBlockSandwichInspector<'_, DB> {
+    type Result = Vec<Bundle>;
+
+    // frontrun is in the previous block, backrun in the most recent one
+    fn block_window(&self) -> usize {
+        2
+    }
+
+    fn get_id(&self) -> &str {
+        "MultiBlockSandwich"
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.inner.utils.quote
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        let [prev, curr] = data.per_block_data.as_slice() else { return vec![] };
+        if prev.block_number() + 1 != curr.block_number() {
+            return vec![]
+        }
+
+        self.inner
+            .utils
+            .get_metrics()
+            .map(|m| {
+                m.run_inspector(MevType::MultiBlockSandwich, || {
+                    self.inspect_block_inner(prev, curr)
+                })
+            })
+            .unwrap_or_else(|| self.inspect_block_inner(prev, curr))
+    }
+}
+
+impl<DB: LibmdbxReader> MultiBlockSandwichInspector<'_, DB> {
+    fn inspect_block_inner(&self, prev: &BlockData, curr: &BlockData) -> Vec<Bundle> {
+        tracing::trace!("starting multi block sandwich");
+        let search_args = TreeSearchBuilder::default().with_actions([
+            Action::is_swap,
+            Action::is_transfer,
+            Action::is_eth_transfer,
+            Action::is_nested_action,
+        ]);
+
+        get_possible_multi_block_sandwiches(prev.tree.clone(), curr.tree.clone(), |root| {
+            self.swap_pools(root, &search_args)
+        })
+        .into_iter()
+        .filter_map(|ps| self.calculate_sandwich(prev, curr, search_args.clone(), ps))
+        .collect()
+    }
+
+    fn calculate_sandwich(
+        &self,
+        prev: &BlockData,
+        curr: &BlockData,
+        search_args: TreeSearchBuilder<Action>,
+        ps: PossibleMultiBlockSandwich,
+    ) -> Option<Bundle> {
+        let utils = &self.inner.utils;
+        let frontrun_info = prev.tree.get_tx_info(ps.frontrun, utils.db)?;
+        let backrun_info = curr.tree.get_tx_info(ps.backrun, utils.db)?;
+
+        // same checks as the single block sandwich, either the same eoa or both
+        // txes go through a mev contract
+        if !((frontrun_info.mev_contract.is_some() && backrun_info.mev_contract.is_some())
+            || frontrun_info.eoa == backrun_info.eoa)
+        {
+            tracing::debug!(
+                target: "brontes_inspect::sandwich",
+                "multi block sandwich doesn't have same eoa and isn't a verified contract"
+            );
+            return None
+        }
+
+        let mut victim_hashes = ps.frontrun_victims.clone();
+        victim_hashes.extend(ps.backrun_victims.iter().copied());
+
+        let victim_info = prev
+            .tree
+            .get_tx_info_batch(&ps.frontrun_victims, utils.db)
+            .into_iter()
+            .chain(curr.tree.get_tx_info_batch(&ps.backrun_victims, utils.db))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut victim_actions = vec![];
+        for (tree, victims) in
+            [(prev.tree.clone(), &ps.frontrun_victims), (curr.tree.clone(), &ps.backrun_victims)]
+        {
+            if victims.is_empty() {
+                continue
+            }
+            victim_actions.extend(
+                self.inner
+                    .get_victim_swap_transfer(
+                        vec![victims.clone()],
+                        tree,
+                        search_args.clone(),
+                        ps.mev_executor_contract,
+                    )?
+                    .into_iter()
+                    .flatten(),
+            );
+        }
+
+        let front_run_actions =
+            self.collect_searcher_actions(prev.tree.clone(), ps.frontrun, search_args.clone())?;
+        let back_run_actions =
+            self.collect_searcher_actions(curr.tree.clone(), ps.backrun, search_args)?;
+
+        let searcher_info = [frontrun_info.clone(), backrun_info.clone()];
+        let mev_addresses: FastHashSet<Address> =
+            collect_address_set_for_accounting(&searcher_info);
+
+        // assert that both the frontrun and backrun can be generated from a swap
+        if [&front_run_actions, &back_run_actions]
+            .iter()
+            .any(|actions| {
+                let (mut swaps, transfers): (Vec<_>, Vec<_>) = actions
+                    .iter()
+                    .cloned()
+                    .split_actions((Action::try_swaps_merged, Action::try_transfer));
+                swaps.extend(utils.try_create_swaps(&transfers, mev_addresses.clone()));
+                swaps.is_empty()
+            })
+        {
+            return None
+        }
+
+        let black_list: FastHashSet<Address> =
+            collect_address_set_for_accounting(std::slice::from_ref(&frontrun_info));
+
+        if !SandwichInspector::<DB>::has_pool_overlap(
+            std::slice::from_ref(&front_run_actions),
+            &back_run_actions,
+            std::slice::from_ref(&victim_actions),
+            std::slice::from_ref(&victim_info),
+            &black_list,
+        ) {
+            return None
+        }
+
+        let front_run_swaps = front_run_actions
+            .clone()
+            .into_iter()
+            .collect_action_vec(Action::try_swaps_merged);
+        let back_run_swaps = back_run_actions
+            .clone()
+            .into_iter()
+            .collect_action_vec(Action::try_swaps_merged);
+
+        // gas of each tx is priced with the block it landed in
+        let gas_used = prev
+            .metadata
+            .get_gas_price_usd(frontrun_info.gas_details.gas_paid(), utils.quote)
+            + curr
+                .metadata
+                .get_gas_price_usd(backrun_info.gas_details.gas_paid(), utils.quote);
+
+        let searcher_deltas = front_run_actions
+            .into_iter()
+            .chain(back_run_actions)
+            .filter(|f| f.is_transfer() || f.is_eth_transfer())
+            .chain(
+                searcher_info
+                    .iter()
+                    .flat_map(|info| info.get_total_eth_value())
+                    .cloned()
+                    .map(Action::from),
+            )
+            .account_for_actions();
+
+        let tokens = searcher_deltas
+            .values()
+            .flat_map(|k| {
+                k.iter()
+                    .filter(|(_, v)| *v != &Rational::ZERO)
+                    .map(|(k, _)| k)
+            })
+            .unique()
+            .collect_vec();
+
+        // ensure valid pricing, the frontrun is checked against the dex prices of
+        // the previous block
+        let mut has_dex_price = utils.valid_pricing(
+            prev.metadata.clone(),
+            &front_run_swaps,
+            tokens.iter().copied(),
+            frontrun_info.tx_index as usize,
+            MAX_PRICE_DIFF,
+            MevType::MultiBlockSandwich,
+        );
+        has_dex_price &= utils.valid_pricing(
+            curr.metadata.clone(),
+            &back_run_swaps,
+            tokens.iter().copied(),
+            backrun_info.tx_index as usize,
+            MAX_PRICE_DIFF,
+            MevType::MultiBlockSandwich,
+        );
+
+        let rev = utils
+            .get_deltas_usd(
+                backrun_info.tx_index,
+                PriceAt::After,
+                &mev_addresses,
+                &searcher_deltas,
+                curr.metadata.clone(),
+                true,
+            )
+            .unwrap_or_else(|| {
+                has_dex_price = false;
+                Rational::ZERO
+            });
+
+        let mut profit_usd = Some(rev - &gas_used)
+            .filter(|_| has_dex_price)
+            .unwrap_or_default();
+
+        if profit_usd >= MAX_PROFIT || profit_usd <= MIN_PROFIT {
+            has_dex_price = false;
+            profit_usd = Rational::ZERO;
+        }
+
+        // sus threshold
+        if front_run_swaps.is_empty() && profit_usd > MAX_NON_SWAP_FRONTRUN {
+            tracing::warn!("multi block frontrun has no swaps");
+            profit_usd = Rational::ZERO;
+            has_dex_price = false;
+        }
+
+        let gas_details = [frontrun_info.gas_details, backrun_info.gas_details];
+        let bundle_hashes = std::iter::once(frontrun_info.tx_hash)
+            .chain(victim_hashes.iter().copied())
+            .chain(std::iter::once(backrun_info.tx_hash))
+            .collect_vec();
+
+        let header = utils.build_bundle_header(
+            vec![searcher_deltas],
+            bundle_hashes,
+            &backrun_info,
+            profit_usd.to_float(),
+            &gas_details,
+            curr.metadata.clone(),
+            MevType::MultiBlockSandwich,
+            !has_dex_price,
+            |this, token, amount| {
+                this.get_token_value_dex(
+                    backrun_info.tx_index as usize,
+                    PriceAt::Average,
+                    token,
+                    &amount,
+                    &curr.metadata,
+                )
+            },
+        );
+
+        let sandwich = Sandwich {
+            block_number:             curr.block_number(),
+            frontrun_tx_hash:         vec![frontrun_info.tx_hash],
+            frontrun_gas_details:     vec![frontrun_info.gas_details],
+            frontrun_swaps:           vec![front_run_swaps],
+            victim_swaps_tx_hashes:   vec![victim_hashes],
+            victim_swaps_gas_details: victim_info.iter().map(|info| info.gas_details).collect(),
+            victim_swaps:             victim_actions.into_iter().map(|(s, _)| s).collect(),
+            backrun_tx_hash:          backrun_info.tx_hash,
+            backrun_swaps:            back_run_swaps,
+            backrun_gas_details:      backrun_info.gas_details,
+            // the victims can't be re-executed on a single parent state when the
+            // frontrun is in the previous block
+            victim_loss:              vec![],
+        };
+        tracing::debug!("{:#?}\n{:#?}", header, sandwich);
+
+        Some(Bundle { header, data: BundleData::Sandwich(sandwich) })
+    }
+
+    /// Pools a tx swaps on, including the swaps nested in aggregators, batches
+    /// and flash loans
+    fn swap_pools(
+        &self,
+        root: &Root<Action>,
+        search_args: &TreeSearchBuilder<Action>,
+    ) -> FastHashSet<Address> {
+        self.inner
+            .utils
+            .flatten_nested_actions_default(root.collect(search_args).into_iter())
+            .filter_map(Action::try_swaps_merged)
+            .map(|swap| swap.pool)
+            .collect()
+    }
+
+    fn collect_searcher_actions(
+        &self,
+        tree: Arc<BlockTree<Action>>,
+        tx_hash: B256,
+        search_args: TreeSearchBuilder<Action>,
+    ) -> Option<Vec<Action>> {
+        tree.collect_txes(&[tx_hash], search_args)
+            .map(|actions| {
+                self.inner
+                    .utils
+                    .flatten_nested_actions_default(actions.into_iter())
+                    .collect_vec()
+            })
+            .collect::<Vec<_>>()
+            .pop()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PossibleMultiBlockSandwich {
+    mev_executor_contract: Address,
+    frontrun:              B256,
+    backrun:               B256,
+    /// victims that landed after the frontrun in the previous block
+    frontrun_victims:      Vec<B256>,
+    /// victims that landed before the backrun in the most recent block
+    backrun_victims:       Vec<B256>,
+}
+
+/// Pairs the last tx of each eoa and mev contract in the previous block with
+/// its first tx in the most recent block that swaps on the same pool.
+/// Everything landing in between is treated as a possible victim.
+///
+/// Keying on the pool as well as the searcher stops unrelated txes that only
+/// share a router or eoa from being paired across the block boundary.
+fn get_possible_multi_block_sandwiches(
+    prev: Arc<BlockTree<Action>>,
+    curr: Arc<BlockTree<Action>>,
+    swap_pools: impl Fn(&Root<Action>) -> FastHashSet<Address>,
+) -> Vec<PossibleMultiBlockSandwich> {
+    let prev_roots = prev
+        .tx_roots
+        .iter()
+        .filter(|root| !root.get_root_action().is_revert())
+        .collect_vec();
+    let curr_roots = curr
+        .tx_roots
+        .iter()
+        .filter(|root| !root.get_root_action().is_revert())
+        .collect_vec();
+
+    // last occurrence in the previous block for each searcher and pool
+    let mut frontruns: FastHashMap<(Address, Address), usize> = FastHashMap::default();
+    for (i, root) in prev_roots.iter().enumerate() {
+        for pool in swap_pools(root) {
+            frontruns.insert((root.head.address, pool), i);
+            frontruns.insert((root.get_to_address(), pool), i);
+        }
+    }
+
+    // first occurrence in the most recent block
+    let mut backruns: FastHashMap<(Address, Address), usize> = FastHashMap::default();
+    for (i, root) in curr_roots.iter().enumerate() {
+        for pool in swap_pools(root) {
+            backruns.entry((root.head.address, pool)).or_insert(i);
+            backruns.entry((root.get_to_address(), pool)).or_insert(i);
+        }
+    }
+
+    frontruns
+        .into_iter()
+        .filter_map(|(key, front_idx)| {
+            let back_idx = *backruns.get(&key)?;
+            let frontrun_victims = prev_roots[front_idx + 1..]
+                .iter()
+                .map(|root| root.tx_hash)
+                .collect_vec();
+            let backrun_victims = curr_roots[..back_idx]
+                .iter()
+                .map(|root| root.tx_hash)
+                .collect_vec();
+
+            let victim_count = frontrun_victims.len() + backrun_victims.len();
+            if victim_count == 0 || victim_count > MAX_VICTIMS {
+                return None
+            }
+
+            Some(PossibleMultiBlockSandwich {
+                mev_executor_contract: curr_roots[back_idx].get_to_address(),
+                frontrun: prev_roots[front_idx].tx_hash,
+                backrun: curr_roots[back_idx].tx_hash,
+                frontrun_victims,
+                backrun_victims,
+            })
+        })
+        .unique()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+    use brontes_types::constants::USDT_ADDRESS;
+
+    use super::*;
+    use crate::{
+        test_utils::{InspectorTestUtils, InspectorTxRunConfig},
+        Inspectors,
+    };
+
+    const FRONTRUN: B256 =
+        B256::new(hex!("db9c9f7ecfd33d4856bcd36d7af1228d29be90bfc7301fe7eadb0ddb23c68e3a"));
+    const VICTIM: B256 =
+        B256::new(hex!("e4b3824c6cc238a1cf402f626c339f66a8cde9834b0dd84864ce82d7472cb763"));
+    const BACKRUN: B256 =
+        B256::new(hex!("152487feea8f726e8e09f2304bc32b0b2937a0386362231542f4e7189d4ac3b8"));
+
+    fn loan_sandwich_config(blocks: Vec<Vec<B256>>) -> InspectorTxRunConfig {
+        InspectorTxRunConfig::new(Inspectors::MultiBlockSandwich)
+            .with_split_block_tx_hashes(blocks)
+            .with_dex_prices()
+            .needs_tokens(vec![
+                hex!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48").into(),
+                hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2").into(),
+            ])
+    }
+
+    /// the loan sandwich with the frontrun moved to the end of the previous
+    /// block
+    #[brontes_macros::test]
+    async fn test_multi_block_sandwich_frontrun_in_prev_block() {
+        let inspector_util = InspectorTestUtils::new(USDT_ADDRESS, 1.0).await;
+
+        let config = loan_sandwich_config(vec![vec![FRONTRUN], vec![VICTIM, BACKRUN]])
+            .with_gas_paid_usd(2734.3)
+            .with_expected_profit_usd(195.27);
+
+        inspector_util
+            .run_inspector(
+                config,
+                Some(Box::new(|bundle| {
+                    let BundleData::Sandwich(sandwich) = &bundle.data else {
+                        panic!("expected a sandwich, got {:?}", bundle.data)
+                    };
+                    assert_eq!(sandwich.frontrun_tx_hash, vec![FRONTRUN]);
+                    assert_eq!(sandwich.victim_swaps_tx_hashes, vec![vec![VICTIM]]);
+                    assert_eq!(sandwich.backrun_tx_hash, BACKRUN);
+                })),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[brontes_macros::test]
+    async fn test_multi_block_sandwich_victim_in_prev_block() {
+        let inspector_util = InspectorTestUtils::new(USDT_ADDRESS, 1.0).await;
+
+        let config = loan_sandwich_config(vec![vec![FRONTRUN, VICTIM], vec![BACKRUN]])
+            .with_gas_paid_usd(2734.3)
+            .with_expected_profit_usd(195.27);
+
+        inspector_util.run_inspector(config, None).await.unwrap();
+    }
+
+    #[brontes_macros::test]
+    async fn test_multi_block_sandwich_no_victims() {
+        let inspector_util = InspectorTestUtils::new(USDT_ADDRESS, 1.0).await;
+
+        let config = loan_sandwich_config(vec![vec![FRONTRUN], vec![BACKRUN]]);
+
+        inspector_util.assert_no_mev(config).await.unwrap();
+    }
+
+    #[brontes_macros::test]
+    async fn test_multi_block_sandwich_ignores_single_block_sandwich() {
+        let inspector_util = InspectorTestUtils::new(USDT_ADDRESS, 1.0).await;
+
+        // the whole sandwich lands in the most recent block
+        let config = loan_sandwich_config(vec![vec![], vec![FRONTRUN, VICTIM, BACKRUN]]);
+
+        inspector_util.assert_no_mev(config).await.unwrap();
+    }
+}
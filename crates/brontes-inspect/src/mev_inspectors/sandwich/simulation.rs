@@ -0,0 +1,633 @@
+//! Measures what a sandwich victim lost by re-executing the victim
+//! transaction twice on top of the parent block: once with the state the
+//! victim actually saw and once with the pools the attacker touched reset to
+//! their pre-frontrun state.
+//!
+//! The state of the block up to the victim is rebuilt from the per transaction
+//! state diffs recorded in [`TxTrace`] and passed to `eth_call` as state
+//! overrides. To read the balances of the recipient of the victim's swap
+//! around the swap within the same call, the victim's account is given the
+//! [`PROBE_CODE`] which forwards the original calldata to the original target
+//! and returns the token and eth balances of the recipient before and after.
+//!
+//! Victims that can't be re-executed are estimated instead, by quoting their
+//! last swap through the pre-frontrun state of the pool at the execution
+//! price, which accounts for every tick the swap crosses.
+use std::{future::Future, sync::Arc};
+
+use alloy_primitives::{hex, Address, Bytes, B256, U256};
+use brontes_pricing::{
+    to_raw_amount,
+    types::{PoolState, PoolVariants, ProtocolState},
+    uniswap_v3::UniswapV3Pool,
+    UpdatableProtocol,
+};
+use brontes_types::{
+    constants::WETH_ADDRESS,
+    db::traits::LibmdbxReader,
+    normalized_actions::NormalizedSwap,
+    structured_trace::{TraceActions, TxTrace},
+    traits::TracingProvider,
+    FastHashSet, Protocol, TxInfo,
+};
+use itertools::Itertools;
+use reth_primitives::BlockId;
+use reth_rpc_types::{request::TransactionInput, state::StateOverride, TransactionRequest};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// Runtime code set on the victim's account during the simulation.
+///
+/// Expects `target, value, token, account` as the first four words of the
+/// calldata followed by the calldata of the victim transaction. Calls `target`
+/// with `value` and the victim calldata, bubbling up any revert, and returns
+/// `token.balanceOf(account)` before and after the call followed by the eth
+/// balance of `account` before and after the call. Calls from any other
+/// account than itself stop right away so eth sent back to the victim
+/// during the swap is accepted.
+const PROBE_CODE: [u8; 129] = hex!(
+    "333014600757005b606035316040526370a0823160e01b60805260603560845260206000602460806040355afa"
+    "506000600060803603806080610100376101006020356000355af16055573d600060003e3d6000fd5b6370a08231"
+    "60e01b60805260603560845260206020602460806040355afa506060353160605260806000f3"
+);
+
+/// A victim transaction to re-execute along with the token and recipient of
+/// its last swap
+#[derive(Debug, Clone, Copy)]
+pub struct SimulatedVictim {
+    pub tx_hash:   B256,
+    pub token_out: Address,
+    pub recipient: Address,
+}
+
+/// The amount of `token` the recipient of the victim's swap received with and
+/// without the frontruns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VictimSimulation {
+    pub tx_hash:                   B256,
+    pub tx_index:                  u64,
+    pub victim_eoa:                Address,
+    pub token:                     Address,
+    pub amount_out:                U256,
+    pub counterfactual_amount_out: U256,
+}
+
+/// Re-executes sandwich victims through the [`TracingProvider`].
+#[derive(Clone)]
+pub struct VictimSimulator {
+    tracer: Arc<dyn TracingProvider>,
+    handle: Handle,
+}
+
+impl VictimSimulator {
+    pub fn new(tracer: Arc<dyn TracingProvider>, handle: Handle) -> Self {
+        Self { tracer, handle }
+    }
+
+    /// Simulates every victim of a sandwich in `block`. Victims that can't be
+    /// re-executed or didn't receive any of their out token are skipped.
+    pub fn simulate_victims<DB: LibmdbxReader>(
+        &self,
+        db: &DB,
+        block: u64,
+        frontruns: &[B256],
+        victims: &[SimulatedVictim],
+    ) -> eyre::Result<Vec<VictimSimulation>> {
+        let traces = self.load_traces(db, block)?;
+        if traces.iter().all(|trace| trace.state_diff.is_empty()) {
+            return Err(eyre::eyre!(
+                "traces of block {block} have no state diffs, the block needs to be retraced"
+            ))
+        }
+
+        let simulations = victims
+            .iter()
+            .filter_map(|victim| {
+                let Some(trace) = traces.iter().find(|t| t.tx_hash == victim.tx_hash) else {
+                    tracing::debug!(tx=?victim.tx_hash, "no trace for sandwich victim");
+                    return None
+                };
+                Some(self.simulate_victim(&traces, trace, frontruns, *victim, block))
+            })
+            .collect_vec();
+
+        Ok(self
+            .block_on(futures::future::join_all(simulations))
+            .into_iter()
+            .filter_map(|res| {
+                res.map_err(|e| tracing::debug!(err=%e, "failed to simulate sandwich victim"))
+                    .ok()
+                    .flatten()
+            })
+            .collect())
+    }
+
+    /// Estimates the victims that couldn't be re-executed from their last swap.
+    /// Only swaps on uniswap v3 pools are estimated, as the other pools quote
+    /// their spot price for any amount and the v3 forks keep their liquidity in
+    /// other slots than the state diffs are read from.
+    pub fn estimate_victims<DB: LibmdbxReader>(
+        &self,
+        db: &DB,
+        block: u64,
+        frontruns: &[B256],
+        victims: &[(&TxInfo, &NormalizedSwap)],
+    ) -> Vec<VictimSimulation> {
+        let traces = match self.load_traces(db, block) {
+            Ok(traces) if traces.iter().any(|trace| !trace.state_diff.is_empty()) => traces,
+            Ok(_) => {
+                tracing::debug!(block, "traces have no state diffs to estimate victims from");
+                return vec![]
+            }
+            Err(e) => {
+                tracing::debug!(err=%e, block, "failed to load traces to estimate victims");
+                return vec![]
+            }
+        };
+        let first_frontrun = traces
+            .iter()
+            .filter(|trace| frontruns.contains(&trace.tx_hash))
+            .map(|trace| trace.tx_index)
+            .min();
+
+        let estimates = victims
+            .iter()
+            .filter(|(_, swap)| swap.protocol == Protocol::UniswapV3)
+            .map(|(info, swap)| {
+                self.estimate_victim(
+                    &traces,
+                    first_frontrun.unwrap_or(info.tx_index),
+                    info,
+                    swap,
+                    block,
+                )
+            })
+            .collect_vec();
+
+        self.block_on(futures::future::join_all(estimates))
+            .into_iter()
+            .filter_map(|res| {
+                res.map_err(|e| tracing::debug!(err=%e, "failed to estimate sandwich victim"))
+                    .ok()
+                    .flatten()
+            })
+            .collect()
+    }
+
+    fn load_traces<DB: LibmdbxReader>(&self, db: &DB, block: u64) -> eyre::Result<Vec<TxTrace>> {
+        if let Ok(traces) = db.load_trace(block) {
+            return Ok(traces)
+        }
+
+        self.block_on(self.tracer.replay_block_transactions(block.into()))?
+            .ok_or_else(|| eyre::eyre!("no traces for block {block}"))
+    }
+
+    async fn simulate_victim(
+        &self,
+        traces: &[TxTrace],
+        victim: &TxTrace,
+        frontruns: &[B256],
+        victim_swap: SimulatedVictim,
+        block: u64,
+    ) -> eyre::Result<Option<VictimSimulation>> {
+        let root = victim
+            .trace
+            .first()
+            .ok_or_else(|| eyre::eyre!("empty trace for {:?}", victim.tx_hash))?;
+        let victim_eoa = root.get_from_addr();
+        let target = root.get_to_address();
+        let value = root.get_msg_value();
+        let token_out = victim_swap.token_out;
+
+        // routers that unwrap weth or take a fee receive the output of the swap
+        // and forward it to the victim
+        let account =
+            if victim_swap.recipient == target { victim_eoa } else { victim_swap.recipient };
+        // only the victim's own balance pays for the eth sent with the swap
+        let sent = if account == victim_eoa { value } else { U256::ZERO };
+
+        let (mut actual, mut counterfactual) = build_overrides(traces, victim.tx_index, frontruns);
+        for overrides in [&mut actual, &mut counterfactual] {
+            overrides.entry(victim_eoa).or_default().code = Some(Bytes::from_static(&PROBE_CODE));
+        }
+
+        let input = encode_probe_call(target, value, token_out, account, &root.get_calldata());
+        let request = TransactionRequest {
+            from: Some(victim_eoa),
+            to: Some(victim_eoa),
+            value: Some(value),
+            input: TransactionInput::new(input),
+            ..Default::default()
+        };
+        let parent = Some(BlockId::from(block - 1));
+
+        let (actual, counterfactual) = tokio::try_join!(
+            self.tracer
+                .eth_call(request.clone(), parent, Some(actual), None),
+            self.tracer
+                .eth_call(request, parent, Some(counterfactual), None),
+        )?;
+
+        let amount_out = decode_probe_output(&actual, token_out, sent)?;
+        if amount_out == U256::ZERO {
+            return Ok(None)
+        }
+
+        Ok(Some(VictimSimulation {
+            tx_hash: victim.tx_hash,
+            tx_index: victim.tx_index,
+            victim_eoa,
+            token: token_out,
+            amount_out,
+            counterfactual_amount_out: decode_probe_output(&counterfactual, token_out, sent)?,
+        }))
+    }
+
+    /// Loads the pool on the parent block and brings it to the state before the
+    /// first frontrun with the state diffs of the transactions before it
+    async fn estimate_victim(
+        &self,
+        traces: &[TxTrace],
+        first_frontrun: u64,
+        victim: &TxInfo,
+        swap: &NormalizedSwap,
+        block: u64,
+    ) -> eyre::Result<Option<VictimSimulation>> {
+        let mut pool = UniswapV3Pool::new_from_address(
+            swap.pool,
+            swap.protocol,
+            block - 1,
+            Arc::new(self.tracer.clone()),
+        )
+        .await?;
+        for diff in traces
+            .iter()
+            .filter(|trace| trace.tx_index < first_frontrun)
+            .sorted_by_key(|trace| trace.tx_index)
+            .filter_map(|trace| trace.state_diff_for(swap.pool))
+        {
+            pool.sync_from_state_diff(diff)?;
+        }
+        let pool = PoolState::new(PoolVariants::UniswapV3(Box::new(pool)), block);
+
+        Ok(quote_counterfactual(&pool, swap).map(|(amount_out, counterfactual_amount_out)| {
+            VictimSimulation {
+                tx_hash: victim.tx_hash,
+                tx_index: victim.tx_index,
+                victim_eoa: victim.eoa,
+                token: swap.token_out.address,
+                amount_out,
+                counterfactual_amount_out,
+            }
+        }))
+    }
+
+    /// inspectors run on the rayon pool, but can be called from within the
+    /// runtime in tests
+    fn block_on<F: Future>(&self, fut: F) -> F::Output {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(fut))
+            }
+            _ => self.handle.block_on(fut),
+        }
+    }
+}
+
+/// Builds the state the victim executed on from the state diffs of the
+/// transactions before it in the block, along with the same state where the
+/// frontruns never happened.
+///
+/// In the counterfactual state every account balance and storage slot changed
+/// by a frontrun keeps the value it had before the first frontrun, while the
+/// state the frontruns didn't touch is the same as in the actual state.
+fn build_overrides(
+    traces: &[TxTrace],
+    victim_index: u64,
+    frontruns: &[B256],
+) -> (StateOverride, StateOverride) {
+    let preceding = traces
+        .iter()
+        .filter(|trace| trace.tx_index < victim_index)
+        .sorted_by_key(|trace| trace.tx_index)
+        .collect_vec();
+
+    let first_frontrun = preceding
+        .iter()
+        .filter(|trace| frontruns.contains(&trace.tx_hash))
+        .map(|trace| trace.tx_index)
+        .min()
+        .unwrap_or(victim_index);
+
+    let mut attacked_balances = FastHashSet::default();
+    let mut attacked_slots = FastHashSet::default();
+    for diff in preceding
+        .iter()
+        .filter(|trace| frontruns.contains(&trace.tx_hash))
+        .flat_map(|trace| &trace.state_diff)
+    {
+        if diff.balance.is_some() {
+            attacked_balances.insert(diff.address);
+        }
+        attacked_slots.extend(diff.storage.iter().map(|slot| (diff.address, slot.slot)));
+    }
+
+    let mut actual = StateOverride::default();
+    let mut counterfactual = StateOverride::default();
+
+    for trace in preceding {
+        let is_frontrun = frontruns.contains(&trace.tx_hash);
+        let after_frontrun = trace.tx_index > first_frontrun;
+
+        for diff in &trace.state_diff {
+            if let Some(balance) = diff.balance {
+                actual.entry(diff.address).or_default().balance = Some(balance.post);
+                if !is_frontrun && !(after_frontrun && attacked_balances.contains(&diff.address)) {
+                    counterfactual.entry(diff.address).or_default().balance = Some(balance.post);
+                }
+            }
+
+            for slot in &diff.storage {
+                let (key, value) = (B256::from(slot.slot), B256::from(slot.post));
+                actual
+                    .entry(diff.address)
+                    .or_default()
+                    .state_diff
+                    .get_or_insert_with(Default::default)
+                    .insert(key, value);
+
+                if is_frontrun
+                    || (after_frontrun && attacked_slots.contains(&(diff.address, slot.slot)))
+                {
+                    continue
+                }
+                counterfactual
+                    .entry(diff.address)
+                    .or_default()
+                    .state_diff
+                    .get_or_insert_with(Default::default)
+                    .insert(key, value);
+            }
+        }
+    }
+
+    (actual, counterfactual)
+}
+
+/// The raw amount out of the victim's swap along with the amount it would
+/// have gotten selling the same amount in into `pool` at the execution price
+fn quote_counterfactual<P: ProtocolState>(pool: &P, swap: &NormalizedSwap) -> Option<(U256, U256)> {
+    let price = pool
+        .execution_price(swap.token_in.address, swap.token_out.address, &swap.amount_in)
+        .ok()?;
+    let decimals = swap.token_out.decimals;
+
+    Some((
+        to_raw_amount(swap.amount_out.clone(), decimals)?,
+        to_raw_amount(&swap.amount_in * price, decimals)?,
+    ))
+}
+
+fn encode_probe_call(
+    target: Address,
+    value: U256,
+    token: Address,
+    account: Address,
+    calldata: &[u8],
+) -> Bytes {
+    let mut input = Vec::with_capacity(128 + calldata.len());
+    input.extend_from_slice(target.into_word().as_slice());
+    input.extend_from_slice(&value.to_be_bytes::<32>());
+    input.extend_from_slice(token.into_word().as_slice());
+    input.extend_from_slice(account.into_word().as_slice());
+    input.extend_from_slice(calldata);
+    input.into()
+}
+
+/// The amount of `token` the recipient received. When swapping into weth, eth
+/// received from routers that unwrap is counted as well, adding back the eth
+/// the recipient sent along with the swap.
+fn decode_probe_output(output: &[u8], token: Address, sent: U256) -> eyre::Result<U256> {
+    if output.len() != 128 {
+        return Err(eyre::eyre!("unexpected probe output length {}", output.len()))
+    }
+    let word = |i: usize| U256::from_be_slice(&output[i * 32..(i + 1) * 32]);
+    let (token_before, token_after, eth_before, eth_after) = (word(0), word(1), word(2), word(3));
+
+    let mut amount_out = token_after.saturating_sub(token_before);
+    if token == WETH_ADDRESS {
+        amount_out += (eth_after + sent).saturating_sub(eth_before);
+    }
+
+    Ok(amount_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_pricing::uniswap_v3::Info;
+    use brontes_types::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        structured_trace::{AccountStateDiff, BalanceDiff, StorageSlotDiff},
+        ToScaledRational,
+    };
+    use malachite::Rational;
+
+    use super::*;
+
+    const POOL: Address = Address::new(hex!("B4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc"));
+    const OTHER_POOL: Address = Address::new(hex!("0d4a11d5EEaaC28EC3F61d100daF4d40471f1852"));
+    const ATTACKER: Address = Address::new(hex!("6b75d8AF000000e20B7a7DDf000Ba900b4009A80"));
+
+    fn trace(tx_index: u64, state_diff: Vec<AccountStateDiff>) -> TxTrace {
+        TxTrace {
+            tx_hash: B256::with_last_byte(tx_index as u8 + 1),
+            tx_index,
+            state_diff,
+            ..Default::default()
+        }
+    }
+
+    fn reserves(pool: Address, pre: u64, post: u64) -> AccountStateDiff {
+        AccountStateDiff {
+            address: pool,
+            balance: None,
+            storage: vec![StorageSlotDiff {
+                slot: U256::from(8),
+                pre:  U256::from(pre),
+                post: U256::from(post),
+            }],
+        }
+    }
+
+    fn slot(overrides: &StateOverride, pool: Address) -> Option<B256> {
+        overrides
+            .get(&pool)?
+            .state_diff
+            .as_ref()?
+            .get(&B256::from(U256::from(8)))
+            .copied()
+    }
+
+    #[test]
+    fn test_counterfactual_resets_attacked_state() {
+        let traces = vec![
+            trace(0, vec![reserves(POOL, 100, 110), reserves(OTHER_POOL, 5, 6)]),
+            // frontrun
+            trace(
+                1,
+                vec![
+                    reserves(POOL, 110, 150),
+                    AccountStateDiff {
+                        address: ATTACKER,
+                        balance: Some(BalanceDiff { pre: U256::from(10), post: U256::from(2) }),
+                        storage: vec![],
+                    },
+                ],
+            ),
+            // unrelated tx between the frontrun and the victim
+            trace(2, vec![reserves(POOL, 150, 155), reserves(OTHER_POOL, 6, 7)]),
+            // victim
+            trace(3, vec![reserves(POOL, 155, 190)]),
+        ];
+        let frontrun = traces[1].tx_hash;
+
+        let (actual, counterfactual) = build_overrides(&traces, 3, &[frontrun]);
+
+        assert_eq!(slot(&actual, POOL), Some(B256::from(U256::from(155))));
+        assert_eq!(slot(&actual, OTHER_POOL), Some(B256::from(U256::from(7))));
+        assert_eq!(actual[&ATTACKER].balance, Some(U256::from(2)));
+
+        assert_eq!(slot(&counterfactual, POOL), Some(B256::from(U256::from(110))));
+        assert_eq!(slot(&counterfactual, OTHER_POOL), Some(B256::from(U256::from(7))));
+        assert!(!counterfactual.contains_key(&ATTACKER));
+    }
+
+    #[test]
+    fn test_decode_probe_output_counts_unwrapped_eth() {
+        let value = U256::from(3);
+        let mut output = Vec::new();
+        for word in [10u64, 15, 100, 120] {
+            output.extend_from_slice(&U256::from(word).to_be_bytes::<32>());
+        }
+
+        let token = Address::new(hex!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"));
+        assert_eq!(decode_probe_output(&output, token, value).unwrap(), U256::from(5));
+        // 120 + 3 sent - 100
+        assert_eq!(decode_probe_output(&output, WETH_ADDRESS, value).unwrap(), U256::from(28));
+    }
+
+    /// pool at a price of 1 with a position from tick -600 to 600 and a wider
+    /// one from -6000 to 6000
+    fn v3_pool() -> UniswapV3Pool {
+        const LIQUIDITY: i128 = 2_000_000_000_000_000_000;
+
+        let mut pool = UniswapV3Pool {
+            token_a: Address::with_last_byte(1),
+            token_a_decimals: 18,
+            token_b: Address::with_last_byte(2),
+            token_b_decimals: 18,
+            liquidity: 2 * LIQUIDITY as u128,
+            sqrt_price: U256::from(1) << 96,
+            fee: 3000,
+            tick: 0,
+            tick_spacing: 60,
+            loaded_ticks: Some((-12000, 12000)),
+            ..Default::default()
+        };
+        for (tick, liquidity_net) in
+            [(-6000, LIQUIDITY), (-600, LIQUIDITY), (600, -LIQUIDITY), (6000, -LIQUIDITY)]
+        {
+            pool.ticks
+                .insert(tick, Info::new(LIQUIDITY as u128, liquidity_net, true));
+            pool.set_tick_initialized(tick);
+        }
+
+        pool
+    }
+
+    #[test]
+    fn test_quote_counterfactual_crosses_ticks() {
+        let pool = v3_pool();
+        let token = |address: Address| TokenInfoWithAddress {
+            address,
+            inner: TokenInfo { decimals: 18, symbol: String::new() },
+        };
+        // moves the price past tick 600 where half of the liquidity drops out
+        let amount_in = U256::from(500_000_000_000_000_000u64);
+        let swap = NormalizedSwap {
+            protocol: Protocol::UniswapV3,
+            token_in: token(pool.token_b),
+            token_out: token(pool.token_a),
+            amount_in: amount_in.to_scaled_rational(18),
+            amount_out: Rational::from_unsigneds(4u64, 10u64),
+            ..Default::default()
+        };
+
+        let through_ticks = pool.simulate_swap(pool.token_b, amount_in).unwrap();
+        let state = PoolState::new(PoolVariants::UniswapV3(Box::new(pool)), 0);
+        let (amount_out, counterfactual) = quote_counterfactual(&state, &swap).unwrap();
+
+        assert_eq!(amount_out, U256::from(400_000_000_000_000_000u64));
+        assert_eq!(counterfactual, through_ticks);
+        // at the spot price of 1 the victim would be owed the full amount in
+        assert!(counterfactual < amount_in);
+        assert!(counterfactual > amount_out);
+    }
+
+    #[brontes_macros::test]
+    async fn test_simulate_sandwich_victim() {
+        use brontes_types::{normalized_actions::Action, TreeSearchBuilder};
+
+        use crate::test_utils::{InspectorTestUtils, USDC_ADDRESS};
+
+        let utils = InspectorTestUtils::new(USDC_ADDRESS, 1.0).await;
+        let tracer = utils.classifier_inspector.get_tracing_provider();
+
+        let frontrun =
+            hex!("ff79c471b191c0021cfb62408cb1d7418d09334665a02106191f6ed16a47e36c").into();
+        let victim =
+            hex!("19122ffe65a714f0551edbb16a24551031056df16ccaab39db87a73ac657b722").into();
+
+        let tree = Arc::new(
+            utils
+                .classifier_inspector
+                .build_tree_tx(victim)
+                .await
+                .unwrap(),
+        );
+        let swap = tree
+            .clone()
+            .collect(&victim, TreeSearchBuilder::default().with_action(Action::is_swap))
+            .filter_map(|action| action.try_swaps_merged_ref().cloned())
+            .last()
+            .unwrap();
+
+        let block = tree.header.number;
+        let traces = tracer
+            .replay_block_transactions(block.into())
+            .await
+            .unwrap()
+            .unwrap();
+        let trace = traces.iter().find(|t| t.tx_hash == victim).unwrap();
+
+        let simulator = VictimSimulator::new(Arc::new(tracer.clone()), Handle::current());
+        let simulation = simulator
+            .simulate_victim(
+                &traces,
+                trace,
+                &[frontrun],
+                SimulatedVictim {
+                    tx_hash:   victim,
+                    token_out: swap.token_out.address,
+                    recipient: swap.recipient,
+                },
+                block,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(simulation.amount_out > U256::ZERO);
+        assert!(simulation.counterfactual_amount_out > simulation.amount_out);
+    }
+}
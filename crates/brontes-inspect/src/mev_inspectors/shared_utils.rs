@@ -6,6 +6,7 @@ use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
     db::{
         dex::{BlockPrice, PriceAt},
+        fund::Fund,
         metadata::Metadata,
         token_info::TokenInfoWithAddress,
     },
@@ -321,6 +322,18 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         metadata.dex_quotes.as_ref()?.price_for_block(pair, block)
     }
 
+    /// The fund of the searcher contract, or of the eoa if the contract isn't
+    /// attributed, resolved to its canonical name through the fund registry
+    fn searcher_fund(&self, info: &TxInfo) -> Fund {
+        let fund = info
+            .get_searcher_contract_info()
+            .map(|i| i.fund.clone())
+            .or_else(|| info.get_searcher_eao_info().map(|f| f.fund.clone()))
+            .unwrap_or_default();
+
+        self.db.try_resolve_fund(&fund).unwrap_or(fund)
+    }
+
     pub fn build_bundle_header_searcher_activity(
         &self,
         bundle_deltas: Vec<AddressDeltas>,
@@ -351,11 +364,7 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
             })
             .sum::<f64>();
 
-        let fund = info
-            .get_searcher_contract_info()
-            .map(|i| i.fund)
-            .or_else(|| info.get_searcher_eao_info().map(|f| f.fund))
-            .unwrap_or_default();
+        let fund = self.searcher_fund(info);
 
         BundleHeader {
             block_number: metadata.block_num,
@@ -405,11 +414,7 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
                 .inspect(|m| m.inspector_100x_profit(mev_type));
         }
 
-        let fund = info
-            .get_searcher_contract_info()
-            .map(|i| i.fund)
-            .or_else(|| info.get_searcher_eao_info().map(|f| f.fund))
-            .unwrap_or_default();
+        let fund = self.searcher_fund(info);
 
         BundleHeader {
             block_number: metadata.block_num,
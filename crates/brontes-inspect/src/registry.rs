@@ -0,0 +1,254 @@
+//! Registry for inspectors that live outside of this crate.
+//!
+//! Downstream crates register a factory under a unique name before the cli
+//! arguments are parsed, which `brontes::run_with_registry` gives them a hook
+//! for. The name can then be passed to `--inspectors` like any of the built-in
+//! [`Inspectors`].
+//!
+//! ```ignore
+//! brontes::run_with_registry(|| {
+//!     register_inspector("my-strategy", |ctx| {
+//!         static_object(MyStrategyInspector::new(ctx.quote_token, ctx.db, ctx.metrics))
+//!             as DynMevInspector
+//!     })
+//! })
+//! ```
+//!
+//! Inspectors that don't map to one of the built-in [`MevType`]s should return
+//! bundles of [`MevType::Custom`] with a
+//! [`CustomMev`](brontes_types::mev::CustomMev) payload.
+//!
+//! [`MevType`]: brontes_types::mev::MevType
+//! [`MevType::Custom`]: brontes_types::mev::MevType::Custom
+
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use alloy_primitives::Address;
+use brontes_metrics::inspectors::OutlierMetrics;
+use brontes_types::{
+    db::{
+        cex::{trades::CexDexTradeConfig, CexExchange},
+        traits::LibmdbxReader,
+    },
+    FastHashMap,
+};
+use lazy_static::lazy_static;
+use strum::IntoEnumIterator;
+
+use crate::{sandwich::VictimSimulator, static_object, DynMevInspector, Inspectors};
+
+/// Type erased database handed to external inspectors. [`LibmdbxReader`] is
+/// implemented for references to any reader, trait objects included, so it
+/// can be used as the `DB` of the generic inspector utils of this crate, e.g.
+/// `SharedInspectorUtils::new(ctx.quote_token, ctx.db, ctx.metrics)`.
+pub type DynLibmdbxReader = &'static dyn LibmdbxReader;
+
+/// Everything an external inspector gets to construct itself with.
+pub struct InspectorContext<'a> {
+    pub quote_token:   Address,
+    pub db:            &'static DynLibmdbxReader,
+    pub cex_exchanges: &'a [CexExchange],
+    pub trade_config:  CexDexTradeConfig,
+    pub metrics:       Option<OutlierMetrics>,
+}
+
+pub type InspectorFactory =
+    Arc<dyn Fn(InspectorContext<'_>) -> DynMevInspector + Send + Sync + 'static>;
+
+lazy_static! {
+    static ref EXTERNAL_INSPECTORS: RwLock<FastHashMap<String, InspectorFactory>> =
+        RwLock::new(FastHashMap::default());
+}
+
+/// Registers an external inspector factory under the given name. Fails if the
+/// name is taken by a built-in or previously registered inspector.
+pub fn register_inspector<F>(name: impl Into<String>, factory: F) -> eyre::Result<()>
+where
+    F: Fn(InspectorContext<'_>) -> DynMevInspector + Send + Sync + 'static,
+{
+    let name = name.into();
+    if Inspectors::from_str(&name).is_ok() {
+        eyre::bail!("{name} is the name of a built-in inspector")
+    }
+
+    let mut registry = EXTERNAL_INSPECTORS.write().unwrap();
+    if registry.contains_key(&name) {
+        eyre::bail!("an inspector is already registered under {name}")
+    }
+    registry.insert(name, Arc::new(factory));
+
+    Ok(())
+}
+
+/// Names of all registered external inspectors
+pub fn registered_inspectors() -> Vec<String> {
+    let mut names = EXTERNAL_INSPECTORS
+        .read()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    names.sort();
+
+    names
+}
+
+fn get_factory(name: &str) -> Option<InspectorFactory> {
+    EXTERNAL_INSPECTORS.read().unwrap().get(name).cloned()
+}
+
+/// An inspector selected to run, either one of the built-in [`Inspectors`] or
+/// one registered through [`register_inspector`].
+#[derive(Debug, PartialEq, Clone, Eq, Hash)]
+pub enum InspectorName {
+    Builtin(Inspectors),
+    External(String),
+}
+
+impl InspectorName {
+    /// All built-in inspectors that aren't opt-in, followed by the registered
+    /// external ones
+    pub fn all() -> Vec<Self> {
+        Inspectors::iter()
+            .filter(|inspector| !inspector.is_opt_in())
+            .map(Self::Builtin)
+            .chain(registered_inspectors().into_iter().map(Self::External))
+            .collect()
+    }
+
+    pub fn init_mev_inspector<DB: LibmdbxReader>(
+        &self,
+        quote_token: Address,
+        db: &'static DB,
+        cex_exchanges: &[CexExchange],
+        trade_config: CexDexTradeConfig,
+        metrics: Option<OutlierMetrics>,
+        victim_simulator: Option<VictimSimulator>,
+    ) -> eyre::Result<DynMevInspector> {
+        match self {
+            Self::Builtin(inspector) => Ok(inspector.init_mev_inspector(
+                quote_token,
+                db,
+                cex_exchanges,
+                trade_config,
+                metrics,
+                victim_simulator,
+            )),
+            Self::External(name) => {
+                let factory = get_factory(name)
+                    .ok_or_else(|| eyre::eyre!("no inspector registered under {name}"))?;
+
+                Ok(factory(InspectorContext {
+                    quote_token,
+                    db: static_object(db as DynLibmdbxReader),
+                    cex_exchanges,
+                    trade_config,
+                    metrics,
+                }))
+            }
+        }
+    }
+}
+
+impl From<Inspectors> for InspectorName {
+    fn from(value: Inspectors) -> Self {
+        Self::Builtin(value)
+    }
+}
+
+impl FromStr for InspectorName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(inspector) = Inspectors::from_str(s) {
+            return Ok(Self::Builtin(inspector))
+        }
+
+        if get_factory(s).is_some() {
+            return Ok(Self::External(s.to_string()))
+        }
+
+        Err(format!(
+            "unknown inspector {s}, available inspectors are: {}",
+            Self::all()
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+impl fmt::Display for InspectorName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Builtin(inspector) => write!(f, "{inspector}"),
+            Self::External(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{mev::Bundle, MultiBlockData};
+
+    use super::*;
+    use crate::Inspector;
+
+    struct NoopInspector(Address);
+
+    impl Inspector for NoopInspector {
+        type Result = Vec<Bundle>;
+
+        fn get_id(&self) -> &str {
+            "Noop"
+        }
+
+        fn inspect_block(&self, _: MultiBlockData) -> Self::Result {
+            vec![]
+        }
+
+        fn get_quote_token(&self) -> Address {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_external_inspector_registry() {
+        register_inspector("noop", |ctx| {
+            static_object(NoopInspector(ctx.quote_token)) as DynMevInspector
+        })
+        .unwrap();
+
+        assert!(register_inspector("noop", |ctx| {
+            static_object(NoopInspector(ctx.quote_token)) as DynMevInspector
+        })
+        .is_err());
+        assert!(register_inspector("AtomicArb", |ctx| {
+            static_object(NoopInspector(ctx.quote_token)) as DynMevInspector
+        })
+        .is_err());
+
+        assert_eq!(
+            InspectorName::from_str("AtomicArb"),
+            Ok(InspectorName::Builtin(Inspectors::AtomicArb))
+        );
+        assert_eq!(InspectorName::from_str("noop"), Ok(InspectorName::External("noop".into())));
+        assert!(InspectorName::from_str("unknown").is_err());
+        assert!(InspectorName::all().contains(&InspectorName::External("noop".into())));
+
+        // opt-in inspectors can be selected but don't run by default
+        for inspector in [Inspectors::MultiBlockSandwich, Inspectors::MultiBlockJit] {
+            assert!(inspector.is_opt_in());
+            assert_eq!(
+                InspectorName::from_str(&inspector.to_string()),
+                Ok(InspectorName::Builtin(inspector))
+            );
+            assert!(!InspectorName::all().contains(&InspectorName::Builtin(inspector)));
+        }
+    }
+}
@@ -49,6 +49,7 @@ impl InspectorBenchUtils {
                     &[CexExchange::Binance],
                     CexDexTradeConfig::default(),
                     None,
+                    None,
                 )
             })
             .collect::<Vec<_>>();
@@ -100,6 +101,7 @@ impl InspectorBenchUtils {
             &[CexExchange::Binance],
             CexDexTradeConfig::default(),
             None,
+            None,
         );
 
         let mut trees =
@@ -163,6 +165,7 @@ impl InspectorBenchUtils {
             &[CexExchange::Binance],
             CexDexTradeConfig::default(),
             None,
+            None,
         );
 
         let (tree, prices) =
@@ -217,6 +220,7 @@ impl InspectorBenchUtils {
             &[CexExchange::Binance],
             CexDexTradeConfig::default(),
             None,
+            None,
         );
 
         let mut trees = self
@@ -264,6 +268,7 @@ impl InspectorBenchUtils {
                     &[CexExchange::Binance],
                     CexDexTradeConfig::default(),
                     None,
+                    None,
                 )
             })
             .collect::<Vec<_>>();
@@ -323,6 +328,7 @@ impl InspectorBenchUtils {
                     &[CexExchange::Binance],
                     CexDexTradeConfig::default(),
                     None,
+                    None,
                 )
             })
             .collect::<Vec<_>>();
@@ -169,9 +169,10 @@ impl InspectorTestUtils {
             ],
             CexDexTradeConfig::default(),
             None,
+            None,
         );
-        let data = BlockData { metadata: metadata.into(), tree: tree.into() };
-        let multi = MultiBlockData { per_block_data: vec![data], blocks: 1 };
+        let per_block_data = split_into_blocks(tree, metadata, config.split_block_txes.as_deref());
+        let multi = MultiBlockData { blocks: per_block_data.len(), per_block_data };
         let results = inspector.inspect_block(multi);
 
         assert_eq!(results.len(), 0, "found mev when we shouldn't of {:#?}", results);
@@ -190,6 +191,50 @@ impl InspectorTestUtils {
         let profit_usd = config.expected_profit_usd.ok_or_else(err)?;
         let gas_used_usd = config.expected_gas_usd.ok_or_else(err)?;
 
+        let mut results = self.inspect(config).await?;
+
+        assert_eq!(
+            results.len(),
+            1,
+            "Identified an incorrect number of MEV bundles. Expected 1, found: {:#?}",
+            results
+        );
+
+        let bundle = results.remove(0);
+
+        if let Some(specific_state_tests) = specific_state_tests {
+            specific_state_tests(&bundle);
+        }
+
+        // check gas
+        assert!(
+            (bundle.header.bribe_usd - gas_used_usd).abs() < self.max_result_difference,
+            "Finalized Bribe != Expected Bribe, {} != {}",
+            bundle.header.bribe_usd,
+            gas_used_usd
+        );
+
+        // check profit
+        assert!(
+            (bundle.header.profit_usd - profit_usd).abs() < self.max_result_difference,
+            "Finalized Profit != Expected Profit, {} != {}",
+            bundle.header.profit_usd,
+            profit_usd
+        );
+
+        Ok(())
+    }
+
+    /// Runs the inspector over the configured txes or block and returns the
+    /// deduplicated bundles it found, for tests that check more than the
+    /// profit and gas of a single bundle
+    pub async fn inspect(
+        &self,
+        config: InspectorTxRunConfig,
+    ) -> Result<Vec<Bundle>, InspectorTestUtilsError> {
+        let copied = config.clone();
+        let err = || InspectorTestUtilsError::InspectorConfig(Box::new(copied.clone()));
+
         let mut quotes = None;
         let tree = if let Some(tx_hashes) = config.mev_tx_hashes {
             if config.needs_dex_prices {
@@ -259,43 +304,14 @@ impl InspectorTestUtils {
             ],
             cex_trade_config,
             None,
+            None,
         );
 
-        let data = BlockData { metadata: metadata.into(), tree: tree.into() };
-        let multi = MultiBlockData { per_block_data: vec![data], blocks: 1 };
+        let per_block_data = split_into_blocks(tree, metadata, config.split_block_txes.as_deref());
+        let multi = MultiBlockData { blocks: per_block_data.len(), per_block_data };
         let results = inspector.inspect_block(multi);
-        let mut results = SharedInspectorUtils::<LibmdbxReadWriter>::dedup_bundles(results);
-
-        assert_eq!(
-            results.len(),
-            1,
-            "Identified an incorrect number of MEV bundles. Expected 1, found: {:#?}",
-            results
-        );
 
-        let bundle = results.remove(0);
-
-        if let Some(specific_state_tests) = specific_state_tests {
-            specific_state_tests(&bundle);
-        }
-
-        // check gas
-        assert!(
-            (bundle.header.bribe_usd - gas_used_usd).abs() < self.max_result_difference,
-            "Finalized Bribe != Expected Bribe, {} != {}",
-            bundle.header.bribe_usd,
-            gas_used_usd
-        );
-
-        // check profit
-        assert!(
-            (bundle.header.profit_usd - profit_usd).abs() < self.max_result_difference,
-            "Finalized Profit != Expected Profit, {} != {}",
-            bundle.header.profit_usd,
-            profit_usd
-        );
-
-        Ok(())
+        Ok(SharedInspectorUtils::<LibmdbxReadWriter>::dedup_bundles(results))
     }
 
     pub async fn run_composer(
@@ -368,6 +384,7 @@ impl InspectorTestUtils {
                     &[CexExchange::Binance],
                     CexDexTradeConfig::default(),
                     None,
+                    None,
                 )
             })
             .collect::<Vec<_>>();
@@ -429,6 +446,36 @@ impl InspectorTestUtils {
     }
 }
 
+/// Splits the tree into one block per group of tx hashes, numbered
+/// consecutively from the tree's block. All blocks share the metadata and dex
+/// quotes of the original block, so a bundle can be replayed as if its txes
+/// landed in consecutive slots.
+fn split_into_blocks(
+    tree: BlockTree<Action>,
+    metadata: Metadata,
+    split_block_txes: Option<&[Vec<TxHash>]>,
+) -> Vec<BlockData> {
+    let Some(groups) = split_block_txes else {
+        return vec![BlockData { metadata: metadata.into(), tree: tree.into() }]
+    };
+
+    groups
+        .iter()
+        .enumerate()
+        .map(|(i, txes)| {
+            let block = tree.header.number + i as u64;
+            let mut tree = tree.clone();
+            tree.tx_roots.retain(|root| txes.contains(&root.tx_hash));
+            tree.header.number = block;
+
+            let mut metadata = metadata.clone();
+            metadata.block_metadata.block_num = block;
+
+            BlockData { metadata: metadata.into(), tree: tree.into() }
+        })
+        .collect()
+}
+
 /// This inspector test config is to configure an inspector test for a single
 /// bundle. MevTxHashes is a list of tx hashes that are expected be in the
 /// bundle.
@@ -443,6 +490,7 @@ pub struct InspectorTxRunConfig {
     pub needs_dex_prices: bool,
     pub needs_tokens: Vec<Address>,
     pub use_block_time_weights_for_cex_pricing: bool,
+    pub split_block_txes: Option<Vec<Vec<TxHash>>>,
 }
 
 impl InspectorTxRunConfig {
@@ -457,6 +505,7 @@ impl InspectorTxRunConfig {
             needs_tokens: Vec::new(),
             needs_dex_prices: false,
             use_block_time_weights_for_cex_pricing: false,
+            split_block_txes: None,
         }
     }
 
@@ -490,6 +539,14 @@ impl InspectorTxRunConfig {
         self
     }
 
+    /// Txes of a single block, replayed as consecutive blocks with one group
+    /// of txes each. Used to test the multi block inspectors
+    pub fn with_split_block_tx_hashes(mut self, blocks: Vec<Vec<TxHash>>) -> Self {
+        self.mev_tx_hashes = Some(blocks.iter().flatten().copied().collect());
+        self.split_block_txes = Some(blocks);
+        self
+    }
+
     pub fn with_expected_profit_usd(mut self, profit: f64) -> Self {
         self.expected_profit_usd = Some(profit);
         self
@@ -70,23 +70,31 @@ impl ActionMacro {
         call.value_mut().ident = Ident::new(&solidity, call.span());
         return_import.segments.push(call.into_value());
 
-        let dex_price_return = if action_type.to_string().to_lowercase().as_str()
-            == "poolconfigupdate"
-        {
-            quote!(Ok(::brontes_pricing::types::DexPriceMsg::DiscoveredPool(result)))
+        // when the action type is `Action`, the closure decides what action the call
+        // results in
+        let action = if action_type == "Action" {
+            quote!(result)
         } else {
-            quote!(
-                Ok(::brontes_pricing::types::DexPriceMsg::Update(
-                    ::brontes_pricing::types::PoolUpdate {
-                        block,
-                        tx_idx,
-                        logs: call_info.logs.clone().to_vec(),
-                        action: ::brontes_types::normalized_actions::Action::#action_type(result)
-                    },
-                ))
-            )
+            quote!(::brontes_types::normalized_actions::Action::#action_type(result))
         };
 
+        let dex_price_return =
+            if action_type.to_string().to_lowercase().as_str() == "poolconfigupdate" {
+                quote!(Ok(::brontes_pricing::types::DexPriceMsg::DiscoveredPool(result)))
+            } else {
+                quote!(
+                    Ok(::brontes_pricing::types::DexPriceMsg::Update(
+                        ::brontes_pricing::types::PoolUpdate {
+                            block,
+                            tx_idx,
+                            logs: call_info.logs.clone().to_vec(),
+                            action: #action,
+                            state_diff: None,
+                        },
+                    ))
+                )
+            };
+
         Ok(quote! {
             #[allow(unused_imports)]
             use #path_to_call;
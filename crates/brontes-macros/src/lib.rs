@@ -92,6 +92,11 @@ use crate::action_classifier::{ActionDispatch, ActionMacro};
 ///  call_data: true
 ///  ````
 ///  ```|index, from_address, target_address, return_data, log_data|```
+///
+/// ## Dynamic Action Types
+/// if the call can result in different actions depending on its inputs, use
+/// `Action` as the call type. The closure then returns the
+/// `Action` directly instead of the normalized type.
 pub fn action_impl(input: TokenStream) -> TokenStream {
     parse_macro_input!(input as ActionMacro)
         .expand()
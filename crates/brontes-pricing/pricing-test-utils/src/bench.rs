@@ -110,12 +110,15 @@ impl BrontesPricingBencher {
                 block_number,
                 pool_pair,
                 brontes_pricing::types::PairWithFirstPoolHop::from_pair_gt(pool_pair, pool_pair),
+                None,
             ))
             .unwrap()
             .2;
 
-        let pair_1 = pool_pair.0;
-        c.bench_function(bench_name, move |b| b.iter(|| black_box(state.price(pair_1).unwrap())));
+        let Pair(pair_0, pair_1) = pool_pair;
+        c.bench_function(bench_name, move |b| {
+            b.iter(|| black_box(state.price(pair_0, pair_1).unwrap()))
+        });
 
         Ok(())
     }
@@ -141,6 +144,7 @@ impl BrontesPricingBencher {
                             brontes_pricing::types::PairWithFirstPoolHop::from_pair_gt(
                                 pool_pair, pool_pair,
                             ),
+                            None,
                         )
                         .await,
                 )
@@ -183,6 +183,10 @@ impl AllPairGraph {
 
         if let Some(edge) = self.graph.find_edge(node_0.into(), node_1.into()) {
             let mut pools = self.graph.edge_weight(edge).unwrap().clone();
+            // multi token pools can be re-added when we see a new pair trade on them
+            if pools.iter().any(|p| p.pool_addr == pool_addr) {
+                return
+            }
             pools.push(pool_pair);
             self.graph.update_edge(node_0.into(), node_1.into(), pools);
         } else {
@@ -1,6 +1,7 @@
 mod all_pair_graph;
 mod dijkstras;
 mod registry;
+mod snapshot;
 mod state_tracker;
 mod subgraph;
 mod yens;
@@ -21,6 +22,7 @@ use tracing::error_span;
 
 pub use self::{
     registry::SubGraphRegistry,
+    snapshot::GraphSnapshot,
     state_tracker::{StateTracker, StateWithDependencies},
     subgraph::PairSubGraph,
     subgraph_verifier::*,
@@ -100,6 +102,56 @@ impl GraphManager {
         self.graph_state = state;
     }
 
+    /// snapshot of the finalized graph state at the end of the given block
+    pub fn snapshot(&self, block: u64) -> GraphSnapshot {
+        GraphSnapshot {
+            block,
+            sub_graphs: self.sub_graph_registry.to_snapshot(),
+            pool_state: self.graph_state.finalized_snapshot(block),
+            verification_state: self.subgraph_verifier.to_snapshot(),
+        }
+    }
+
+    /// restores the graph state from a snapshot. Subgraphs that depend on pool
+    /// state missing from the snapshot are dropped and the state dependents are
+    /// recounted from the subgraphs that are kept.
+    pub fn restore_snapshot(&mut self, snapshot: GraphSnapshot) {
+        let pool_state = snapshot
+            .pool_state
+            .into_iter()
+            .collect::<FastHashMap<_, _>>();
+
+        let sub_graphs = snapshot
+            .sub_graphs
+            .into_iter()
+            .filter(|(_, _, graph)| {
+                graph
+                    .edges
+                    .iter()
+                    .all(|(info, _)| pool_state.contains_key(&info.pool_addr))
+            })
+            .map(|(pair, gt, graph)| (pair, gt, PairSubGraph::from_snapshot(graph)))
+            .collect_vec();
+
+        let mut dependents = FastHashMap::<Address, u64>::default();
+        sub_graphs
+            .iter()
+            .flat_map(|(_, _, graph)| graph.get_all_pools().flatten())
+            .for_each(|edge| *dependents.entry(edge.pool_addr).or_default() += 1);
+
+        let finalized_state = pool_state
+            .into_iter()
+            .filter_map(|(pool, state)| {
+                let dependents = *dependents.get(&pool)?;
+                Some((pool, StateWithDependencies { state, dependents }))
+            })
+            .collect();
+
+        self.sub_graph_registry.restore_snapshot(sub_graphs);
+        self.subgraph_verifier = SubgraphVerifier::from_snapshot(snapshot.verification_state);
+        self.graph_state.restore_finalized_state(finalized_state);
+    }
+
     pub fn add_pool(&mut self, pair: Pair, pool_addr: Address, dex: Protocol, block: u64) {
         self.all_pair_graph.add_node(pair, pool_addr, dex, block);
     }
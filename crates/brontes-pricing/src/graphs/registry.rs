@@ -12,7 +12,7 @@ use malachite::{
     Rational,
 };
 
-use super::{subgraph::PairSubGraph, PoolState};
+use super::{snapshot::SubGraphSnapshot, subgraph::PairSubGraph, PoolState};
 use crate::types::{PairWithFirstPoolHop, ProtocolState};
 
 /// Manages subgraphs in the BrontesBatchPricer module, crucial for DEX pricing.
@@ -65,6 +65,34 @@ impl SubGraphRegistry {
         Self { sub_graphs, pending_finalized_graphs: FastHashMap::default(), metrics }
     }
 
+    /// all finalized subgraphs with the keys they are registered under.
+    /// pending subgraphs aren't included as they are for future blocks
+    pub fn to_snapshot(&self) -> Vec<(Pair, Pair, SubGraphSnapshot)> {
+        self.sub_graphs
+            .iter()
+            .flat_map(|(pair, gts)| {
+                gts.iter()
+                    .map(|(gt, graph)| (*pair, *gt, graph.to_snapshot()))
+            })
+            .collect_vec()
+    }
+
+    /// replaces the finalized subgraphs with the ones restored from a
+    /// snapshot
+    pub fn restore_snapshot(&mut self, sub_graphs: Vec<(Pair, Pair, PairSubGraph)>) {
+        self.sub_graphs.clear();
+        self.pending_finalized_graphs.clear();
+
+        for (pair, gt, graph) in sub_graphs {
+            self.sub_graphs.entry(pair).or_default().insert(gt, graph);
+        }
+
+        let subgraphs_cnt = self.sub_graphs.values().map(|f| f.len()).sum::<usize>();
+        self.metrics
+            .as_ref()
+            .inspect(|m| m.active_subgraphs.set(subgraphs_cnt as f64));
+    }
+
     // for all subgraphs that haven't been used in a given time period, will
     // remove them from and return each pool with the amount to decrement.
     pub fn prune_dead_subgraphs(&mut self, block: u64) -> FastHashMap<Address, u64> {
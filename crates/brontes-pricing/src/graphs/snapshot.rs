@@ -0,0 +1,50 @@
+use alloy_primitives::Address;
+use brontes_types::{pair::Pair, price_graph_types::PoolPairInformation};
+use malachite::Rational;
+use serde::{Deserialize, Serialize};
+
+use super::subgraph::BadEdge;
+use crate::types::PoolState;
+
+/// Serializable form of the graph state at the end of a block.
+///
+/// Pending subgraphs and the pool state that is still being verified belong to
+/// blocks after the snapshot, which a resumed run will receive again from the
+/// classifier and rebuild on its own. The edges the verifier has already
+/// dropped are kept, so the rebuilt subgraphs fall back to the same edges as
+/// an uninterrupted run.
+///
+/// All maps are stored as lists as most of the keys are tuples that can't be
+/// used as json object keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphSnapshot {
+    pub block:              u64,
+    /// finalized subgraphs with the pair and goes through they are registered
+    /// under
+    pub sub_graphs:         Vec<(Pair, Pair, SubGraphSnapshot)>,
+    pub pool_state:         Vec<(Address, PoolState)>,
+    /// verifier state with the pair and goes through it is kept for
+    #[serde(default)]
+    pub verification_state: Vec<(Pair, Pair, VerificationStateSnapshot)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubGraphSnapshot {
+    pub pair:                   Pair,
+    pub complete_pair:          Pair,
+    pub must_go_through:        Pair,
+    pub extends_to:             Option<Pair>,
+    /// pool info with the `token_0_in` direction of the edge
+    pub edges:                  Vec<(PoolPairInformation, bool)>,
+    pub start_nodes_liq:        Vec<(Address, Rational)>,
+    pub last_block_for_pricing: u64,
+    pub remove_at:              Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationStateSnapshot {
+    /// fully removed edges with their liquidity, keyed by the node they were
+    /// removed from
+    pub edges:            Vec<(Address, Vec<BadEdge>)>,
+    pub removed_recusing: Vec<(Pair, Address)>,
+}
@@ -67,6 +67,27 @@ impl StateTracker {
         }
     }
 
+    /// finalized pool state as of the end of the given block
+    pub fn finalized_snapshot(&self, block: u64) -> Vec<(Address, PoolState)> {
+        self.finalized_edge_state
+            .iter()
+            .filter(|(_, state)| state.last_update <= block)
+            .map(|(addr, state)| (*addr, state.state.clone()))
+            .collect_vec()
+    }
+
+    /// replaces all state with the finalized state restored from a snapshot
+    pub fn restore_finalized_state(
+        &mut self,
+        finalized_edge_state: FastHashMap<Address, StateWithDependencies>,
+    ) {
+        self.verification_edge_state.clear();
+        self.finalized_edge_state = finalized_edge_state;
+        self.metrics
+            .as_ref()
+            .inspect(|m| m.active_state.set(self.finalized_edge_state.len() as f64));
+    }
+
     pub fn remove_finalized_state_dep(&mut self, pool: Address, amount: u64) {
         self.finalized_edge_state.retain(|i_pool, state| {
             if pool != *i_pool {
@@ -27,8 +27,10 @@ use petgraph::{
     prelude::*,
     visit::{VisitMap, Visitable},
 };
+use serde::{Deserialize, Serialize};
 use tracing::error;
 
+use super::snapshot::SubGraphSnapshot;
 use crate::{types::ProtocolState, Pair};
 
 pub struct VerificationOutcome {
@@ -38,7 +40,7 @@ pub struct VerificationOutcome {
     pub frayed_ends:    Vec<Address>,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BadEdge {
     pub pair:         Pair,
     pub pool_address: Address,
@@ -55,6 +57,9 @@ struct BfsArgs {
 const MIN_LIQUIDITY_USD_PEGGED_TOKEN: u128 = 15_000;
 const MIN_LIQUIDITY_USD_PEGGED_TOKEN_RUNDOWN: u128 = 7_500;
 const INACTIVE_REMOVAL_PERIOD: u64 = 750;
+/// share of the base token reserves that is quoted to measure the price impact
+/// of a pool when weighting it
+const PRICE_IMPACT_QUOTE_SHARE: Rational = Rational::const_from_unsigneds(1, 100);
 
 /// [`PairSubGraph`] is a directed subgraph, specifically designed to calculate
 /// and optimize the pricing of a particular token pair in a decentralized
@@ -167,6 +172,51 @@ impl PairSubGraph {
         }
     }
 
+    /// rebuilds a finalized subgraph from its snapshot
+    pub fn from_snapshot(snapshot: SubGraphSnapshot) -> Self {
+        let edges = snapshot
+            .edges
+            .into_iter()
+            .map(|(info, token_0_in)| {
+                SubGraphEdge::new(PoolPairInfoDirection::new(Box::leak(Box::new(info)), token_0_in))
+            })
+            .collect_vec();
+
+        let mut graph = Self::init(
+            snapshot.pair,
+            snapshot.complete_pair,
+            snapshot.must_go_through,
+            snapshot.extends_to,
+            edges,
+            snapshot.last_block_for_pricing,
+        );
+        graph.start_nodes_liq = snapshot.start_nodes_liq.into_iter().collect();
+        graph.remove_at = snapshot.remove_at;
+
+        graph
+    }
+
+    pub fn to_snapshot(&self) -> SubGraphSnapshot {
+        SubGraphSnapshot {
+            pair:                   self.pair,
+            complete_pair:          self.complete_pair,
+            must_go_through:        self.must_go_through,
+            extends_to:             self.extends_to,
+            edges:                  self
+                .get_all_pools()
+                .flatten()
+                .map(|edge| (*edge.info.info, edge.token_0_in))
+                .collect(),
+            start_nodes_liq:        self
+                .start_nodes_liq
+                .iter()
+                .map(|(addr, liq)| (*addr, liq.clone()))
+                .collect(),
+            last_block_for_pricing: self.last_block_for_pricing.load(SeqCst),
+            remove_at:              self.remove_at,
+        }
+    }
+
     pub fn should_use_for_new(&self) -> bool {
         self.remove_at.is_none()
     }
@@ -202,7 +252,7 @@ impl PairSubGraph {
             .edge_weights()
             .flat_map(|weight| {
                 weight.iter().filter_map(|edge| {
-                    let (r0, r1) = state.get(&edge.pool_addr)?.tvl(edge.token_0);
+                    let (r0, r1) = state.get(&edge.pool_addr)?.tvl(edge.token_0, edge.token_1);
                     let tvl_added = r0 + r1;
 
                     Some((edge.pool_addr, tvl_added))
@@ -222,7 +272,10 @@ impl PairSubGraph {
                 weight
                     .iter()
                     .map(|edge| {
-                        let (r0, r1) = state.get(&edge.pool_addr).unwrap().tvl(edge.token_0);
+                        let (r0, r1) = state
+                            .get(&edge.pool_addr)
+                            .unwrap()
+                            .tvl(edge.token_0, edge.token_1);
                         let tvl_added = r0 + r1;
                         let start_tvl = self.start_nodes_liq.get(&edge.pool_addr).unwrap();
 
@@ -499,14 +552,15 @@ impl PairSubGraph {
                         continue;
                     };
 
-                    let Ok(pool_price) =
-                        pool_state.price(info.get_token_with_direction(is_outgoing))
-                    else {
+                    let base = info.get_token_with_direction(is_outgoing);
+                    let quote = info.get_token_with_direction(!is_outgoing);
+
+                    let Ok(pool_price) = pool_state.price(base, quote) else {
                         Self::bad_state(pair, info, Rational::ZERO, &mut removal_map.removal_state);
                         continue;
                     };
 
-                    let (t0, t1) = pool_state.tvl(info.get_token_with_direction(is_outgoing));
+                    let (t0, t1) = pool_state.tvl(base, quote);
                     let liq0 = prev_price.clone().reciprocal() * &t0;
 
                     let goes_through_arg = if ignore_goes_through {
@@ -519,7 +573,19 @@ impl PairSubGraph {
                     if liq0 < MIN_LIQUIDITY_USD_PEGGED_TOKEN && goes_through_arg {
                         Self::bad_state(pair, info, liq0.clone(), &mut removal_map.removal_state);
                     } else {
-                        let t0xt1 = &t0 * &t1;
+                        // pools that can't fill a small share of their reserves close to
+                        // spot, e.g v3 pools with most of their liquidity out of range, are
+                        // weighted down by the price impact of the fill
+                        let depth = pool_state
+                            .execution_price(base, quote, &(&t0 * PRICE_IMPACT_QUOTE_SHARE))
+                            .ok()
+                            .filter(|_| pool_price != Rational::ZERO)
+                            .map(|exec_price| {
+                                std::cmp::min(exec_price / &pool_price, Rational::ONE)
+                            })
+                            .unwrap_or(Rational::ONE);
+
+                        let t0xt1 = &t0 * &t1 * depth;
                         pxw += pool_price * &t0xt1;
                         weight += t0xt1;
                     }
@@ -913,11 +979,13 @@ impl PairSubGraph {
                         continue;
                     };
 
-                    let Ok(pool_price) = pool_state.price(info.get_base_token()) else {
+                    let Ok(pool_price) =
+                        pool_state.price(info.get_base_token(), info.get_quote_token())
+                    else {
                         continue;
                     };
 
-                    let (t0, t1) = pool_state.tvl(info.get_base_token());
+                    let (t0, t1) = pool_state.tvl(info.get_base_token(), info.get_quote_token());
 
                     let t0xt1 = &t0 * &t1;
                     pxw += pool_price * &t0xt1;
@@ -1055,11 +1123,15 @@ pub mod test {
     }
 
     impl ProtocolState for MockPoolState {
-        fn price(&self, _base: Address) -> Result<Rational, crate::errors::ArithmeticError> {
+        fn price(
+            &self,
+            _base: Address,
+            _quote: Address,
+        ) -> Result<Rational, crate::errors::ArithmeticError> {
             Ok(self.price.clone())
         }
 
-        fn tvl(&self, _base: Address) -> (Rational, Rational) {
+        fn tvl(&self, _base: Address, _quote: Address) -> (Rational, Rational) {
             self.tvl.clone()
         }
     }
@@ -1133,4 +1205,21 @@ pub mod test {
 
         assert_eq!(price, Rational::from_unsigneds(1usize, 390usize))
     }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        addresses!(t0);
+        let mut graph = make_simple_graph();
+        graph.start_nodes_liq.insert(t0, Rational::from(10_000));
+        graph.remove_at = Some(10);
+
+        let snapshot = graph.to_snapshot();
+        let json = serde_json::to_vec(&snapshot).unwrap();
+        let restored = PairSubGraph::from_snapshot(serde_json::from_slice(&json).unwrap());
+
+        assert_eq!(restored.complete_pair(), graph.complete_pair());
+        assert_eq!(restored.get_all_pools().flatten().count(), 4);
+        assert_eq!(restored.start_nodes_liq, graph.start_nodes_liq);
+        assert!(restored.ready_to_remove(11));
+    }
 }
@@ -6,6 +6,7 @@ use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use tracing::{error_span, instrument};
 
 use super::{
+    snapshot::VerificationStateSnapshot,
     state_tracker::StateTracker,
     subgraph::{BadEdge, PairSubGraph, VerificationOutcome},
 };
@@ -69,6 +70,33 @@ impl SubgraphVerifier {
         }
     }
 
+    /// verification state of all subgraphs. pending subgraphs aren't included
+    /// as they are for future blocks
+    pub fn to_snapshot(&self) -> Vec<(Pair, Pair, VerificationStateSnapshot)> {
+        self.subgraph_verification_state
+            .iter()
+            .map(|(pair, state)| {
+                let (pair, gt) = pair.pair_gt();
+                (pair, gt, state.to_snapshot())
+            })
+            .collect_vec()
+    }
+
+    pub fn from_snapshot(snapshot: Vec<(Pair, Pair, VerificationStateSnapshot)>) -> Self {
+        Self {
+            pending_subgraphs:           FastHashMap::default(),
+            subgraph_verification_state: snapshot
+                .into_iter()
+                .map(|(pair, gt, state)| {
+                    (
+                        PairWithFirstPoolHop::from_pair_gt(pair, gt),
+                        SubgraphVerificationState::from_snapshot(state),
+                    )
+                })
+                .collect(),
+        }
+    }
+
     pub fn get_subgraph_extends(&self, pair: PairWithFirstPoolHop) -> Option<Pair> {
         self.pending_subgraphs
             .get(&pair)
@@ -433,6 +461,35 @@ pub struct SubgraphVerificationState {
 }
 
 impl SubgraphVerificationState {
+    fn to_snapshot(&self) -> VerificationStateSnapshot {
+        VerificationStateSnapshot {
+            edges:            self
+                .edges
+                .0
+                .iter()
+                .map(|(addr, edges)| (*addr, edges.iter().cloned().collect_vec()))
+                .collect_vec(),
+            removed_recusing: self
+                .removed_recusing
+                .iter()
+                .map(|(pair, addr)| (*pair, *addr))
+                .collect_vec(),
+        }
+    }
+
+    fn from_snapshot(snapshot: VerificationStateSnapshot) -> Self {
+        Self {
+            edges:            EdgesWithLiq(
+                snapshot
+                    .edges
+                    .into_iter()
+                    .map(|(addr, edges)| (addr, edges.into_iter().collect()))
+                    .collect(),
+            ),
+            removed_recusing: snapshot.removed_recusing.into_iter().collect(),
+        }
+    }
+
     /// returns pairs to ignore from lowest to highest liquidity.
     fn sorted_ignore_nodes_by_liquidity(&self) -> Vec<Pair> {
         self.edges
@@ -487,3 +544,39 @@ impl SubgraphVerificationState {
 
 #[derive(Debug, Default, Clone)]
 pub struct EdgesWithLiq(FastHashMap<Address, FastHashSet<BadEdge>>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification_state_snapshot_round_trip() {
+        let [t0, t1, t2, pool0, pool1] = [1, 2, 3, 4, 5].map(Address::with_last_byte);
+
+        let mut state = SubgraphVerificationState::default();
+        state.removed_recusing.insert(Pair(t0, t2), pool1);
+        for (pair, pool, liq) in [(Pair(t0, t1), pool0, 10u64), (Pair(t1, t2), pool1, 500)] {
+            let edge = BadEdge {
+                pair,
+                pool_address: pool,
+                edge_liq: pair.0,
+                liquidity: Rational::from(liq),
+            };
+            state.add_edge_with_liq(pair.0, edge.clone());
+            state.add_edge_with_liq(pair.1, edge);
+        }
+
+        let key = PairWithFirstPoolHop::from_pair_gt(Pair(t0, t2), Pair(t0, t1));
+        let mut verifier = SubgraphVerifier::new();
+        verifier.subgraph_verification_state.insert(key, state);
+
+        let json = serde_json::to_vec(&verifier.to_snapshot()).unwrap();
+        let restored = SubgraphVerifier::from_snapshot(serde_json::from_slice(&json).unwrap());
+
+        let original = &verifier.subgraph_verification_state[&key];
+        let restored = &restored.subgraph_verification_state[&key];
+        assert_eq!(restored.get_nodes_to_ignore(), original.get_nodes_to_ignore());
+        assert_eq!(restored.sorted_ignore_nodes_by_liquidity(), vec![Pair(t0, t1), Pair(t1, t2)]);
+        assert_eq!(restored.removed_recusing, original.removed_recusing);
+    }
+}
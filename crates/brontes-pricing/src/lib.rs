@@ -22,12 +22,14 @@
 
 use brontes_metrics::pricing::DexPricingMetrics;
 use brontes_types::{
-    db::dex::PriceAt, execute_on, normalized_actions::pool::NormalizedPoolConfigUpdate,
+    db::dex::PriceAt,
+    execute_on,
+    normalized_actions::{pool::NormalizedPoolConfigUpdate, Action},
     BrontesTaskExecutor, UnboundedYapperReceiver,
 };
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::graphs::StateWithDependencies;
+use crate::graphs::{GraphSnapshot, StateWithDependencies};
 pub mod function_call_bench;
 mod graphs;
 pub mod protocols;
@@ -47,7 +49,11 @@ pub use brontes_types::price_graph_types::{
     PoolPairInfoDirection, PoolPairInformation, SubGraphEdge, SubGraphsEntry,
 };
 use brontes_types::{
-    db::dex::{DexPrices, DexQuotes},
+    db::{
+        dex::{DexPrices, DexQuotes},
+        pricing_snapshot::PricingGraphSnapshot,
+        uniswap_v4_pool_key::UniswapV4PoolKey,
+    },
     pair::Pair,
     traits::TracingProvider,
     FastHashMap, FastHashSet,
@@ -148,6 +154,15 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
         }
     }
 
+    /// seeds the pool ids of the v4 pools we already know of
+    pub fn with_v4_pool_keys(mut self, keys: Vec<(Address, UniswapV4PoolKey)>) -> Self {
+        keys.iter().for_each(|(address, key)| {
+            self.lazy_loader
+                .register_v4_pool(*address, uniswap_v4::V4PoolInfo::from(key))
+        });
+        self
+    }
+
     pub fn current_block_processing(&self) -> u64 {
         self.completed_block
     }
@@ -173,6 +188,50 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
             .set_state(sub_graph_registry, verifier, state)
     }
 
+    /// encodes the finalized graph state as it is at the end of the given
+    /// block. This should only be called right after the pricer has returned
+    /// the block.
+    pub fn graph_snapshot(&self, block: u64) -> eyre::Result<PricingGraphSnapshot> {
+        let snapshot = self.graph_manager.snapshot(block);
+
+        Ok(PricingGraphSnapshot {
+            quote_asset: self.quote_asset,
+            data:        serde_json::to_vec(&snapshot)?.into(),
+        })
+    }
+
+    /// resumes pricing from a snapshot that was taken at the end of the block
+    /// before the one this pricer starts at, skipping the re-verification of
+    /// all the subgraphs that were already known at that point.
+    pub fn restore_graph_snapshot(&mut self, snapshot: PricingGraphSnapshot) -> eyre::Result<()> {
+        if snapshot.quote_asset != self.quote_asset {
+            eyre::bail!(
+                "snapshot was taken for quote asset {:?}, pricer is using {:?}",
+                snapshot.quote_asset,
+                self.quote_asset
+            );
+        }
+
+        let snapshot: GraphSnapshot = serde_json::from_slice(&snapshot.data)?;
+        if snapshot.block + 1 != self.completed_block {
+            eyre::bail!(
+                "snapshot was taken at block {}, pricer starts at block {}",
+                snapshot.block,
+                self.completed_block
+            );
+        }
+
+        info!(
+            range_id = self.range_id,
+            block = snapshot.block,
+            subgraphs = snapshot.sub_graphs.len(),
+            "resuming dex pricing from graph snapshot"
+        );
+        self.graph_manager.restore_snapshot(snapshot);
+
+        Ok(())
+    }
+
     /// Handles pool updates for the BrontesBatchPricer system.
     ///
     /// This function processes a vector of `PoolUpdate` messages, updating the
@@ -209,6 +268,8 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
                 self.graph_manager
                     .add_pool(pair, pool_addr, protocol, block);
             });
+        self.add_multi_token_pool_edges(&updates);
+        self.register_v4_pools(&updates);
 
         updates.iter().for_each(|msg| {
             let Some(pair) = msg.get_pair(self.quote_asset) else { return };
@@ -290,6 +351,8 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
                 self.graph_manager
                     .add_pool(pair, pool_addr, protocol, block);
             });
+        self.add_multi_token_pool_edges(&updates);
+        self.register_v4_pools(&updates);
 
         updates.into_iter().for_each(|update| {
             self.graph_manager
@@ -297,6 +360,42 @@ impl<T: TracingProvider> BrontesBatchPricer<T> {
         });
     }
 
+    /// curve and balancer pools can hold more than two tokens but are only
+    /// registered with their first pair. when a swap routes through another
+    /// pair of the pool, we add the pair as a new edge so that it can be used
+    /// for pricing. metapool swaps through `exchange_underlying` are skipped,
+    /// the pool doesn't hold the base pool coins so it can't quote them.
+    fn add_multi_token_pool_edges(&mut self, updates: &[PoolUpdate]) {
+        updates
+            .iter()
+            .filter_map(|update| match &update.action {
+                Action::Swap(s) => Some((s, update)),
+                Action::SwapWithFee(s) => Some((&s.swap, update)),
+                _ => None,
+            })
+            .filter(|(swap, update)| {
+                swap.protocol.is_multi_token_pool()
+                    && !curve::is_underlying_exchange(swap.pool, &update.logs)
+            })
+            .for_each(|(swap, update)| {
+                self.graph_manager.add_pool(
+                    Pair(swap.token_in.address, swap.token_out.address),
+                    swap.pool,
+                    swap.protocol,
+                    update.block,
+                );
+            });
+    }
+
+    /// v4 pools can only be loaded with their pool id, which we pick up from
+    /// the pool manager events of the updates
+    fn register_v4_pools(&mut self, updates: &[PoolUpdate]) {
+        updates
+            .iter()
+            .filter_map(uniswap_v4::V4PoolInfo::from_update)
+            .for_each(|(address, info)| self.lazy_loader.register_v4_pool(address, info));
+    }
+
     fn get_dex_price(&mut self, pool_pair: Pair, goes_through: Pair) -> Option<Rational> {
         if pool_pair.0 == pool_pair.1 {
             return Some(Rational::ONE)
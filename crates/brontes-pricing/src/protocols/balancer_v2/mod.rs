@@ -0,0 +1,502 @@
+use std::sync::Arc;
+
+use alloy_primitives::{hex, Address, FixedBytes, Log, U256};
+use alloy_sol_macro::sol;
+use async_trait::async_trait;
+use brontes_types::{
+    normalized_actions::Action, queries::make_call_request, traits::TracingProvider,
+    ToScaledRational,
+};
+use malachite::{
+    num::{arithmetic::traits::Pow, basic::traits::Zero},
+    Natural, Rational,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    curve::math::{stable_swap_spot_price, u256_to_natural},
+    errors::{AmmError, ArithmeticError},
+    to_raw_amount, UpdatableProtocol,
+};
+
+pub const BALANCER_V2_VAULT: Address =
+    Address::new(hex!("BA12222222228d8Ba445958a75a0704d566BF2C8"));
+
+sol!(
+    interface IBalancerV2Pool {
+        function getPoolId() external view returns (bytes32);
+        function getNormalizedWeights() external view returns (uint256[]);
+        function getAmplificationParameter() external view returns (
+            uint256 value,
+            bool isUpdating,
+            uint256 precision
+        );
+        function getScalingFactors() external view returns (uint256[]);
+    }
+);
+
+sol!(
+    interface IBalancerV2Vault {
+        function getPoolTokens(bytes32 poolId) external view returns (
+            address[] tokens,
+            uint256[] balances,
+            uint256 lastChangeBlock
+        );
+    }
+);
+
+sol!(
+    interface IBalancerErc20 {
+        function decimals() external view returns (uint8);
+    }
+);
+
+const PRECISION: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BalancerPoolKind {
+    #[default]
+    Weighted,
+    /// stable and composable stable pools. composable stable pools hold their
+    /// own bpt which we strip from the pool tokens
+    Stable,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BalancerV2Pool {
+    pub address:         Address,
+    pub pool_id:         FixedBytes<32>,
+    pub kind:            BalancerPoolKind,
+    pub tokens:          Vec<Address>,
+    pub decimals:        Vec<u8>,
+    /// raw token balances as held by the vault
+    pub balances:        Vec<U256>,
+    /// normalized weights, 1e18 precision. only used for weighted pools
+    pub weights:         Vec<U256>,
+    /// scaling factors that upscale the raw balances to 18 decimals including
+    /// any rate provider. only used for stable pools
+    pub scaling_factors: Vec<U256>,
+    /// amplification parameter without the balancer amp precision
+    pub amp:             U256,
+}
+
+#[async_trait]
+impl UpdatableProtocol for BalancerV2Pool {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn tokens(&self) -> Vec<Address> {
+        self.tokens.clone()
+    }
+
+    /// price of the base token denominated in the first other token of the
+    /// pool
+    fn calculate_price(&self, base_token: Address) -> Result<Rational, ArithmeticError> {
+        let quote = self
+            .tokens
+            .iter()
+            .find(|t| **t != base_token)
+            .copied()
+            .unwrap_or_default();
+
+        self.calculate_price_for(base_token, quote)
+    }
+
+    fn sync_from_action(&mut self, action: Action) -> Result<(), AmmError> {
+        match action {
+            Action::Swap(s) => {
+                self.apply_delta(s.token_in.address, s.amount_in, true)?;
+                self.apply_delta(s.token_out.address, s.amount_out, false)
+            }
+            Action::SwapWithFee(s) => {
+                let s = s.swap;
+                self.apply_delta(s.token_in.address, s.amount_in, true)?;
+                self.apply_delta(s.token_out.address, s.amount_out, false)
+            }
+            Action::Mint(m) => m
+                .token
+                .iter()
+                .zip(m.amount)
+                .try_for_each(|(token, amount)| self.apply_delta(token.address, amount, true)),
+            Action::Burn(b) => b
+                .token
+                .iter()
+                .zip(b.amount)
+                .try_for_each(|(token, amount)| self.apply_delta(token.address, amount, false)),
+            _ => Err(AmmError::SyncError(self.address)),
+        }
+    }
+
+    fn sync_from_log(&mut self, _log: Log) -> Result<(), AmmError> {
+        // balances live in the vault, so the pool emits nothing we can sync from.
+        // we use the classified actions instead
+        Err(AmmError::SyncError(self.address))
+    }
+}
+
+impl BalancerV2Pool {
+    pub async fn new_load_on_block<M: TracingProvider>(
+        address: Address,
+        middleware: Arc<M>,
+        block: u64,
+    ) -> Result<Self, AmmError> {
+        let mut pool = BalancerV2Pool { address, ..Default::default() };
+        pool.populate_data(block, middleware).await?;
+
+        if !pool.data_is_populated() {
+            return Err(AmmError::NoStateError(address))
+        }
+
+        Ok(pool)
+    }
+
+    async fn populate_data<M: TracingProvider>(
+        &mut self,
+        block: u64,
+        middleware: Arc<M>,
+    ) -> Result<(), AmmError> {
+        let block = Some(block);
+
+        self.pool_id =
+            make_call_request(IBalancerV2Pool::getPoolIdCall {}, &middleware, self.address, block)
+                .await?
+                ._0;
+
+        let pool_tokens = make_call_request(
+            IBalancerV2Vault::getPoolTokensCall { poolId: self.pool_id },
+            &middleware,
+            BALANCER_V2_VAULT,
+            block,
+        )
+        .await?;
+        let mut tokens = pool_tokens.tokens;
+        let mut balances = pool_tokens.balances;
+
+        if let Ok(weights) = make_call_request(
+            IBalancerV2Pool::getNormalizedWeightsCall {},
+            &middleware,
+            self.address,
+            block,
+        )
+        .await
+        {
+            self.kind = BalancerPoolKind::Weighted;
+            self.weights = weights._0;
+        } else {
+            let amp = make_call_request(
+                IBalancerV2Pool::getAmplificationParameterCall {},
+                &middleware,
+                self.address,
+                block,
+            )
+            .await?;
+            if amp.precision.is_zero() {
+                return Err(AmmError::PoolDataError)
+            }
+
+            self.kind = BalancerPoolKind::Stable;
+            self.amp = amp.value / amp.precision;
+            let mut scaling_factors = make_call_request(
+                IBalancerV2Pool::getScalingFactorsCall {},
+                &middleware,
+                self.address,
+                block,
+            )
+            .await?
+            ._0;
+
+            // composable stable pools register their own bpt as a pool token
+            if let Some(bpt_idx) = tokens.iter().position(|t| *t == self.address) {
+                tokens.remove(bpt_idx);
+                balances.remove(bpt_idx);
+                if bpt_idx < scaling_factors.len() {
+                    scaling_factors.remove(bpt_idx);
+                }
+            }
+            self.scaling_factors = scaling_factors;
+        }
+
+        for token in &tokens {
+            let decimals =
+                make_call_request(IBalancerErc20::decimalsCall {}, &middleware, *token, block)
+                    .await?
+                    ._0;
+            self.decimals.push(decimals);
+        }
+
+        self.tokens = tokens;
+        self.balances = balances;
+
+        Ok(())
+    }
+
+    pub fn data_is_populated(&self) -> bool {
+        self.tokens.len() >= 2
+            && self.balances.len() == self.tokens.len()
+            && self.balances.iter().all(|b| !b.is_zero())
+            && match self.kind {
+                BalancerPoolKind::Weighted => self.weights.len() == self.tokens.len(),
+                BalancerPoolKind::Stable => {
+                    !self.amp.is_zero() && self.scaling_factors.len() == self.tokens.len()
+                }
+            }
+    }
+
+    fn index_of(&self, token: Address) -> Option<usize> {
+        self.tokens.iter().position(|t| *t == token)
+    }
+
+    /// price of the base token denominated in the quote token
+    pub fn calculate_price_for(
+        &self,
+        base: Address,
+        quote: Address,
+    ) -> Result<Rational, ArithmeticError> {
+        let i = self
+            .index_of(base)
+            .ok_or(ArithmeticError::TokenNotInPool(base))?;
+        let j = self
+            .index_of(quote)
+            .ok_or(ArithmeticError::TokenNotInPool(quote))?;
+
+        match self.kind {
+            // (B_j / W_j) / (B_i / W_i)
+            BalancerPoolKind::Weighted => {
+                let (b_i, b_j) = (
+                    self.balances[i].to_scaled_rational(self.decimals[i]),
+                    self.balances[j].to_scaled_rational(self.decimals[j]),
+                );
+                if b_i == Rational::ZERO || self.weights[j].is_zero() {
+                    return Err(ArithmeticError::YIsZero)
+                }
+
+                Ok((b_j * Rational::from(u256_to_natural(self.weights[i])))
+                    / (b_i * Rational::from(u256_to_natural(self.weights[j]))))
+            }
+            // balancer stable math uses the same invariant as the curve
+            // stableswap pools
+            BalancerPoolKind::Stable => {
+                let xp = self
+                    .balances
+                    .iter()
+                    .zip(&self.scaling_factors)
+                    .map(|(balance, factor)| *balance * factor / U256::from(PRECISION))
+                    .collect::<Vec<_>>();
+
+                let price = stable_swap_spot_price(&xp, self.amp, i, j)?;
+                let unit = |idx: usize| {
+                    u256_to_natural(self.scaling_factors[idx])
+                        * Natural::from(10u8).pow(self.decimals[idx] as u64)
+                };
+
+                Ok(price * Rational::from_naturals(unit(i), unit(j)))
+            }
+        }
+    }
+
+    pub fn get_tvl_for(&self, base: Address, quote: Address) -> (Rational, Rational) {
+        let balance_of = |token: Address| {
+            self.index_of(token)
+                .map(|i| self.balances[i].to_scaled_rational(self.decimals[i]))
+                .unwrap_or(Rational::ZERO)
+        };
+
+        (balance_of(base), balance_of(quote))
+    }
+
+    fn apply_delta(&mut self, token: Address, amount: Rational, add: bool) -> Result<(), AmmError> {
+        let i = self
+            .index_of(token)
+            .ok_or(AmmError::SyncError(self.address))?;
+
+        let raw =
+            to_raw_amount(amount, self.decimals[i]).ok_or(AmmError::SyncError(self.address))?;
+
+        if add {
+            self.balances[i] = self.balances[i].saturating_add(raw);
+        } else {
+            self.balances[i] = self.balances[i].saturating_sub(raw);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::{NormalizedBurn, NormalizedMint, NormalizedSwap},
+    };
+    use malachite::num::basic::traits::One;
+
+    use super::*;
+
+    const BAL: Address = Address::new(hex!("ba100000625a3754423978a60c9317c58a424e3D"));
+    const WETH: Address = Address::new(hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"));
+    const WSTETH: Address = Address::new(hex!("7f39C581F595B53c5cb19bD0b3f8dA6c935E2Ca0"));
+    const USDC: Address = Address::new(hex!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"));
+    const DAI: Address = Address::new(hex!("6B175474E89094C44Da98b954EedeAC495271d0F"));
+
+    fn e(amount: u64, decimals: u8) -> U256 {
+        U256::from(amount) * U256::from(10).pow(U256::from(decimals))
+    }
+
+    fn token(address: Address, decimals: u8) -> TokenInfoWithAddress {
+        TokenInfoWithAddress { address, inner: TokenInfo::new(decimals, String::new()) }
+    }
+
+    /// the BAL/WETH 80/20 pool
+    fn bal_weth() -> BalancerV2Pool {
+        BalancerV2Pool {
+            address: Address::new(hex!("5c6Ee304399DBdB9C8Ef030aB642B10820DB8F56")),
+            kind: BalancerPoolKind::Weighted,
+            tokens: vec![BAL, WETH],
+            decimals: vec![18, 18],
+            balances: vec![e(40_000_000, 18), e(5_000, 18)],
+            weights: vec![e(8, 17), e(2, 17)],
+            ..Default::default()
+        }
+    }
+
+    /// the wstETH/WETH composable stable pool, the wstETH scaling factor
+    /// includes the wstETH rate provider
+    fn wsteth_weth() -> BalancerV2Pool {
+        BalancerV2Pool {
+            address: Address::new(hex!("93d199263632a4EF4Bb438F1feB99e57b4b5f0BD")),
+            kind: BalancerPoolKind::Stable,
+            tokens: vec![WSTETH, WETH],
+            decimals: vec![18, 18],
+            balances: vec![e(10_000, 18), e(11_500, 18)],
+            scaling_factors: vec![e(115, 16), e(1, 18)],
+            amp: U256::from(50),
+            ..Default::default()
+        }
+    }
+
+    fn usdc_dai() -> BalancerV2Pool {
+        BalancerV2Pool {
+            address: Address::with_last_byte(1),
+            kind: BalancerPoolKind::Stable,
+            tokens: vec![USDC, DAI],
+            decimals: vec![6, 18],
+            balances: vec![e(1_000_000, 6), e(1_000_000, 18)],
+            scaling_factors: vec![e(1, 30), e(1, 18)],
+            amp: U256::from(1000),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_weighted_spot_price() {
+        let pool = bal_weth();
+
+        // (5000 / 0.2) / (40m / 0.8)
+        assert_eq!(
+            pool.calculate_price_for(BAL, WETH).unwrap(),
+            Rational::from_unsigneds(1u32, 2000u32)
+        );
+        assert_eq!(pool.calculate_price_for(WETH, BAL).unwrap(), Rational::from(2000));
+        assert_eq!(pool.calculate_price(BAL).unwrap(), Rational::from_unsigneds(1u32, 2000u32));
+    }
+
+    #[test]
+    fn test_stable_spot_price_applies_rate() {
+        let pool = wsteth_weth();
+
+        // the scaled balances are equal, so the pool trades at the wstETH rate
+        assert_eq!(
+            pool.calculate_price_for(WSTETH, WETH).unwrap(),
+            Rational::from_unsigneds(115u32, 100u32)
+        );
+        assert_eq!(
+            pool.calculate_price_for(WETH, WSTETH).unwrap(),
+            Rational::from_unsigneds(100u32, 115u32)
+        );
+    }
+
+    #[test]
+    fn test_stable_spot_price_mixed_decimals() {
+        let pool = usdc_dai();
+
+        assert_eq!(pool.calculate_price_for(USDC, DAI).unwrap(), Rational::ONE);
+        assert_eq!(pool.calculate_price_for(DAI, USDC).unwrap(), Rational::ONE);
+    }
+
+    #[test]
+    fn test_sync_swap() {
+        let mut pool = bal_weth();
+
+        let swap = Action::Swap(NormalizedSwap {
+            pool: pool.address,
+            token_in: token(WETH, 18),
+            token_out: token(BAL, 18),
+            amount_in: Rational::from(1_250),
+            amount_out: Rational::from(8_000_000),
+            ..Default::default()
+        });
+        pool.sync_from_action(swap).unwrap();
+
+        assert_eq!(pool.balances, vec![e(32_000_000, 18), e(6_250, 18)]);
+        // (6250 / 0.2) / (32m / 0.8)
+        assert_eq!(
+            pool.calculate_price_for(BAL, WETH).unwrap(),
+            Rational::from_unsigneds(1u32, 1280u32)
+        );
+
+        let stable_swap = Action::Swap(NormalizedSwap {
+            token_in: token(USDC, 6),
+            token_out: token(DAI, 18),
+            amount_in: Rational::from(100_000),
+            amount_out: Rational::from(99_900),
+            ..Default::default()
+        });
+        let mut pool = usdc_dai();
+        pool.sync_from_action(stable_swap).unwrap();
+
+        assert_eq!(pool.balances, vec![e(1_100_000, 6), e(900_100, 18)]);
+        assert!(pool.calculate_price_for(USDC, DAI).unwrap() < Rational::ONE);
+    }
+
+    #[test]
+    fn test_sync_mint_and_burn() {
+        let mut pool = wsteth_weth();
+        let tokens = vec![token(WSTETH, 18), token(WETH, 18)];
+
+        let mint = Action::Mint(NormalizedMint {
+            token: tokens.clone(),
+            amount: vec![Rational::from(100), Rational::from(115)],
+            ..Default::default()
+        });
+        pool.sync_from_action(mint).unwrap();
+        assert_eq!(pool.balances, vec![e(10_100, 18), e(11_615, 18)]);
+        // a proportional join keeps the price
+        assert_eq!(
+            pool.calculate_price_for(WSTETH, WETH).unwrap(),
+            Rational::from_unsigneds(115u32, 100u32)
+        );
+
+        let burn = Action::Burn(NormalizedBurn {
+            token: tokens,
+            amount: vec![Rational::from(100), Rational::from(115)],
+            ..Default::default()
+        });
+        pool.sync_from_action(burn).unwrap();
+        assert_eq!(pool.balances, wsteth_weth().balances);
+    }
+
+    #[test]
+    fn test_sync_unknown_token_errors() {
+        let mut pool = bal_weth();
+
+        let swap = Action::Swap(NormalizedSwap {
+            token_in: token(DAI, 18),
+            token_out: token(BAL, 18),
+            amount_in: Rational::ONE,
+            amount_out: Rational::ONE,
+            ..Default::default()
+        });
+        assert!(pool.sync_from_action(swap).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use alloy_primitives::U256;
+use malachite::{Natural, Rational};
+
+use crate::errors::ArithmeticError;
+
+/// max amount of newton iterations the curve contracts use when converging on
+/// the invariant
+const MAX_ITERATIONS: usize = 255;
+
+pub fn u256_to_natural(value: U256) -> Natural {
+    Natural::from_limbs_asc(value.as_limbs())
+}
+
+/// computes the stableswap invariant `D` for the given normalized (18 decimal)
+/// balances. mirrors `get_D` from the curve stableswap contracts.
+pub fn stable_swap_d(xp: &[U256], amp: U256) -> Result<U256, ArithmeticError> {
+    let n = U256::from(xp.len());
+    let sum = xp.iter().fold(U256::ZERO, |acc, x| acc + x);
+    if sum.is_zero() {
+        return Ok(U256::ZERO)
+    }
+
+    let ann = amp * n;
+    let mut d = sum;
+
+    for _ in 0..MAX_ITERATIONS {
+        let d_p = stable_swap_d_p(xp, d)?;
+        let d_prev = d;
+
+        let numerator = (ann * sum + d_p * n) * d;
+        let denominator = (ann - U256::from(1)) * d + (n + U256::from(1)) * d_p;
+        if denominator.is_zero() {
+            return Err(ArithmeticError::RoundingError)
+        }
+        d = numerator / denominator;
+
+        if d.abs_diff(d_prev) <= U256::from(1) {
+            return Ok(d)
+        }
+    }
+
+    Err(ArithmeticError::RoundingError)
+}
+
+/// `D^(n+1) / (n^n * prod(x))`, calculated the same way the contract does to
+/// avoid overflowing
+fn stable_swap_d_p(xp: &[U256], d: U256) -> Result<U256, ArithmeticError> {
+    let n = U256::from(xp.len());
+    xp.iter().try_fold(d, |d_p, x| {
+        if x.is_zero() {
+            return Err(ArithmeticError::YIsZero)
+        }
+        Ok(d_p * d / (*x * n))
+    })
+}
+
+/// spot price of coin `i` denominated in coin `j` on the stableswap invariant
+/// `Ann * S + D = Ann * D + D^(n+1) / (n^n * P)`.
+///
+/// The price is the ratio of the partial derivatives of the invariant,
+/// `(Ann + D_P / x_i) / (Ann + D_P / x_j)`, which is returned in normalized
+/// units.
+pub fn stable_swap_spot_price(
+    xp: &[U256],
+    amp: U256,
+    i: usize,
+    j: usize,
+) -> Result<Rational, ArithmeticError> {
+    let d = stable_swap_d(xp, amp)?;
+    let d_p = stable_swap_d_p(xp, d)?;
+    let ann = amp * U256::from(xp.len());
+
+    let (x_i, x_j) = (xp[i], xp[j]);
+    if x_i.is_zero() || x_j.is_zero() {
+        return Err(ArithmeticError::YIsZero)
+    }
+
+    let numerator = u256_to_natural(ann * x_i + d_p) * u256_to_natural(x_j);
+    let denominator = u256_to_natural(ann * x_j + d_p) * u256_to_natural(x_i);
+
+    Ok(Rational::from_naturals(numerator, denominator))
+}
+
+#[cfg(test)]
+mod tests {
+    use malachite::num::basic::traits::One;
+
+    use super::*;
+
+    fn e18(amount: u64) -> U256 {
+        U256::from(amount) * U256::from(10).pow(U256::from(18))
+    }
+
+    #[test]
+    fn test_balanced_pool_is_at_peg() {
+        let xp = vec![e18(1_000_000), e18(1_000_000), e18(1_000_000)];
+        let price = stable_swap_spot_price(&xp, U256::from(2000), 0, 1).unwrap();
+
+        assert_eq!(price, Rational::ONE);
+    }
+
+    #[test]
+    fn test_imbalanced_pool_discounts_abundant_coin() {
+        let xp = vec![e18(3_000_000), e18(1_000_000)];
+        let price = stable_swap_spot_price(&xp, U256::from(1000), 0, 1).unwrap();
+        let flipped = stable_swap_spot_price(&xp, U256::from(1000), 1, 0).unwrap();
+
+        assert!(price < Rational::ONE);
+        assert!(price > Rational::from_unsigneds(99u32, 100u32));
+        assert_eq!(price * flipped, Rational::ONE);
+    }
+}
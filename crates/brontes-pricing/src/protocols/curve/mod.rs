@@ -0,0 +1,763 @@
+pub mod math;
+
+use std::sync::Arc;
+
+use alloy_primitives::{Address, Log, U256};
+use alloy_sol_macro::sol;
+use alloy_sol_types::SolEvent;
+use async_trait::async_trait;
+use brontes_types::{
+    constants::ETH_ADDRESS, normalized_actions::Action, queries::make_call_request,
+    traits::TracingProvider, ToScaledRational,
+};
+use malachite::{
+    num::basic::traits::{One, Zero},
+    Rational,
+};
+use serde::{Deserialize, Serialize};
+
+use self::math::{stable_swap_spot_price, u256_to_natural};
+use crate::{
+    errors::{AmmError, ArithmeticError},
+    to_raw_amount, Protocol, UpdatableProtocol,
+};
+
+sol!(
+    interface ICurvePool {
+        function coins(uint256 i) external view returns (address);
+        function balances(uint256 i) external view returns (uint256);
+        function A() external view returns (uint256);
+        function base_pool() external view returns (address);
+        function get_virtual_price() external view returns (uint256);
+        function stored_rates() external view returns (uint256[]);
+        function last_prices() external view returns (uint256);
+        function decimals() external view returns (uint8);
+    }
+);
+
+sol!(
+    interface ICurveLegacyPool {
+        function coins(int128 i) external view returns (address);
+        function balances(int128 i) external view returns (uint256);
+    }
+);
+
+sol!(
+    interface ICurveTriCryptoPool {
+        function last_prices(uint256 k) external view returns (uint256);
+    }
+);
+
+sol!(
+    interface ICurveMetaPool {
+        event TokenExchangeUnderlying(
+            address indexed buyer,
+            int128 sold_id,
+            uint256 tokens_sold,
+            int128 bought_id,
+            uint256 tokens_bought
+        );
+    }
+);
+
+/// max amount of coins a curve pool can hold
+const MAX_COINS: usize = 8;
+const PRECISION: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CurveInvariant {
+    #[default]
+    StableSwap,
+    CryptoSwap,
+}
+
+impl CurveInvariant {
+    pub fn from_protocol(protocol: Protocol) -> Option<Self> {
+        match protocol {
+            Protocol::CurveBasePool2
+            | Protocol::CurveBasePool3
+            | Protocol::CurveBasePool4
+            | Protocol::CurveV1MetaPool
+            | Protocol::CurveV1MetapoolImpl
+            | Protocol::CurveV2MetaPool
+            | Protocol::CurveV2MetapoolImpl
+            | Protocol::CurveV2PlainPool
+            | Protocol::CurveV2PlainPoolImpl
+            | Protocol::CurvecrvUSDMetaPool
+            | Protocol::CurvecrvUSDMetapoolImpl
+            | Protocol::CurvecrvUSDPlainPool
+            | Protocol::CurvecrvUSDPlainPoolImpl => Some(Self::StableSwap),
+            Protocol::CurveCryptoSwapPool | Protocol::CurveTriCryptoPool => Some(Self::CryptoSwap),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CurvePool {
+    pub address:     Address,
+    pub protocol:    Protocol,
+    pub invariant:   CurveInvariant,
+    pub tokens:      Vec<Address>,
+    pub decimals:    Vec<u8>,
+    /// raw token balances held by the pool
+    pub balances:    Vec<U256>,
+    /// 1e18 based multiplier applied to the decimal normalized balances. this
+    /// is the base pool virtual price for metapool lp tokens and the oracle
+    /// rate for ng pools holding lsts. 1e18 for everything else
+    pub rates:       Vec<U256>,
+    pub amp:         U256,
+    /// price of each coin denominated in coin 0. only used for the cryptoswap
+    /// invariant
+    pub last_prices: Vec<Rational>,
+}
+
+#[async_trait]
+impl UpdatableProtocol for CurvePool {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn tokens(&self) -> Vec<Address> {
+        self.tokens.clone()
+    }
+
+    /// price of the base token denominated in the first other coin of the pool
+    fn calculate_price(&self, base_token: Address) -> Result<Rational, ArithmeticError> {
+        let quote = self
+            .tokens
+            .iter()
+            .find(|t| **t != base_token)
+            .copied()
+            .unwrap_or_default();
+
+        self.calculate_price_for(base_token, quote)
+    }
+
+    fn sync_from_action(&mut self, action: Action) -> Result<(), AmmError> {
+        match action {
+            Action::Swap(s) => {
+                self.apply_swap(s.token_in.address, s.amount_in, s.token_out.address, s.amount_out)
+            }
+            Action::SwapWithFee(s) => {
+                let s = s.swap;
+                self.apply_swap(s.token_in.address, s.amount_in, s.token_out.address, s.amount_out)
+            }
+            Action::Mint(m) => m
+                .token
+                .iter()
+                .zip(m.amount)
+                .try_for_each(|(token, amount)| self.apply_delta(token.address, amount, true)),
+            Action::Burn(b) => b
+                .token
+                .iter()
+                .zip(b.amount)
+                .try_for_each(|(token, amount)| self.apply_delta(token.address, amount, false)),
+            _ => Err(AmmError::SyncError(self.address)),
+        }
+    }
+
+    fn sync_from_log(&mut self, _log: Log) -> Result<(), AmmError> {
+        // curve pools are synced from the classified actions as the event
+        // layout differs between every pool implementation
+        Err(AmmError::SyncError(self.address))
+    }
+}
+
+/// Whether the logs of a swap on `pool` are from `exchange_underlying`. One
+/// side of the swap is then a base pool coin, which the metapool doesn't hold.
+pub fn is_underlying_exchange(pool: Address, logs: &[Log]) -> bool {
+    logs.iter().any(|log| {
+        log.address == pool
+            && log.topics().first()
+                == Some(&ICurveMetaPool::TokenExchangeUnderlying::SIGNATURE_HASH)
+    })
+}
+
+impl CurvePool {
+    pub async fn new_load_on_block<M: TracingProvider>(
+        address: Address,
+        protocol: Protocol,
+        middleware: Arc<M>,
+        block: u64,
+    ) -> Result<Self, AmmError> {
+        let invariant =
+            CurveInvariant::from_protocol(protocol).ok_or(AmmError::UnsupportedProtocol)?;
+
+        let mut pool = CurvePool { address, protocol, invariant, ..Default::default() };
+        pool.populate_data(block, middleware).await?;
+
+        if !pool.data_is_populated() {
+            return Err(AmmError::NoStateError(address))
+        }
+
+        Ok(pool)
+    }
+
+    async fn populate_data<M: TracingProvider>(
+        &mut self,
+        block: u64,
+        middleware: Arc<M>,
+    ) -> Result<(), AmmError> {
+        let block = Some(block);
+        let legacy = self.load_coins(block, &middleware).await?;
+
+        for (i, token) in self.tokens.iter().enumerate() {
+            let balance = if legacy {
+                make_call_request(
+                    ICurveLegacyPool::balancesCall { i: i as i128 },
+                    &middleware,
+                    self.address,
+                    block,
+                )
+                .await?
+                ._0
+            } else {
+                make_call_request(
+                    ICurvePool::balancesCall { i: U256::from(i) },
+                    &middleware,
+                    self.address,
+                    block,
+                )
+                .await?
+                ._0
+            };
+            self.balances.push(balance);
+
+            let decimals = if *token == ETH_ADDRESS {
+                18
+            } else {
+                make_call_request(ICurvePool::decimalsCall {}, &middleware, *token, block)
+                    .await?
+                    ._0
+            };
+            self.decimals.push(decimals);
+        }
+
+        self.rates = vec![U256::from(PRECISION); self.tokens.len()];
+
+        match self.invariant {
+            CurveInvariant::StableSwap => {
+                self.amp =
+                    make_call_request(ICurvePool::ACall {}, &middleware, self.address, block)
+                        .await?
+                        ._0;
+                self.load_rates(block, &middleware).await;
+            }
+            CurveInvariant::CryptoSwap => self.load_last_prices(block, &middleware).await?,
+        }
+
+        Ok(())
+    }
+
+    /// loads all coins of the pool. returns true if the pool uses the legacy
+    /// int128 indexed getters
+    async fn load_coins<M: TracingProvider>(
+        &mut self,
+        block: Option<u64>,
+        middleware: &Arc<M>,
+    ) -> Result<bool, AmmError> {
+        let legacy = make_call_request(
+            ICurvePool::coinsCall { i: U256::ZERO },
+            middleware,
+            self.address,
+            block,
+        )
+        .await
+        .is_err();
+
+        for i in 0..MAX_COINS {
+            let coin = if legacy {
+                make_call_request(
+                    ICurveLegacyPool::coinsCall { i: i as i128 },
+                    middleware,
+                    self.address,
+                    block,
+                )
+                .await
+                .map(|r| r._0)
+            } else {
+                make_call_request(
+                    ICurvePool::coinsCall { i: U256::from(i) },
+                    middleware,
+                    self.address,
+                    block,
+                )
+                .await
+                .map(|r| r._0)
+            };
+
+            match coin {
+                Ok(coin) if !coin.is_zero() => self.tokens.push(coin),
+                _ => break,
+            }
+        }
+
+        Ok(legacy)
+    }
+
+    /// metapools price their base pool lp token at the base pool virtual price
+    /// and ng pools expose oracle rates for rebasing or yield bearing coins.
+    async fn load_rates<M: TracingProvider>(&mut self, block: Option<u64>, middleware: &Arc<M>) {
+        if let Ok(rates) =
+            make_call_request(ICurvePool::stored_ratesCall {}, middleware, self.address, block)
+                .await
+        {
+            // stored rates include the decimal normalization, strip it so that
+            // we only keep the oracle rate.
+            if rates._0.len() == self.tokens.len() {
+                self.rates = rates
+                    ._0
+                    .into_iter()
+                    .zip(&self.decimals)
+                    .map(|(rate, dec)| {
+                        rate * U256::from(10).pow(U256::from(*dec)) / U256::from(PRECISION)
+                    })
+                    .collect();
+            }
+            return
+        }
+
+        let Ok(base_pool) =
+            make_call_request(ICurvePool::base_poolCall {}, middleware, self.address, block).await
+        else {
+            return
+        };
+
+        if let Ok(vp) =
+            make_call_request(ICurvePool::get_virtual_priceCall {}, middleware, base_pool._0, block)
+                .await
+        {
+            // the base pool lp token is always the last coin of a metapool
+            if let Some(rate) = self.rates.last_mut() {
+                *rate = vp._0;
+            }
+        }
+    }
+
+    async fn load_last_prices<M: TracingProvider>(
+        &mut self,
+        block: Option<u64>,
+        middleware: &Arc<M>,
+    ) -> Result<(), AmmError> {
+        self.last_prices = vec![Rational::ONE];
+
+        if self.tokens.len() == 2 {
+            let price =
+                make_call_request(ICurvePool::last_pricesCall {}, middleware, self.address, block)
+                    .await?;
+            self.last_prices.push(price._0.to_scaled_rational(18));
+            return Ok(())
+        }
+
+        for k in 0..self.tokens.len() - 1 {
+            let price = make_call_request(
+                ICurveTriCryptoPool::last_pricesCall { k: U256::from(k) },
+                middleware,
+                self.address,
+                block,
+            )
+            .await?;
+            self.last_prices.push(price._0.to_scaled_rational(18));
+        }
+
+        Ok(())
+    }
+
+    pub fn data_is_populated(&self) -> bool {
+        self.tokens.len() >= 2
+            && self.balances.len() == self.tokens.len()
+            && self.balances.iter().all(|b| !b.is_zero())
+            && match self.invariant {
+                CurveInvariant::StableSwap => !self.amp.is_zero(),
+                CurveInvariant::CryptoSwap => self.last_prices.len() == self.tokens.len(),
+            }
+    }
+
+    fn index_of(&self, token: Address) -> Option<usize> {
+        self.tokens.iter().position(|t| *t == token)
+    }
+
+    /// balances normalized to 18 decimals with the rate multiplier applied
+    fn xp(&self) -> Vec<U256> {
+        self.balances
+            .iter()
+            .zip(&self.decimals)
+            .zip(&self.rates)
+            .map(|((balance, dec), rate)| {
+                *balance * U256::from(10).pow(U256::from(18u64.saturating_sub(*dec as u64))) * rate
+                    / U256::from(PRECISION)
+            })
+            .collect()
+    }
+
+    /// price of the base token denominated in the quote token
+    pub fn calculate_price_for(
+        &self,
+        base: Address,
+        quote: Address,
+    ) -> Result<Rational, ArithmeticError> {
+        let i = self
+            .index_of(base)
+            .ok_or(ArithmeticError::TokenNotInPool(base))?;
+        let j = self
+            .index_of(quote)
+            .ok_or(ArithmeticError::TokenNotInPool(quote))?;
+
+        match self.invariant {
+            CurveInvariant::StableSwap => {
+                let price = stable_swap_spot_price(&self.xp(), self.amp, i, j)?;
+                Ok(price
+                    * Rational::from_naturals(
+                        u256_to_natural(self.rates[i]),
+                        u256_to_natural(self.rates[j]),
+                    ))
+            }
+            CurveInvariant::CryptoSwap => {
+                if self.last_prices[j] == Rational::ZERO {
+                    return Err(ArithmeticError::YIsZero)
+                }
+                Ok(&self.last_prices[i] / &self.last_prices[j])
+            }
+        }
+    }
+
+    pub fn get_tvl_for(&self, base: Address, quote: Address) -> (Rational, Rational) {
+        let balance_of = |token: Address| {
+            self.index_of(token)
+                .map(|i| self.balances[i].to_scaled_rational(self.decimals[i]))
+                .unwrap_or(Rational::ZERO)
+        };
+
+        (balance_of(base), balance_of(quote))
+    }
+
+    fn apply_swap(
+        &mut self,
+        token_in: Address,
+        amount_in: Rational,
+        token_out: Address,
+        amount_out: Rational,
+    ) -> Result<(), AmmError> {
+        let (i, j) = match (self.index_of(token_in), self.index_of(token_out)) {
+            (Some(i), Some(j)) => (i, j),
+            _ if !self.is_metapool() => return Err(AmmError::SyncError(self.address)),
+            // both coins are base pool coins, the exchange only goes through the
+            // base pool
+            (None, None) => return Ok(()),
+            (Some(_), None) => {
+                let lp_out = self.underlying_to_lp(amount_out)?;
+                return self.apply_swap(token_in, amount_in, self.base_lp_token(), lp_out)
+            }
+            (None, Some(_)) => {
+                let lp_in = self.underlying_to_lp(amount_in)?;
+                return self.apply_swap(self.base_lp_token(), lp_in, token_out, amount_out)
+            }
+        };
+
+        if self.invariant == CurveInvariant::CryptoSwap && amount_in != Rational::ZERO {
+            // price of the coin sold denominated in the coin bought. we anchor
+            // on the coin bought unless coin 0 is involved
+            let rate = &amount_out / &amount_in;
+            if i == 0 {
+                if rate != Rational::ZERO {
+                    self.last_prices[j] = Rational::ONE / rate;
+                }
+            } else {
+                self.last_prices[i] = &self.last_prices[j] * rate;
+            }
+        }
+
+        self.apply_delta(token_in, amount_in, true)?;
+        self.apply_delta(token_out, amount_out, false)
+    }
+
+    fn is_metapool(&self) -> bool {
+        matches!(
+            self.protocol,
+            Protocol::CurveV1MetaPool
+                | Protocol::CurveV1MetapoolImpl
+                | Protocol::CurveV2MetaPool
+                | Protocol::CurveV2MetapoolImpl
+                | Protocol::CurvecrvUSDMetaPool
+                | Protocol::CurvecrvUSDMetapoolImpl
+        )
+    }
+
+    /// the base pool lp token is always the last coin of a metapool
+    fn base_lp_token(&self) -> Address {
+        self.tokens.last().copied().unwrap_or_default()
+    }
+
+    /// `exchange_underlying` deposits into or withdraws from the base pool, so
+    /// the metapool sees the base pool lp token instead of the underlying coin.
+    /// The amount of lp tokens is derived from the base pool virtual price,
+    /// assuming the base pool coins trade at peg with each other.
+    fn underlying_to_lp(&self, amount: Rational) -> Result<Rational, AmmError> {
+        let virtual_price = self
+            .rates
+            .last()
+            .filter(|rate| !rate.is_zero())
+            .ok_or(AmmError::SyncError(self.address))?;
+
+        Ok(amount
+            * Rational::from_naturals(
+                u256_to_natural(U256::from(PRECISION)),
+                u256_to_natural(*virtual_price),
+            ))
+    }
+
+    fn apply_delta(&mut self, token: Address, amount: Rational, add: bool) -> Result<(), AmmError> {
+        let i = self
+            .index_of(token)
+            .ok_or(AmmError::SyncError(self.address))?;
+
+        let raw =
+            to_raw_amount(amount, self.decimals[i]).ok_or(AmmError::SyncError(self.address))?;
+
+        if add {
+            self.balances[i] = self.balances[i].saturating_add(raw);
+        } else {
+            self.balances[i] = self.balances[i].saturating_sub(raw);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::{NormalizedBurn, NormalizedMint, NormalizedSwap},
+    };
+
+    use super::*;
+
+    fn e(amount: u64, decimals: u8) -> U256 {
+        U256::from(amount) * U256::from(10).pow(U256::from(decimals))
+    }
+
+    fn token(pool: &CurvePool, address: Address) -> TokenInfoWithAddress {
+        let decimals = pool.decimals[pool.index_of(address).unwrap_or_default()];
+        TokenInfoWithAddress { address, inner: TokenInfo::new(decimals, String::new()) }
+    }
+
+    fn swap(
+        pool: &CurvePool,
+        token_in: Address,
+        amount_in: Rational,
+        token_out: Address,
+        amount_out: Rational,
+    ) -> Action {
+        Action::Swap(NormalizedSwap {
+            pool: pool.address,
+            token_in: token(pool, token_in),
+            token_out: token(pool, token_out),
+            amount_in,
+            amount_out,
+            ..Default::default()
+        })
+    }
+
+    /// dai, usdc, usdt
+    fn three_pool() -> CurvePool {
+        CurvePool {
+            address:     Address::with_last_byte(1),
+            protocol:    Protocol::CurveBasePool3,
+            invariant:   CurveInvariant::StableSwap,
+            tokens:      vec![
+                Address::with_last_byte(2),
+                Address::with_last_byte(3),
+                Address::with_last_byte(4),
+            ],
+            decimals:    vec![18, 6, 6],
+            balances:    vec![e(100_000_000, 18), e(100_000_000, 6), e(100_000_000, 6)],
+            rates:       vec![U256::from(PRECISION); 3],
+            amp:         U256::from(2000),
+            last_prices: vec![],
+        }
+    }
+
+    /// lusd paired with the 3pool lp token at a virtual price of 1.02
+    fn lusd_meta_pool() -> CurvePool {
+        CurvePool {
+            address:     Address::with_last_byte(10),
+            protocol:    Protocol::CurveV1MetaPool,
+            invariant:   CurveInvariant::StableSwap,
+            tokens:      vec![Address::with_last_byte(11), Address::with_last_byte(12)],
+            decimals:    vec![18, 18],
+            balances:    vec![e(1_020_000, 18), e(1_000_000, 18)],
+            rates:       vec![U256::from(PRECISION), U256::from(1_020_000_000_000_000_000u128)],
+            amp:         U256::from(200),
+            last_prices: vec![],
+        }
+    }
+
+    /// usdt, wbtc, weth
+    fn tricrypto() -> CurvePool {
+        CurvePool {
+            address:     Address::with_last_byte(20),
+            protocol:    Protocol::CurveTriCryptoPool,
+            invariant:   CurveInvariant::CryptoSwap,
+            tokens:      vec![
+                Address::with_last_byte(21),
+                Address::with_last_byte(22),
+                Address::with_last_byte(23),
+            ],
+            decimals:    vec![6, 8, 18],
+            balances:    vec![e(60_000_000, 6), e(1_000, 8), e(20_000, 18)],
+            rates:       vec![U256::from(PRECISION); 3],
+            amp:         U256::ZERO,
+            last_prices: vec![Rational::ONE, Rational::from(60_000), Rational::from(3_000)],
+        }
+    }
+
+    #[test]
+    fn test_sync_swap_updates_balances() {
+        let mut pool = three_pool();
+        let (dai, usdc) = (pool.tokens[0], pool.tokens[1]);
+
+        let action = swap(&pool, dai, Rational::from(1_000), usdc, Rational::from(999));
+        pool.sync_from_action(action).unwrap();
+
+        assert_eq!(pool.balances[0], e(100_001_000, 18));
+        assert_eq!(pool.balances[1], e(100_000_000, 6) - e(999, 6));
+        assert_eq!(pool.balances[2], e(100_000_000, 6));
+        // dai is now the abundant coin
+        assert!(pool.calculate_price_for(dai, usdc).unwrap() < Rational::ONE);
+    }
+
+    #[test]
+    fn test_price_for_token_not_in_pool() {
+        let pool = three_pool();
+        let other = Address::with_last_byte(0xff);
+
+        assert!(matches!(
+            pool.calculate_price_for(pool.tokens[0], other),
+            Err(ArithmeticError::TokenNotInPool(token)) if token == other
+        ));
+    }
+
+    #[test]
+    fn test_sync_mint_and_burn() {
+        let mut pool = three_pool();
+        let tokens = pool
+            .tokens
+            .iter()
+            .map(|t| token(&pool, *t))
+            .collect::<Vec<_>>();
+
+        let mint = Action::Mint(NormalizedMint {
+            pool: pool.address,
+            token: tokens.clone(),
+            amount: vec![Rational::from(10), Rational::from(20), Rational::from(30)],
+            ..Default::default()
+        });
+        pool.sync_from_action(mint).unwrap();
+        assert_eq!(pool.balances, vec![e(100_000_010, 18), e(100_000_020, 6), e(100_000_030, 6)]);
+
+        let burn = Action::Burn(NormalizedBurn {
+            pool: pool.address,
+            token: tokens,
+            amount: vec![Rational::from(10), Rational::from(20), Rational::from(30)],
+            ..Default::default()
+        });
+        pool.sync_from_action(burn).unwrap();
+        assert_eq!(pool.balances, three_pool().balances);
+    }
+
+    #[test]
+    fn test_metapool_prices_lp_at_virtual_price() {
+        let pool = lusd_meta_pool();
+        let (lusd, lp) = (pool.tokens[0], pool.tokens[1]);
+
+        // the rate adjusted balances are equal, so the pool is at peg and the lp
+        // token trades at the base pool virtual price
+        assert_eq!(
+            pool.calculate_price_for(lp, lusd).unwrap(),
+            Rational::from_unsigneds(102u32, 100u32)
+        );
+        assert_eq!(
+            pool.calculate_price_for(lusd, lp).unwrap(),
+            Rational::from_unsigneds(100u32, 102u32)
+        );
+    }
+
+    #[test]
+    fn test_metapool_underlying_swap() {
+        let mut pool = lusd_meta_pool();
+        let lusd = pool.tokens[0];
+        let dai = Address::with_last_byte(2);
+
+        // dai is deposited into the 3pool and the minted lp tokens are swapped
+        let action = swap(&pool, dai, Rational::from(1_020), lusd, Rational::from(1_019));
+        pool.sync_from_action(action).unwrap();
+
+        assert_eq!(pool.balances[0], e(1_020_000, 18) - e(1_019, 18));
+        assert_eq!(pool.balances[1], e(1_001_000, 18));
+
+        // exchanges between two base pool coins don't touch the metapool
+        let usdc = Address::with_last_byte(3);
+        let action = swap(&pool, dai, Rational::from(1_000), usdc, Rational::from(1_000));
+        pool.sync_from_action(action).unwrap();
+        assert_eq!(pool.balances[1], e(1_001_000, 18));
+    }
+
+    #[test]
+    fn test_is_underlying_exchange() {
+        let pool = lusd_meta_pool();
+        let buyer = Address::with_last_byte(30);
+        let log = |address: Address, sold_id: i128| Log {
+            address,
+            data: ICurveMetaPool::TokenExchangeUnderlying {
+                buyer,
+                sold_id,
+                tokens_sold: e(1_020, 18),
+                bought_id: 0,
+                tokens_bought: e(1_019, 18),
+            }
+            .encode_log_data(),
+        };
+
+        assert!(is_underlying_exchange(pool.address, &[log(pool.address, 1)]));
+        // logged by another pool
+        assert!(!is_underlying_exchange(pool.address, &[log(three_pool().address, 1)]));
+        assert!(!is_underlying_exchange(pool.address, &[]));
+    }
+
+    #[test]
+    fn test_underlying_swap_on_plain_pool_errors() {
+        let mut pool = three_pool();
+        let dai = pool.tokens[0];
+
+        let action =
+            swap(&pool, dai, Rational::from(1_000), Address::with_last_byte(99), Rational::ONE);
+        assert!(pool.sync_from_action(action).is_err());
+    }
+
+    #[test]
+    fn test_cryptoswap_last_prices() {
+        let mut pool = tricrypto();
+        let (usdt, wbtc, weth) = (pool.tokens[0], pool.tokens[1], pool.tokens[2]);
+
+        assert_eq!(
+            pool.calculate_price_for(weth, wbtc).unwrap(),
+            Rational::from_unsigneds(1u32, 20u32)
+        );
+
+        // selling weth reprices weth against coin 0
+        let action = swap(&pool, weth, Rational::ONE, usdt, Rational::from(3_100));
+        pool.sync_from_action(action).unwrap();
+        assert_eq!(pool.last_prices[2], Rational::from(3_100));
+        assert_eq!(pool.calculate_price_for(weth, usdt).unwrap(), Rational::from(3_100));
+
+        // buying with coin 0 reprices the coin bought
+        let action =
+            swap(&pool, usdt, Rational::from(1_000), weth, Rational::from_unsigneds(1u32, 2u32));
+        pool.sync_from_action(action).unwrap();
+        assert_eq!(pool.last_prices[2], Rational::from(2_000));
+        assert_eq!(pool.last_prices[1], Rational::from(60_000));
+        assert_eq!(pool.balances[0], e(60_001_000, 6) - e(3_100, 6));
+    }
+}
@@ -65,6 +65,8 @@ pub enum ArithmeticError {
     RoundingError,
     #[error("Y is zero")]
     YIsZero,
+    #[error("token {0:?} is not in the pool")]
+    TokenNotInPool(Address),
     #[error("Sqrt price overflow")]
     SqrtPriceOverflow,
     #[error("U128 conversion error")]
@@ -73,6 +75,8 @@ pub enum ArithmeticError {
     UniswapV3MathError(#[from] UniswapV3MathError),
     #[error("v2 div by zero")]
     UniV2DivZero,
+    #[error("Swap simulation error")]
+    SwapSimulationError(#[from] SwapSimulationError),
 }
 
 #[derive(Error, Debug)]
@@ -91,6 +95,8 @@ pub enum SwapSimulationError {
     UniswapV3MathError(#[from] UniswapV3MathError),
     #[error("Liquidity underflow")]
     LiquidityUnderflow,
+    #[error("Swap moved past the loaded ticks")]
+    TickOutOfRange,
 }
 
 #[derive(Error, Debug)]
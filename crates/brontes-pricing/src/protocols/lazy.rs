@@ -14,6 +14,7 @@ use crate::{
     errors::AmmError,
     protocols::LoadState,
     types::{PairWithFirstPoolHop, PoolState},
+    uniswap_v4::V4PoolInfo,
     Protocol,
 };
 
@@ -70,6 +71,9 @@ pub struct LazyExchangeLoader<T: TracingProvider> {
     /// requests we are processing for a given block.
     req_per_block:     FastHashMap<BlockNumber, u64>,
     state_tracking:    LoadingStateTracker,
+    /// v4 pools are keyed by a address derived from the pool id, this maps
+    /// them back to the pool id so we can load their state
+    v4_pools:          FastHashMap<Address, V4PoolInfo>,
     ex:                BrontesTaskExecutor,
 }
 
@@ -81,10 +85,15 @@ impl<T: TracingProvider> LazyExchangeLoader<T> {
             pool_load_futures: MultiBlockPoolFutures::new(),
             provider,
             req_per_block: FastHashMap::default(),
+            v4_pools: FastHashMap::default(),
             ex,
         }
     }
 
+    pub fn register_v4_pool(&mut self, address: Address, info: V4PoolInfo) {
+        self.v4_pools.entry(address).or_insert(info);
+    }
+
     pub fn is_loading(&self, k: &Address) -> bool {
         self.pool_buf.contains_key(k)
     }
@@ -165,7 +174,8 @@ impl<T: TracingProvider> LazyExchangeLoader<T> {
         let provider = self.provider.clone();
         self.add_state_trackers(block_number, id, address, pair);
 
-        let fut = ex_type.try_load_state(address, provider, block_number, pool_pair, pair);
+        let v4_pool = self.v4_pools.get(&address).copied();
+        let fut = ex_type.try_load_state(address, provider, block_number, pool_pair, pair, v4_pool);
         self.pool_load_futures.add_future(
             block_number,
             Box::pin(self.ex.handle().spawn(async move {
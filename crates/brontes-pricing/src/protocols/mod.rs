@@ -1,23 +1,36 @@
+pub mod balancer_v2;
+pub mod curve;
 pub mod errors;
 pub mod lazy;
 pub mod uniswap_v2;
 pub mod uniswap_v3;
+pub mod uniswap_v4;
 
 use std::{future::Future, sync::Arc};
 
-use alloy_primitives::{Address, Log};
+use alloy_primitives::{Address, Log, U256};
 use async_trait::async_trait;
-use brontes_types::{normalized_actions::Action, pair::Pair, traits::TracingProvider};
+use brontes_types::{
+    normalized_actions::Action, pair::Pair, structured_trace::AccountStateDiff,
+    traits::TracingProvider,
+};
 pub use brontes_types::{queries::make_call_request, Protocol};
-use malachite::Rational;
+use malachite::{
+    num::{arithmetic::traits::Pow, conversion::traits::RoundingFrom},
+    rounding_modes::RoundingMode,
+    Natural, Rational,
+};
 use tracing::{debug, warn};
 
 use crate::{
+    balancer_v2::BalancerV2Pool,
+    curve::{CurveInvariant, CurvePool},
     lazy::{PoolFetchError, PoolFetchSuccess},
     protocols::errors::{AmmError, ArithmeticError},
     types::PairWithFirstPoolHop,
     uniswap_v2::UniswapV2Pool,
     uniswap_v3::UniswapV3Pool,
+    uniswap_v4::{UniswapV4Pool, V4PoolInfo},
     LoadResult, PoolState,
 };
 
@@ -28,10 +41,27 @@ pub trait UpdatableProtocol {
     fn calculate_price(&self, base_token: Address) -> Result<Rational, ArithmeticError>;
     fn sync_from_action(&mut self, action: Action) -> Result<(), AmmError>;
     fn sync_from_log(&mut self, log: Log) -> Result<(), AmmError>;
+    /// Syncs the pool from the storage it had at the end of the transaction.
+    /// Pools that can't be read from their storage are left as is
+    fn sync_from_state_diff(&mut self, _diff: &AccountStateDiff) -> Result<(), AmmError> {
+        Ok(())
+    }
+}
+
+/// converts a decimal scaled amount back into the raw token amount
+pub fn to_raw_amount(amount: Rational, decimals: u8) -> Option<U256> {
+    let raw = amount * Rational::from(Natural::from(10u8).pow(decimals as u64));
+    let raw = Natural::rounding_from(raw, RoundingMode::Floor).0;
+    U256::checked_from_limbs_slice(&raw.to_limbs_asc())
 }
 
 pub trait LoadState {
     fn has_state_updater(&self) -> bool;
+    /// pools that can hold more than two tokens. these only get registered
+    /// with their first pair, so other pairs are added as we see them traded
+    fn is_multi_token_pool(&self) -> bool;
+    /// `v4_pool` is the pool id and token ordering of the pool, only needed
+    /// for uniswap v4 pools
     fn try_load_state<T: TracingProvider>(
         self,
         address: Address,
@@ -39,6 +69,7 @@ pub trait LoadState {
         block_number: u64,
         pool_pair: Pair,
         full_pair: PairWithFirstPoolHop,
+        v4_pool: Option<V4PoolInfo>,
     ) -> impl Future<Output = Result<PoolFetchSuccess, PoolFetchError>> + Send;
 }
 
@@ -52,7 +83,13 @@ impl LoadState for Protocol {
                 | Self::SushiSwapV3
                 | Self::PancakeSwapV2
                 | Self::PancakeSwapV3
-        )
+                | Self::BalancerV2
+                | Self::UniswapV4
+        ) || CurveInvariant::from_protocol(*self).is_some()
+    }
+
+    fn is_multi_token_pool(&self) -> bool {
+        matches!(self, Self::BalancerV2) || CurveInvariant::from_protocol(*self).is_some()
     }
 
     async fn try_load_state<T: TracingProvider>(
@@ -62,6 +99,7 @@ impl LoadState for Protocol {
         block_number: u64,
         pool_pair: Pair,
         fp: PairWithFirstPoolHop,
+        v4_pool: Option<V4PoolInfo>,
     ) -> Result<PoolFetchSuccess, PoolFetchError> {
         match self {
             Self::UniswapV2 | Self::SushiSwapV2 | Self::PancakeSwapV2 => {
@@ -93,14 +131,18 @@ impl LoadState for Protocol {
                 ))
             }
             Self::UniswapV3 | Self::SushiSwapV3 | Self::PancakeSwapV3 => {
-                let (pool, res) = if let Ok(pool) =
-                    UniswapV3Pool::new_from_address(address, block_number - 1, provider.clone())
-                        .await
+                let (pool, res) = if let Ok(pool) = UniswapV3Pool::new_from_address(
+                    address,
+                    self,
+                    block_number - 1,
+                    provider.clone(),
+                )
+                .await
                 {
                     (pool, LoadResult::Ok)
                 } else {
                     (
-                        UniswapV3Pool::new_from_address(address, block_number, provider)
+                        UniswapV3Pool::new_from_address(address, self, block_number, provider)
                             .await
                             .map_err(|e| {
                                 debug!(?pool_pair, protocol=%self, %block_number, pool_address=?address, err=%e, "lazy load failed");
@@ -120,6 +162,103 @@ impl LoadState for Protocol {
                     res,
                 ))
             }
+            Self::BalancerV2 => {
+                let (pool, res) = if let Ok(pool) =
+                    BalancerV2Pool::new_load_on_block(address, provider.clone(), block_number - 1)
+                        .await
+                {
+                    (pool, LoadResult::Ok)
+                } else {
+                    (
+                        BalancerV2Pool::new_load_on_block(address, provider, block_number)
+                            .await
+                            .map_err(|e| {
+                                debug!(?pool_pair, protocol=%self, %block_number, pool_address=?address, err=%e, "lazy load failed");
+                                (address, Protocol::BalancerV2, block_number, pool_pair, fp, e)
+                            })?,
+                        LoadResult::PoolInitOnBlock,
+                    )
+                };
+
+                Ok((
+                    block_number,
+                    address,
+                    PoolState::new(
+                        crate::types::PoolVariants::BalancerV2(Box::new(pool)),
+                        block_number,
+                    ),
+                    res,
+                ))
+            }
+            Self::UniswapV4 => {
+                let Some(info) = v4_pool else {
+                    debug!(?pool_pair, %block_number, pool_address=?address, "no pool id for v4 pool");
+                    return Err((
+                        address,
+                        Protocol::UniswapV4,
+                        block_number,
+                        pool_pair,
+                        fp,
+                        AmmError::NoStateError(address),
+                    ))
+                };
+
+                let (pool, res) = if let Ok(pool) = UniswapV4Pool::new_load_on_block(
+                    address,
+                    info,
+                    provider.clone(),
+                    block_number - 1,
+                )
+                .await
+                {
+                    (pool, LoadResult::Ok)
+                } else {
+                    (
+                        UniswapV4Pool::new_load_on_block(address, info, provider, block_number)
+                            .await
+                            .map_err(|e| {
+                                debug!(?pool_pair, protocol=%self, %block_number, pool_address=?address, err=%e, "lazy load failed");
+                                (address, Protocol::UniswapV4, block_number, pool_pair, fp, e)
+                            })?,
+                        LoadResult::PoolInitOnBlock,
+                    )
+                };
+
+                Ok((
+                    block_number,
+                    address,
+                    PoolState::new(
+                        crate::types::PoolVariants::UniswapV4(Box::new(pool)),
+                        block_number,
+                    ),
+                    res,
+                ))
+            }
+            rest if CurveInvariant::from_protocol(rest).is_some() => {
+                let (pool, res) = if let Ok(pool) =
+                    CurvePool::new_load_on_block(address, rest, provider.clone(), block_number - 1)
+                        .await
+                {
+                    (pool, LoadResult::Ok)
+                } else {
+                    (
+                        CurvePool::new_load_on_block(address, rest, provider, block_number)
+                            .await
+                            .map_err(|e| {
+                                debug!(?pool_pair, protocol=%self, %block_number, pool_address=?address, err=%e, "lazy load failed");
+                                (address, rest, block_number, pool_pair, fp, e)
+                            })?,
+                        LoadResult::PoolInitOnBlock,
+                    )
+                };
+
+                Ok((
+                    block_number,
+                    address,
+                    PoolState::new(crate::types::PoolVariants::Curve(Box::new(pool)), block_number),
+                    res,
+                ))
+            }
             rest => {
                 warn!(protocol=?rest, "no state updater is build for");
                 Err((address, self, block_number, pool_pair, fp, AmmError::UnsupportedProtocol))
@@ -3,12 +3,15 @@ pub mod factory;
 
 use std::sync::Arc;
 
-use alloy_primitives::{Address, FixedBytes, Log, B256};
+use alloy_primitives::{Address, FixedBytes, Log, B256, U256};
 use alloy_rlp::{RlpDecodable, RlpEncodable};
 use alloy_sol_macro::sol;
 use alloy_sol_types::SolEvent;
 use async_trait::async_trait;
-use brontes_types::{normalized_actions::Action, traits::TracingProvider, ToScaledRational};
+use brontes_types::{
+    normalized_actions::Action, structured_trace::AccountStateDiff, traits::TracingProvider,
+    ToScaledRational,
+};
 use malachite::{
     num::{arithmetic::traits::Pow, basic::traits::Zero},
     Natural, Rational,
@@ -47,6 +50,8 @@ pub const SYNC_EVENT_SIGNATURE: B256 = FixedBytes([
     28, 65, 30, 154, 150, 224, 113, 36, 28, 47, 33, 247, 114, 107, 23, 174, 137, 227, 202, 180,
     199, 139, 229, 14, 6, 43, 3, 169, 255, 251, 186, 209,
 ]);
+/// `reserve0`, `reserve1` and `blockTimestampLast` are packed into this slot
+pub const RESERVES_SLOT: U256 = U256::from_limbs([8, 0, 0, 0]);
 
 #[derive(
     Debug, Clone, Default, Serialize, Deserialize, RlpEncodable, RlpDecodable, Hash, PartialEq, Eq,
@@ -87,6 +92,16 @@ impl UpdatableProtocol for UniswapV2Pool {
         }
     }
 
+    fn sync_from_state_diff(&mut self, diff: &AccountStateDiff) -> Result<(), AmmError> {
+        if let Some(reserves) = diff.slot(RESERVES_SLOT) {
+            let mask = (U256::from(1) << 112) - U256::from(1);
+            self.reserve_0 = (reserves.post & mask).to();
+            self.reserve_1 = ((reserves.post >> 112) & mask).to();
+        }
+
+        Ok(())
+    }
+
     //Calculates base/quote, meaning the price of base token per quote (ie.
     // exchange rate is X base per 1 quote)
     fn calculate_price(&self, base_token: Address) -> Result<Rational, ArithmeticError> {
@@ -193,9 +193,9 @@ pub async fn get_uniswap_v3_tick_data_batch_request<M: TracingProvider>(
     let res = middleware
         .eth_call(req, block_number.map(Into::into), None, None)
         .await
-        .unwrap();
+        .map_err(|e| eyre::eyre!("v3 tick data fetch call failed, err={}", e))?;
 
-    let return_data = tick_constructorCall::abi_decode_returns(&res, false).unwrap();
+    let return_data = tick_constructorCall::abi_decode_returns(&res, false)?;
 
     Ok((return_data._0, return_data._1))
 }
@@ -7,19 +7,23 @@ use alloy_sol_macro::sol;
 use alloy_sol_types::{SolCall, SolEvent};
 use async_trait::async_trait;
 use brontes_types::{
-    normalized_actions::Action, traits::TracingProvider, FastHashMap, ToScaledRational,
+    normalized_actions::Action, structured_trace::AccountStateDiff, traits::TracingProvider,
+    FastHashMap, Protocol, ToScaledRational,
 };
 use malachite::Rational;
 use serde::{Deserialize, Serialize};
 
-use self::batch_request::get_v3_pool_data_batch_request;
-use super::make_call_request;
-#[cfg(feature = "uni-v3-ticks")]
-use crate::uniswap_v3::batch_request::get_uniswap_v3_tick_data_batch_request;
-#[cfg(feature = "uni-v3-ticks")]
-use crate::uniswap_v3::uniswap_v3_math::tick_math::{MAX_TICK, MIN_TICK};
+use self::{
+    batch_request::{get_uniswap_v3_tick_data_batch_request, get_v3_pool_data_batch_request},
+    uniswap_v3_math::{
+        swap_math::compute_swap_step,
+        tick_bitmap::{next_initialized_tick_within_one_word, position},
+        tick_math::{get_sqrt_ratio_at_tick, get_tick_at_sqrt_ratio, MAX_TICK, MIN_TICK},
+    },
+};
+use super::{make_call_request, to_raw_amount};
 use crate::{
-    errors::{AmmError, ArithmeticError, EventLogError},
+    errors::{AmmError, ArithmeticError, EventLogError, SwapSimulationError},
     UpdatableProtocol,
 };
 
@@ -101,6 +105,17 @@ pub const MIN_SQRT_RATIO: U256 = U256::from_limbs([4295128739, 0, 0, 0]);
 pub const MAX_SQRT_RATIO: U256 =
     U256::from_limbs([6743328256752651558, 17280870778742802505, 4294805859, 0]);
 pub const POPULATE_TICK_DATA_STEP: u64 = 100000;
+/// amount of tick bitmap steps loaded on each side of the current tick
+pub const TICKS_TO_LOAD: u16 = 100;
+
+/// `slot0` starts with `sqrtPriceX96` followed by `tick`. The rest of the
+/// struct and the slots after it differ between forks, so only these two are
+/// read from a state diff of any fork
+pub const SLOT0_SLOT: U256 = U256::ZERO;
+/// the active `uint128` liquidity of Uniswap V3 itself, after slot0, the two
+/// fee growth globals and the protocol fees. PancakeSwap V3's slot0 spans two
+/// slots, which moves its liquidity to slot 5
+pub const LIQUIDITY_SLOT: U256 = U256::from_limbs([4, 0, 0, 0]);
 
 pub const U256_TWO: U256 = U256::from_limbs([2, 0, 0, 0]);
 pub const Q128: U256 = U256::from_limbs([0, 0, 1, 0]);
@@ -126,6 +141,10 @@ pub const MINT_EVENT_SIGNATURE: B256 = FixedBytes([
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct UniswapV3Pool {
     pub address:          Address,
+    /// the fork the pool belongs to, which decides what can be read from its
+    /// storage
+    #[serde(default)]
+    pub protocol:         Protocol,
     pub token_a:          Address,
     pub token_a_decimals: u8,
     pub token_b:          Address,
@@ -137,6 +156,10 @@ pub struct UniswapV3Pool {
     pub tick_spacing:     i32,
     pub tick_bitmap:      FastHashMap<i16, U256>,
     pub ticks:            FastHashMap<i32, Info>,
+    /// lowest and highest tick the bitmap has been loaded for, swaps can only
+    /// be simulated within this range
+    #[serde(default)]
+    pub loaded_ticks:     Option<(i32, i32)>,
 
     // non v3 native state
     pub reserve_0: U256,
@@ -182,6 +205,37 @@ impl UpdatableProtocol for UniswapV3Pool {
         Ok(())
     }
 
+    fn sync_from_state_diff(&mut self, diff: &AccountStateDiff) -> Result<(), AmmError> {
+        if let Some(slot0) = diff.slot(SLOT0_SLOT) {
+            let sqrt_price = slot0.post & ((U256::from(1) << 160) - U256::from(1));
+            // sign extend the int24
+            let tick = (((slot0.post >> 160).wrapping_to::<u32>() << 8) as i32) >> 8;
+
+            // guards against pools that don't share the layout. A swap that ends on
+            // a tick boundary leaves the tick one below the price's tick
+            if get_tick_at_sqrt_ratio(sqrt_price)
+                .is_ok_and(|price_tick| (price_tick - tick).abs() <= 1)
+            {
+                self.sqrt_price = sqrt_price;
+                self.tick = tick;
+            }
+        }
+
+        // crossing a tick or minting and burning around the current tick changes
+        // the active liquidity, the latter without touching slot0. Only read on
+        // the layout it has been checked against, forks keep it in another slot
+        if self.protocol != Protocol::UniswapV3 {
+            return Ok(())
+        }
+        if let Some(liquidity) = diff.slot(LIQUIDITY_SLOT) {
+            if liquidity.post >> 128 == U256::ZERO {
+                self.liquidity = liquidity.post.to();
+            }
+        }
+
+        Ok(())
+    }
+
     fn tokens(&self) -> Vec<Address> {
         vec![self.token_a, self.token_b]
     }
@@ -221,11 +275,13 @@ impl UniswapV3Pool {
     // Creates a new instance of the pool from the pair address
     pub async fn new_from_address<M: 'static + TracingProvider>(
         pair_address: Address,
+        protocol: Protocol,
         block_number: u64,
         middleware: Arc<M>,
     ) -> Result<Self, AmmError> {
         let mut pool = UniswapV3Pool {
             address: pair_address,
+            protocol,
             token_a: Address::ZERO,
             token_a_decimals: 0,
             token_b: Address::ZERO,
@@ -240,62 +296,182 @@ impl UniswapV3Pool {
             ..Default::default()
         };
 
-        //We need to get tick spacing before populating tick data because tick spacing
-        // can not be uninitialized when syncing burn and mint logs
-        #[cfg(feature = "uni-v3-ticks")]
-        pool.sync_ticks_around_current(block_number, 100, middleware.clone())
-            .await;
-
-        pool.populate_data(Some(block_number), middleware).await?;
+        pool.populate_data(Some(block_number), middleware.clone())
+            .await?;
 
         if !pool.data_is_populated() {
             return Err(AmmError::NoStateError(pair_address))
         }
 
+        // tick spacing is loaded with the pool data, so the ticks can only be
+        // fetched after. If this fails we still have the spot price to fall back on
+        if let Err(e) = pool
+            .sync_ticks_around_current(block_number, TICKS_TO_LOAD, middleware)
+            .await
+        {
+            tracing::debug!(?pair_address, ?e, "failed to load v3 ticks");
+        }
+
         Ok(pool)
     }
 
-    #[cfg(feature = "uni-v3-ticks")]
+    /// Loads the initialized ticks walking `tick_amount` bitmap steps down and
+    /// up from the current tick.
     pub async fn sync_ticks_around_current<M: 'static + TracingProvider>(
         &mut self,
         block: u64,
-        tick_amount: i32,
+        tick_amount: u16,
         provider: Arc<M>,
-    ) {
-        if tick_amount.is_negative() {
-            return
-        }
-
-        if self.tick == 0 {
-            self.tick = self.get_tick(provider.clone(), block).await.unwrap();
+    ) -> Result<(), AmmError> {
+        if self.tick_spacing == 0 {
+            return Err(AmmError::PoolDataError)
         }
 
-        let cur_tick = self.tick;
-
-        let (start_tick, am) = if self.tick - tick_amount < MIN_TICK {
-            (MIN_TICK, tick_amount * 2)
-        } else if self.tick + tick_amount > MAX_TICK {
-            // cur_tick - max_tick_delta we can support
-            let left_shift = MAX_TICK - self.tick;
-            (self.tick - left_shift, left_shift * 2)
-        } else {
-            (cur_tick - tick_amount, tick_amount * 2)
-        };
-        let ticks = get_uniswap_v3_tick_data_batch_request(
+        let (lower, _) = get_uniswap_v3_tick_data_batch_request(
             self,
-            start_tick,
+            self.tick,
             true,
-            am as u16,
+            tick_amount,
+            Some(block),
+            provider.clone(),
+        )
+        .await?;
+        let (upper, _) = get_uniswap_v3_tick_data_batch_request(
+            self,
+            self.tick,
+            false,
+            tick_amount,
             Some(block),
             provider,
         )
-        .await
-        .unwrap()
-        .0;
+        .await?;
+
+        let lowest = lower.iter().map(|t| t.tick).min().unwrap_or(MIN_TICK);
+        let highest = upper.iter().map(|t| t.tick).max().unwrap_or(MAX_TICK);
+
+        for tick in lower.into_iter().chain(upper).filter(|t| t.initialized) {
+            self.ticks.insert(
+                tick.tick,
+                Info::new(tick.liquidityNet.unsigned_abs(), tick.liquidityNet, true),
+            );
+            self.set_tick_initialized(tick.tick);
+        }
+        self.loaded_ticks = Some((lowest.max(MIN_TICK), highest.min(MAX_TICK)));
+
+        Ok(())
+    }
+
+    /// Simulates an exact input swap of `amount_in` through the loaded ticks
+    /// and returns the amount out. Errors if the swap would move the price
+    /// past the ticks that have been loaded.
+    pub fn simulate_swap(
+        &self,
+        token_in: Address,
+        amount_in: U256,
+    ) -> Result<U256, SwapSimulationError> {
+        let Some((lowest_tick, highest_tick)) = self.loaded_ticks else {
+            return Err(SwapSimulationError::TickOutOfRange)
+        };
+
+        let zero_for_one = token_in == self.token_a;
+        let sqrt_price_limit = if zero_for_one {
+            MIN_SQRT_RATIO + U256::from(1)
+        } else {
+            MAX_SQRT_RATIO - U256::from(1)
+        };
+
+        let mut sqrt_price = self.sqrt_price;
+        let mut tick = self.tick;
+        let mut liquidity = self.liquidity;
+        let mut amount_remaining = amount_in;
+        let mut amount_out = U256::ZERO;
+
+        while !amount_remaining.is_zero() && sqrt_price != sqrt_price_limit {
+            let sqrt_price_start = sqrt_price;
+
+            let (tick_next, initialized) = next_initialized_tick_within_one_word(
+                &self.tick_bitmap,
+                tick,
+                self.tick_spacing,
+                zero_for_one,
+            )?;
+            let tick_next = tick_next.clamp(MIN_TICK, MAX_TICK);
+
+            if tick_next < lowest_tick || tick_next > highest_tick {
+                return Err(SwapSimulationError::TickOutOfRange)
+            }
+
+            let sqrt_price_next = get_sqrt_ratio_at_tick(tick_next)?;
+            let sqrt_price_target = if zero_for_one {
+                sqrt_price_next.max(sqrt_price_limit)
+            } else {
+                sqrt_price_next.min(sqrt_price_limit)
+            };
+
+            let (next_sqrt_price, step_in, step_out, fee_amount) = compute_swap_step(
+                sqrt_price,
+                sqrt_price_target,
+                liquidity,
+                amount_remaining,
+                self.fee,
+            )?;
+
+            sqrt_price = next_sqrt_price;
+            amount_remaining = amount_remaining.saturating_sub(step_in + fee_amount);
+            amount_out += step_out;
+
+            if sqrt_price == sqrt_price_next {
+                if initialized {
+                    let liquidity_net = self
+                        .ticks
+                        .get(&tick_next)
+                        .map(|info| info.liquidity_net)
+                        .unwrap_or_default();
+                    let liquidity_net = if zero_for_one { -liquidity_net } else { liquidity_net };
+
+                    liquidity = if liquidity_net < 0 {
+                        liquidity
+                            .checked_sub(liquidity_net.unsigned_abs())
+                            .ok_or(SwapSimulationError::LiquidityUnderflow)?
+                    } else {
+                        liquidity + liquidity_net as u128
+                    };
+                }
+
+                tick = if zero_for_one { tick_next - 1 } else { tick_next };
+            } else if sqrt_price != sqrt_price_start {
+                tick = get_tick_at_sqrt_ratio(sqrt_price)?;
+            }
+        }
+
+        Ok(amount_out)
+    }
+
+    /// Execution price of selling `amount_in` of `base_token` into the pool,
+    /// quoted the same way as [`UpdatableProtocol::calculate_price`]. Unlike
+    /// the spot price this accounts for the fee and every tick that is crossed.
+    pub fn calculate_execution_price(
+        &self,
+        base_token: Address,
+        amount_in: &Rational,
+    ) -> Result<Rational, ArithmeticError> {
+        let (in_decimals, out_decimals) = if base_token == self.token_a {
+            (self.token_a_decimals, self.token_b_decimals)
+        } else {
+            (self.token_b_decimals, self.token_a_decimals)
+        };
 
-        for tick in ticks {
-            self.update_tick(tick.tick, tick.liquidityNet, tick.initialized);
+        let raw_amount_in =
+            to_raw_amount(amount_in.clone(), in_decimals).ok_or(ArithmeticError::RoundingError)?;
+        if raw_amount_in.is_zero() {
+            return self.calculate_price(base_token)
         }
+
+        let amount_out = self
+            .simulate_swap(base_token, raw_amount_in)?
+            .to_scaled_rational(out_decimals);
+
+        Ok(amount_out / raw_amount_in.to_scaled_rational(in_decimals))
     }
 
     pub fn fee(&self) -> u32 {
@@ -343,7 +519,6 @@ impl UniswapV3Pool {
         self.reserve_0 -= burn_event.amount0;
         self.reserve_1 -= burn_event.amount1;
 
-        #[cfg(feature = "uni-v3-ticks")]
         self.modify_position(
             burn_event.tickLower,
             burn_event.tickUpper,
@@ -359,7 +534,6 @@ impl UniswapV3Pool {
         self.reserve_0 += mint_event.amount0;
         self.reserve_1 += mint_event.amount1;
 
-        #[cfg(feature = "uni-v3-ticks")]
         self.modify_position(mint_event.tickLower, mint_event.tickUpper, mint_event.amount as i128);
 
         Ok(())
@@ -374,9 +548,10 @@ impl UniswapV3Pool {
         if liquidity_delta != 0 {
             //if the tick is between the tick lower and tick upper, update the liquidity
             // between the ticks
-            if self.tick > tick_lower && self.tick < tick_upper {
+            if self.tick >= tick_lower && self.tick < tick_upper {
                 self.liquidity = if liquidity_delta < 0 {
-                    self.liquidity - ((-liquidity_delta) as u128)
+                    self.liquidity
+                        .saturating_sub(liquidity_delta.unsigned_abs())
                 } else {
                     self.liquidity + (liquidity_delta as u128)
                 }
@@ -385,28 +560,18 @@ impl UniswapV3Pool {
     }
 
     pub fn update_position(&mut self, tick_lower: i32, tick_upper: i32, liquidity_delta: i128) {
-        let mut flipped_lower = false;
-        let mut flipped_upper = false;
-
-        if liquidity_delta != 0 {
-            flipped_lower = self.update_tick(tick_lower, liquidity_delta, false);
-            flipped_upper = self.update_tick(tick_upper, liquidity_delta, true);
-            if flipped_lower {
-                self.flip_tick(tick_lower, self.tick_spacing);
-            }
-            if flipped_upper {
-                self.flip_tick(tick_upper, self.tick_spacing);
-            }
+        if liquidity_delta == 0 {
+            return
         }
 
-        if liquidity_delta < 0 {
-            if flipped_lower {
-                self.ticks.remove(&tick_lower);
-            }
+        self.update_tick(tick_lower, liquidity_delta, false);
+        self.update_tick(tick_upper, liquidity_delta, true);
 
-            if flipped_upper {
-                self.ticks.remove(&tick_upper);
-            }
+        // we only know the net liquidity of the loaded ticks, so ticks are never
+        // uninitialized on burns. Crossing a tick without net liquidity is a noop
+        if liquidity_delta > 0 {
+            self.set_tick_initialized(tick_lower);
+            self.set_tick_initialized(tick_upper);
         }
     }
 
@@ -424,7 +589,7 @@ impl UniswapV3Pool {
         let liquidity_gross_before = info.liquidity_gross;
 
         let liquidity_gross_after = if liquidity_delta < 0 {
-            liquidity_gross_before - ((-liquidity_delta) as u128)
+            liquidity_gross_before.saturating_sub(liquidity_delta.unsigned_abs())
         } else {
             liquidity_gross_before + (liquidity_delta as u128)
         };
@@ -449,15 +614,13 @@ impl UniswapV3Pool {
         flipped
     }
 
-    pub fn flip_tick(&mut self, tick: i32, tick_spacing: i32) {
-        let (word_pos, bit_pos) = uniswap_v3_math::tick_bitmap::position(tick / tick_spacing);
-        let mask = U256::from(1) << bit_pos;
-
-        if let Some(word) = self.tick_bitmap.get_mut(&word_pos) {
-            *word ^= mask;
-        } else {
-            self.tick_bitmap.insert(word_pos, mask);
+    pub fn set_tick_initialized(&mut self, tick: i32) {
+        if self.tick_spacing == 0 {
+            return
         }
+
+        let (word_pos, bit_pos) = position(tick / self.tick_spacing);
+        *self.tick_bitmap.entry(word_pos).or_default() |= U256::from(1) << bit_pos;
     }
 
     pub fn sync_from_swap_log(&mut self, log: Log) -> Result<(), AmmError> {
@@ -514,3 +677,143 @@ pub struct Tick {
     pub seconds_outside: u32,
     pub initialized: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::structured_trace::StorageSlotDiff;
+    use malachite::num::basic::traits::One;
+
+    use super::*;
+
+    const LIQUIDITY: i128 = 2_000_000_000_000_000_000;
+
+    /// pool at a price of 1 with a position from tick -600 to 600 and a wider
+    /// one from -6000 to 6000
+    fn pool() -> UniswapV3Pool {
+        let mut pool = UniswapV3Pool {
+            protocol: Protocol::UniswapV3,
+            token_a: Address::with_last_byte(1),
+            token_a_decimals: 18,
+            token_b: Address::with_last_byte(2),
+            token_b_decimals: 18,
+            liquidity: 2 * LIQUIDITY as u128,
+            sqrt_price: U256::from(1) << 96,
+            fee: 3000,
+            tick: 0,
+            tick_spacing: 60,
+            loaded_ticks: Some((-12000, 12000)),
+            ..Default::default()
+        };
+        pool.ticks
+            .insert(-600, Info::new(LIQUIDITY as u128, LIQUIDITY, true));
+        pool.ticks
+            .insert(600, Info::new(LIQUIDITY as u128, -LIQUIDITY, true));
+        pool.ticks
+            .insert(-6000, Info::new(LIQUIDITY as u128, LIQUIDITY, true));
+        pool.ticks
+            .insert(6000, Info::new(LIQUIDITY as u128, -LIQUIDITY, true));
+        [-6000, -600, 600, 6000]
+            .into_iter()
+            .for_each(|tick| pool.set_tick_initialized(tick));
+
+        pool
+    }
+
+    #[test]
+    fn test_execution_price_within_range() {
+        let pool = pool();
+        let amount_in = Rational::from_unsigneds(1u64, 1000u64);
+
+        let price = pool
+            .calculate_execution_price(pool.token_a, &amount_in)
+            .unwrap();
+
+        // the fee and price impact are paid on top of the spot price
+        assert!(price < Rational::ONE);
+        assert!(price > Rational::from_unsigneds(996u64, 1000u64));
+    }
+
+    #[test]
+    fn test_execution_price_crosses_ticks() {
+        let pool = pool();
+        let small = pool
+            .calculate_execution_price(pool.token_b, &Rational::from_unsigneds(1u64, 1000u64))
+            .unwrap();
+        // moves the price past tick 600 where half of the liquidity drops out
+        let large = pool
+            .calculate_execution_price(pool.token_b, &Rational::from_unsigneds(1u64, 2u64))
+            .unwrap();
+
+        assert!(large < small);
+    }
+
+    #[test]
+    fn test_simulate_swap_outside_loaded_ticks() {
+        let mut pool = pool();
+
+        // draining both positions moves the price past the loaded ticks
+        let amount_in = U256::from(1_000_000_000_000_000_000_000u128);
+        assert!(matches!(
+            pool.simulate_swap(pool.token_a, amount_in),
+            Err(SwapSimulationError::TickOutOfRange)
+        ));
+
+        pool.loaded_ticks = None;
+        assert!(pool.simulate_swap(pool.token_a, U256::from(1)).is_err());
+    }
+
+    fn slot_diff(slot: U256, value: U256) -> AccountStateDiff {
+        AccountStateDiff {
+            address: Address::ZERO,
+            balance: None,
+            storage: vec![StorageSlotDiff { slot, pre: U256::ZERO, post: value }],
+        }
+    }
+
+    #[test]
+    fn test_sync_from_state_diff() {
+        let mut pool = pool();
+        let slot0 = |value: U256| slot_diff(SLOT0_SLOT, value);
+
+        let sqrt_price = get_sqrt_ratio_at_tick(-600).unwrap();
+        let tick = U256::from((-600i32 as u32) & 0xffffff) << 160;
+        // the unlocked flag sits in the highest byte
+        let unlocked = U256::from(1) << 248;
+        pool.sync_from_state_diff(&slot0(sqrt_price | tick | unlocked))
+            .unwrap();
+
+        assert_eq!(pool.sqrt_price, sqrt_price);
+        assert_eq!(pool.tick, -600);
+
+        // a slot that doesn't hold a matching price and tick is ignored
+        pool.sync_from_state_diff(&slot0(U256::from(1) << 200))
+            .unwrap();
+        assert_eq!(pool.tick, -600);
+    }
+
+    #[test]
+    fn test_sync_liquidity_from_state_diff() {
+        let mut pool = pool();
+
+        pool.sync_from_state_diff(&slot_diff(LIQUIDITY_SLOT, U256::from(LIQUIDITY)))
+            .unwrap();
+        assert_eq!(pool.liquidity, LIQUIDITY as u128);
+        // the price is left alone when slot0 didn't change
+        assert_eq!(pool.sqrt_price, U256::from(1) << 96);
+
+        // a slot that doesn't fit a uint128 is ignored
+        pool.sync_from_state_diff(&slot_diff(LIQUIDITY_SLOT, U256::from(1) << 200))
+            .unwrap();
+        assert_eq!(pool.liquidity, LIQUIDITY as u128);
+    }
+
+    #[test]
+    fn test_fork_liquidity_not_synced_from_state_diff() {
+        // slot 4 holds the protocol fees on PancakeSwap V3
+        let mut pool = UniswapV3Pool { protocol: Protocol::PancakeSwapV3, ..pool() };
+
+        pool.sync_from_state_diff(&slot_diff(LIQUIDITY_SLOT, U256::from(1_000)))
+            .unwrap();
+        assert_eq!(pool.liquidity, 2 * LIQUIDITY as u128);
+    }
+}
@@ -0,0 +1,88 @@
+use alloy_primitives::{U256, U512};
+
+use super::error::UniswapV3MathError;
+
+// Calculates floor(a×b÷denominator) with full precision. Throws if result
+// overflows a uint256 or denominator == 0
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256, UniswapV3MathError> {
+    if denominator.is_zero() {
+        return Err(UniswapV3MathError::DenominatorIsZero);
+    }
+
+    let product: U512 = a.widening_mul(b);
+    let (quotient, _) = product.div_rem(U512::from(denominator));
+
+    U256::uint_try_from(quotient).map_err(|_| UniswapV3MathError::DenominatorIsLteProdOne)
+}
+
+// Calculates ceil(a×b÷denominator) with full precision. Throws if result
+// overflows a uint256 or denominator == 0
+pub fn mul_div_rounding_up(
+    a: U256,
+    b: U256,
+    denominator: U256,
+) -> Result<U256, UniswapV3MathError> {
+    let result = mul_div(a, b, denominator)?;
+
+    if a.mul_mod(b, denominator) > U256::ZERO {
+        if result == U256::MAX {
+            return Err(UniswapV3MathError::ResultIsU256MAX);
+        }
+        Ok(result + U256::from(1))
+    } else {
+        Ok(result)
+    }
+}
+
+// Returns ceil(x / y), division by zero has unspecified behavior
+pub fn div_rounding_up(x: U256, y: U256) -> U256 {
+    let quotient = x / y;
+    if (x % y).is_zero() {
+        quotient
+    } else {
+        quotient + U256::from(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy_primitives::U256;
+
+    use super::{mul_div, mul_div_rounding_up};
+
+    const Q128: U256 = U256::from_limbs([0, 0, 1, 0]);
+
+    #[test]
+    fn test_mul_div() {
+        // denominator is zero
+        assert!(mul_div(Q128, U256::from(5), U256::ZERO).is_err());
+        // result overflows
+        assert!(mul_div(Q128, Q128, U256::from(1)).is_err());
+        // all max inputs
+        assert_eq!(mul_div(U256::MAX, U256::MAX, U256::MAX).unwrap(), U256::MAX);
+        // accurate without phantom overflow
+        assert_eq!(
+            mul_div(
+                Q128,
+                U256::from(50) * Q128 / U256::from(100),
+                U256::from(150) * Q128 / U256::from(100)
+            )
+            .unwrap(),
+            Q128 / U256::from(3)
+        );
+    }
+
+    #[test]
+    fn test_mul_div_rounding_up() {
+        assert!(mul_div_rounding_up(U256::MAX, U256::MAX, U256::MAX - U256::from(1)).is_err());
+        assert_eq!(
+            mul_div_rounding_up(
+                Q128,
+                U256::from(50) * Q128 / U256::from(100),
+                U256::from(150) * Q128 / U256::from(100)
+            )
+            .unwrap(),
+            Q128 / U256::from(3) + U256::from(1)
+        );
+    }
+}
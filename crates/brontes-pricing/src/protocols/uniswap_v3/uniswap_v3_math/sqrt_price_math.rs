@@ -0,0 +1,255 @@
+use alloy_primitives::U256;
+
+use super::{
+    error::UniswapV3MathError,
+    full_math::{div_rounding_up, mul_div, mul_div_rounding_up},
+};
+
+pub const MAX_U160: U256 =
+    U256::from_limbs([18446744073709551615, 18446744073709551615, 4294967295, 0]);
+pub const Q96: U256 = U256::from_limbs([0, 4294967296, 0, 0]);
+pub const FIXED_POINT_96_RESOLUTION: usize = 96;
+
+// Gets the next sqrt price given an input amount of token0 or token1. Throws
+// if price or liquidity are 0, or if the next price is out of bounds
+pub fn get_next_sqrt_price_from_input(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if sqrt_price.is_zero() {
+        return Err(UniswapV3MathError::SqrtPriceIsZero);
+    } else if liquidity == 0 {
+        return Err(UniswapV3MathError::LiquidityIsZero);
+    }
+
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+// Gets the next sqrt price given an output amount of token0 or token1. Throws
+// if price or liquidity are 0 or the next price is out of bounds
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_out: U256,
+    zero_for_one: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if sqrt_price.is_zero() {
+        return Err(UniswapV3MathError::SqrtPriceIsZero);
+    } else if liquidity == 0 {
+        return Err(UniswapV3MathError::LiquidityIsZero);
+    }
+
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price, liquidity, amount_out, false)
+    } else {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+// Gets the next sqrt price given a delta of token0, always rounding up so that
+// the price moves far enough to get the desired output amount or not too far
+// for the input amount
+pub fn get_next_sqrt_price_from_amount_0_rounding_up(
+    sqrt_price_x_96: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if amount.is_zero() {
+        return Ok(sqrt_price_x_96);
+    }
+
+    let numerator_1 = U256::from(liquidity) << FIXED_POINT_96_RESOLUTION;
+
+    if add {
+        let product = amount.wrapping_mul(sqrt_price_x_96);
+
+        if product / amount == sqrt_price_x_96 {
+            let denominator = numerator_1.wrapping_add(product);
+
+            if denominator >= numerator_1 {
+                return mul_div_rounding_up(numerator_1, sqrt_price_x_96, denominator);
+            }
+        }
+
+        Ok(div_rounding_up(numerator_1, (numerator_1 / sqrt_price_x_96).wrapping_add(amount)))
+    } else {
+        let product = amount.wrapping_mul(sqrt_price_x_96);
+        if product / amount == sqrt_price_x_96 && numerator_1 > product {
+            let denominator = numerator_1 - product;
+            mul_div_rounding_up(numerator_1, sqrt_price_x_96, denominator)
+        } else {
+            Err(UniswapV3MathError::ProductDivAmount)
+        }
+    }
+}
+
+// Gets the next sqrt price given a delta of token1, always rounding down so
+// that the price moves far enough to get the desired output amount or not too
+// far for the input amount
+pub fn get_next_sqrt_price_from_amount_1_rounding_down(
+    sqrt_price_x_96: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Result<U256, UniswapV3MathError> {
+    let liquidity = U256::from(liquidity);
+
+    if add {
+        let quotient = if amount <= MAX_U160 {
+            (amount << FIXED_POINT_96_RESOLUTION) / liquidity
+        } else {
+            mul_div(amount, Q96, liquidity)?
+        };
+
+        let next_sqrt_price = sqrt_price_x_96 + quotient;
+        if next_sqrt_price > MAX_U160 {
+            return Err(UniswapV3MathError::SafeCastToU160Overflow);
+        }
+
+        Ok(next_sqrt_price)
+    } else {
+        let quotient = if amount <= MAX_U160 {
+            div_rounding_up(amount << FIXED_POINT_96_RESOLUTION, liquidity)
+        } else {
+            mul_div_rounding_up(amount, Q96, liquidity)?
+        };
+
+        if sqrt_price_x_96 <= quotient {
+            return Err(UniswapV3MathError::SqrtPriceIsLteQuotient);
+        }
+
+        Ok(sqrt_price_x_96 - quotient)
+    }
+}
+
+// Gets the amount0 delta between two prices
+pub fn get_amount_0_delta(
+    mut sqrt_ratio_a_x_96: U256,
+    mut sqrt_ratio_b_x_96: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if sqrt_ratio_a_x_96 > sqrt_ratio_b_x_96 {
+        (sqrt_ratio_a_x_96, sqrt_ratio_b_x_96) = (sqrt_ratio_b_x_96, sqrt_ratio_a_x_96)
+    };
+
+    if sqrt_ratio_a_x_96.is_zero() {
+        return Err(UniswapV3MathError::SqrtPriceIsZero);
+    }
+
+    let numerator_1 = U256::from(liquidity) << FIXED_POINT_96_RESOLUTION;
+    let numerator_2 = sqrt_ratio_b_x_96 - sqrt_ratio_a_x_96;
+
+    if round_up {
+        let numerator_partial = mul_div_rounding_up(numerator_1, numerator_2, sqrt_ratio_b_x_96)?;
+        Ok(div_rounding_up(numerator_partial, sqrt_ratio_a_x_96))
+    } else {
+        Ok(mul_div(numerator_1, numerator_2, sqrt_ratio_b_x_96)? / sqrt_ratio_a_x_96)
+    }
+}
+
+// Gets the amount1 delta between two prices
+pub fn get_amount_1_delta(
+    mut sqrt_ratio_a_x_96: U256,
+    mut sqrt_ratio_b_x_96: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256, UniswapV3MathError> {
+    if sqrt_ratio_a_x_96 > sqrt_ratio_b_x_96 {
+        (sqrt_ratio_a_x_96, sqrt_ratio_b_x_96) = (sqrt_ratio_b_x_96, sqrt_ratio_a_x_96)
+    };
+
+    let delta = sqrt_ratio_b_x_96 - sqrt_ratio_a_x_96;
+
+    if round_up {
+        mul_div_rounding_up(U256::from(liquidity), delta, Q96)
+    } else {
+        mul_div(U256::from(liquidity), delta, Q96)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy_primitives::U256;
+
+    use super::*;
+
+    // 1e18
+    const ONE_ETHER: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+    #[test]
+    fn test_get_next_sqrt_price_from_input() {
+        // fails if price is zero
+        assert!(get_next_sqrt_price_from_input(U256::ZERO, 0, ONE_ETHER, false).is_err());
+        // fails if liquidity is zero
+        assert!(get_next_sqrt_price_from_input(U256::from(1), 0, ONE_ETHER, true).is_err());
+
+        // returns input price if amount in is zero
+        let price = Q96;
+        assert_eq!(
+            get_next_sqrt_price_from_input(price, 1_000_000_000_000_000_000, U256::ZERO, true)
+                .unwrap(),
+            price
+        );
+
+        // input amount of 0.1 token1 at a price of 1
+        assert_eq!(
+            get_next_sqrt_price_from_input(
+                Q96,
+                1_000_000_000_000_000_000,
+                ONE_ETHER / U256::from(10),
+                false
+            )
+            .unwrap(),
+            U256::from(87150978765690771352898345369_u128)
+        );
+
+        // input amount of 0.1 token0 at a price of 1
+        assert_eq!(
+            get_next_sqrt_price_from_input(
+                Q96,
+                1_000_000_000_000_000_000,
+                ONE_ETHER / U256::from(10),
+                true
+            )
+            .unwrap(),
+            U256::from(72025602285694852357767227579_u128)
+        );
+    }
+
+    #[test]
+    fn test_get_amount_deltas() {
+        // returns 0 if liquidity is 0
+        assert_eq!(get_amount_0_delta(Q96, Q96 * U256::from(2), 0, true).unwrap(), U256::ZERO);
+        // returns 0 if prices are equal
+        assert_eq!(
+            get_amount_1_delta(Q96, Q96, 1_000_000_000_000_000_000, true).unwrap(),
+            U256::ZERO
+        );
+
+        // price of 1 to 1.21, sqrt(1.21) * Q96
+        let sqrt_price_b = U256::from(87150978765690771352898345369_u128);
+        let amount_0 =
+            get_amount_0_delta(Q96, sqrt_price_b, 1_000_000_000_000_000_000, true).unwrap();
+        assert_eq!(amount_0, U256::from(90909090909090910_u128));
+
+        let amount_0_rounded_down =
+            get_amount_0_delta(Q96, sqrt_price_b, 1_000_000_000_000_000_000, false).unwrap();
+        assert_eq!(amount_0_rounded_down, amount_0 - U256::from(1));
+
+        let amount_1 =
+            get_amount_1_delta(Q96, sqrt_price_b, 1_000_000_000_000_000_000, true).unwrap();
+        assert_eq!(amount_1, U256::from(100000000000000000_u128));
+
+        let amount_1_rounded_down =
+            get_amount_1_delta(Q96, sqrt_price_b, 1_000_000_000_000_000_000, false).unwrap();
+        assert_eq!(amount_1_rounded_down, amount_1 - U256::from(1));
+    }
+}
@@ -0,0 +1,131 @@
+use alloy_primitives::U256;
+
+use super::{
+    error::UniswapV3MathError,
+    full_math::{mul_div, mul_div_rounding_up},
+    sqrt_price_math::{get_amount_0_delta, get_amount_1_delta, get_next_sqrt_price_from_input},
+};
+
+const FEE_DENOMINATOR: u32 = 1_000_000;
+
+// Computes the result of swapping some amount in, given the parameters of the
+// swap. Only exact input swaps are supported as that is all we quote.
+//
+// Returns (sqrt_ratio_next_x_96, amount_in, amount_out, fee_amount)
+pub fn compute_swap_step(
+    sqrt_ratio_current_x_96: U256,
+    sqrt_ratio_target_x_96: U256,
+    liquidity: u128,
+    amount_remaining: U256,
+    fee_pips: u32,
+) -> Result<(U256, U256, U256, U256), UniswapV3MathError> {
+    let zero_for_one = sqrt_ratio_current_x_96 >= sqrt_ratio_target_x_96;
+
+    let amount_remaining_less_fee = mul_div(
+        amount_remaining,
+        U256::from(FEE_DENOMINATOR - fee_pips),
+        U256::from(FEE_DENOMINATOR),
+    )?;
+
+    let amount_in_to_target = if zero_for_one {
+        get_amount_0_delta(sqrt_ratio_target_x_96, sqrt_ratio_current_x_96, liquidity, true)?
+    } else {
+        get_amount_1_delta(sqrt_ratio_current_x_96, sqrt_ratio_target_x_96, liquidity, true)?
+    };
+
+    let sqrt_ratio_next_x_96 = if amount_remaining_less_fee >= amount_in_to_target {
+        sqrt_ratio_target_x_96
+    } else {
+        get_next_sqrt_price_from_input(
+            sqrt_ratio_current_x_96,
+            liquidity,
+            amount_remaining_less_fee,
+            zero_for_one,
+        )?
+    };
+
+    let max = sqrt_ratio_target_x_96 == sqrt_ratio_next_x_96;
+
+    let (amount_in, amount_out) = if zero_for_one {
+        (
+            if max {
+                amount_in_to_target
+            } else {
+                get_amount_0_delta(sqrt_ratio_next_x_96, sqrt_ratio_current_x_96, liquidity, true)?
+            },
+            get_amount_1_delta(sqrt_ratio_next_x_96, sqrt_ratio_current_x_96, liquidity, false)?,
+        )
+    } else {
+        (
+            if max {
+                amount_in_to_target
+            } else {
+                get_amount_1_delta(sqrt_ratio_current_x_96, sqrt_ratio_next_x_96, liquidity, true)?
+            },
+            get_amount_0_delta(sqrt_ratio_current_x_96, sqrt_ratio_next_x_96, liquidity, false)?,
+        )
+    };
+
+    // we didn't reach the target, so take the remainder of the maximum input as
+    // fee
+    let fee_amount = if !max {
+        amount_remaining - amount_in
+    } else {
+        mul_div_rounding_up(
+            amount_in,
+            U256::from(fee_pips),
+            U256::from(FEE_DENOMINATOR - fee_pips),
+        )?
+    };
+
+    Ok((sqrt_ratio_next_x_96, amount_in, amount_out, fee_amount))
+}
+
+#[cfg(test)]
+mod test {
+    use alloy_primitives::U256;
+
+    use super::compute_swap_step;
+    use crate::uniswap_v3::uniswap_v3_math::sqrt_price_math::{
+        get_next_sqrt_price_from_input, Q96,
+    };
+
+    const ONE_ETHER: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+    #[test]
+    fn test_compute_swap_step_capped_at_target() {
+        // price of 1 to 101/100, sqrt(1.01) * Q96
+        let price_target = U256::from(79623317895830914510639640423_u128);
+        let liquidity = 2_000_000_000_000_000_000;
+
+        let (sqrt_price, amount_in, amount_out, fee_amount) =
+            compute_swap_step(Q96, price_target, liquidity, ONE_ETHER, 600).unwrap();
+
+        assert_eq!(amount_in, U256::from(9975124224178055_u128));
+        assert_eq!(fee_amount, U256::from(5988667735148_u128));
+        assert_eq!(amount_out, U256::from(9925619580021728_u128));
+        assert!(amount_in + fee_amount < ONE_ETHER);
+        assert_eq!(sqrt_price, price_target);
+    }
+
+    #[test]
+    fn test_compute_swap_step_fully_spent() {
+        // price of 1 to 1000/100, sqrt(10) * Q96
+        let price_target = U256::from(250541448375047931186413801569_u128);
+        let liquidity = 2_000_000_000_000_000_000;
+
+        let (sqrt_price, amount_in, amount_out, fee_amount) =
+            compute_swap_step(Q96, price_target, liquidity, ONE_ETHER, 600).unwrap();
+
+        assert_eq!(amount_in, U256::from(999400000000000000_u128));
+        assert_eq!(fee_amount, U256::from(600000000000000_u128));
+        assert_eq!(amount_out, U256::from(666399946655997866_u128));
+        assert_eq!(amount_in + fee_amount, ONE_ETHER);
+
+        let price_after_whole_input_less_fee =
+            get_next_sqrt_price_from_input(Q96, liquidity, ONE_ETHER - fee_amount, false).unwrap();
+
+        assert!(sqrt_price < price_target);
+        assert_eq!(sqrt_price, price_after_whole_input_less_fee);
+    }
+}
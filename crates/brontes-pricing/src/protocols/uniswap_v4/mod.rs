@@ -0,0 +1,348 @@
+use std::sync::Arc;
+
+use alloy_primitives::{hex, Address, Log, B256, U256};
+use alloy_sol_macro::sol;
+use alloy_sol_types::SolEvent;
+use async_trait::async_trait;
+use brontes_types::{
+    db::uniswap_v4_pool_key::UniswapV4PoolKey,
+    normalized_actions::{Action, NormalizedSwap},
+    queries::make_call_request,
+    traits::TracingProvider,
+};
+use malachite::{
+    num::{arithmetic::traits::Pow, basic::traits::Zero},
+    Natural, Rational,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    curve::math::u256_to_natural,
+    errors::{AmmError, ArithmeticError, EventLogError},
+    types::PoolUpdate,
+    uniswap_v3::uniswap_v3_math::error::UniswapV3MathError,
+    Protocol, UpdatableProtocol,
+};
+
+/// the v4 lens contract that exposes the pool manager storage per pool id
+pub const UNISWAP_V4_STATE_VIEW: Address =
+    Address::new(hex!("7fFE42C4a5DEeA5b0feC41C94C136Cf115597227"));
+
+sol!(
+    interface IUniswapV4StateView {
+        function getSlot0(bytes32 poolId) external view returns (
+            uint160 sqrtPriceX96,
+            int24 tick,
+            uint24 protocolFee,
+            uint24 lpFee
+        );
+        function getLiquidity(bytes32 poolId) external view returns (uint128 liquidity);
+    }
+);
+
+sol!(
+    interface IUniswapV4PoolManager {
+        event Swap(
+            bytes32 indexed id,
+            address indexed sender,
+            int128 amount0,
+            int128 amount1,
+            uint160 sqrtPriceX96,
+            uint128 liquidity,
+            int24 tick,
+            uint24 fee
+        );
+        event ModifyLiquidity(
+            bytes32 indexed id,
+            address indexed sender,
+            int24 tickLower,
+            int24 tickUpper,
+            int256 liquidityDelta,
+            bytes32 salt
+        );
+    }
+);
+
+sol!(
+    interface IUniswapV4Erc20 {
+        function decimals() external view returns (uint8);
+    }
+);
+
+/// the pool id and token ordering of a v4 pool. v4 pools are keyed by an
+/// address derived from their pool id, so this is needed to go back from that
+/// address to the pool in the pool manager.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct V4PoolInfo {
+    pub pool_id: B256,
+    pub token0:  Address,
+    pub token1:  Address,
+}
+
+impl From<&UniswapV4PoolKey> for V4PoolInfo {
+    fn from(key: &UniswapV4PoolKey) -> Self {
+        let [token0, token1] = key.tokens();
+        Self { pool_id: key.pool_id, token0, token1 }
+    }
+}
+
+impl V4PoolInfo {
+    /// recovers the pool info from a classified v4 update. The pool id is taken
+    /// from the pool manager event and the token ordering from the action. For
+    /// swaps the ordering is derived from the sign of the deltas, a negative
+    /// amount0 means that token0 was paid into the pool.
+    pub fn from_update(update: &PoolUpdate) -> Option<(Address, Self)> {
+        let modify_id = || {
+            update.logs.iter().find_map(|log| {
+                IUniswapV4PoolManager::ModifyLiquidity::decode_log_data(log, true)
+                    .ok()
+                    .map(|modify| modify.id)
+            })
+        };
+
+        let swap_info = |s: &NormalizedSwap| {
+            let swap = update
+                .logs
+                .iter()
+                .find_map(|log| IUniswapV4PoolManager::Swap::decode_log_data(log, true).ok())?;
+
+            if swap.amount0.is_negative() {
+                Some((s.pool, swap.id, s.token_in.address, s.token_out.address))
+            } else {
+                Some((s.pool, swap.id, s.token_out.address, s.token_in.address))
+            }
+        };
+
+        let (pool, pool_id, token0, token1) = match &update.action {
+            Action::Swap(s) if s.protocol == Protocol::UniswapV4 => swap_info(s)?,
+            // a hook took a fee out of the swap
+            Action::SwapWithFee(s) if s.protocol == Protocol::UniswapV4 => swap_info(&s.swap)?,
+            Action::Mint(m) if m.protocol == Protocol::UniswapV4 => {
+                (m.pool, modify_id()?, m.token.first()?.address, m.token.get(1)?.address)
+            }
+            Action::Burn(b) if b.protocol == Protocol::UniswapV4 => {
+                (b.pool, modify_id()?, b.token.first()?.address, b.token.get(1)?.address)
+            }
+            _ => return None,
+        };
+
+        Some((pool, Self { pool_id, token0, token1 }))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UniswapV4Pool {
+    pub address:          Address,
+    pub pool_id:          B256,
+    pub token_a:          Address,
+    pub token_a_decimals: u8,
+    pub token_b:          Address,
+    pub token_b_decimals: u8,
+    pub liquidity:        u128,
+    pub sqrt_price:       U256,
+    pub tick:             i32,
+    pub fee:              u32,
+}
+
+#[async_trait]
+impl UpdatableProtocol for UniswapV4Pool {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn tokens(&self) -> Vec<Address> {
+        vec![self.token_a, self.token_b]
+    }
+
+    fn calculate_price(&self, base_token: Address) -> Result<Rational, ArithmeticError> {
+        if self.liquidity <= 10_000 {
+            return Err(ArithmeticError::UniswapV3MathError(UniswapV3MathError::LiquidityTooLow(
+                self.liquidity,
+            )))
+        }
+        if self.sqrt_price.is_zero() {
+            return Err(ArithmeticError::YIsZero)
+        }
+
+        // (sqrtPriceX96 / 2^96)^2 is the price of token0 in token1 raw units
+        let sqrt_price = u256_to_natural(self.sqrt_price);
+        let price = Rational::from_naturals(
+            &sqrt_price * &sqrt_price * Natural::from(10u8).pow(self.token_a_decimals as u64),
+            Natural::from(2u8).pow(192) * Natural::from(10u8).pow(self.token_b_decimals as u64),
+        );
+
+        if base_token == self.token_a {
+            Ok(price)
+        } else {
+            Ok(Rational::from(1) / price)
+        }
+    }
+
+    fn sync_from_action(&mut self, _action: Action) -> Result<(), AmmError> {
+        Err(AmmError::SyncError(self.address))
+    }
+
+    fn sync_from_log(&mut self, log: Log) -> Result<(), AmmError> {
+        // the pool manager emits the events of all pools
+        if log.topics().get(1) != Some(&self.pool_id) {
+            return Err(AmmError::SyncError(self.address))
+        }
+
+        if let Ok(swap) = IUniswapV4PoolManager::Swap::decode_log_data(&log, true) {
+            self.sqrt_price = swap.sqrtPriceX96;
+            self.liquidity = swap.liquidity;
+            self.tick = swap.tick;
+            self.fee = swap.fee;
+        } else if let Ok(modify) =
+            IUniswapV4PoolManager::ModifyLiquidity::decode_log_data(&log, true)
+        {
+            // only positions that contain the current tick are active
+            if self.tick >= modify.tickLower && self.tick < modify.tickUpper {
+                let delta = modify.liquidityDelta;
+                let abs = delta.unsigned_abs().saturating_to::<u128>();
+                self.liquidity = if delta.is_negative() {
+                    self.liquidity.saturating_sub(abs)
+                } else {
+                    self.liquidity.saturating_add(abs)
+                };
+            }
+        } else {
+            Err(EventLogError::InvalidEventSignature)?
+        }
+
+        Ok(())
+    }
+}
+
+impl UniswapV4Pool {
+    pub async fn new_load_on_block<M: TracingProvider>(
+        address: Address,
+        info: V4PoolInfo,
+        middleware: Arc<M>,
+        block: u64,
+    ) -> Result<Self, AmmError> {
+        let mut pool = UniswapV4Pool {
+            address,
+            pool_id: info.pool_id,
+            token_a: info.token0,
+            token_b: info.token1,
+            ..Default::default()
+        };
+        pool.populate_data(block, middleware).await?;
+
+        if !pool.data_is_populated() {
+            return Err(AmmError::NoStateError(address))
+        }
+
+        Ok(pool)
+    }
+
+    async fn populate_data<M: TracingProvider>(
+        &mut self,
+        block: u64,
+        middleware: Arc<M>,
+    ) -> Result<(), AmmError> {
+        let block = Some(block);
+
+        let slot0 = make_call_request(
+            IUniswapV4StateView::getSlot0Call { poolId: self.pool_id },
+            &middleware,
+            UNISWAP_V4_STATE_VIEW,
+            block,
+        )
+        .await?;
+        self.sqrt_price = slot0.sqrtPriceX96;
+        self.tick = slot0.tick;
+        self.fee = slot0.lpFee;
+
+        self.liquidity = make_call_request(
+            IUniswapV4StateView::getLiquidityCall { poolId: self.pool_id },
+            &middleware,
+            UNISWAP_V4_STATE_VIEW,
+            block,
+        )
+        .await?
+        .liquidity;
+
+        self.token_a_decimals =
+            make_call_request(IUniswapV4Erc20::decimalsCall {}, &middleware, self.token_a, block)
+                .await?
+                ._0;
+        self.token_b_decimals =
+            make_call_request(IUniswapV4Erc20::decimalsCall {}, &middleware, self.token_b, block)
+                .await?
+                ._0;
+
+        Ok(())
+    }
+
+    pub fn data_is_populated(&self) -> bool {
+        !(self.token_a.is_zero() || self.token_b.is_zero() || self.sqrt_price.is_zero())
+    }
+
+    /// the pool manager holds the tokens of all pools, so we use the virtual
+    /// reserves of the active liquidity as the tvl of the pool
+    pub fn get_tvl(&self, base: Address) -> (Rational, Rational) {
+        if self.sqrt_price.is_zero() {
+            return (Rational::ZERO, Rational::ZERO)
+        }
+
+        let liquidity = Natural::from(self.liquidity);
+        let sqrt_price = u256_to_natural(self.sqrt_price);
+        let q96 = Natural::from(2u8).pow(96);
+
+        let reserve_0 = Rational::from_naturals(
+            &liquidity * &q96,
+            &sqrt_price * Natural::from(10u8).pow(self.token_a_decimals as u64),
+        );
+        let reserve_1 = Rational::from_naturals(
+            liquidity * sqrt_price,
+            q96 * Natural::from(10u8).pow(self.token_b_decimals as u64),
+        );
+
+        if self.token_a == base {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use malachite::num::basic::traits::One;
+
+    use super::*;
+
+    fn pool(sqrt_price: U256) -> UniswapV4Pool {
+        UniswapV4Pool {
+            token_a: Address::with_last_byte(1),
+            token_a_decimals: 18,
+            token_b: Address::with_last_byte(2),
+            token_b_decimals: 18,
+            liquidity: 1_000_000_000_000,
+            sqrt_price,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_price_at_parity() {
+        let pool = pool(U256::from(1) << 96);
+
+        assert_eq!(pool.calculate_price(pool.token_a).unwrap(), Rational::ONE);
+        assert_eq!(pool.calculate_price(pool.token_b).unwrap(), Rational::ONE);
+    }
+
+    #[test]
+    fn test_price_is_inverse_for_quote() {
+        // sqrt price of 2 means token0 is worth 4 token1
+        let pool = pool(U256::from(2) << 96);
+
+        assert_eq!(pool.calculate_price(pool.token_a).unwrap(), Rational::from(4));
+        assert_eq!(
+            pool.calculate_price(pool.token_b).unwrap(),
+            Rational::from_unsigneds(1u32, 4u32)
+        );
+    }
+}
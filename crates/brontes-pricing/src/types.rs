@@ -5,11 +5,14 @@ use brontes_types::{
     constants::WETH_ADDRESS,
     normalized_actions::{pool::NormalizedPoolConfigUpdate, Action},
     pair::Pair,
+    structured_trace::AccountStateDiff,
 };
 use malachite::Rational;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    errors::ArithmeticError, uniswap_v2::UniswapV2Pool, uniswap_v3::UniswapV3Pool, LoadState,
+    balancer_v2::BalancerV2Pool, curve::CurvePool, errors::ArithmeticError,
+    uniswap_v2::UniswapV2Pool, uniswap_v3::UniswapV3Pool, uniswap_v4::UniswapV4Pool, LoadState,
     Protocol, UpdatableProtocol,
 };
 
@@ -51,22 +54,48 @@ impl PairWithFirstPoolHop {
     }
 }
 
+/// pools can hold more than two tokens, so both sides of the edge are needed
+/// to resolve a price or tvl.
 pub trait ProtocolState: Debug {
-    fn price(&self, base: Address) -> Result<Rational, ArithmeticError>;
-    fn tvl(&self, base: Address) -> (Rational, Rational);
+    fn price(&self, base: Address, quote: Address) -> Result<Rational, ArithmeticError>;
+    fn tvl(&self, base: Address, quote: Address) -> (Rational, Rational);
+
+    /// Price received for selling `amount` of the base token into the pool.
+    /// Protocols that can't simulate their swaps quote the spot price.
+    ///
+    /// The pricing graph uses this to weight edges, and the sandwich inspector
+    /// to estimate what victims it couldn't re-execute would have received
+    /// without the frontruns.
+    fn execution_price(
+        &self,
+        base: Address,
+        quote: Address,
+        _amount: &Rational,
+    ) -> Result<Rational, ArithmeticError> {
+        self.price(base, quote)
+    }
 }
 
 impl ProtocolState for PoolState {
-    fn tvl(&self, base: Address) -> (Rational, Rational) {
-        self.get_tvl(base)
+    fn tvl(&self, base: Address, quote: Address) -> (Rational, Rational) {
+        self.get_tvl(base, quote)
+    }
+
+    fn price(&self, base: Address, quote: Address) -> Result<Rational, ArithmeticError> {
+        self.get_price(base, quote)
     }
 
-    fn price(&self, base: Address) -> Result<Rational, ArithmeticError> {
-        self.get_price(base)
+    fn execution_price(
+        &self,
+        base: Address,
+        quote: Address,
+        amount: &Rational,
+    ) -> Result<Rational, ArithmeticError> {
+        self.get_execution_price(base, quote, amount)
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PoolState {
     variant:         PoolVariants,
     pub last_update: u64,
@@ -76,8 +105,8 @@ impl Debug for PoolState {
         f.debug_struct("Pool State")
             .field("addr", &self.address())
             .field("pair", &self.pair())
-            .field("tvl 0", &self.get_tvl(self.pair().0).0)
-            .field("tvl 1", &self.get_tvl(self.pair().0).1)
+            .field("tvl 0", &self.get_tvl(self.pair().0, self.pair().1).0)
+            .field("tvl 1", &self.get_tvl(self.pair().0, self.pair().1).1)
             .field("block", &self.last_update)
             .finish()
     }
@@ -92,6 +121,9 @@ impl PoolState {
         match &self.variant {
             PoolVariants::UniswapV2(v) => Pair(v.token_a, v.token_b),
             PoolVariants::UniswapV3(v) => Pair(v.token_a, v.token_b),
+            PoolVariants::Curve(v) => Pair(v.tokens[0], v.tokens[1]),
+            PoolVariants::BalancerV2(v) => Pair(v.tokens[0], v.tokens[1]),
+            PoolVariants::UniswapV4(v) => Pair(v.token_a, v.token_b),
         }
     }
 
@@ -99,6 +131,9 @@ impl PoolState {
         match &self.variant {
             PoolVariants::UniswapV2(_) => Protocol::UniswapV2,
             PoolVariants::UniswapV3(_) => Protocol::UniswapV3,
+            PoolVariants::Curve(v) => v.protocol,
+            PoolVariants::BalancerV2(_) => Protocol::BalancerV2,
+            PoolVariants::UniswapV4(_) => Protocol::UniswapV4,
         }
     }
 
@@ -108,43 +143,101 @@ impl PoolState {
             return
         }
         self.last_update = state.block;
-        self.variant.increment_state(state.logs);
+        self.variant
+            .increment_state(state.action, state.logs, state.state_diff);
     }
 
     pub fn address(&self) -> Address {
         match &self.variant {
             PoolVariants::UniswapV2(v) => v.address(),
             PoolVariants::UniswapV3(v) => v.address(),
+            PoolVariants::Curve(v) => v.address(),
+            PoolVariants::BalancerV2(v) => v.address(),
+            PoolVariants::UniswapV4(v) => v.address(),
         }
     }
 
-    pub fn get_tvl(&self, base: Address) -> (Rational, Rational) {
+    pub fn get_tvl(&self, base: Address, quote: Address) -> (Rational, Rational) {
         match &self.variant {
             PoolVariants::UniswapV2(v) => v.get_tvl(base),
             PoolVariants::UniswapV3(v) => v.get_tvl(base),
+            PoolVariants::Curve(v) => v.get_tvl_for(base, quote),
+            PoolVariants::BalancerV2(v) => v.get_tvl_for(base, quote),
+            PoolVariants::UniswapV4(v) => v.get_tvl(base),
         }
     }
 
-    pub fn get_price(&self, base: Address) -> Result<Rational, ArithmeticError> {
+    pub fn get_price(&self, base: Address, quote: Address) -> Result<Rational, ArithmeticError> {
         match &self.variant {
             PoolVariants::UniswapV2(v) => v.calculate_price(base),
             PoolVariants::UniswapV3(v) => v.calculate_price(base),
+            PoolVariants::Curve(v) => v.calculate_price_for(base, quote),
+            PoolVariants::BalancerV2(v) => v.calculate_price_for(base, quote),
+            PoolVariants::UniswapV4(v) => v.calculate_price(base),
+        }
+    }
+
+    pub fn get_execution_price(
+        &self,
+        base: Address,
+        quote: Address,
+        amount: &Rational,
+    ) -> Result<Rational, ArithmeticError> {
+        match &self.variant {
+            PoolVariants::UniswapV3(v) => v.calculate_execution_price(base, amount),
+            _ => self.get_price(base, quote),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PoolVariants {
     UniswapV2(Box<UniswapV2Pool>),
     UniswapV3(Box<UniswapV3Pool>),
+    Curve(Box<CurvePool>),
+    BalancerV2(Box<BalancerV2Pool>),
+    UniswapV4(Box<UniswapV4Pool>),
 }
 
 impl PoolVariants {
-    fn increment_state(&mut self, logs: Vec<Log>) {
-        for log in logs {
+    fn increment_state(
+        &mut self,
+        action: Action,
+        logs: Vec<Log>,
+        state_diff: Option<AccountStateDiff>,
+    ) {
+        match self {
+            PoolVariants::UniswapV3(a) => logs.into_iter().for_each(|log| {
+                let _ = a.sync_from_log(log);
+            }),
+            PoolVariants::UniswapV2(a) => logs.into_iter().for_each(|log| {
+                let _ = a.sync_from_log(log);
+            }),
+            // the pool manager emits the events of every pool, the pool filters on
+            // its own id
+            PoolVariants::UniswapV4(a) => logs.into_iter().for_each(|log| {
+                let _ = a.sync_from_log(log);
+            }),
+            // curve emits different events per pool implementation and balancer
+            // balances live in the vault, so we sync off of the classified action
+            // instead
+            PoolVariants::Curve(a) => {
+                let _ = a.sync_from_action(action);
+            }
+            PoolVariants::BalancerV2(a) => {
+                let _ = a.sync_from_action(action);
+            }
+        }
+
+        // the diff holds the exact values the pool ended the transaction with, so
+        // it overrides whatever was derived from the logs or action
+        if let Some(diff) = state_diff {
             let _ = match self {
-                PoolVariants::UniswapV3(a) => a.sync_from_log(log),
-                PoolVariants::UniswapV2(a) => a.sync_from_log(log),
+                PoolVariants::UniswapV2(a) => a.sync_from_state_diff(&diff),
+                PoolVariants::UniswapV3(a) => a.sync_from_state_diff(&diff),
+                PoolVariants::Curve(a) => a.sync_from_state_diff(&diff),
+                PoolVariants::BalancerV2(a) => a.sync_from_state_diff(&diff),
+                PoolVariants::UniswapV4(a) => a.sync_from_state_diff(&diff),
             };
         }
     }
@@ -185,10 +278,13 @@ impl DiscoveredPool {
 
 #[derive(Debug, Clone)]
 pub struct PoolUpdate {
-    pub block:  u64,
-    pub tx_idx: u64,
-    pub logs:   Vec<Log>,
-    pub action: Action,
+    pub block:      u64,
+    pub tx_idx:     u64,
+    pub logs:       Vec<Log>,
+    pub action:     Action,
+    /// Balance and storage changes of the pool over the transaction, if the
+    /// trace recorded them
+    pub state_diff: Option<AccountStateDiff>,
 }
 
 impl PoolUpdate {
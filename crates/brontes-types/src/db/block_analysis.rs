@@ -1,18 +1,19 @@
 use std::{collections::HashMap, hash::Hash, str::FromStr};
-use crate::serde_utils::address;
+
 use alloy_primitives::Address;
 use clickhouse::Row;
 use itertools::Itertools;
 use reth_primitives::TxHash;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use crate::serde_utils::vec_address;
+
 use crate::{
-    db::{searcher::Fund, token_info::TokenInfoWithAddress},
+    db::{fund::Fund, token_info::TokenInfoWithAddress},
     mev::{Bundle, BundleData, Mev, MevBlock, MevType},
     pair::Pair,
     serde_utils::{
-        option_address, option_fund, option_protocol, option_txhash, vec_fund, vec_protocol,
+        address, option_address, option_fund, option_protocol, option_txhash, vec_address,
+        vec_fund, vec_protocol,
     },
     Protocol,
 };
@@ -1830,10 +1831,10 @@ impl BlockAnalysis {
             .iter()
             .filter(|b| mev_type(b.mev_type()))
             .filter_map(|b| {
-                if b.header.fund == Fund::None {
+                if b.header.fund.is_none() {
                     None
                 } else {
-                    Some((b.header.fund, b.header.profit_usd))
+                    Some((b.header.fund.clone(), b.header.profit_usd))
                 }
             })
             .max_by(|a, b| a.1.total_cmp(&b.1))
@@ -1848,10 +1849,10 @@ impl BlockAnalysis {
             .iter()
             .filter(|b| mev_type(b.mev_type()))
             .filter_map(|b| {
-                if b.header.fund == Fund::None {
+                if b.header.fund.is_none() {
                     None
                 } else {
-                    Some((b.header.fund, b.header.profit_usd))
+                    Some((b.header.fund.clone(), b.header.profit_usd))
                 }
             })
             .for_each(|(f, amt)| {
@@ -1869,10 +1870,10 @@ impl BlockAnalysis {
             .iter()
             .filter(|b| mev_type(b.mev_type()))
             .filter_map(|b| {
-                if b.header.fund == Fund::None {
+                if b.header.fund.is_none() {
                     None
                 } else {
-                    Some((b.header.fund, b.header.profit_usd + b.header.bribe_usd))
+                    Some((b.header.fund.clone(), b.header.profit_usd + b.header.bribe_usd))
                 }
             })
             .max_by(|a, b| a.1.total_cmp(&b.1))
@@ -1887,10 +1888,10 @@ impl BlockAnalysis {
             .iter()
             .filter(|b| mev_type(b.mev_type()))
             .filter_map(|b| {
-                if b.header.fund == Fund::None {
+                if b.header.fund.is_none() {
                     None
                 } else {
-                    Some((b.header.fund, b.header.profit_usd + b.header.bribe_usd))
+                    Some((b.header.fund.clone(), b.header.profit_usd + b.header.bribe_usd))
                 }
             })
             .for_each(|(f, amt)| {
@@ -1923,12 +1924,10 @@ impl BlockAnalysis {
             .for_each(|r| {
                 if mev_contract {
                     if let Some(contract) = r.header.mev_contract {
-                        *map.entry(contract).or_insert(0.0) +=
-                            r.header.profit_usd;
+                        *map.entry(contract).or_insert(0.0) += r.header.profit_usd;
                     }
                 } else {
-                    *map.entry(r.header.eoa).or_insert(0.0) +=
-                        r.header.profit_usd;
+                    *map.entry(r.header.eoa).or_insert(0.0) += r.header.profit_usd;
                 }
             });
 
@@ -2064,7 +2063,8 @@ impl BlockAnalysis {
         bundles
             .iter()
             .filter(|b| mev_type(b.mev_type()))
-            .filter_map(|b| if b.header.fund == Fund::None { None } else { Some(b.header.fund) })
+            .map(|b| &b.header.fund)
+            .filter(|fund| !fund.is_none())
             .unique()
             .count() as u64
     }
@@ -2286,9 +2286,9 @@ impl Default for BlockAnalysis {
             atomic_searcher_eoa_all_revenue_amt: vec![Default::default()],
             atomic_searcher_eoa_count:           Default::default(),
 
-            atomic_fund_all_profit:      vec![Fund::JaneStreet],
+            atomic_fund_all_profit:      vec![Fund::new("Jane Street")],
             atomic_fund_all_profit_amt:  vec![0.0],
-            atomic_fund_all_revenue:     vec![Fund::JaneStreet],
+            atomic_fund_all_revenue:     vec![Fund::new("Jane Street")],
             atomic_fund_all_revenue_amt: vec![0.0],
 
             atomic_arbed_pool_all_profit:      vec![Default::default()],
@@ -2317,9 +2317,9 @@ impl Default for BlockAnalysis {
             sandwich_searcher_eoa_all_revenue_amt: vec![Default::default()],
             sandwich_searcher_eoa_count:           Default::default(),
 
-            sandwich_fund_all_profit:      vec![Fund::JaneStreet],
+            sandwich_fund_all_profit:      vec![Fund::new("Jane Street")],
             sandwich_fund_all_profit_amt:  vec![0.0],
-            sandwich_fund_all_revenue:     vec![Fund::JaneStreet],
+            sandwich_fund_all_revenue:     vec![Fund::new("Jane Street")],
             sandwich_fund_all_revenue_amt: vec![0.0],
 
             sandwich_arbed_pool_all_profit:      vec![Default::default()],
@@ -2348,9 +2348,9 @@ impl Default for BlockAnalysis {
             jit_searcher_eoa_all_revenue_amt: vec![Default::default()],
             jit_searcher_eoa_count:           Default::default(),
 
-            jit_fund_all_profit:      vec![Fund::JaneStreet],
+            jit_fund_all_profit:      vec![Fund::new("Jane Street")],
             jit_fund_all_profit_amt:  vec![0.0],
-            jit_fund_all_revenue:     vec![Fund::JaneStreet],
+            jit_fund_all_revenue:     vec![Fund::new("Jane Street")],
             jit_fund_all_revenue_amt: vec![0.0],
 
             jit_arbed_pool_all_profit:      vec![Default::default()],
@@ -2379,9 +2379,9 @@ impl Default for BlockAnalysis {
             jit_sandwich_searcher_eoa_all_revenue_amt: vec![Default::default()],
             jit_sandwich_searcher_eoa_count:           Default::default(),
 
-            jit_sandwich_fund_all_profit:      vec![Fund::JaneStreet],
+            jit_sandwich_fund_all_profit:      vec![Fund::new("Jane Street")],
             jit_sandwich_fund_all_profit_amt:  vec![0.0],
-            jit_sandwich_fund_all_revenue:     vec![Fund::JaneStreet],
+            jit_sandwich_fund_all_revenue:     vec![Fund::new("Jane Street")],
             jit_sandwich_fund_all_revenue_amt: vec![0.0],
 
             jit_sandwich_arbed_pool_all_profit:      vec![Default::default()],
@@ -2415,9 +2415,9 @@ impl Default for BlockAnalysis {
             cex_dex_arbed_dex_all_revenue:     vec![Protocol::UniswapV2],
             cex_dex_arbed_dex_all_revenue_amt: vec![Default::default()],
 
-            cex_dex_fund_all_profit:      vec![Fund::JaneStreet],
+            cex_dex_fund_all_profit:      vec![Fund::new("Jane Street")],
             cex_dex_fund_all_profit_amt:  vec![0.0],
-            cex_dex_fund_all_revenue:     vec![Fund::JaneStreet],
+            cex_dex_fund_all_revenue:     vec![Fund::new("Jane Street")],
             cex_dex_fund_all_revenue_amt: vec![0.0],
 
             cex_dex_arbed_pool_all_profit:      vec![Default::default()],
@@ -2441,9 +2441,9 @@ impl Default for BlockAnalysis {
             liquidation_searcher_eoa_all_revenue_amt: vec![Default::default()],
             liquidation_searcher_eoa_count:           Default::default(),
 
-            liquidation_fund_all_profit:      vec![Fund::JaneStreet],
+            liquidation_fund_all_profit:      vec![Fund::new("Jane Street")],
             liquidation_fund_all_profit_amt:  vec![0.0],
-            liquidation_fund_all_revenue:     vec![Fund::JaneStreet],
+            liquidation_fund_all_revenue:     vec![Fund::new("Jane Street")],
             liquidation_fund_all_revenue_amt: vec![0.0],
 
             liquidated_tokens_profit:        vec![Default::default()],
@@ -8,8 +8,8 @@ use serde_with::serde_as;
 
 use crate::{
     db::{
+        fund::Fund,
         redefined_types::primitives::{AddressRedefined, BlsPublicKeyRedefined},
-        searcher::Fund,
     },
     implement_table_value_codecs_with_zc,
     serde_utils::{addresss, option_addresss, option_fund, vec_address, vec_bls_pub_key},
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use redefined::{self_convert_redefined, Redefined};
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{implement_table_value_codecs_with_zc, FastHashMap};
+
+/// The trading firm behind a searcher or builder. Funds are keyed by their
+/// canonical name in the `Funds` table so new entities only need a config
+/// entry. An empty name means the searcher isn't attributed to any fund.
+#[derive(
+    Debug, Default, PartialEq, Eq, Clone, rSerialize, rDeserialize, Archive, PartialOrd, Ord, Hash,
+)]
+pub struct Fund(String);
+
+impl Fund {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self::from(name.into())
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Fund {
+    fn as_ref(&self) -> &str {
+        if self.is_none() {
+            "None"
+        } else {
+            &self.0
+        }
+    }
+}
+
+impl fmt::Display for Fund {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl From<String> for Fund {
+    fn from(value: String) -> Self {
+        let name = value.trim();
+        if name.is_empty() || name == "None" {
+            return Self::default()
+        }
+
+        Self(name.to_string())
+    }
+}
+
+impl From<&str> for Fund {
+    fn from(value: &str) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl Serialize for Fund {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Serialize::serialize(self.as_ref(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fund {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let fund: String = Deserialize::deserialize(deserializer)?;
+
+        Ok(fund.into())
+    }
+}
+
+self_convert_redefined!(Fund);
+
+/// Registry entry of a fund, keyed by its canonical name.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+pub struct FundInfo {
+    /// other names the fund goes by in the configs and external label sets
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl FundInfo {
+    pub fn merge(&mut self, other: FundInfo) {
+        for alias in other.aliases {
+            if !self.aliases.contains(&alias) {
+                self.aliases.push(alias);
+            }
+        }
+    }
+}
+
+implement_table_value_codecs_with_zc!(FundInfoRedefined);
+
+/// Resolves the names a fund goes by to its canonical [`Fund`].
+#[derive(Debug, Default, Clone)]
+pub struct FundRegistry {
+    names: FastHashMap<String, Fund>,
+}
+
+impl FundRegistry {
+    pub fn insert(&mut self, name: String, info: &FundInfo) {
+        let fund = Fund::from(name);
+        if fund.is_none() {
+            return
+        }
+
+        for alias in &info.aliases {
+            self.names.insert(alias.trim().to_lowercase(), fund.clone());
+        }
+        self.names.insert(fund.name().to_lowercase(), fund);
+    }
+
+    /// Returns the canonical fund for the given name. Unregistered funds are
+    /// kept as is so that they are still labelled.
+    pub fn resolve(&self, fund: &Fund) -> Fund {
+        self.names
+            .get(&fund.name().to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| fund.clone())
+    }
+}
+
+impl FromIterator<(String, FundInfo)> for FundRegistry {
+    fn from_iter<T: IntoIterator<Item = (String, FundInfo)>>(iter: T) -> Self {
+        let mut registry = Self::default();
+        for (name, info) in iter {
+            registry.insert(name, &info);
+        }
+
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fund_registry_resolves_aliases() {
+        let registry = FundRegistry::from_iter([(
+            "SCP".to_string(),
+            FundInfo { aliases: vec!["Symbolic Capital Partners".to_string()] },
+        )]);
+
+        assert_eq!(registry.resolve(&"Symbolic Capital Partners".into()), Fund::new("SCP"));
+        assert_eq!(registry.resolve(&"scp".into()), Fund::new("SCP"));
+        assert_eq!(registry.resolve(&"Beelder".into()), Fund::new("Beelder"));
+        assert!(registry.resolve(&Fund::default()).is_none());
+    }
+
+    #[test]
+    fn test_fund_serde() {
+        assert_eq!(serde_json::to_string(&Fund::default()).unwrap(), "\"None\"");
+        assert_eq!(serde_json::to_string(&Fund::new("Jane Street")).unwrap(), "\"Jane Street\"");
+        assert!(serde_json::from_str::<Fund>("\"None\"").unwrap().is_none());
+        assert!(serde_json::from_str::<Fund>("\"\"").unwrap().is_none());
+    }
+}
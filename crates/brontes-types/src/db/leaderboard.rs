@@ -0,0 +1,438 @@
+use std::{cmp::Ordering, io::Write};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::{
+    db::{fund::FundRegistry, mev_block::MevBlockWithClassified},
+    mev::{Bundle, MevType},
+    FastHashMap,
+};
+
+/// The kind of entity a leaderboard row aggregates over.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    Display,
+    ValueEnum,
+)]
+pub enum LeaderboardEntity {
+    /// Keyed by the searcher eoa
+    Searcher,
+    /// Keyed by the fund of the bundle, unattributed bundles are skipped
+    Fund,
+    /// Keyed by the builder name, or address if the builder is unknown
+    Builder,
+}
+
+/// Metric the leaderboard rows are ranked by.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum LeaderboardSort {
+    #[default]
+    Profit,
+    Revenue,
+    Bribe,
+    BundleCount,
+    WinRate,
+}
+
+/// Aggregated stats of a single entity for a [`MevType`] over a block range.
+/// Rows without a mev type aggregate all mev types, excluding searcher txs and
+/// cex-dex trades like the block analysis does.
+///
+/// For searchers and funds the win rate is the share of their bundles that
+/// were profitable. For builders, the rows aggregate the bundles that landed
+/// in their blocks and the win rate is the share of the range's bundles they
+/// included.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardRow {
+    pub entity_type:        LeaderboardEntity,
+    pub entity:             String,
+    pub mev_type:           Option<MevType>,
+    pub bundle_count:       u64,
+    pub profit_usd:         f64,
+    pub revenue_usd:        f64,
+    pub bribe_usd:          f64,
+    /// Share of the revenue that was paid as bribe
+    pub bribe_share:        f64,
+    pub win_rate:           f64,
+    /// Only set on the aggregate rows of builders
+    pub blocks_built:       Option<u64>,
+    /// Only set on the aggregate rows of builders
+    pub builder_profit_usd: Option<f64>,
+}
+
+impl LeaderboardRow {
+    const CSV_HEADER: &'static str = "entity_type,entity,mev_type,bundle_count,profit_usd,\
+                                      revenue_usd,bribe_usd,bribe_share,win_rate,blocks_built,\
+                                      builder_profit_usd";
+
+    pub fn mev_type_name(&self) -> String {
+        self.mev_type
+            .map(|mev_type| mev_type.to_string())
+            .unwrap_or_else(|| "All".to_string())
+    }
+
+    fn sort_value(&self, sort: LeaderboardSort) -> f64 {
+        match sort {
+            LeaderboardSort::Profit => self.profit_usd,
+            LeaderboardSort::Revenue => self.revenue_usd,
+            LeaderboardSort::Bribe => self.bribe_usd,
+            LeaderboardSort::BundleCount => self.bundle_count as f64,
+            LeaderboardSort::WinRate => self.win_rate,
+        }
+    }
+
+    fn to_csv_line(&self) -> String {
+        [
+            self.entity_type.to_string(),
+            csv_field(&self.entity),
+            self.mev_type_name(),
+            self.bundle_count.to_string(),
+            self.profit_usd.to_string(),
+            self.revenue_usd.to_string(),
+            self.bribe_usd.to_string(),
+            self.bribe_share.to_string(),
+            self.win_rate.to_string(),
+            self.blocks_built.map(|b| b.to_string()).unwrap_or_default(),
+            self.builder_profit_usd
+                .map(|p| p.to_string())
+                .unwrap_or_default(),
+        ]
+        .join(",")
+    }
+}
+
+/// Searcher, fund and builder stats per [`MevType`] aggregated over a range
+/// of [`MevBlockWithClassified`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub start_block: u64,
+    pub end_block:   u64,
+    pub block_count: u64,
+    pub rows:        Vec<LeaderboardRow>,
+}
+
+#[derive(Debug, Default)]
+struct Totals {
+    bundle_count:       u64,
+    profitable_count:   u64,
+    profit_usd:         f64,
+    bribe_usd:          f64,
+    blocks_built:       u64,
+    builder_profit_usd: f64,
+}
+
+impl Totals {
+    fn add_bundle(&mut self, bundle: &Bundle) {
+        self.bundle_count += 1;
+        self.profit_usd += bundle.header.profit_usd;
+        self.bribe_usd += bundle.header.bribe_usd;
+        if bundle.header.profit_usd > 0.0 {
+            self.profitable_count += 1;
+        }
+    }
+}
+
+type EntityKey = (LeaderboardEntity, String, Option<MevType>);
+
+impl Leaderboard {
+    /// Bundles are attributed to the canonical name of their fund, so that
+    /// blocks classified before a fund alias was registered still aggregate
+    /// under the same row.
+    pub fn new(
+        start_block: u64,
+        end_block: u64,
+        blocks: &[MevBlockWithClassified],
+        funds: &FundRegistry,
+    ) -> Self {
+        let mut totals: FastHashMap<EntityKey, Totals> = FastHashMap::default();
+        let mut bundles_per_type: FastHashMap<Option<MevType>, u64> = FastHashMap::default();
+
+        for MevBlockWithClassified { block, mev } in blocks {
+            let builder = block
+                .builder_name
+                .clone()
+                .unwrap_or_else(|| block.builder_address.to_string());
+
+            let builder_totals = totals
+                .entry((LeaderboardEntity::Builder, builder.clone(), None))
+                .or_default();
+            builder_totals.blocks_built += 1;
+            builder_totals.builder_profit_usd += block.builder_profit_usd;
+
+            for bundle in mev {
+                let mev_type = bundle.mev_type();
+                let mev_types = if Self::in_aggregate(mev_type) {
+                    vec![Some(mev_type), None]
+                } else {
+                    vec![Some(mev_type)]
+                };
+
+                let mut entities = vec![
+                    (LeaderboardEntity::Searcher, bundle.header.eoa.to_string()),
+                    (LeaderboardEntity::Builder, builder.clone()),
+                ];
+                let fund = funds.resolve(&bundle.header.fund);
+                if !fund.is_none() {
+                    entities.push((LeaderboardEntity::Fund, fund.to_string()));
+                }
+
+                for mev_type in mev_types {
+                    *bundles_per_type.entry(mev_type).or_default() += 1;
+                    for (entity_type, entity) in &entities {
+                        totals
+                            .entry((*entity_type, entity.clone(), mev_type))
+                            .or_default()
+                            .add_bundle(bundle);
+                    }
+                }
+            }
+        }
+
+        let mut rows = totals
+            .into_iter()
+            .map(|((entity_type, entity, mev_type), totals)| {
+                let revenue_usd = totals.profit_usd + totals.bribe_usd;
+                let win_rate = match entity_type {
+                    LeaderboardEntity::Builder => ratio(
+                        totals.bundle_count as f64,
+                        bundles_per_type.get(&mev_type).copied().unwrap_or_default() as f64,
+                    ),
+                    _ => ratio(totals.profitable_count as f64, totals.bundle_count as f64),
+                };
+                let is_builder_aggregate =
+                    entity_type == LeaderboardEntity::Builder && mev_type.is_none();
+
+                LeaderboardRow {
+                    entity_type,
+                    entity,
+                    mev_type,
+                    bundle_count: totals.bundle_count,
+                    profit_usd: totals.profit_usd,
+                    revenue_usd,
+                    bribe_usd: totals.bribe_usd,
+                    bribe_share: ratio(totals.bribe_usd, revenue_usd),
+                    win_rate,
+                    blocks_built: is_builder_aggregate.then_some(totals.blocks_built),
+                    builder_profit_usd: is_builder_aggregate.then_some(totals.builder_profit_usd),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        rows.sort_by(|a, b| {
+            a.entity_type
+                .cmp(&b.entity_type)
+                .then_with(|| a.mev_type_name().cmp(&b.mev_type_name()))
+                .then_with(|| b.profit_usd.total_cmp(&a.profit_usd))
+                .then_with(|| a.entity.cmp(&b.entity))
+        });
+
+        Self { start_block, end_block, block_count: blocks.len() as u64, rows }
+    }
+
+    /// Returns the top `n` rows of the entity for the given mev type, `None`
+    /// being the aggregate over all mev types.
+    pub fn top(
+        &self,
+        entity_type: LeaderboardEntity,
+        mev_type: Option<MevType>,
+        sort: LeaderboardSort,
+        n: usize,
+    ) -> Vec<&LeaderboardRow> {
+        let mut rows = self
+            .rows
+            .iter()
+            .filter(|row| row.entity_type == entity_type && row.mev_type == mev_type)
+            .collect::<Vec<_>>();
+
+        rows.sort_by(|a, b| {
+            b.sort_value(sort)
+                .partial_cmp(&a.sort_value(sort))
+                .unwrap_or(Ordering::Equal)
+        });
+        rows.truncate(n);
+
+        rows
+    }
+
+    pub fn write_csv<'a, W: Write>(
+        writer: &mut W,
+        rows: impl IntoIterator<Item = &'a LeaderboardRow>,
+    ) -> std::io::Result<()> {
+        writeln!(writer, "{}", LeaderboardRow::CSV_HEADER)?;
+        for row in rows {
+            writeln!(writer, "{}", row.to_csv_line())?;
+        }
+
+        Ok(())
+    }
+
+    fn in_aggregate(mev_type: MevType) -> bool {
+        mev_type != MevType::SearcherTx && mev_type != MevType::CexDexTrades
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Address;
+
+    use super::*;
+    use crate::{
+        db::fund::{Fund, FundInfo},
+        mev::{BundleData, BundleHeader, MevBlock},
+    };
+
+    fn bundle(eoa: Address, fund: &str, mev_type: MevType, profit: f64, bribe: f64) -> Bundle {
+        Bundle {
+            header: BundleHeader {
+                eoa,
+                fund: Fund::new(fund),
+                mev_type,
+                profit_usd: profit,
+                bribe_usd: bribe,
+                ..Default::default()
+            },
+            data:   BundleData::Unknown(Default::default()),
+        }
+    }
+
+    fn block(builder: &str, builder_profit: f64, mev: Vec<Bundle>) -> MevBlockWithClassified {
+        MevBlockWithClassified {
+            block: MevBlock {
+                builder_name: Some(builder.to_string()),
+                builder_profit_usd: builder_profit,
+                ..Default::default()
+            },
+            mev,
+        }
+    }
+
+    fn leaderboard() -> Leaderboard {
+        let alice = Address::with_last_byte(1);
+        let bob = Address::with_last_byte(2);
+        let funds = FundRegistry::from_iter([(
+            "Wintermute".to_string(),
+            FundInfo { aliases: vec!["Wintermute Trading".to_string()] },
+        )]);
+
+        Leaderboard::new(
+            1,
+            2,
+            &[
+                block(
+                    "beaver",
+                    2.0,
+                    vec![
+                        bundle(alice, "Wintermute", MevType::AtomicArb, 10.0, 30.0),
+                        bundle(bob, "", MevType::Sandwich, -5.0, 5.0),
+                        bundle(bob, "", MevType::SearcherTx, 1.0, 1.0),
+                    ],
+                ),
+                block(
+                    "titan",
+                    3.0,
+                    vec![bundle(alice, "Wintermute Trading", MevType::AtomicArb, 20.0, 10.0)],
+                ),
+            ],
+            &funds,
+        )
+    }
+
+    #[test]
+    fn test_searcher_aggregates() {
+        let leaderboard = leaderboard();
+        let top = leaderboard.top(LeaderboardEntity::Searcher, None, LeaderboardSort::Profit, 10);
+
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].entity, Address::with_last_byte(1).to_string());
+        assert_eq!(top[0].bundle_count, 2);
+        assert_eq!(top[0].profit_usd, 30.0);
+        assert_eq!(top[0].revenue_usd, 70.0);
+        assert_eq!(top[0].bribe_share, 40.0 / 70.0);
+        assert_eq!(top[0].win_rate, 1.0);
+
+        // the searcher tx is excluded from the aggregate
+        assert_eq!(top[1].bundle_count, 1);
+        assert_eq!(top[1].win_rate, 0.0);
+
+        let searcher_txs = leaderboard.top(
+            LeaderboardEntity::Searcher,
+            Some(MevType::SearcherTx),
+            LeaderboardSort::Profit,
+            10,
+        );
+        assert_eq!(searcher_txs.len(), 1);
+    }
+
+    #[test]
+    fn test_fund_and_builder_aggregates() {
+        let leaderboard = leaderboard();
+
+        // the alias resolves to the registered fund
+        let funds = leaderboard.top(LeaderboardEntity::Fund, None, LeaderboardSort::Profit, 10);
+        assert_eq!(funds.len(), 1);
+        assert_eq!(funds[0].bundle_count, 2);
+        assert_eq!(funds[0].entity, "Wintermute");
+
+        let builders =
+            leaderboard.top(LeaderboardEntity::Builder, None, LeaderboardSort::BundleCount, 10);
+        assert_eq!(builders[0].entity, "beaver");
+        assert_eq!(builders[0].bundle_count, 2);
+        assert_eq!(builders[0].win_rate, 2.0 / 3.0);
+        assert_eq!(builders[0].blocks_built, Some(1));
+        assert_eq!(builders[1].builder_profit_usd, Some(3.0));
+
+        let arbs = leaderboard.top(
+            LeaderboardEntity::Builder,
+            Some(MevType::AtomicArb),
+            LeaderboardSort::Profit,
+            1,
+        );
+        assert_eq!(arbs.len(), 1);
+        assert_eq!(arbs[0].entity, "titan");
+        assert_eq!(arbs[0].blocks_built, None);
+    }
+
+    #[test]
+    fn test_csv_output() {
+        let leaderboard = leaderboard();
+        let mut out = Vec::new();
+        Leaderboard::write_csv(
+            &mut out,
+            leaderboard.top(LeaderboardEntity::Fund, None, LeaderboardSort::Profit, 1),
+        )
+        .unwrap();
+
+        let csv = String::from_utf8(out).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(LeaderboardRow::CSV_HEADER));
+        assert_eq!(lines.next(), Some("Fund,Wintermute,All,2,30,70,40,0.5714285714285714,1,,"));
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+}
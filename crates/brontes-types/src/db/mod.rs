@@ -14,16 +14,20 @@ pub mod clickhouse;
 pub mod clickhouse_serde;
 pub mod codecs;
 pub mod dex;
+pub mod fund;
 pub mod initialized_state;
+pub mod leaderboard;
 pub mod metadata;
 pub mod mev_block;
 pub mod normalized_actions;
 pub mod pool_creation_block;
+pub mod pricing_snapshot;
 pub mod redefined_types;
 pub mod searcher;
 pub mod token_info;
 pub mod traces;
 pub mod traits;
+pub mod uniswap_v4_pool_key;
 
 /// This table is used to add run id inserts for each clickhouse table in order
 /// for us to not have to clear runs multiple times
@@ -0,0 +1,22 @@
+use alloy_primitives::{Address, Bytes};
+use clickhouse::Row;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{db::redefined_types::primitives::*, implement_table_value_codecs_with_zc};
+
+/// Snapshot of the dex pricing graph state after the block it is keyed by.
+/// The subgraph registry, verifier and state tracker live in
+/// `brontes-pricing`, so they are stored here as an already encoded blob
+/// that only the pricer knows how to decode.
+#[derive(Debug, Default, Clone, Row, PartialEq, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+pub struct PricingGraphSnapshot {
+    /// the quote asset the graph was built for, a snapshot can't be reused
+    /// with a different one
+    pub quote_asset: Address,
+    pub data:        Bytes,
+}
+
+implement_table_value_codecs_with_zc!(PricingGraphSnapshotRedefined);
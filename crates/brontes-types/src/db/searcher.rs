@@ -1,4 +1,4 @@
-use std::{fmt, ops::Add};
+use std::ops::Add;
 
 use alloy_primitives::Address;
 use clickhouse::Row;
@@ -7,10 +7,9 @@ use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use serde_with::serde_as;
-use strum::AsRefStr;
 
 use crate::{
-    db::redefined_types::primitives::AddressRedefined,
+    db::{fund::Fund, redefined_types::primitives::AddressRedefined},
     implement_table_value_codecs_with_zc,
     mev::{BundleHeader, MevCount, MevType},
     serde_utils::{addresss, option_addresss, vec_address},
@@ -68,13 +67,13 @@ impl SearcherInfo {
             MevType::CexDexQuotes => self.mev_count.cex_dex_quote_count,
             MevType::CexDexRfq => self.mev_count.cex_dex_rfq_count,
             MevType::JitCexDex => self.mev_count.jit_cex_dex_count,
-            MevType::Sandwich => self.mev_count.sandwich_count,
-            MevType::Jit => self.mev_count.jit_count,
+            MevType::Sandwich | MevType::MultiBlockSandwich => self.mev_count.sandwich_count,
+            MevType::Jit | MevType::MultiBlockJit => self.mev_count.jit_count,
             MevType::JitSandwich => self.mev_count.jit_sandwich_count,
             MevType::AtomicArb => self.mev_count.atomic_backrun_count,
             MevType::Liquidation => self.mev_count.liquidation_count,
             MevType::SearcherTx => self.mev_count.searcher_tx_count,
-            MevType::Unknown => None,
+            MevType::Custom | MevType::Unknown => None,
         }
     }
 
@@ -107,8 +106,7 @@ impl SearcherInfo {
         }
         let mut parts: Vec<String> = Vec::new();
 
-        if let Fund::None = self.fund {
-        } else {
+        if !self.fund.is_none() {
             parts.push(self.fund.to_string());
         }
 
@@ -221,7 +219,7 @@ impl TollByType {
                         .add(header.profit_usd),
                 )
             }
-            MevType::Sandwich => {
+            MevType::Sandwich | MevType::MultiBlockSandwich => {
                 self.sandwich = Some(self.sandwich.unwrap_or_default().add(header.profit_usd))
             }
             MevType::AtomicArb => {
@@ -231,7 +229,9 @@ impl TollByType {
                         .add(header.profit_usd),
                 )
             }
-            MevType::Jit => self.jit = Some(self.jit.unwrap_or_default().add(header.profit_usd)),
+            MevType::Jit | MevType::MultiBlockJit => {
+                self.jit = Some(self.jit.unwrap_or_default().add(header.profit_usd))
+            }
             MevType::JitSandwich => {
                 self.jit_sandwich =
                     Some(self.jit_sandwich.unwrap_or_default().add(header.profit_usd))
@@ -263,7 +263,7 @@ impl TollByType {
                         .add(header.bribe_usd),
                 )
             }
-            MevType::Sandwich => {
+            MevType::Sandwich | MevType::MultiBlockSandwich => {
                 self.sandwich = Some(self.sandwich.unwrap_or_default().add(header.bribe_usd))
             }
             MevType::AtomicArb => {
@@ -273,7 +273,9 @@ impl TollByType {
                         .add(header.bribe_usd),
                 )
             }
-            MevType::Jit => self.jit = Some(self.jit.unwrap_or_default().add(header.bribe_usd)),
+            MevType::Jit | MevType::MultiBlockJit => {
+                self.jit = Some(self.jit.unwrap_or_default().add(header.bribe_usd))
+            }
             MevType::JitSandwich => {
                 self.jit_sandwich =
                     Some(self.jit_sandwich.unwrap_or_default().add(header.bribe_usd))
@@ -289,103 +291,6 @@ impl TollByType {
     }
 }
 
-#[derive(
-    Debug,
-    Default,
-    PartialEq,
-    Eq,
-    Clone,
-    rSerialize,
-    rDeserialize,
-    Archive,
-    Copy,
-    AsRefStr,
-    PartialOrd,
-    Hash,
-)]
-pub enum Fund {
-    #[default]
-    None,
-    SymbolicCapitalPartners,
-    Wintermute,
-    JaneStreet,
-    JumpTrading,
-    Kronos,
-    FlowTraders,
-    TokkaLabs,
-    EthBuilder,
-    ICANHAZBLOCK,
-}
-
-impl fmt::Display for Fund {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Fund::None => "None",
-                Fund::SymbolicCapitalPartners => "SCP",
-                Fund::Wintermute => "Wintermute",
-                Fund::JaneStreet => "Jane Street",
-                Fund::JumpTrading => "Jump Trading",
-                Fund::Kronos => "Kronos",
-                Fund::FlowTraders => "Flow Traders",
-                Fund::TokkaLabs => "Tokka Labs",
-                Fund::EthBuilder => "Eth Builder",
-                Fund::ICANHAZBLOCK => "I CAN HAZ BLOCK",
-            }
-        )
-    }
-}
-
-impl Fund {
-    pub fn is_none(&self) -> bool {
-        matches!(self, Fund::None)
-    }
-}
-
-impl From<String> for Fund {
-    fn from(value: String) -> Self {
-        match value.as_str() {
-            "Symbolic Capital Partners" => Self::SymbolicCapitalPartners,
-            "SymbolicCapitalPartners" => Self::SymbolicCapitalPartners,
-            "Wintermute" => Self::Wintermute,
-            "Jane Street" => Self::JaneStreet,
-            "Jump Trading" => Self::JumpTrading,
-            "Flow Traders" => Self::FlowTraders,
-            "Tokka Labs" => Self::TokkaLabs,
-            "Kronos Research" => Self::Kronos,
-            "EthBuilder" => Self::EthBuilder,
-            "ICANHAZBLOCK" => Self::ICANHAZBLOCK,
-            _ => Self::None,
-        }
-    }
-}
-
-impl Serialize for Fund {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let fund_str = format!("{}", self);
-
-        Serialize::serialize(&fund_str, serializer)
-    }
-}
-
-impl<'de> Deserialize<'de> for Fund {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let fund: String = Deserialize::deserialize(deserializer)?;
-
-        Ok(fund.into())
-    }
-}
-
-self_convert_redefined!(Fund);
-
 #[derive(Debug, Row, PartialEq, Clone, Serialize, Deserialize)]
 pub struct JoinedSearcherInfo {
     #[serde(with = "addresss")]
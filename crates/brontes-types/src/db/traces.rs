@@ -1,6 +1,6 @@
 use alloy_primitives::{Log, LogData};
 use clickhouse::Row;
-use redefined::Redefined;
+use redefined::{Redefined, RedefinedConvert};
 use reth_rpc_types::trace::parity::{
     Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput, RewardAction, RewardType,
     SelfdestructAction, TraceOutput, TransactionTrace,
@@ -11,7 +11,10 @@ use serde::{Deserialize, Serialize};
 use super::redefined_types::primitives::*;
 use crate::{
     implement_table_value_codecs_with_zc,
-    structured_trace::{DecodedCallData, TransactionTraceWithLogs, TxTrace},
+    structured_trace::{
+        AccountStateDiff, BalanceDiff, DecodedCallData, StorageSlotDiff, TransactionTraceWithLogs,
+        TxTrace,
+    },
 };
 
 #[derive(Debug, Default, PartialEq, Row, Clone, Serialize, Deserialize, Redefined)]
@@ -28,6 +31,52 @@ impl TxTracesInner {
 
 implement_table_value_codecs_with_zc!(TxTracesInnerRedefined);
 
+/// The state diffs of the transactions of a block. They are stored in their own
+/// table so the layout of the stored traces doesn't depend on them.
+#[derive(Debug, Default, PartialEq, Row, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+pub struct TxStateDiffsInner {
+    /// Only holds the transactions that changed state
+    pub txs: Vec<TxStateDiff>,
+}
+
+impl TxStateDiffsInner {
+    pub fn from_traces(traces: &[TxTrace]) -> Self {
+        Self {
+            txs: traces
+                .iter()
+                .filter(|trace| !trace.state_diff.is_empty())
+                .map(|trace| TxStateDiff {
+                    tx_index: trace.tx_index,
+                    accounts: trace.state_diff.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    /// Sets the state diff of the traces to the one stored for their tx index
+    pub fn apply(self, traces: &mut [TxTrace]) {
+        for TxStateDiff { tx_index, accounts } in self.txs {
+            if let Some(trace) = traces.iter_mut().find(|trace| trace.tx_index == tx_index) {
+                trace.state_diff = accounts;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+pub struct TxStateDiff {
+    pub tx_index: u64,
+    pub accounts: Vec<AccountStateDiff>,
+}
+
+implement_table_value_codecs_with_zc!(TxStateDiffsInnerRedefined);
+
 #[derive(
     Debug,
     Clone,
@@ -40,6 +89,7 @@ implement_table_value_codecs_with_zc!(TxTracesInnerRedefined);
     Default,
 )]
 #[redefined(TxTrace)]
+#[redefined_attr(to_source = "self.into_trace()", from_source = "Self::from_trace(src)")]
 pub struct TxTraceRedefined {
     pub block_number:    u64,
     pub trace:           Vec<TransactionTraceWithLogsRedefined>,
@@ -51,6 +101,64 @@ pub struct TxTraceRedefined {
     pub is_success:      bool,
 }
 
+/// The state diff isn't part of the stored trace, it lives in
+/// [`TxStateDiffsInner`]
+impl TxTraceRedefined {
+    fn from_trace(trace: TxTrace) -> Self {
+        Self {
+            block_number:    trace.block_number,
+            trace:           Vec::<TransactionTraceWithLogsRedefined>::from_source(trace.trace),
+            tx_hash:         FixedBytesRedefined::from_source(trace.tx_hash),
+            gas_used:        trace.gas_used,
+            effective_price: trace.effective_price,
+            tx_index:        trace.tx_index,
+            is_success:      trace.is_success,
+        }
+    }
+
+    fn into_trace(self) -> TxTrace {
+        TxTrace::new(
+            self.block_number,
+            self.trace.to_source(),
+            self.tx_hash.to_source(),
+            self.tx_index,
+            self.gas_used,
+            self.effective_price,
+            self.is_success,
+            vec![],
+        )
+    }
+}
+
+#[derive(
+    Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
+)]
+#[redefined(AccountStateDiff)]
+pub struct AccountStateDiffRedefined {
+    pub address: AddressRedefined,
+    pub balance: Option<BalanceDiffRedefined>,
+    pub storage: Vec<StorageSlotDiffRedefined>,
+}
+
+#[derive(
+    Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
+)]
+#[redefined(BalanceDiff)]
+pub struct BalanceDiffRedefined {
+    pub pre:  U256Redefined,
+    pub post: U256Redefined,
+}
+
+#[derive(
+    Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
+)]
+#[redefined(StorageSlotDiff)]
+pub struct StorageSlotDiffRedefined {
+    pub slot: U256Redefined,
+    pub pre:  U256Redefined,
+    pub post: U256Redefined,
+}
+
 #[derive(
     Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
 )]
@@ -2,10 +2,19 @@ use alloy_primitives::Address;
 
 use crate::{
     db::{
-        address_metadata::AddressMetadata, address_to_protocol_info::ProtocolInfo,
-        builder::BuilderInfo, cex::trades::CexTradeMap, dex::DexQuotes, metadata::Metadata,
-        mev_block::MevBlockWithClassified, searcher::SearcherInfo,
+        address_metadata::AddressMetadata,
+        address_to_protocol_info::ProtocolInfo,
+        builder::BuilderInfo,
+        cex::{quotes::CexPriceMap, trades::CexTradeMap},
+        dex::{DexQuoteWithIndex, DexQuotes},
+        fund::{Fund, FundInfo, FundRegistry},
+        leaderboard::Leaderboard,
+        metadata::Metadata,
+        mev_block::MevBlockWithClassified,
+        pricing_snapshot::PricingGraphSnapshot,
+        searcher::SearcherInfo,
         token_info::TokenInfoWithAddress,
+        uniswap_v4_pool_key::UniswapV4PoolKey,
     },
     pair::Pair,
     structured_trace::TxTrace,
@@ -96,6 +105,25 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
 
     fn fetch_all_builder_info(&self) -> eyre::Result<Vec<(Address, BuilderInfo)>>;
 
+    fn try_fetch_fund_info(&self, name: &str) -> eyre::Result<Option<FundInfo>>;
+
+    fn fetch_all_fund_info(&self) -> eyre::Result<Vec<(String, FundInfo)>>;
+
+    /// builds the lookup from every name a fund goes by to its canonical name
+    fn fetch_fund_registry(&self) -> eyre::Result<FundRegistry> {
+        Ok(self.fetch_all_fund_info()?.into_iter().collect())
+    }
+
+    /// resolves the fund to its canonical name, unregistered funds are kept
+    /// as is
+    fn try_resolve_fund(&self, fund: &Fund) -> eyre::Result<Fund> {
+        if fund.is_none() {
+            return Ok(Fund::default())
+        }
+
+        Ok(self.fetch_fund_registry()?.resolve(fund))
+    }
+
     fn get_metadata(&self, block_num: u64, quote_asset: Address) -> eyre::Result<Metadata>;
 
     fn get_cex_trades(&self, block: u64) -> eyre::Result<CexTradeMap>;
@@ -119,6 +147,21 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
         end_block: u64,
     ) -> eyre::Result<Vec<MevBlockWithClassified>>;
 
+    /// fetches the mev blocks of all blocks in the inclusive range
+    fn fetch_mev_block_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<MevBlockWithClassified>>;
+
+    /// Aggregates the searcher, fund and builder stats of the mev blocks in
+    /// the inclusive block range
+    fn fetch_leaderboard(&self, start_block: u64, end_block: u64) -> eyre::Result<Leaderboard> {
+        let blocks = self.fetch_mev_block_range(start_block, end_block)?;
+        let funds = self.fetch_fund_registry()?;
+        Ok(Leaderboard::new(start_block, end_block, &blocks, &funds))
+    }
+
     fn fetch_all_mev_blocks(
         &self,
         start_block: Option<u64>,
@@ -154,5 +197,46 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
         })
     }
 
+    /// fetches the pool key of a uniswap v4 pool from the address the pool is
+    /// keyed by
+    fn try_fetch_v4_pool_key(&self, pool: Address) -> eyre::Result<Option<UniswapV4PoolKey>>;
+
+    fn fetch_all_v4_pool_keys(&self) -> eyre::Result<Vec<(Address, UniswapV4PoolKey)>>;
+
+    /// fetches the most recent pricing graph snapshot taken at or before the
+    /// given block, along with the block it was taken after
+    fn try_fetch_latest_pricing_snapshot(
+        &self,
+        block: u64,
+    ) -> eyre::Result<Option<(u64, PricingGraphSnapshot)>>;
+
     fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>>;
+
+    /// fetches the raw dex quotes of all blocks in the inclusive range, keyed
+    /// by block number. Used for bulk exports
+    fn fetch_dex_price_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, DexQuoteWithIndex)>>;
+
+    fn fetch_cex_price_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, CexPriceMap)>>;
+
+    fn fetch_cex_trades_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, CexTradeMap)>>;
+
+    fn fetch_trace_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, Vec<TxTrace>)>>;
+
+    fn fetch_all_token_info(&self) -> eyre::Result<Vec<TokenInfoWithAddress>>;
 }
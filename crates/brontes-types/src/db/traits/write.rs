@@ -4,7 +4,8 @@ use futures::Future;
 use crate::{
     db::{
         address_metadata::AddressMetadata, block_analysis::BlockAnalysis, builder::BuilderInfo,
-        dex::DexQuotes, searcher::SearcherInfo,
+        dex::DexQuotes, fund::FundInfo, pricing_snapshot::PricingGraphSnapshot,
+        searcher::SearcherInfo, uniswap_v4_pool_key::UniswapV4PoolKey,
     },
     mev::{Bundle, MevBlock},
     normalized_actions::Action,
@@ -98,6 +99,14 @@ pub trait DBWriter: Send + Unpin + 'static {
         self.inner().write_address_meta(address, metadata)
     }
 
+    fn write_fund_info(
+        &self,
+        name: String,
+        info: FundInfo,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().write_fund_info(name, info)
+    }
+
     fn insert_pool(
         &self,
         block: u64,
@@ -110,6 +119,25 @@ pub trait DBWriter: Send + Unpin + 'static {
             .insert_pool(block, address, tokens, curve_lp_token, classifier_name)
     }
 
+    /// stores the full pool key for a uniswap v4 pool. The pool itself is
+    /// inserted through `insert_pool` keyed by the address derived from its id
+    fn insert_v4_pool_key(
+        &self,
+        pool_key: UniswapV4PoolKey,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().insert_v4_pool_key(pool_key)
+    }
+
+    /// stores the pricing graph state as it was after the given block so that
+    /// later runs can resume pricing from it
+    fn save_pricing_snapshot(
+        &self,
+        block: u64,
+        snapshot: PricingGraphSnapshot,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().save_pricing_snapshot(block, snapshot)
+    }
+
     fn insert_tree(
         &self,
         tree: BlockTree<Action>,
@@ -124,4 +152,11 @@ pub trait DBWriter: Send + Unpin + 'static {
     ) -> impl Future<Output = eyre::Result<()>> + Send {
         self.inner().save_traces(block, traces)
     }
+
+    /// removes all mev, trace and dex pricing data that was written for the
+    /// given block. Used to clean up blocks that got reorged out of the
+    /// canonical chain
+    fn rollback_block(&self, block: u64) -> impl Future<Output = eyre::Result<()>> + Send {
+        self.inner().rollback_block(block)
+    }
 }
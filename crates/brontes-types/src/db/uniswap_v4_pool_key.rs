@@ -0,0 +1,63 @@
+use alloy_primitives::{hex, Address, B256};
+use clickhouse::Row;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::WETH_ADDRESS, db::redefined_types::primitives::*,
+    implement_table_value_codecs_with_zc,
+};
+
+pub const UNISWAP_V4_POOL_MANAGER: Address =
+    Address::new(hex!("000000000004444c5dc75cB358380D2e3dE08A90"));
+
+/// Uniswap V4 pools all live inside of the singleton pool manager and are
+/// identified by a `bytes32` pool id instead of an address. In order to fit
+/// them into the rest of the pipeline which is keyed by address, we key the
+/// pool by the first 20 bytes of its id.
+pub fn v4_pool_id_to_address(pool_id: B256) -> Address {
+    Address::from_slice(&pool_id[0..20])
+}
+
+/// V4 uses the zero address for native eth. We price and classify it as weth
+pub fn v4_currency_to_token(currency: Address) -> Address {
+    if currency.is_zero() {
+        WETH_ADDRESS
+    } else {
+        currency
+    }
+}
+
+/// The full `PoolKey` of a V4 pool along with its id. This is needed to go
+/// from the address we key the pool by back to the pool id when querying the
+/// pool state.
+#[derive(Debug, Default, Clone, Row, PartialEq, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+pub struct UniswapV4PoolKey {
+    pub pool_id:      B256,
+    pub currency0:    Address,
+    pub currency1:    Address,
+    pub fee:          u32,
+    pub tick_spacing: i32,
+    /// the hook contract that is attached to the pool. Zero if the pool has no
+    /// hooks
+    pub hooks:        Address,
+    pub init_block:   u64,
+}
+
+impl UniswapV4PoolKey {
+    pub fn pool_address(&self) -> Address {
+        v4_pool_id_to_address(self.pool_id)
+    }
+
+    pub fn tokens(&self) -> [Address; 2] {
+        [v4_currency_to_token(self.currency0), v4_currency_to_token(self.currency1)]
+    }
+
+    pub fn has_hooks(&self) -> bool {
+        !self.hooks.is_zero()
+    }
+}
+
+implement_table_value_codecs_with_zc!(UniswapV4PoolKeyRedefined);
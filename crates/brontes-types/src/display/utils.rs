@@ -146,6 +146,15 @@ pub fn display_sandwich(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Result
             .to_string()
             .bright_red()
     )?;
+    for loss in &sandwich_data.victim_loss {
+        writeln!(
+            f,
+            " - {}: {} for {}",
+            "Simulated Victim Loss (USD)".bright_white(),
+            format_bribe(loss.amount_lost_usd).to_string().bright_red(),
+            format_etherscan_url(&loss.tx_hash)
+        )?;
+    }
 
     bundle
         .header
@@ -336,6 +345,16 @@ const LONG_TAIL_HEADER: &str = indoc! {r#"
                    |___/                                        
 "#};
 
+const FLASH_LOAN_ARB: &str = indoc! {r#"
+______  _              _        _                                ___       _     
+|  ___|| |            | |      | |                              / _ \     | |    
+| |_   | |  __ _  ___ | |__    | |       ___    __ _  _ __     / /_\ \_ __| |__  
+|  _|  | | / _` |/ __|| '_ \   | |      / _ \  / _` || '_ \    |  _  | '__| '_ \ 
+| |    | || (_| |\__ \| | | |  | |____ | (_) || (_| || | | |   | | | | |  | |_) |
+\_|    |_| \__,_||___/|_| |_|  \_____/  \___/  \__,_||_| |_|   \_| |_/_|  |_.__/ 
+                                                                                 
+"#};
+
 const CROSS_PAIR: &str = indoc! {r#"
  _____                    ______     _         ___       _     
 /  __ \                   | ___ \   (_)       / _ \     | |    
@@ -384,6 +403,11 @@ pub fn display_atomic_backrun(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::R
                 writeln!(f, "{}", line.bright_green())?;
             }
         }
+        AtomicArbType::FlashLoanArb => {
+            for line in FLASH_LOAN_ARB.lines() {
+                writeln!(f, "{}", line.bright_magenta())?;
+            }
+        }
     }
 
     // Tx details
@@ -447,6 +471,16 @@ pub fn display_atomic_backrun(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::R
             .to_string()
             .bright_red()
     )?;
+    if let Some(refund) = &atomic_backrun_data.refund {
+        writeln!(
+            f,
+            " - {}: {} to {} ({} refund)",
+            "OFA Refund (USD)".bright_white(),
+            format_bribe(refund.amount_usd).to_string().bright_red(),
+            refund.recipient,
+            refund.refund_type
+        )?;
+    }
 
     bundle
         .header
@@ -665,6 +699,14 @@ pub fn display_jit_liquidity(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Re
             .to_string()
             .bright_red()
     )?;
+    writeln!(
+        f,
+        " - {}: {}",
+        "Fees Earned (USD)".bright_white(),
+        format_profit(jit_data.fees_earned_usd)
+            .to_string()
+            .bright_white()
+    )?;
 
     bundle
         .header
@@ -1014,6 +1056,47 @@ pub fn display_searcher_tx(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Resu
     Ok(())
 }
 
+pub fn display_custom(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::Result {
+    let custom_data = match &bundle.data {
+        BundleData::Custom(data) => data,
+        _ => panic!("Wrong bundle type"),
+    };
+
+    writeln!(
+        f,
+        "\n{}: \n",
+        format!("Custom Mev ({})", custom_data.inspector)
+            .bold()
+            .underline()
+            .bright_yellow()
+    )?;
+    writeln!(f, "   - Tx Index: {}", bundle.header.tx_index.to_string().bold())?;
+    writeln!(f, "   - EOA: {}", bundle.header.eoa)?;
+    for tx_hash in &custom_data.tx_hashes {
+        writeln!(f, "   - Etherscan: {}", format_etherscan_url(tx_hash))?;
+    }
+
+    writeln!(f, "  - {}:", "PnL".bright_blue())?;
+    writeln!(f, "   - Bundle Profit (USD): {}", format_profit(bundle.header.profit_usd))?;
+    writeln!(f, "   - Bribe (USD): {}", (format_bribe(bundle.header.bribe_usd)).to_string().red())?;
+
+    bundle
+        .header
+        .balance_deltas
+        .iter()
+        .for_each(|tx_delta| writeln!(f, "{}", tx_delta).expect("Failed to write balance deltas"));
+
+    writeln!(f, "\n{}: \n", "Payload".underline().bright_yellow())?;
+    writeln!(f, "   {}", custom_data.payload)?;
+
+    writeln!(f, "\n{}: \n", "Gas Details".underline().bright_yellow())?;
+    for gas_details in &custom_data.gas_details {
+        gas_details.pretty_print_with_spaces(f, 8)?;
+    }
+
+    Ok(())
+}
+
 // Helper function to format profit values
 fn format_profit(value: f64) -> ColoredString {
     if value < 0.0 {
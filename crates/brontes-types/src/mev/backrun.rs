@@ -9,14 +9,14 @@ use ahash::HashSet;
 #[allow(unused)]
 use clickhouse::fixed_string::FixedString;
 use redefined::{self_convert_redefined, Redefined};
-use reth_primitives::B256;
+use reth_primitives::{Address, B256};
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use super::{Mev, MevType};
 use crate::{
-    db::redefined_types::primitives::B256Redefined,
+    db::redefined_types::primitives::{AddressRedefined, B256Redefined},
     normalized_actions::{ClickhouseVecNormalizedSwap, NormalizedSwap, NormalizedSwapRedefined},
     GasDetails, Protocol,
 };
@@ -33,10 +33,56 @@ pub struct AtomicArb {
     pub gas_details:  GasDetails,
     #[redefined(same_fields)]
     pub arb_type:     AtomicArbType,
+    /// refund paid to the originator of the trigger tx, if the arb was a
+    /// backrun won through an order flow auction
+    pub refund:       Option<OfaRefund>,
 }
+
+/// A refund paid back to the originator of the trigger tx through an order
+/// flow auction such as MEV-Share or MEV Blocker
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+pub struct OfaRefund {
+    pub recipient:   Address,
+    /// tx the refund was paid in, either the arb itself or a payout of the
+    /// builder
+    pub tx_hash:     B256,
+    #[redefined(same_fields)]
+    pub refund_type: RefundType,
+    pub amount_eth:  f64,
+    pub amount_usd:  f64,
+}
+
+/// Who paid the refund to the user
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+    strum::Display,
+)]
+pub enum RefundType {
+    /// the searcher refunds the user in the backrun itself
+    #[default]
+    Searcher,
+    /// the builder refunds the user at the end of the block out of the
+    /// searcher's bid, as done by MEV-Share and MEV Blocker
+    Builder,
+}
+
+self_convert_redefined!(RefundType);
 /// Represents the different types of atomic arb
 /// A triangle arb is a simple arb that goes from token A -> B -> C -> A
 /// A cross pair arb is a more complex arb that goes from token A -> B -> C -> A
+/// A flash loan arb is any of the above funded by a flash loan, the borrowed
+/// principal is netted out of the deltas
 
 #[derive(
     Debug,
@@ -57,6 +103,7 @@ pub enum AtomicArbType {
     CrossPair(usize),
     StablecoinArb,
     LongTail,
+    FlashLoanArb,
 }
 impl Display for AtomicArbType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -65,12 +112,11 @@ impl Display for AtomicArbType {
             AtomicArbType::CrossPair(_) => writeln!(f, "Cross Pair Arbitrage"),
             AtomicArbType::StablecoinArb => writeln!(f, "Stablecoin Arbitrage"),
             AtomicArbType::LongTail => writeln!(f, "LongTail Arbitrage"),
+            AtomicArbType::FlashLoanArb => writeln!(f, "Flash Loan Arbitrage"),
         }
     }
 }
 
-//TODO: Ludwig, add flashloan arb support
-
 self_convert_redefined!(AtomicArbType);
 
 impl Mev for AtomicArb {
@@ -129,6 +175,22 @@ impl Serialize for AtomicArb {
         );
         ser_struct.serialize_field("gas_details", &gas_details)?;
         ser_struct.serialize_field("arb_type", &self.arb_type.to_string())?;
+        ser_struct.serialize_field(
+            "refund_recipient",
+            &self.refund.as_ref().map(|r| format!("{:?}", r.recipient)),
+        )?;
+        ser_struct.serialize_field(
+            "refund_tx_hash",
+            &self.refund.as_ref().map(|r| format!("{:?}", r.tx_hash)),
+        )?;
+        ser_struct.serialize_field(
+            "refund_type",
+            &self.refund.as_ref().map(|r| r.refund_type.to_string()),
+        )?;
+        ser_struct
+            .serialize_field("refund_amount_eth", &self.refund.as_ref().map(|r| r.amount_eth))?;
+        ser_struct
+            .serialize_field("refund_amount_usd", &self.refund.as_ref().map(|r| r.amount_usd))?;
         ser_struct.end()
     }
 }
@@ -148,5 +210,10 @@ impl DbRow for AtomicArb {
         "swaps.amount_out",
         "gas_details",
         "arb_type",
+        "refund_recipient",
+        "refund_tx_hash",
+        "refund_type",
+        "refund_amount_eth",
+        "refund_amount_usd",
     ];
 }
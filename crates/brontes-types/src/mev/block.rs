@@ -211,14 +211,16 @@ impl MevCount {
             MevType::CexDexQuotes => {
                 self.cex_dex_quote_count = Some(self.cex_dex_quote_count.unwrap_or_default().add(1))
             }
-            MevType::Sandwich => {
+            MevType::Sandwich | MevType::MultiBlockSandwich => {
                 self.sandwich_count = Some(self.sandwich_count.unwrap_or_default().add(1))
             }
             MevType::AtomicArb => {
                 self.atomic_backrun_count =
                     Some(self.atomic_backrun_count.unwrap_or_default().add(1))
             }
-            MevType::Jit => self.jit_count = Some(self.jit_count.unwrap_or_default().add(1)),
+            MevType::Jit | MevType::MultiBlockJit => {
+                self.jit_count = Some(self.jit_count.unwrap_or_default().add(1))
+            }
             MevType::JitSandwich => {
                 self.jit_sandwich_count = Some(self.jit_sandwich_count.unwrap_or_default().add(1))
             }
@@ -13,8 +13,8 @@ use serde_with::serde_as;
 use super::MevType;
 use crate::{
     db::{
+        fund::Fund,
         redefined_types::primitives::*,
-        searcher::Fund,
         token_info::{TokenInfoWithAddress, TokenInfoWithAddressRedefined},
     },
     serde_utils::{addresss, option_addresss, txhash},
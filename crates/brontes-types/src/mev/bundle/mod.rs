@@ -53,7 +53,7 @@ impl Bundle {
 impl fmt::Display for Bundle {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.header.mev_type {
-            MevType::Sandwich => display_sandwich(self, f)?,
+            MevType::Sandwich | MevType::MultiBlockSandwich => display_sandwich(self, f)?,
             MevType::CexDexTrades | MevType::JitCexDex => display_cex_dex(self, f)?,
             MevType::CexDexQuotes => display_cex_dex_quotes(self, f)?,
             MevType::CexDexRfq => {
@@ -63,11 +63,12 @@ impl fmt::Display for Bundle {
                     display_cex_dex_quotes(self, f)?
                 }
             }
-            MevType::Jit => display_jit_liquidity(self, f)?,
+            MevType::Jit | MevType::MultiBlockJit => display_jit_liquidity(self, f)?,
             MevType::AtomicArb => display_atomic_backrun(self, f)?,
             MevType::Liquidation => display_liquidation(self, f)?,
             MevType::JitSandwich => display_jit_liquidity_sandwich(self, f)?,
             MevType::SearcherTx => display_searcher_tx(self, f)?,
+            MevType::Custom => display_custom(self, f)?,
             MevType::Unknown => (),
         }
 
@@ -104,17 +105,27 @@ pub enum MevType {
     SearcherTx,
     #[default]
     Unknown,
+    /// sandwich where the frontrun and backrun land in consecutive blocks
+    MultiBlockSandwich,
+    /// bundles found by inspectors registered outside of brontes
+    Custom,
+    /// jit where the liquidity is minted in an earlier block than the one it
+    /// is burned in
+    MultiBlockJit,
 }
 
 impl MevType {
     pub fn use_cex_pricing_for_deltas(&self) -> bool {
         match self {
             MevType::Sandwich
+            | MevType::MultiBlockSandwich
             | MevType::JitSandwich
             | MevType::Jit
+            | MevType::MultiBlockJit
             | MevType::AtomicArb
             | MevType::Liquidation
             | MevType::SearcherTx
+            | MevType::Custom
             | MevType::Unknown => false,
             MevType::CexDexRfq
             | MevType::CexDexTrades
@@ -131,10 +142,13 @@ impl MevType {
             | MevType::CexDexTrades => "cex-dex",
             MevType::AtomicArb => "atomic-arb",
             MevType::Jit => "jit",
+            MevType::MultiBlockJit => "multi-block-jit",
             MevType::Sandwich => "sandwich",
+            MevType::MultiBlockSandwich => "multi-block-sandwich",
             MevType::JitSandwich => "jit-sandwich",
             MevType::SearcherTx => "searcher-tx",
             MevType::Liquidation => "liquidation",
+            MevType::Custom => "custom",
             MevType::Unknown => "header",
         }
     }
@@ -149,11 +163,14 @@ impl From<String> for MevType {
             "CexDexTrades" => MevType::CexDexTrades,
             "CexDexRfq" => MevType::CexDexRfq,
             "Sandwich" => MevType::Sandwich,
+            "MultiBlockSandwich" => MevType::MultiBlockSandwich,
             "Jit" => MevType::Jit,
+            "MultiBlockJit" => MevType::MultiBlockJit,
             "Liquidation" => MevType::Liquidation,
             "JitSandwich" => MevType::JitSandwich,
             "AtomicArb" => MevType::AtomicArb,
             "SearcherTx" => MevType::SearcherTx,
+            "Custom" => MevType::Custom,
             _ => MevType::Unknown,
         }
     }
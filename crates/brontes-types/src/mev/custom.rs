@@ -0,0 +1,141 @@
+use std::fmt::Debug;
+
+use ::clickhouse::DbRow;
+use ::serde::ser::{SerializeStruct, Serializer};
+use ahash::HashSet;
+use redefined::Redefined;
+use reth_primitives::B256;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use super::{Mev, MevType};
+use crate::{db::redefined_types::primitives::*, ClickhouseVecGasDetails, GasDetails, Protocol};
+
+/// Generic payload for bundles found by inspectors that live outside of this
+/// crate. As we don't know the shape of their data, the inspector specific
+/// fields are stored as a json string in `payload`.
+#[serde_as]
+#[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+pub struct CustomMev {
+    /// Name the inspector was registered under
+    pub inspector:    String,
+    pub block_number: u64,
+    pub tx_hashes:    Vec<B256>,
+    /// Gas details of each transaction in `tx_hashes`
+    #[redefined(same_fields)]
+    pub gas_details:  Vec<GasDetails>,
+    pub protocols:    Vec<Protocol>,
+    /// Inspector specific data, serialized as json
+    pub payload:      String,
+}
+
+impl CustomMev {
+    pub fn new<T: Serialize>(
+        inspector: impl Into<String>,
+        block_number: u64,
+        tx_hashes: Vec<B256>,
+        gas_details: Vec<GasDetails>,
+        protocols: Vec<Protocol>,
+        payload: &T,
+    ) -> serde_json::Result<Self> {
+        Ok(Self {
+            inspector: inspector.into(),
+            block_number,
+            tx_hashes,
+            gas_details,
+            protocols,
+            payload: serde_json::to_string(payload)?,
+        })
+    }
+}
+
+impl Mev for CustomMev {
+    fn mev_type(&self) -> MevType {
+        MevType::Custom
+    }
+
+    fn mev_transaction_hashes(&self) -> Vec<B256> {
+        self.tx_hashes.clone()
+    }
+
+    fn total_gas_paid(&self) -> u128 {
+        self.gas_details.iter().map(|gd| gd.gas_paid()).sum()
+    }
+
+    fn total_priority_fee_paid(&self, base_fee: u128) -> u128 {
+        self.gas_details
+            .iter()
+            .map(|gd| gd.priority_fee_paid(base_fee))
+            .sum()
+    }
+
+    fn bribe(&self) -> u128 {
+        self.gas_details
+            .iter()
+            .filter_map(|gd| gd.coinbase_transfer)
+            .sum()
+    }
+
+    fn protocols(&self) -> HashSet<Protocol> {
+        self.protocols.iter().copied().collect()
+    }
+}
+
+impl Serialize for CustomMev {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ser_struct = serializer.serialize_struct("CustomMev", 10)?;
+
+        ser_struct.serialize_field("inspector", &self.inspector)?;
+        ser_struct.serialize_field("block_number", &self.block_number)?;
+        ser_struct.serialize_field(
+            "tx_hashes",
+            &self
+                .tx_hashes
+                .iter()
+                .map(|tx| format!("{:?}", tx))
+                .collect::<Vec<_>>(),
+        )?;
+
+        let gas_details: ClickhouseVecGasDetails =
+            (self.tx_hashes.clone(), self.gas_details.clone()).into();
+        ser_struct.serialize_field("gas_details.tx_hash", &gas_details.tx_hash)?;
+        ser_struct
+            .serialize_field("gas_details.coinbase_transfer", &gas_details.coinbase_transfer)?;
+        ser_struct.serialize_field("gas_details.priority_fee", &gas_details.priority_fee)?;
+        ser_struct.serialize_field("gas_details.gas_used", &gas_details.gas_used)?;
+        ser_struct
+            .serialize_field("gas_details.effective_gas_price", &gas_details.effective_gas_price)?;
+
+        ser_struct.serialize_field(
+            "protocols",
+            &self
+                .protocols
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>(),
+        )?;
+        ser_struct.serialize_field("payload", &self.payload)?;
+
+        ser_struct.end()
+    }
+}
+
+impl DbRow for CustomMev {
+    const COLUMN_NAMES: &'static [&'static str] = &[
+        "inspector",
+        "block_number",
+        "tx_hashes",
+        "gas_details.tx_hash",
+        "gas_details.coinbase_transfer",
+        "gas_details.priority_fee",
+        "gas_details.gas_used",
+        "gas_details.effective_gas_price",
+        "protocols",
+        "payload",
+    ];
+}
@@ -41,6 +41,9 @@ pub struct JitLiquidity {
     pub backrun_burns: Vec<NormalizedBurn>,
     #[redefined(same_fields)]
     pub backrun_burn_gas_details: GasDetails,
+    /// swap fees the position earned while it was live, valued in usd at the
+    /// time of the burn
+    pub fees_earned_usd: f64,
 }
 
 impl Mev for JitLiquidity {
@@ -83,7 +86,7 @@ impl Serialize for JitLiquidity {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("JitLiquidity", 31)?;
+        let mut ser_struct = serializer.serialize_struct("JitLiquidity", 32)?;
 
         // frontrun mint
         ser_struct.serialize_field(
@@ -172,6 +175,7 @@ impl Serialize for JitLiquidity {
         );
 
         ser_struct.serialize_field("backrun_burn_gas_details", &(backrun_burn_gas_details))?;
+        ser_struct.serialize_field("fees_earned_usd", &self.fees_earned_usd)?;
 
         ser_struct.end()
     }
@@ -210,5 +214,6 @@ impl DbRow for JitLiquidity {
         "backrun_burns.tokens",
         "backrun_burns.amounts",
         "backrun_burn_gas_details",
+        "fees_earned_usd",
     ];
 }
@@ -19,3 +19,6 @@ pub use searcher_tx::*;
 
 pub mod cex_dex_quotes;
 pub use cex_dex_quotes::*;
+
+pub mod custom;
+pub use custom::*;
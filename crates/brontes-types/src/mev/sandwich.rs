@@ -5,7 +5,7 @@ use ::serde::ser::{SerializeStruct, Serializer};
 use ahash::HashSet;
 use malachite::Rational;
 use redefined::Redefined;
-use reth_primitives::{Address, B256};
+use reth_primitives::{Address, B256, U256};
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -88,6 +88,25 @@ pub struct Sandwich {
     /// Gas details for each backrunning transaction.
     #[redefined(same_fields)]
     pub backrun_gas_details:      GasDetails,
+    /// Loss of each victim measured by re-executing it without the frontruns.
+    /// Only set when the inspector runs with victim simulation enabled
+    #[serde(default)]
+    pub victim_loss:              Vec<SimulatedVictimLoss>,
+}
+
+/// The amount out a victim got compared to the amount out it would have
+/// gotten if the frontruns hadn't executed before it, both in the raw units of
+/// `token`
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+pub struct SimulatedVictimLoss {
+    pub tx_hash:                   B256,
+    pub victim_eoa:                Address,
+    pub token:                     Address,
+    pub amount_out:                U256,
+    pub counterfactual_amount_out: U256,
+    /// is zero if we don't have a price for the given token
+    pub amount_lost_usd:           f64,
 }
 
 /// calcuation for the loss per user
@@ -282,6 +301,47 @@ impl Serialize for Sandwich {
             &vec![self.backrun_gas_details.effective_gas_price],
         )?;
 
+        let victim_loss_tx_hash = self
+            .victim_loss
+            .iter()
+            .map(|loss| format!("{:?}", loss.tx_hash))
+            .collect::<Vec<_>>();
+        let victim_loss_eoa = self
+            .victim_loss
+            .iter()
+            .map(|loss| format!("{:?}", loss.victim_eoa))
+            .collect::<Vec<_>>();
+        let victim_loss_token = self
+            .victim_loss
+            .iter()
+            .map(|loss| format!("{:?}", loss.token))
+            .collect::<Vec<_>>();
+        let victim_loss_amount_out = self
+            .victim_loss
+            .iter()
+            .map(|loss| loss.amount_out.to_le_bytes::<32>())
+            .collect::<Vec<_>>();
+        let victim_loss_counterfactual_amount_out = self
+            .victim_loss
+            .iter()
+            .map(|loss| loss.counterfactual_amount_out.to_le_bytes::<32>())
+            .collect::<Vec<_>>();
+        let victim_loss_amount_lost_usd = self
+            .victim_loss
+            .iter()
+            .map(|loss| loss.amount_lost_usd)
+            .collect::<Vec<_>>();
+
+        ser_struct.serialize_field("victim_loss.tx_hash", &victim_loss_tx_hash)?;
+        ser_struct.serialize_field("victim_loss.victim_eoa", &victim_loss_eoa)?;
+        ser_struct.serialize_field("victim_loss.token", &victim_loss_token)?;
+        ser_struct.serialize_field("victim_loss.amount_out", &victim_loss_amount_out)?;
+        ser_struct.serialize_field(
+            "victim_loss.counterfactual_amount_out",
+            &victim_loss_counterfactual_amount_out,
+        )?;
+        ser_struct.serialize_field("victim_loss.amount_lost_usd", &victim_loss_amount_lost_usd)?;
+
         ser_struct.end()
     }
 }
@@ -333,5 +393,11 @@ impl DbRow for Sandwich {
         "backrun_gas_details.priority_fee",
         "backrun_gas_details.gas_used",
         "backrun_gas_details.effective_gas_price",
+        "victim_loss.tx_hash",
+        "victim_loss.victim_eoa",
+        "victim_loss.token",
+        "victim_loss.amount_out",
+        "victim_loss.counterfactual_amount_out",
+        "victim_loss.amount_lost_usd",
     ];
 }
@@ -101,6 +101,15 @@ utils!(
         ClipperExchange,
         PropellerLabsSolver,
         Dodo,
+        UniswapV4,
+        MorphoBlue,
+        Spark,
+        Liquity,
+        EulerV2,
+        OneInchV6,
+        ParaswapV5,
+        ParaswapV6,
+        KyberSwap,
         #[default]
         Unknown,
     }
@@ -146,6 +155,15 @@ impl Protocol {
             Protocol::ClipperExchange => ("ClipperExchange", ""),
             Protocol::PropellerLabsSolver => ("Propeller Labs Solver", ""),
             Protocol::Dodo => ("Dodo", "V1/V2"),
+            Protocol::UniswapV4 => ("Uniswap", "V4"),
+            Protocol::MorphoBlue => ("Morpho", "Blue"),
+            Protocol::Spark => ("Spark", ""),
+            Protocol::Liquity => ("Liquity", "V1"),
+            Protocol::EulerV2 => ("Euler", "V2"),
+            Protocol::OneInchV6 => ("OneInch", "V6"),
+            Protocol::ParaswapV5 => ("Paraswap", "V5"),
+            Protocol::ParaswapV6 => ("Paraswap", "V6"),
+            Protocol::KyberSwap => ("KyberSwap", "MetaAggregationRouterV2"),
             Protocol::Unknown => ("Unknown", "Unknown"),
         }
     }
@@ -156,6 +174,7 @@ impl Protocol {
             "sushiswapv2" => Protocol::SushiSwapV2,
             "uniswapv3" => Protocol::UniswapV3,
             "sushiswapv3" => Protocol::SushiSwapV3,
+            "uniswapv4" => Protocol::UniswapV4,
             "curve.fibase2" => Protocol::CurveBasePool2,
             "curve.fibase3" => Protocol::CurveBasePool3,
             "curve.fibase4" => Protocol::CurveBasePool4,
@@ -230,6 +249,15 @@ impl fmt::Display for Protocol {
                 Protocol::ClipperExchange => "Clipper",
                 Protocol::PropellerLabsSolver => "Propeller Labs",
                 Protocol::Dodo => "Dodo",
+                Protocol::UniswapV4 => "Uni V4",
+                Protocol::MorphoBlue => "Morpho Blue",
+                Protocol::Spark => "Spark",
+                Protocol::Liquity => "Liquity",
+                Protocol::EulerV2 => "Euler V2",
+                Protocol::OneInchV6 => "1inch V6",
+                Protocol::ParaswapV5 => "Paraswap V5",
+                Protocol::ParaswapV6 => "Paraswap V6",
+                Protocol::KyberSwap => "KyberSwap",
                 Protocol::Unknown => "Unknown",
             }
         )
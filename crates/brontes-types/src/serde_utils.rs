@@ -721,9 +721,9 @@ pub mod option_fund {
         ser::{Serialize, Serializer},
     };
 
-    use crate::db::searcher::Fund;
+    use crate::db::fund::Fund;
     pub fn serialize<S: Serializer>(u: &Option<Fund>, serializer: S) -> Result<S::Ok, S::Error> {
-        let st = u.map(|f| f.to_string());
+        let st = u.as_ref().map(|f| f.to_string());
         st.serialize(serializer)
     }
 
@@ -743,7 +743,7 @@ pub mod vec_fund {
         ser::{Serialize, Serializer},
     };
 
-    use crate::db::searcher::Fund;
+    use crate::db::fund::Fund;
     pub fn serialize<S: Serializer>(u: &[Fund], serializer: S) -> Result<S::Ok, S::Error> {
         let st = u.iter().map(|f| f.to_string()).collect::<Vec<_>>();
         st.serialize(serializer)
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use alloy_primitives::{Address, Log, U256};
+use alloy_primitives::{Address, Log, I256, U256};
 use clickhouse::DbRow;
 use itertools::Itertools;
 use redefined::self_convert_redefined;
@@ -226,9 +226,15 @@ pub struct TxTrace {
     pub tx_index:        u64,
     // False if the transaction reverted
     pub is_success:      bool,
+    /// The accounts whose balance or storage the transaction changed, sorted
+    /// by address. Only stored in libmdbx, traces loaded from clickhouse don't
+    /// have it
+    #[serde(default)]
+    pub state_diff:      Vec<AccountStateDiff>,
 }
 
 impl TxTrace {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         block_number: u64,
         trace: Vec<TransactionTraceWithLogs>,
@@ -237,11 +243,76 @@ impl TxTrace {
         gas_used: u128,
         effective_price: u128,
         is_success: bool,
+        state_diff: Vec<AccountStateDiff>,
     ) -> Self {
-        Self { block_number, trace, tx_hash, tx_index, effective_price, gas_used, is_success }
+        Self {
+            block_number,
+            trace,
+            tx_hash,
+            tx_index,
+            effective_price,
+            gas_used,
+            is_success,
+            state_diff,
+        }
+    }
+
+    pub fn state_diff_for(&self, address: Address) -> Option<&AccountStateDiff> {
+        self.state_diff
+            .binary_search_by_key(&address, |diff| diff.address)
+            .ok()
+            .map(|idx| &self.state_diff[idx])
+    }
+
+    /// The eth balance change of every account the transaction changed the
+    /// balance of, including the gas paid by the sender and the coinbase tip
+    pub fn eth_balance_deltas(&self) -> impl Iterator<Item = (Address, I256)> + '_ {
+        self.state_diff.iter().filter_map(|diff| {
+            diff.balance
+                .as_ref()
+                .map(|balance| (diff.address, balance.delta()))
+        })
     }
 }
 
+/// The balance and storage changes of an account over a transaction
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountStateDiff {
+    pub address: Address,
+    /// Set if the balance changed
+    pub balance: Option<BalanceDiff>,
+    /// The changed storage slots, sorted by slot
+    pub storage: Vec<StorageSlotDiff>,
+}
+
+impl AccountStateDiff {
+    pub fn slot(&self, slot: U256) -> Option<&StorageSlotDiff> {
+        self.storage
+            .binary_search_by_key(&slot, |diff| diff.slot)
+            .ok()
+            .map(|idx| &self.storage[idx])
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceDiff {
+    pub pre:  U256,
+    pub post: U256,
+}
+
+impl BalanceDiff {
+    pub fn delta(&self) -> I256 {
+        I256::from_raw(self.post).wrapping_sub(I256::from_raw(self.pre))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageSlotDiff {
+    pub slot: U256,
+    pub pre:  U256,
+    pub post: U256,
+}
+
 impl Serialize for TxTrace {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -10,7 +10,7 @@ use reth_rpc_types::{
 use crate::structured_trace::TxTrace;
 
 #[async_trait::async_trait]
-#[auto_impl::auto_impl(Box)]
+#[auto_impl::auto_impl(Box, Arc)]
 pub trait TracingProvider: Send + Sync + 'static {
     async fn eth_call(
         &self,
@@ -29,6 +29,7 @@ use reth_tracer::{
     arena::CallTraceArena,
     config::{StackSnapshotType, TracingInspectorConfig},
     inspector::BrontesTracingInspector,
+    utils::state_diff,
 };
 use reth_transaction_pool::{
     blobstore::NoopBlobStore, validate::EthTransactionValidatorBuilder, CoinbaseTipOrdering,
@@ -144,7 +145,9 @@ impl TracingClient {
     }
 
     /// Replays all transactions in a block using a custom inspector for each
-    /// transaction
+    /// transaction. The storage and balance diffs of each transaction are
+    /// taken from the state it leaves behind, so `record_state_diff`, which
+    /// tracks the storage changes of every step, stays off
     pub async fn replay_block_transactions_with_inspector(
         &self,
         block_id: BlockId,
@@ -168,9 +171,13 @@ impl TracingClient {
         };
 
         self.api
-            .trace_block_with_inspector(block_id, insp_setup, move |tx_info, inspector, res, _, _| {
-                Ok(inspector.into_trace_results(tx_info, &res))
-            })
+            .trace_block_with_inspector(
+                block_id,
+                insp_setup,
+                move |tx_info, inspector, res, state, db| {
+                    Ok(inspector.into_trace_results(tx_info, &res, state_diff(state, db)))
+                },
+            )
             .await
     }
 }
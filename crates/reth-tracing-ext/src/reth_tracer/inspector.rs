@@ -2,7 +2,7 @@ use std::fmt::Debug;
 
 use alloy_primitives::{Address, Log, B256, U256};
 use arena::{CallTraceArena, PushTraceKind};
-use brontes_types::structured_trace::{TransactionTraceWithLogs, TxTrace};
+use brontes_types::structured_trace::{AccountStateDiff, TransactionTraceWithLogs, TxTrace};
 use config::TracingInspectorConfig;
 use reth_primitives::{Bytes, U64};
 use reth_rpc_types::{trace::parity::*, TransactionInfo};
@@ -376,7 +376,12 @@ impl BrontesTracingInspector {
 }
 
 impl BrontesTracingInspector {
-    pub fn into_trace_results(self, info: TransactionInfo, res: &ExecutionResult) -> TxTrace {
+    pub fn into_trace_results(
+        self,
+        info: TransactionInfo,
+        res: &ExecutionResult,
+        state_diff: Vec<AccountStateDiff>,
+    ) -> TxTrace {
         let gas_used = res.gas_used().into();
         let trace = self.build_trace(info.hash.unwrap(), info.block_number.unwrap());
 
@@ -388,6 +393,7 @@ impl BrontesTracingInspector {
             effective_price: 0,
             tx_index: info.index.unwrap(),
             is_success: res.is_success(),
+            state_diff,
         }
     }
 
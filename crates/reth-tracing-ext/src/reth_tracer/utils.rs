@@ -2,9 +2,11 @@
 
 use alloy_primitives::hex;
 use alloy_sol_types::{ContractError, GenericRevertReason};
+use brontes_types::structured_trace::{AccountStateDiff, BalanceDiff, StorageSlotDiff};
 use revm::{
+    db::CacheDB,
     interpreter::{opcode, OpCode},
-    primitives::SpecId,
+    primitives::{SpecId, State},
 };
 
 /// creates the memory data in 32byte chunks
@@ -26,6 +28,47 @@ pub(crate) fn gas_used(spec: SpecId, spent: u64, refunded: u64) -> u64 {
     spent - (refunded).min(spent / refund_quotient)
 }
 
+/// Diffs the state a transaction left behind against the state it started
+/// from. `db` has to be the db the transaction was executed on before its state
+/// is committed, it then holds the prior state of every account the
+/// transaction loaded.
+pub(crate) fn state_diff<ExtDB>(state: &State, db: &CacheDB<ExtDB>) -> Vec<AccountStateDiff> {
+    let mut diffs = state
+        .iter()
+        .filter(|(_, account)| account.is_touched())
+        .filter_map(|(address, account)| {
+            let pre_balance = db
+                .accounts
+                .get(address)
+                .map(|prev| prev.info.balance)
+                .unwrap_or_default();
+            let balance = (pre_balance != account.info.balance)
+                .then_some(BalanceDiff { pre: pre_balance, post: account.info.balance });
+
+            let mut storage = account
+                .storage
+                .iter()
+                .filter(|(_, slot)| slot.is_changed())
+                .map(|(key, slot)| StorageSlotDiff {
+                    slot: *key,
+                    pre:  slot.original_value(),
+                    post: slot.present_value(),
+                })
+                .collect::<Vec<_>>();
+            storage.sort_unstable_by_key(|slot| slot.slot);
+
+            (balance.is_some() || !storage.is_empty()).then_some(AccountStateDiff {
+                address: *address,
+                balance,
+                storage,
+            })
+        })
+        .collect::<Vec<_>>();
+    diffs.sort_unstable_by_key(|diff| diff.address);
+
+    diffs
+}
+
 /// Returns a non empty revert reason if the output is a revert/error.
 #[inline]
 pub(crate) fn maybe_revert_reason(output: &[u8]) -> Option<String> {